@@ -5,13 +5,63 @@ use rocket::http::Status;
 use rocket::request::{FromRequest, Outcome, Request};
 
 use crate::error::Error;
+use crate::modules::api_keys::model::{ApiKeyScope, API_KEY_PREFIX};
+use crate::modules::api_keys::service::ApiKeyService;
 use crate::modules::auth::service::AuthService;
 use crate::modules::tournaments::model::VoterId;
+use crate::modules::tournaments::service::TournamentService;
+use crate::modules::users::model::UserRole;
+use crate::modules::users::service::UserService;
 
+/// A registered user, authenticated by an access token or a personal API key.
+/// API keys are only accepted on routes whose scope they carry.
 #[derive(Debug)]
 pub struct AuthenticatedUser {
     pub user_id: ObjectId,
     pub email: String,
+    /// Scopes of the API key used, or `None` for a JWT session
+    pub scopes: Option<Vec<ApiKeyScope>>,
+}
+
+async fn authenticate_api_key(
+    request: &Request<'_>,
+    key: &str,
+) -> Outcome<AuthenticatedUser, Error> {
+    let Some(api_key_service) = request
+        .rocket()
+        .state::<Arc<dyn ApiKeyService + Send + Sync>>()
+    else {
+        return Outcome::Error((
+            Status::InternalServerError,
+            Error::Internal("Missing API key service".to_string()),
+        ));
+    };
+
+    let principal = match api_key_service.authenticate(key).await {
+        Ok(principal) => principal,
+        Err(e) => return Outcome::Error((Status::Unauthorized, Error::Unauthorized(e))),
+    };
+
+    let required = request.route().and_then(|route| {
+        ApiKeyScope::required_for(request.method(), route.uri.base(), route.uri.path())
+    });
+    match required {
+        Some(scope) if principal.scopes.contains(&scope) => {
+            Outcome::Success(AuthenticatedUser {
+                user_id: principal.user_id,
+                email: principal.email,
+                scopes: Some(principal.scopes),
+            })
+        }
+        Some(scope) => Outcome::Error((
+            Status::Forbidden,
+            Error::Forbidden(format!("API key is missing the {} scope", scope.as_str())),
+        )),
+        None => Outcome::Error((
+            Status::Forbidden,
+            Error::Forbidden("API keys cannot be used for this endpoint".to_string()),
+        )),
+    }
 }
 
 #[rocket::async_trait]
@@ -44,6 +94,10 @@ impl<'r> FromRequest<'r> for AuthenticatedUser {
             }
         };
 
+        if token.starts_with(API_KEY_PREFIX) {
+            return authenticate_api_key(request, token).await;
+        }
+
         match auth_service.verify_token(token) {
             Ok(claims) => {
                 if claims.token_type != "access" {
@@ -58,6 +112,7 @@ impl<'r> FromRequest<'r> for AuthenticatedUser {
                     Ok(user_id) => Outcome::Success(AuthenticatedUser {
                         user_id,
                         email: claims.email,
+                        scopes: None,
                     }),
                     Err(_) => Outcome::Error((
                         Status::Unauthorized,
@@ -73,6 +128,84 @@ impl<'r> FromRequest<'r> for AuthenticatedUser {
     }
 }
 
+/// Loads the current role of the authenticated user, which may have changed
+/// since the token was issued, and checks it against `required`.
+async fn require_role(
+    request: &Request<'_>,
+    required: UserRole,
+) -> Outcome<(ObjectId, UserRole), Error> {
+    let auth = match request.guard::<AuthenticatedUser>().await {
+        Outcome::Success(auth) => auth,
+        Outcome::Error(e) => return Outcome::Error(e),
+        Outcome::Forward(s) => return Outcome::Forward(s),
+    };
+
+    let Some(user_service) = request
+        .rocket()
+        .state::<Arc<dyn UserService + Send + Sync>>()
+    else {
+        return Outcome::Error((
+            Status::InternalServerError,
+            Error::Internal("Missing user service".to_string()),
+        ));
+    };
+
+    let user = match user_service.find_by_id(&auth.user_id).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return Outcome::Error((
+                Status::Unauthorized,
+                Error::Unauthorized("User not found".to_string()),
+            ))
+        }
+        Err(e) => return Outcome::Error((Status::InternalServerError, Error::Internal(e))),
+    };
+
+    if user.is_suspended() || user.role < required {
+        return Outcome::Error((
+            Status::Forbidden,
+            Error::Forbidden("Insufficient role".to_string()),
+        ));
+    }
+
+    Outcome::Success((auth.user_id, user.role))
+}
+
+/// A user with at least the moderator role.
+#[derive(Debug)]
+pub struct ModeratorUser {
+    pub user_id: ObjectId,
+    pub role: UserRole,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ModeratorUser {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        require_role(request, UserRole::Moderator)
+            .await
+            .map(|(user_id, role)| ModeratorUser { user_id, role })
+    }
+}
+
+/// A user with the admin role.
+#[derive(Debug)]
+pub struct AdminUser {
+    pub user_id: ObjectId,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminUser {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        require_role(request, UserRole::Admin)
+            .await
+            .map(|(user_id, _)| AdminUser { user_id })
+    }
+}
+
 #[derive(Debug)]
 pub enum TournamentParticipant {
     Registered { user_id: ObjectId, email: String },
@@ -86,6 +219,22 @@ impl TournamentParticipant {
             Self::Anonymous { session_id, .. } => VoterId::Anonymous(session_id.clone()),
         }
     }
+
+    pub fn is_anonymous(&self) -> bool {
+        matches!(self, Self::Anonymous { .. })
+    }
+
+    /// Anonymous sessions are bound to the tournament they were issued for;
+    /// registered users are not scoped by their token.
+    pub fn can_access(&self, tournament_id: &ObjectId) -> bool {
+        match self {
+            Self::Registered { .. } => true,
+            Self::Anonymous {
+                tournament_id: scoped_id,
+                ..
+            } => scoped_id == tournament_id,
+        }
+    }
 }
 
 #[rocket::async_trait]
@@ -118,6 +267,17 @@ impl<'r> FromRequest<'r> for TournamentParticipant {
             }
         };
 
+        if token.starts_with(API_KEY_PREFIX) {
+            return match authenticate_api_key(request, token).await {
+                Outcome::Success(user) => Outcome::Success(TournamentParticipant::Registered {
+                    user_id: user.user_id,
+                    email: user.email,
+                }),
+                Outcome::Error(e) => Outcome::Error(e),
+                Outcome::Forward(s) => Outcome::Forward(s),
+            };
+        }
+
         // Try registered (access) token first
         if let Ok(claims) = auth_service.verify_token(token) {
             if claims.token_type == "access" {
@@ -147,3 +307,93 @@ impl<'r> FromRequest<'r> for TournamentParticipant {
         ))
     }
 }
+
+fn first_dynamic_segment<'r>(request: &'r Request<'_>) -> Option<&'r str> {
+    let route = request.route()?;
+    let index = route
+        .uri
+        .unmounted_origin
+        .path()
+        .segments()
+        .position(|segment| segment.starts_with('<'))?;
+    request.routed_segment(index)
+}
+
+/// A `TournamentParticipant` whose token is valid for the tournament named by
+/// the first dynamic segment of the route (e.g. `/<_>/bracket`). Anonymous
+/// sessions must also still be participants, so kicked guests are turned away.
+#[derive(Debug)]
+pub struct ScopedParticipant {
+    pub tournament_id: ObjectId,
+    pub participant: TournamentParticipant,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ScopedParticipant {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let participant = match request.guard::<TournamentParticipant>().await {
+            Outcome::Success(p) => p,
+            Outcome::Error(e) => return Outcome::Error(e),
+            Outcome::Forward(s) => return Outcome::Forward(s),
+        };
+
+        let tournament_id = match first_dynamic_segment(request) {
+            Some(id) => match ObjectId::parse_str(id) {
+                Ok(oid) => oid,
+                Err(_) => {
+                    return Outcome::Error((
+                        Status::BadRequest,
+                        Error::BadRequest("Invalid tournament ID".to_string()),
+                    ))
+                }
+            },
+            _ => {
+                return Outcome::Error((
+                    Status::BadRequest,
+                    Error::BadRequest("Missing tournament ID".to_string()),
+                ))
+            }
+        };
+
+        if !participant.can_access(&tournament_id) {
+            return Outcome::Error((
+                Status::Forbidden,
+                Error::Forbidden("Token is not valid for this tournament".to_string()),
+            ));
+        }
+
+        // Anonymous tokens cannot be revoked, so a kicked participant is
+        // recognised by no longer being listed in the tournament
+        if participant.is_anonymous() {
+            let Some(tournament_service) = request
+                .rocket()
+                .state::<Arc<dyn TournamentService + Send + Sync>>()
+            else {
+                return Outcome::Error((
+                    Status::InternalServerError,
+                    Error::Internal("Missing tournament service".to_string()),
+                ));
+            };
+            let tournament = match tournament_service.find_by_id(&tournament_id).await {
+                Ok(tournament) => tournament,
+                Err(e) => return Outcome::Error((Status::InternalServerError, Error::Internal(e))),
+            };
+            let voter_id = participant.voter_id();
+            if let Some(tournament) = tournament {
+                if !tournament.users.iter().any(|u| u.voter_id == voter_id) {
+                    return Outcome::Error((
+                        Status::Unauthorized,
+                        Error::Unauthorized("Session is no longer valid".to_string()),
+                    ));
+                }
+            }
+        }
+
+        Outcome::Success(ScopedParticipant {
+            tournament_id,
+            participant,
+        })
+    }
+}
@@ -8,6 +8,14 @@ pub fn deserialize_oid<'de, D: Deserializer<'de>>(d: D) -> Result<ObjectId, D::E
     ObjectId::parse_str(&s).map_err(de::Error::custom)
 }
 
+/// Deserialize a list of ObjectIds from hex strings
+pub fn deserialize_vec_oid<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<ObjectId>, D::Error> {
+    Vec::<String>::deserialize(d)?
+        .iter()
+        .map(|s| ObjectId::parse_str(s).map_err(de::Error::custom))
+        .collect()
+}
+
 pub fn serialize_oid<S: Serializer>(oid: &ObjectId, s: S) -> Result<S::Ok, S::Error> {
     s.serialize_str(&oid.to_hex())
 }
@@ -1,6 +1,7 @@
 pub mod guards;
 pub mod json;
 pub mod pagination;
+pub mod secrets;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,17 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+const SECRET_BYTES: usize = 32;
+
+/// Random URL-safe secret for single-use links and tokens sent to users.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; SECRET_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Digest stored in place of a secret so a database leak does not expose
+/// usable tokens.
+pub fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
@@ -2,18 +2,56 @@ use std::sync::Arc;
 
 use chrono::Utc;
 use jsonwebtoken::{encode, EncodingKey, Header as JwtHeader};
-use mongodb::bson::oid::ObjectId;
+use mockall::mock;
+use mongodb::bson::{oid::ObjectId, DateTime};
 use rocket::http::Header;
 use rocket::{http::Status, local::asynchronous::Client, Build, Rocket};
 
-use crate::common::guards::{AuthenticatedUser, TournamentParticipant};
-use crate::modules::auth::model::{AnonymousClaims, JwtClaims};
+use crate::common::guards::{
+    AdminUser, AuthenticatedUser, ModeratorUser, ScopedParticipant, TournamentParticipant,
+};
+use crate::common::pagination::{PaginatedResponse, PaginationParams};
+use crate::config::email_verification::EmailVerificationConfig;
+use crate::config::rate_limit::RateLimitConfig;
+use crate::modules::account_deletion::model::OwnedTournamentPolicy;
+use crate::modules::api_keys::model::{
+    ApiKeyResponse, ApiKeyScope, CreateApiKeyDto, CreatedApiKeyResponse, UpdateApiKeyDto,
+};
+use crate::modules::api_keys::service::{ApiKeyPrincipal, ApiKeyService};
+use crate::modules::auth::model::{
+    AnonymousClaims, JwtClaims, OneTimeToken, RefreshToken, TokenPurpose,
+};
+use crate::modules::auth::controller::{forgot_password, resend_verification_email};
+use crate::modules::auth::keys::JwtKeys;
+use crate::modules::auth::repository::{OneTimeTokenRepository, RefreshTokenRepository};
 use crate::modules::auth::service::{AuthConfig, AuthService, AuthServiceImpl};
-use crate::modules::users::model::{UpdateUserDto, User};
+use crate::modules::invitations::controller::invite;
+use crate::modules::invitations::model::{CreateInvitationsDto, InvitationResponse};
+use crate::modules::invitations::service::InvitationService;
+use crate::modules::mail::service::LogMailer;
+use crate::modules::rate_limit::repository::InMemoryRateLimitRepository;
+use crate::modules::rate_limit::service::{RateLimitService, RateLimitServiceImpl};
+use crate::modules::tournaments::model::{
+    CreateInviteDto, CreateTournamentDto, GrantMemberDto, InviteRedemptionResponse,
+    InviteResponse, InviteSummaryResponse, JoinTournamentDto, JoinTournamentResponse, Match,
+    MembershipError,
+    Tournament, TournamentMemberResponse, TournamentResponse, TournamentStatus, TournamentUser,
+    TournamentUserResponse, UpdateParticipantDto, UpdateTournamentDto, VoteMatchDto, VoterId,
+};
+use crate::modules::tournaments::controller::vote_match;
+use crate::modules::tournaments::service::TournamentService;
+use crate::modules::users::model::{TwoFactor, UpdateUserDto, User, UserRole};
 use crate::modules::users::service::UserService;
+use crate::modules::websocket::broadcaster::TournamentBroadcaster;
+use crate::modules::websocket::controller::tournament_ws;
 
 use async_trait::async_trait;
 
+const ADMIN_ID: &str = "65f000000000000000000001";
+const MODERATOR_ID: &str = "65f000000000000000000002";
+const VERIFIED_ID: &str = "65f000000000000000000003";
+
+/// Knows one admin, one moderator and one user with a verified email address.
 struct StubUserService;
 
 #[async_trait]
@@ -27,12 +65,30 @@ impl UserService for StubUserService {
         Err("not implemented".to_string())
     }
 
+    async fn provision_user(&self, _email: String, _name: String) -> Result<User, String> {
+        Err("not implemented".to_string())
+    }
+
     async fn find_by_email(&self, _email: &str) -> Result<Option<User>, String> {
         Ok(None)
     }
 
-    async fn find_by_id(&self, _id: &ObjectId) -> Result<Option<User>, String> {
-        Ok(None)
+    async fn find_by_id(&self, id: &ObjectId) -> Result<Option<User>, String> {
+        let role = match id.to_hex().as_str() {
+            ADMIN_ID => UserRole::Admin,
+            MODERATOR_ID => UserRole::Moderator,
+            VERIFIED_ID => UserRole::User,
+            _ => return Ok(None),
+        };
+        let mut user = User::new(
+            "staff@example.com".to_string(),
+            "Staff".to_string(),
+            "hash".to_string(),
+        );
+        user.id = Some(*id);
+        user.role = role;
+        user.email_verified = id.to_hex() == VERIFIED_ID;
+        Ok(Some(user))
     }
 
     async fn verify_credentials(
@@ -47,9 +103,144 @@ impl UserService for StubUserService {
         Err("not implemented".to_string())
     }
 
-    async fn delete_user(&self, _id: &ObjectId) -> Result<(), String> {
+    async fn mark_email_verified(&self, _id: &ObjectId) -> Result<User, String> {
+        Err("not implemented".to_string())
+    }
+
+    async fn update_two_factor(
+        &self,
+        _id: &ObjectId,
+        _two_factor: TwoFactor,
+    ) -> Result<User, String> {
         Err("not implemented".to_string())
     }
+
+    async fn consume_recovery_code(
+        &self,
+        _id: &ObjectId,
+        _code_hash: &str,
+    ) -> Result<bool, String> {
+        Ok(false)
+    }
+
+    fn check_password(
+        &self,
+        _password: &str,
+        _email: &str,
+        _name: &str,
+    ) -> Result<(), crate::modules::users::password_policy::PasswordPolicyError> {
+        Ok(())
+    }
+}
+
+struct StubRefreshTokenRepository;
+
+#[async_trait]
+impl RefreshTokenRepository for StubRefreshTokenRepository {
+    async fn create(&self, _token: &RefreshToken) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn find_by_jti(&self, _jti: &str) -> Result<Option<RefreshToken>, String> {
+        Ok(None)
+    }
+
+    async fn mark_rotated(&self, _jti: &str) -> Result<bool, String> {
+        Ok(false)
+    }
+
+    async fn revoke_family(&self, _family_id: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn revoke_all_for_user(&self, _user_id: &ObjectId) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn find_active_by_user(&self, _user_id: &ObjectId) -> Result<Vec<RefreshToken>, String> {
+        Ok(vec![])
+    }
+
+    async fn revoke_family_for_user(
+        &self,
+        _user_id: &ObjectId,
+        _family_id: &str,
+    ) -> Result<bool, String> {
+        Ok(false)
+    }
+}
+
+struct StubOneTimeTokenRepository;
+
+#[async_trait]
+impl OneTimeTokenRepository for StubOneTimeTokenRepository {
+    async fn create(&self, _token: &OneTimeToken) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn consume(
+        &self,
+        _token_hash: &str,
+        _purpose: TokenPurpose,
+    ) -> Result<Option<OneTimeToken>, String> {
+        Ok(None)
+    }
+
+    async fn invalidate_for_user(
+        &self,
+        _user_id: &ObjectId,
+        _purpose: TokenPurpose,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Knows a single key, allowed to read tournaments.
+struct StubApiKeyService;
+
+const TEST_API_KEY: &str = "torvi_test_key";
+
+#[async_trait]
+impl ApiKeyService for StubApiKeyService {
+    async fn create_key(
+        &self,
+        _user_id: &ObjectId,
+        _dto: CreateApiKeyDto,
+    ) -> Result<CreatedApiKeyResponse, String> {
+        Err("not implemented".to_string())
+    }
+
+    async fn list_keys(&self, _user_id: &ObjectId) -> Result<Vec<ApiKeyResponse>, String> {
+        Ok(vec![])
+    }
+
+    async fn get_key(&self, _user_id: &ObjectId, _id: &ObjectId) -> Result<ApiKeyResponse, String> {
+        Err("API key not found".to_string())
+    }
+
+    async fn update_key(
+        &self,
+        _user_id: &ObjectId,
+        _id: &ObjectId,
+        _dto: UpdateApiKeyDto,
+    ) -> Result<ApiKeyResponse, String> {
+        Err("API key not found".to_string())
+    }
+
+    async fn delete_key(&self, _user_id: &ObjectId, _id: &ObjectId) -> Result<(), String> {
+        Err("API key not found".to_string())
+    }
+
+    async fn authenticate(&self, key: &str) -> Result<ApiKeyPrincipal, String> {
+        if key != TEST_API_KEY {
+            return Err("Invalid API key".to_string());
+        }
+        Ok(ApiKeyPrincipal {
+            user_id: ObjectId::new(),
+            email: "bot@example.com".to_string(),
+            scopes: vec![ApiKeyScope::TournamentsRead],
+        })
+    }
 }
 
 const TEST_SECRET: &str = "test_secret";
@@ -71,17 +262,208 @@ fn participant_route(participant: TournamentParticipant) -> String {
     }
 }
 
+#[get("/tournaments/<_>")]
+fn scoped_route(scope: ScopedParticipant) -> String {
+    format!("Scoped: {}", scope.tournament_id)
+}
+
+#[get("/tournaments/<_>/matches/<match_id>")]
+fn scoped_match_route(scope: ScopedParticipant, match_id: &str) -> String {
+    format!("Scoped: {} {}", scope.tournament_id, match_id)
+}
+
+#[get("/")]
+fn list_tournaments_route(user: AuthenticatedUser) -> String {
+    format!("Tournaments of {}", user.email)
+}
+
+#[post("/")]
+fn create_tournament_route(user: AuthenticatedUser) -> String {
+    format!("Created by {}", user.email)
+}
+
+#[get("/<_>/participant")]
+fn tournament_participant_route(participant: TournamentParticipant) -> String {
+    format!("{:?}", participant.voter_id())
+}
+
+#[get("/moderation")]
+fn moderator_route(moderator: ModeratorUser) -> String {
+    format!("Moderator: {:?}", moderator.role)
+}
+
+#[get("/administration")]
+fn admin_route(admin: AdminUser) -> String {
+    format!("Admin: {}", admin.user_id)
+}
+
+#[get("/me")]
+fn account_route(user: AuthenticatedUser) -> String {
+    format!("Account of {}", user.email)
+}
+
+mock! {
+    Tournaments {}
+
+    #[async_trait]
+    impl TournamentService for Tournaments {
+        async fn create_tournament(&self, tournament_dto: CreateTournamentDto, created_by: ObjectId) -> Result<Tournament, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Tournament>, String>;
+        async fn find_by_creator(&self, user_id: &ObjectId, params: PaginationParams) -> Result<PaginatedResponse<TournamentResponse>, String>;
+        async fn update_tournament(&self, id: &ObjectId, dto: UpdateTournamentDto, user_id: &ObjectId) -> Result<Tournament, String>;
+        async fn delete_tournament(&self, id: &ObjectId, user_id: &ObjectId) -> Result<(), String>;
+        async fn force_complete_tournament(&self, id: &ObjectId) -> Result<Tournament, String>;
+        async fn force_delete_tournament(&self, id: &ObjectId) -> Result<(), String>;
+        async fn pause_tournament(&self, id: &ObjectId, user_id: &ObjectId) -> Result<Tournament, String>;
+        async fn resume_tournament(&self, id: &ObjectId, user_id: &ObjectId) -> Result<Tournament, String>;
+        async fn get_match_detail(&self, tournament_id: &ObjectId, match_id: &str) -> Result<Match, String>;
+        async fn vote_match(&self, vote_dto: VoteMatchDto, voter_id: VoterId) -> Result<Tournament, String>;
+        async fn create_invite(&self, tournament_id: &ObjectId, dto: CreateInviteDto, created_by: ObjectId) -> Result<InviteResponse, String>;
+        async fn list_invites(&self, tournament_id: &ObjectId, user_id: &ObjectId) -> Result<Vec<InviteSummaryResponse>, MembershipError>;
+        async fn revoke_invite(&self, tournament_id: &ObjectId, code: &str, user_id: &ObjectId) -> Result<(), MembershipError>;
+        async fn invite_joins(&self, tournament_id: &ObjectId, code: &str, user_id: &ObjectId) -> Result<Vec<InviteRedemptionResponse>, MembershipError>;
+        async fn invite_qr_code(&self, tournament_id: &ObjectId, code: &str, user_id: &ObjectId) -> Result<Vec<u8>, MembershipError>;
+        async fn join_tournament(&self, tournament_id: &ObjectId, dto: JoinTournamentDto) -> Result<JoinTournamentResponse, String>;
+        async fn join_by_code(&self, dto: JoinTournamentDto) -> Result<JoinTournamentResponse, String>;
+        async fn claim_anonymous_session(&self, tournament_id: &ObjectId, session_id: &str, user_id: ObjectId, name: String) -> Result<(), String>;
+        async fn update_participant(&self, tournament_id: &ObjectId, voter_id: &str, dto: UpdateParticipantDto, user_id: &ObjectId) -> Result<TournamentUserResponse, MembershipError>;
+        async fn kick_participant(&self, tournament_id: &ObjectId, voter_id: &str, user_id: &ObjectId) -> Result<(), MembershipError>;
+        async fn grant_member(&self, tournament_id: &ObjectId, dto: GrantMemberDto, granted_by: &ObjectId) -> Result<Vec<TournamentMemberResponse>, MembershipError>;
+        async fn revoke_member(&self, tournament_id: &ObjectId, member_id: &ObjectId, revoked_by: &ObjectId) -> Result<Vec<TournamentMemberResponse>, MembershipError>;
+        async fn transfer_ownership(&self, tournament_id: &ObjectId, new_owner: &ObjectId, user_id: &ObjectId) -> Result<Tournament, MembershipError>;
+        async fn release_tournaments(&self, user_id: &ObjectId, policy: OwnedTournamentPolicy) -> Result<(), String>;
+        async fn anonymize_participant(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn opponents_in_use(&self, opponent_ids: &[ObjectId]) -> Result<Vec<ObjectId>, String>;
+    }
+}
+
+/// Accepts every invitation request without inviting anyone.
+struct StubInvitationService;
+
+#[async_trait]
+impl InvitationService for StubInvitationService {
+    async fn invite(
+        &self,
+        _tournament_id: &ObjectId,
+        _dto: CreateInvitationsDto,
+        _invited_by: &ObjectId,
+    ) -> Result<Vec<InvitationResponse>, String> {
+        Ok(vec![])
+    }
+
+    async fn list_for_tournament(
+        &self,
+        _tournament_id: &ObjectId,
+        _user_id: &ObjectId,
+    ) -> Result<Vec<InvitationResponse>, String> {
+        Ok(vec![])
+    }
+
+    async fn list_pending(&self, _user_id: &ObjectId) -> Result<Vec<InvitationResponse>, String> {
+        Ok(vec![])
+    }
+
+    async fn accept(
+        &self,
+        _invitation_id: &ObjectId,
+        _user_id: &ObjectId,
+    ) -> Result<InvitationResponse, String> {
+        Err("Invitation not found".to_string())
+    }
+
+    async fn decline(
+        &self,
+        _invitation_id: &ObjectId,
+        _user_id: &ObjectId,
+    ) -> Result<InvitationResponse, String> {
+        Err("Invitation not found".to_string())
+    }
+}
+
+/// Lists one anonymous participant, `session-uuid-123`, in every tournament.
+fn tournament_service() -> MockTournaments {
+    let mut service = MockTournaments::new();
+    service.expect_find_by_id().returning(|id| {
+        let now = DateTime::now();
+        Ok(Some(Tournament {
+            id: Some(*id),
+            name: "Test Tournament".to_string(),
+            created_by: ObjectId::new(),
+            members: vec![],
+            opponents: vec![],
+            users: vec![TournamentUser {
+                voter_id: VoterId::Anonymous("session-uuid-123".to_string()),
+                name: "Player 1".to_string(),
+                muted: false,
+            }],
+            rounds: vec![],
+            status: TournamentStatus::Active,
+            winner: None,
+            created_at: now,
+            updated_at: now,
+            version: 0,
+        }))
+    });
+    service
+}
+
 async fn create_test_rocket() -> Rocket<Build> {
     let auth_service = Arc::new(AuthServiceImpl::new(
         Arc::new(StubUserService) as Arc<dyn UserService + Send + Sync>,
+        Arc::new(StubRefreshTokenRepository),
+        Arc::new(StubOneTimeTokenRepository),
+        Arc::new(LogMailer),
         AuthConfig {
-            jwt_secret: TEST_SECRET.to_string(),
+            keys: Arc::new(JwtKeys::from_secret(TEST_SECRET)),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    ));
+    let rate_limit_service = Arc::new(RateLimitServiceImpl::new(
+        Arc::new(InMemoryRateLimitRepository::new()),
+        RateLimitConfig {
+            password_reset_per_hour: 1,
+            verification_email_per_hour: 1,
+            invitations_per_hour: 1,
+            ..RateLimitConfig::default()
         },
     ));
 
     rocket::build()
-        .mount("/", routes![protected_route, participant_route])
+        .mount(
+            "/",
+            routes![
+                protected_route,
+                participant_route,
+                scoped_route,
+                scoped_match_route,
+                moderator_route,
+                admin_route
+            ],
+        )
+        .mount(
+            "/api/tournaments",
+            routes![
+                list_tournaments_route,
+                create_tournament_route,
+                tournament_participant_route,
+                vote_match,
+                invite
+            ],
+        )
+        .mount("/api/users", routes![account_route])
+        .mount("/api/auth", routes![forgot_password, resend_verification_email])
+        .mount("/ws", routes![tournament_ws])
         .manage(auth_service as Arc<dyn AuthService + Send + Sync>)
+        .manage(Arc::new(StubApiKeyService) as Arc<dyn ApiKeyService + Send + Sync>)
+        .manage(Arc::new(StubUserService) as Arc<dyn UserService + Send + Sync>)
+        .manage(Arc::new(tournament_service()) as Arc<dyn TournamentService + Send + Sync>)
+        .manage(Arc::new(StubInvitationService) as Arc<dyn InvitationService + Send + Sync>)
+        .manage(rate_limit_service as Arc<dyn RateLimitService + Send + Sync>)
+        .manage(Arc::new(TournamentBroadcaster::new()))
+        .manage(EmailVerificationConfig {
+            required_for_invites: true,
+            ..EmailVerificationConfig::default()
+        })
 }
 
 fn create_test_token(user_id: &str, email: &str, secret: &str) -> String {
@@ -315,3 +697,445 @@ async fn test_participant_rejects_missing_token() {
 
     assert_eq!(response.status(), Status::Unauthorized);
 }
+
+// --- ScopedParticipant tests ---
+
+#[test]
+fn test_can_access_registered_any_tournament() {
+    let participant = TournamentParticipant::Registered {
+        user_id: ObjectId::new(),
+        email: "test@example.com".to_string(),
+    };
+    assert!(participant.can_access(&ObjectId::new()));
+}
+
+#[test]
+fn test_can_access_anonymous_own_tournament_only() {
+    let tournament_id = ObjectId::new();
+    let participant = TournamentParticipant::Anonymous {
+        session_id: "session-uuid-123".to_string(),
+        tournament_id,
+        display_name: "Player 1".to_string(),
+    };
+    assert!(participant.can_access(&tournament_id));
+    assert!(!participant.can_access(&ObjectId::new()));
+}
+
+#[tokio::test]
+async fn test_scoped_participant_cross_tournament_matrix() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+
+    let own_tournament = ObjectId::new();
+    let other_tournament = ObjectId::new();
+    let anonymous =
+        create_anonymous_test_token("session-uuid-123", &own_tournament, "Player 1", TEST_SECRET);
+    let registered = create_test_token(
+        &ObjectId::new().to_string(),
+        "test@example.com",
+        TEST_SECRET,
+    );
+
+    let own = format!("/tournaments/{}", own_tournament);
+    let other = format!("/tournaments/{}", other_tournament);
+    let own_match = format!("{}/matches/m1", own);
+    let other_match = format!("{}/matches/m1", other);
+
+    let cases = [
+        (&anonymous, &own, Status::Ok),
+        (&anonymous, &other, Status::Forbidden),
+        (&anonymous, &own_match, Status::Ok),
+        (&anonymous, &other_match, Status::Forbidden),
+        (&registered, &own, Status::Ok),
+        (&registered, &other, Status::Ok),
+        (&registered, &other_match, Status::Ok),
+    ];
+
+    for (token, uri, expected) in cases {
+        let response = client
+            .get(uri.as_str())
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), expected, "GET {}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_scoped_participant_rejects_invalid_tournament_id() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let token = create_anonymous_test_token(
+        "session-uuid-123",
+        &ObjectId::new(),
+        "Player 1",
+        TEST_SECRET,
+    );
+
+    let response = client
+        .get("/tournaments/not-an-id")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[tokio::test]
+async fn test_scoped_participant_rejects_anonymous_token_signed_with_other_secret() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let tournament_id = ObjectId::new();
+    let token = create_anonymous_test_token(
+        "session-uuid-123",
+        &tournament_id,
+        "Player 1",
+        "other_secret",
+    );
+
+    let response = client
+        .get(format!("/tournaments/{}", tournament_id))
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[tokio::test]
+async fn test_scoped_participant_returns_route_tournament_id() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let tournament_id = ObjectId::new();
+    let token = create_anonymous_test_token(
+        "session-uuid-123",
+        &tournament_id,
+        "Player 1",
+        TEST_SECRET,
+    );
+
+    let response = client
+        .get(format!("/tournaments/{}/matches/m1", tournament_id))
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Ok);
+    let body = response.into_string().await.unwrap();
+    assert_eq!(body, format!("Scoped: {} m1", tournament_id));
+}
+
+#[tokio::test]
+async fn test_scoped_participant_rejects_kicked_anonymous_session() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let tournament_id = ObjectId::new();
+    let token =
+        create_anonymous_test_token("session-uuid-456", &tournament_id, "Player 2", TEST_SECRET);
+
+    let response = client
+        .get(format!("/tournaments/{}", tournament_id))
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+/// Request headers of a WebSocket handshake.
+fn upgrade_headers() -> Vec<Header<'static>> {
+    vec![
+        Header::new("Connection", "Upgrade"),
+        Header::new("Upgrade", "websocket"),
+        Header::new("Sec-WebSocket-Version", "13"),
+        Header::new("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ=="),
+    ]
+}
+
+#[tokio::test]
+async fn test_websocket_accepts_listed_anonymous_session() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let tournament_id = ObjectId::new();
+    let token =
+        create_anonymous_test_token("session-uuid-123", &tournament_id, "Player 1", TEST_SECRET);
+
+    let mut request = client.get(format!("/ws/tournaments/{}?token={}", tournament_id, token));
+    for header in upgrade_headers() {
+        request = request.header(header);
+    }
+    let response = request.dispatch().await;
+
+    // The local client does not perform the upgrade itself
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[tokio::test]
+async fn test_websocket_rejects_reconnect_after_kick() {
+    // Arrange: the session's token is still valid but it is no longer listed
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let tournament_id = ObjectId::new();
+    let token =
+        create_anonymous_test_token("session-uuid-456", &tournament_id, "Player 2", TEST_SECRET);
+
+    // Act
+    let mut request = client.get(format!("/ws/tournaments/{}?token={}", tournament_id, token));
+    for header in upgrade_headers() {
+        request = request.header(header);
+    }
+    let response = request.dispatch().await;
+
+    // Assert
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[tokio::test]
+async fn test_vote_match_rejects_anonymous_token_for_other_tournament() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let token = create_anonymous_test_token(
+        "session-uuid-123",
+        &ObjectId::new(),
+        "Player 1",
+        TEST_SECRET,
+    );
+    let vote_dto = serde_json::json!({
+        "tournament_id": ObjectId::new().to_string(),
+        "match_id": "m1",
+        "voted_for": ObjectId::new().to_string()
+    });
+
+    let response = client
+        .post("/api/tournaments/match/vote")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .header(rocket::http::ContentType::JSON)
+        .body(vote_dto.to_string())
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Forbidden);
+}
+
+#[tokio::test]
+async fn test_invite_requires_verified_email() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let token = create_test_token(ADMIN_ID, "staff@example.com", TEST_SECRET);
+
+    let response = client
+        .post(format!("/api/tournaments/{}/invitations", ObjectId::new()))
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .header(rocket::http::ContentType::JSON)
+        .body(r#"{"emails": ["guest@example.com"]}"#)
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Forbidden);
+}
+
+#[tokio::test]
+async fn test_invite_is_rate_limited_per_tournament() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let token = create_test_token(VERIFIED_ID, "staff@example.com", TEST_SECRET);
+    let tournament_id = ObjectId::new();
+    let send = |tournament_id: ObjectId| {
+        client
+            .post(format!("/api/tournaments/{}/invitations", tournament_id))
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .header(rocket::http::ContentType::JSON)
+            .body(r#"{"emails": ["guest@example.com"]}"#)
+            .dispatch()
+    };
+
+    let first = send(tournament_id).await;
+    let second = send(tournament_id).await;
+    let other_tournament = send(ObjectId::new()).await;
+
+    assert_eq!(first.status(), Status::Ok);
+    assert_eq!(second.status(), Status::TooManyRequests);
+    assert_eq!(other_tournament.status(), Status::Ok);
+}
+
+#[tokio::test]
+async fn test_forgot_password_is_rate_limited_per_email() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let send = |email: &'static str| {
+        client
+            .post("/api/auth/password/forgot")
+            .header(rocket::http::ContentType::JSON)
+            .body(format!(r#"{{"email": "{}"}}"#, email))
+            .dispatch()
+    };
+
+    let first = send("nobody@example.com").await;
+    let second = send("nobody@example.com").await;
+    let other_email = send("someone@example.com").await;
+
+    // Unknown accounts get the same answer and count towards the limit
+    assert_eq!(first.status(), Status::Ok);
+    assert!(first
+        .into_string()
+        .await
+        .unwrap()
+        .contains("If an account exists for that email"));
+    assert_eq!(second.status(), Status::TooManyRequests);
+    assert_eq!(other_email.status(), Status::Ok);
+}
+
+#[tokio::test]
+async fn test_resend_verification_email_is_rate_limited_per_email() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let token = create_test_token(ADMIN_ID, "staff@example.com", TEST_SECRET);
+    let send = || {
+        client
+            .post("/api/auth/email/resend")
+            .header(Header::new("Authorization", format!("Bearer {}", token)))
+            .dispatch()
+    };
+
+    let first = send().await;
+    let second = send().await;
+
+    assert_eq!(first.status(), Status::Ok);
+    assert_eq!(second.status(), Status::TooManyRequests);
+}
+
+// --- API key tests ---
+
+#[tokio::test]
+async fn test_api_key_with_scope_is_accepted() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+
+    let response = client
+        .get("/api/tournaments")
+        .header(Header::new("Authorization", format!("Bearer {}", TEST_API_KEY)))
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.into_string().await.unwrap(),
+        "Tournaments of bot@example.com"
+    );
+}
+
+#[tokio::test]
+async fn test_api_key_without_scope_is_forbidden() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+
+    let response = client
+        .post("/api/tournaments")
+        .header(Header::new("Authorization", format!("Bearer {}", TEST_API_KEY)))
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Forbidden);
+}
+
+#[tokio::test]
+async fn test_api_key_rejected_outside_scoped_routes() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+
+    let response = client
+        .get("/api/users/me")
+        .header(Header::new("Authorization", format!("Bearer {}", TEST_API_KEY)))
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Forbidden);
+}
+
+#[tokio::test]
+async fn test_unknown_api_key_is_unauthorized() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+
+    let response = client
+        .get("/api/tournaments")
+        .header(Header::new("Authorization", "Bearer torvi_unknown"))
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[tokio::test]
+async fn test_jwt_is_not_limited_by_scopes() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let token = create_test_token(
+        &ObjectId::new().to_string(),
+        "test@example.com",
+        TEST_SECRET,
+    );
+
+    let response = client
+        .post("/api/tournaments")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[tokio::test]
+async fn test_participant_accepts_api_key() {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+
+    let response = client
+        .get(format!("/api/tournaments/{}/participant", ObjectId::new()))
+        .header(Header::new("Authorization", format!("Bearer {}", TEST_API_KEY)))
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.into_string().await.unwrap().starts_with("Registered"));
+}
+
+// --- Role guard tests ---
+
+async fn get_with_user(path: &str, user_id: &str) -> Status {
+    let rocket = create_test_rocket().await;
+    let client = Client::tracked(rocket).await.expect("valid rocket instance");
+    let token = create_test_token(user_id, "staff@example.com", TEST_SECRET);
+
+    let response = client
+        .get(path)
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    response.status()
+}
+
+#[tokio::test]
+async fn test_admin_passes_both_role_guards() {
+    assert_eq!(get_with_user("/administration", ADMIN_ID).await, Status::Ok);
+    assert_eq!(get_with_user("/moderation", ADMIN_ID).await, Status::Ok);
+}
+
+#[tokio::test]
+async fn test_moderator_is_not_admin() {
+    assert_eq!(get_with_user("/moderation", MODERATOR_ID).await, Status::Ok);
+    assert_eq!(
+        get_with_user("/administration", MODERATOR_ID).await,
+        Status::Forbidden
+    );
+}
+
+#[tokio::test]
+async fn test_role_guard_rejects_unknown_user() {
+    let user_id = ObjectId::new().to_hex();
+
+    assert_eq!(
+        get_with_user("/moderation", &user_id).await,
+        Status::Unauthorized
+    );
+}
@@ -1 +1,2 @@
 mod guards_tests;
+mod secrets_tests;
@@ -0,0 +1,27 @@
+use crate::common::secrets::{generate_secret, hash_secret};
+
+#[test]
+fn test_generate_secret_is_hex_of_expected_length() {
+    let secret = generate_secret();
+    assert_eq!(secret.len(), 64);
+    assert!(secret.chars().all(|c| c.is_ascii_hexdigit()));
+}
+
+#[test]
+fn test_generate_secret_is_unique() {
+    assert_ne!(generate_secret(), generate_secret());
+}
+
+#[test]
+fn test_hash_secret_is_deterministic() {
+    assert_eq!(hash_secret("abc"), hash_secret("abc"));
+    assert_ne!(hash_secret("abc"), hash_secret("abd"));
+}
+
+#[test]
+fn test_hash_secret_does_not_contain_secret() {
+    let secret = generate_secret();
+    let hashed = hash_secret(&secret);
+    assert_eq!(hashed.len(), 64);
+    assert_ne!(hashed, secret);
+}
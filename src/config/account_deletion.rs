@@ -0,0 +1,56 @@
+use rocket::fairing::AdHoc;
+
+use crate::config::rate_limit::parse_var;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountDeletionConfig {
+    /// Days a deleted account can still be restored
+    pub grace_period_days: u32,
+    /// How often accounts past their grace period are looked for
+    pub sweep_interval_secs: u64,
+}
+
+impl Default for AccountDeletionConfig {
+    fn default() -> Self {
+        Self {
+            grace_period_days: 14,
+            sweep_interval_secs: 3600,
+        }
+    }
+}
+
+impl AccountDeletionConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+        let config = Self {
+            grace_period_days: parse_var(
+                "ACCOUNT_DELETION_GRACE_DAYS",
+                defaults.grace_period_days,
+            )?,
+            sweep_interval_secs: parse_var(
+                "ACCOUNT_DELETION_SWEEP_SECS",
+                defaults.sweep_interval_secs,
+            )?,
+        };
+
+        if config.sweep_interval_secs == 0 {
+            return Err("ACCOUNT_DELETION_SWEEP_SECS must be at least 1".to_string());
+        }
+        Ok(config)
+    }
+
+    pub fn grace_period_secs(&self) -> i64 {
+        self.grace_period_days as i64 * 86400
+    }
+}
+
+pub fn init() -> AdHoc {
+    AdHoc::on_ignite("Account Deletion Configuration", |rocket| async {
+        match AccountDeletionConfig::from_env() {
+            Ok(config) => rocket.manage(config),
+            Err(e) => {
+                panic!("Failed to initialize account deletion configuration: {}", e);
+            }
+        }
+    })
+}
@@ -0,0 +1,34 @@
+use rocket::fairing::AdHoc;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AdminConfig {
+    /// Accounts given the admin role at startup, so a fresh deployment has
+    /// someone who can grant roles
+    pub bootstrap_emails: Vec<String>,
+}
+
+impl AdminConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let value = std::env::var("ADMIN_EMAILS").unwrap_or_default();
+        let mut bootstrap_emails = Vec::new();
+        for email in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            if !email.contains('@') {
+                return Err(format!("Invalid ADMIN_EMAILS entry: {}", email));
+            }
+            bootstrap_emails.push(email.to_string());
+        }
+
+        Ok(Self { bootstrap_emails })
+    }
+}
+
+pub fn init() -> AdHoc {
+    AdHoc::on_ignite("Admin Configuration", |rocket| async {
+        match AdminConfig::from_env() {
+            Ok(config) => rocket.manage(config),
+            Err(e) => {
+                panic!("Failed to initialize admin configuration: {}", e);
+            }
+        }
+    })
+}
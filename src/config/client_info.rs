@@ -0,0 +1,55 @@
+use std::convert::Infallible;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::request::{FromRequest, Outcome};
+use rocket::{Data, Request};
+
+/// Client metadata recorded against sessions created by the request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl ClientInfo {
+    fn from_headers(request: &Request<'_>) -> Self {
+        Self {
+            user_agent: request.headers().get_one("User-Agent").map(String::from),
+            ip_address: request.client_ip().map(|ip| ip.to_string()),
+        }
+    }
+}
+
+pub struct ClientInfoFairing;
+
+#[rocket::async_trait]
+impl Fairing for ClientInfoFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Client Info",
+            kind: Kind::Request,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        let info = ClientInfo::from_headers(request);
+        request.local_cache(|| info);
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ClientInfo {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(
+            request
+                .local_cache(|| ClientInfo::from_headers(request))
+                .clone(),
+        )
+    }
+}
+
+pub fn init() -> ClientInfoFairing {
+    ClientInfoFairing
+}
@@ -0,0 +1,61 @@
+use rocket::fairing::AdHoc;
+
+use crate::config::rate_limit::parse_var;
+
+/// Rules for the display names participants choose when joining.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayNameConfig {
+    pub max_length: usize,
+    /// Replaces the bundled blocklist with another one
+    pub blocklist_file: Option<String>,
+    /// Terms blocked on top of the list, e.g. the name of the event
+    pub blocked_terms: Vec<String>,
+}
+
+impl Default for DisplayNameConfig {
+    fn default() -> Self {
+        Self {
+            max_length: 32,
+            blocklist_file: None,
+            blocked_terms: Vec::new(),
+        }
+    }
+}
+
+impl DisplayNameConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+
+        let config = Self {
+            max_length: parse_var("DISPLAY_NAME_MAX_LENGTH", defaults.max_length)?,
+            blocklist_file: std::env::var("DISPLAY_NAME_BLOCKLIST_FILE")
+                .ok()
+                .filter(|path| !path.trim().is_empty()),
+            blocked_terms: std::env::var("DISPLAY_NAME_BLOCKED_TERMS")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|term| !term.is_empty())
+                .map(str::to_string)
+                .collect(),
+        };
+
+        // Leave room for the " (n)" suffix added to duplicate names
+        if !(8..=100).contains(&config.max_length) {
+            return Err("DISPLAY_NAME_MAX_LENGTH must be between 8 and 100".to_string());
+        }
+
+        Ok(config)
+    }
+}
+
+pub fn init() -> AdHoc {
+    AdHoc::on_ignite("Display Name Configuration", |rocket| async {
+        match DisplayNameConfig::from_env() {
+            Ok(config) => rocket.manage(config),
+            Err(e) => {
+                panic!("Failed to initialize display name configuration: {}", e);
+            }
+        }
+    })
+}
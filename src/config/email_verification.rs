@@ -0,0 +1,39 @@
+use rocket::fairing::AdHoc;
+
+/// Actions that require the caller to have verified their email address.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EmailVerificationConfig {
+    pub required_for_tournaments: bool,
+    pub required_for_invites: bool,
+}
+
+impl EmailVerificationConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let mut config = Self::default();
+
+        let value = std::env::var("REQUIRE_VERIFIED_EMAIL").unwrap_or_default();
+        for action in value.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+            match action {
+                "tournaments" => config.required_for_tournaments = true,
+                "invites" => config.required_for_invites = true,
+                other => return Err(format!("Unknown REQUIRE_VERIFIED_EMAIL value: {}", other)),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+pub fn init() -> AdHoc {
+    AdHoc::on_ignite("Email Verification Configuration", |rocket| async {
+        match EmailVerificationConfig::from_env() {
+            Ok(config) => rocket.manage(config),
+            Err(e) => {
+                panic!(
+                    "Failed to initialize email verification configuration: {}",
+                    e
+                );
+            }
+        }
+    })
+}
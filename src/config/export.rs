@@ -0,0 +1,46 @@
+use rocket::fairing::AdHoc;
+
+use crate::config::rate_limit::parse_var;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportConfig {
+    /// Hours a finished export can be downloaded before it is deleted
+    pub retention_hours: u32,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            retention_hours: 24,
+        }
+    }
+}
+
+impl ExportConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+        let config = Self {
+            retention_hours: parse_var("EXPORT_RETENTION_HOURS", defaults.retention_hours)?,
+        };
+
+        if config.retention_hours == 0 {
+            return Err("EXPORT_RETENTION_HOURS must be at least 1".to_string());
+        }
+        Ok(config)
+    }
+
+    pub fn retention_secs(&self) -> i64 {
+        self.retention_hours as i64 * 3600
+    }
+}
+
+pub fn init() -> AdHoc {
+    AdHoc::on_ignite("Export Configuration", |rocket| async {
+        match ExportConfig::from_env() {
+            Ok(config) => rocket.manage(config),
+            Err(e) => {
+                panic!("Failed to initialize export configuration: {}", e);
+            }
+        }
+    })
+}
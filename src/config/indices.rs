@@ -1,4 +1,5 @@
 use crate::config::database::MongoDB;
+use crate::modules::users::repository::search_collation;
 use mongodb::bson::doc;
 use mongodb::options::IndexOptions;
 use mongodb::IndexModel;
@@ -42,11 +43,21 @@ pub fn init() -> AdHoc {
             .await
             .expect("Failed to create index on tournaments.created_by");
 
+        // Index on tournaments.members.user_id
+        db.collection::<mongodb::bson::Document>("tournaments")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "members.user_id": 1 })
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on tournaments.members.user_id");
+
         // Index on tournaments.users.voter_id
         db.collection::<mongodb::bson::Document>("tournaments")
             .create_index(
                 IndexModel::builder()
-                    .keys(doc! { "users.voter_id.id": 1 })
+                    .keys(doc! { "users.voter_id.type": 1, "users.voter_id.id": 1 })
                     .build(),
             )
             .await
@@ -83,6 +94,287 @@ pub fn init() -> AdHoc {
             .await
             .expect("Failed to create unique index on tournament_invites.code");
 
+        // Index on tournament_invites.tournament_id
+        db.collection::<mongodb::bson::Document>("tournament_invites")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "tournament_id": 1 })
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on tournament_invites.tournament_id");
+
+        // TTL index on tournament_invites.expires_at
+        db.collection::<mongodb::bson::Document>("tournament_invites")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "expires_at": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .expire_after(std::time::Duration::from_secs(0))
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .expect("Failed to create TTL index on tournament_invites.expires_at");
+
+        // Index on refresh_tokens.family_id
+        db.collection::<mongodb::bson::Document>("refresh_tokens")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "family_id": 1 })
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on refresh_tokens.family_id");
+
+        // Index on refresh_tokens.user_id
+        db.collection::<mongodb::bson::Document>("refresh_tokens")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1 })
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on refresh_tokens.user_id");
+
+        // TTL index on refresh_tokens.expires_at
+        db.collection::<mongodb::bson::Document>("refresh_tokens")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "expires_at": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .expire_after(std::time::Duration::from_secs(0))
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .expect("Failed to create TTL index on refresh_tokens.expires_at");
+
+        // Index on one_time_tokens.user_id
+        db.collection::<mongodb::bson::Document>("one_time_tokens")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1 })
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on one_time_tokens.user_id");
+
+        // TTL index on one_time_tokens.expires_at
+        db.collection::<mongodb::bson::Document>("one_time_tokens")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "expires_at": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .expire_after(std::time::Duration::from_secs(0))
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .expect("Failed to create TTL index on one_time_tokens.expires_at");
+
+        // TTL index on oidc_login_states.expires_at
+        db.collection::<mongodb::bson::Document>("oidc_login_states")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "expires_at": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .expire_after(std::time::Duration::from_secs(0))
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .expect("Failed to create TTL index on oidc_login_states.expires_at");
+
+        // TTL index on rate_limits.expires_at
+        db.collection::<mongodb::bson::Document>("rate_limits")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "expires_at": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .expire_after(std::time::Duration::from_secs(0))
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .expect("Failed to create TTL index on rate_limits.expires_at");
+
+        // Unique index on user_identities.provider + subject
+        db.collection::<mongodb::bson::Document>("user_identities")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "provider": 1, "subject": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await
+            .expect("Failed to create unique index on user_identities.provider+subject");
+
+        // Index on user_identities.user_id
+        db.collection::<mongodb::bson::Document>("user_identities")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1 })
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on user_identities.user_id");
+
+        // Unique index on api_keys.key_hash
+        db.collection::<mongodb::bson::Document>("api_keys")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "key_hash": 1 })
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build(),
+            )
+            .await
+            .expect("Failed to create unique index on api_keys.key_hash");
+
+        // Index on api_keys.user_id + created_at
+        db.collection::<mongodb::bson::Document>("api_keys")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "created_at": -1 })
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on api_keys.user_id+created_at");
+
+        // Index on users.role
+        db.collection::<mongodb::bson::Document>("users")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "role": 1 })
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on users.role");
+
+        // Case-insensitive index on users.name, used by user search
+        db.collection::<mongodb::bson::Document>("users")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "name": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .name("name_ci".to_string())
+                            .collation(search_collation())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .expect("Failed to create case-insensitive index on users.name");
+
+        // Case-insensitive index on users.email, used by user search
+        db.collection::<mongodb::bson::Document>("users")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "email": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .name("email_ci".to_string())
+                            .collation(search_collation())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .expect("Failed to create case-insensitive index on users.email");
+
+        // Sparse index on users.deletion.scheduled_for, used by the deletion sweep
+        db.collection::<mongodb::bson::Document>("users")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "deletion.scheduled_for": 1 })
+                    .options(IndexOptions::builder().sparse(true).build())
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on users.deletion.scheduled_for");
+
+        // Index on tournament_invitations.tournament_id
+        db.collection::<mongodb::bson::Document>("tournament_invitations")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "tournament_id": 1 })
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on tournament_invitations.tournament_id");
+
+        // Index on tournament_invitations.user_id + status
+        db.collection::<mongodb::bson::Document>("tournament_invitations")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "status": 1 })
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on tournament_invitations.user_id+status");
+
+        // Index on tournament_invitations.email + status
+        db.collection::<mongodb::bson::Document>("tournament_invitations")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "email": 1, "status": 1 })
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on tournament_invitations.email+status");
+
+        // TTL index on tournament_invitations.expires_at
+        db.collection::<mongodb::bson::Document>("tournament_invitations")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "expires_at": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .expire_after(std::time::Duration::from_secs(0))
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .expect("Failed to create TTL index on tournament_invitations.expires_at");
+
+        // TTL index on export_jobs.expires_at, removing only the job records; the archives
+        // live in S3 and are deleted by the `purge_expired` sweeper in exports/service.rs
+        db.collection::<mongodb::bson::Document>("export_jobs")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "expires_at": 1 })
+                    .options(
+                        IndexOptions::builder()
+                            .expire_after(std::time::Duration::from_secs(0))
+                            .build(),
+                    )
+                    .build(),
+            )
+            .await
+            .expect("Failed to create TTL index on export_jobs.expires_at");
+
+        // Index on export_jobs.user_id + status
+        db.collection::<mongodb::bson::Document>("export_jobs")
+            .create_index(
+                IndexModel::builder()
+                    .keys(doc! { "user_id": 1, "status": 1 })
+                    .build(),
+            )
+            .await
+            .expect("Failed to create index on export_jobs.user_id+status");
+
         rocket
     })
 }
@@ -0,0 +1,95 @@
+use rocket::fairing::AdHoc;
+
+use crate::config::rate_limit::parse_var;
+
+/// Characters that are easy to tell apart when read aloud or from a screen:
+/// no 0/O, 1/I/L.
+pub const DEFAULT_ALPHABET: &str = "23456789ABCDEFGHJKMNPQRSTUVWXYZ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InviteCodeFormat {
+    /// Characters drawn from the configured alphabet, e.g. `K7QM2XPD`
+    Random,
+    /// Short lowercase words joined by hyphens, e.g. `maple-river-comet`
+    Words,
+}
+
+/// How new invite codes are generated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InviteCodeConfig {
+    pub format: InviteCodeFormat,
+    pub alphabet: String,
+    /// Characters in a random code
+    pub length: usize,
+    /// Words in a word code
+    pub word_count: usize,
+}
+
+impl Default for InviteCodeConfig {
+    fn default() -> Self {
+        Self {
+            format: InviteCodeFormat::Random,
+            alphabet: DEFAULT_ALPHABET.to_string(),
+            length: 8,
+            word_count: 3,
+        }
+    }
+}
+
+impl InviteCodeConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+
+        let format = match std::env::var("INVITE_CODE_FORMAT")
+            .unwrap_or_default()
+            .trim()
+        {
+            "" | "random" => InviteCodeFormat::Random,
+            "words" => InviteCodeFormat::Words,
+            other => return Err(format!("Unknown INVITE_CODE_FORMAT value: {}", other)),
+        };
+        let config = Self {
+            format,
+            alphabet: std::env::var("INVITE_CODE_ALPHABET")
+                .ok()
+                .filter(|alphabet| !alphabet.trim().is_empty())
+                .unwrap_or(defaults.alphabet),
+            length: parse_var("INVITE_CODE_LENGTH", defaults.length)?,
+            word_count: parse_var("INVITE_CODE_WORDS", defaults.word_count)?,
+        };
+
+        // Codes end up in URLs and QR codes, so keep them to plain letters
+        // and digits
+        if !config.alphabet.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err("INVITE_CODE_ALPHABET may only contain letters and digits".to_string());
+        }
+        let mut chars: Vec<char> = config.alphabet.chars().collect();
+        chars.sort_unstable();
+        chars.dedup();
+        if chars.len() != config.alphabet.len() {
+            return Err("INVITE_CODE_ALPHABET must not repeat characters".to_string());
+        }
+        if chars.len() < 10 {
+            return Err("INVITE_CODE_ALPHABET needs at least 10 characters".to_string());
+        }
+        if !(6..=32).contains(&config.length) {
+            return Err("INVITE_CODE_LENGTH must be between 6 and 32".to_string());
+        }
+        if !(2..=6).contains(&config.word_count) {
+            return Err("INVITE_CODE_WORDS must be between 2 and 6".to_string());
+        }
+
+        Ok(config)
+    }
+}
+
+pub fn init() -> AdHoc {
+    AdHoc::on_ignite("Invite Code Configuration", |rocket| async {
+        match InviteCodeConfig::from_env() {
+            Ok(config) => rocket.manage(config),
+            Err(e) => {
+                panic!("Failed to initialize invite code configuration: {}", e);
+            }
+        }
+    })
+}
@@ -2,14 +2,27 @@ use rocket::fairing::AdHoc;
 
 #[derive(Debug)]
 pub struct JwtConfig {
-    pub secret: String,
+    /// HS256 secret. Once signing keys are configured it is only used to
+    /// accept tokens issued before the switch.
+    pub secret: Option<String>,
+    /// Directory of `<kid>.pem` private keys (RSA or Ed25519)
+    pub keys_dir: Option<String>,
+    /// Key that signs new tokens; the others only verify
+    pub active_key_id: Option<String>,
 }
 
 impl JwtConfig {
     pub fn from_env() -> Result<Self, String> {
+        let secret = std::env::var("JWT_SECRET").ok();
+        let keys_dir = std::env::var("JWT_KEYS_DIR").ok();
+        if secret.is_none() && keys_dir.is_none() {
+            return Err("Missing JWT_SECRET or JWT_KEYS_DIR environment variable".to_string());
+        }
+
         Ok(Self {
-            secret: std::env::var("JWT_SECRET")
-                .map_err(|_| "Missing JWT_SECRET environment variable")?,
+            secret,
+            keys_dir,
+            active_key_id: std::env::var("JWT_ACTIVE_KEY_ID").ok(),
         })
     }
 }
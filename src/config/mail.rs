@@ -0,0 +1,87 @@
+use rocket::fairing::AdHoc;
+use rocket::figment::Profile;
+use rocket::Config;
+
+const DEFAULT_SMTP_PORT: u16 = 587;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MailTransport {
+    Smtp {
+        host: String,
+        port: u16,
+        username: Option<String>,
+        password: Option<String>,
+    },
+    File {
+        dir: String,
+    },
+    Log,
+}
+
+#[derive(Debug)]
+pub struct MailConfig {
+    pub transport: MailTransport,
+    pub from: String,
+    /// Public URL of the web app, used to build links in emails
+    pub app_url: String,
+}
+
+impl MailConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let transport = match std::env::var("MAIL_TRANSPORT") {
+            Ok(transport) => transport,
+            Err(_) => {
+                // The log transport drops every email, which production should not do unnoticed
+                if is_release_profile() {
+                    tracing::warn!(
+                        "MAIL_TRANSPORT is not set; emails are only logged, not delivered"
+                    );
+                }
+                "log".to_string()
+            }
+        };
+        let transport = match transport.as_str() {
+            "smtp" => MailTransport::Smtp {
+                host: std::env::var("SMTP_HOST")
+                    .map_err(|_| "Missing SMTP_HOST environment variable")?,
+                port: match std::env::var("SMTP_PORT") {
+                    Ok(port) => port.parse().map_err(|_| "Invalid SMTP_PORT value")?,
+                    Err(_) => DEFAULT_SMTP_PORT,
+                },
+                username: std::env::var("SMTP_USERNAME").ok(),
+                password: std::env::var("SMTP_PASSWORD").ok(),
+            },
+            "file" => MailTransport::File {
+                dir: std::env::var("MAIL_DIR").unwrap_or_else(|_| "mail".to_string()),
+            },
+            "log" => MailTransport::Log,
+            other => return Err(format!("Unknown MAIL_TRANSPORT: {}", other)),
+        };
+
+        Ok(Self {
+            transport,
+            from: std::env::var("MAIL_FROM")
+                .unwrap_or_else(|_| "Torvi <no-reply@localhost>".to_string()),
+            app_url: std::env::var("APP_URL")
+                .unwrap_or_else(|_| "http://localhost:3000".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+        })
+    }
+}
+
+fn is_release_profile() -> bool {
+    let profile = Profile::from_env_or("ROCKET_PROFILE", Config::DEFAULT_PROFILE);
+    profile == Config::RELEASE_PROFILE
+}
+
+pub fn init() -> AdHoc {
+    AdHoc::on_ignite("Mail Configuration", |rocket| async {
+        match MailConfig::from_env() {
+            Ok(config) => rocket.manage(config),
+            Err(e) => {
+                panic!("Failed to initialize mail configuration: {}", e);
+            }
+        }
+    })
+}
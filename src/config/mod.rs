@@ -1,8 +1,20 @@
+pub mod account_deletion;
+pub mod admin;
+pub mod client_info;
 pub mod cors;
 pub mod database;
+pub mod display_name;
+pub mod email_verification;
+pub mod export;
 pub mod indices;
+pub mod invite_code;
 pub mod jwt;
 pub mod logging;
+pub mod mail;
+pub mod oidc;
+pub mod password;
+pub mod password_policy;
+pub mod rate_limit;
 pub mod request_id;
 pub mod s3;
 pub mod security;
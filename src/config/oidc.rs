@@ -0,0 +1,84 @@
+use rocket::fairing::AdHoc;
+
+const DEFAULT_SCOPES: &str = "openid email profile";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OidcProviderConfig {
+    /// Short name used in URLs, e.g. `/api/auth/oidc/<name>/authorize`
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    /// Omitted for public clients, which rely on PKCE alone
+    pub client_secret: Option<String>,
+    pub scopes: Vec<String>,
+    pub redirect_url: String,
+}
+
+#[derive(Debug, Default)]
+pub struct OidcConfig {
+    pub providers: Vec<OidcProviderConfig>,
+}
+
+impl OidcConfig {
+    /// Reads the providers listed in `OIDC_PROVIDERS`, each configured
+    /// through `OIDC_<NAME>_*` variables.
+    pub fn from_env() -> Result<Self, String> {
+        let app_url = std::env::var("APP_URL")
+            .unwrap_or_else(|_| "http://localhost:3000".to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        let names = std::env::var("OIDC_PROVIDERS").unwrap_or_default();
+        let mut providers = Vec::new();
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            if !name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+            {
+                return Err(format!("Invalid OIDC provider name: {}", name));
+            }
+            providers.push(Self::provider_from_env(name, &app_url)?);
+        }
+
+        Ok(Self { providers })
+    }
+
+    fn provider_from_env(name: &str, app_url: &str) -> Result<OidcProviderConfig, String> {
+        let prefix = format!("OIDC_{}_", name.to_uppercase().replace('-', "_"));
+        let var = |key: &str| std::env::var(format!("{}{}", prefix, key)).ok();
+        let required = |key: &str| {
+            var(key).ok_or_else(|| format!("Missing {}{} environment variable", prefix, key))
+        };
+
+        let mut scopes: Vec<String> = var("SCOPES")
+            .unwrap_or_else(|| DEFAULT_SCOPES.to_string())
+            .split([' ', ','])
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        if !scopes.iter().any(|s| s == "openid") {
+            scopes.insert(0, "openid".to_string());
+        }
+
+        Ok(OidcProviderConfig {
+            name: name.to_string(),
+            issuer: required("ISSUER")?,
+            client_id: required("CLIENT_ID")?,
+            client_secret: var("CLIENT_SECRET"),
+            scopes,
+            redirect_url: var("REDIRECT_URL")
+                .unwrap_or_else(|| format!("{}/auth/oidc/{}/callback", app_url, name)),
+        })
+    }
+}
+
+pub fn init() -> AdHoc {
+    AdHoc::on_ignite("OIDC Configuration", |rocket| async {
+        match OidcConfig::from_env() {
+            Ok(config) => rocket.manage(config),
+            Err(e) => {
+                panic!("Failed to initialize OIDC configuration: {}", e);
+            }
+        }
+    })
+}
@@ -0,0 +1,53 @@
+use rocket::fairing::AdHoc;
+
+use crate::config::rate_limit::parse_var;
+
+/// Argon2id cost parameters for newly hashed passwords. Defaults follow the
+/// OWASP recommendation of 19 MiB, two passes and one lane.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordHashConfig {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for PasswordHashConfig {
+    fn default() -> Self {
+        Self {
+            memory_kib: argon2::Params::DEFAULT_M_COST,
+            iterations: argon2::Params::DEFAULT_T_COST,
+            parallelism: argon2::Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl PasswordHashConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+
+        let config = Self {
+            memory_kib: parse_var("ARGON2_MEMORY_KIB", defaults.memory_kib)?,
+            iterations: parse_var("ARGON2_ITERATIONS", defaults.iterations)?,
+            parallelism: parse_var("ARGON2_PARALLELISM", defaults.parallelism)?,
+        };
+        config.params()?;
+
+        Ok(config)
+    }
+
+    pub fn params(&self) -> Result<argon2::Params, String> {
+        argon2::Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| format!("Invalid Argon2 parameters: {}", e))
+    }
+}
+
+pub fn init() -> AdHoc {
+    AdHoc::on_ignite("Password Hash Configuration", |rocket| async {
+        match PasswordHashConfig::from_env() {
+            Ok(config) => rocket.manage(config),
+            Err(e) => {
+                panic!("Failed to initialize password hash configuration: {}", e);
+            }
+        }
+    })
+}
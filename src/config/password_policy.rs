@@ -0,0 +1,60 @@
+use rocket::fairing::AdHoc;
+
+use crate::config::rate_limit::parse_var;
+
+/// Rules new passwords must satisfy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordPolicyConfig {
+    pub min_length: usize,
+    /// How many of lowercase, uppercase, digits and symbols must appear
+    pub min_character_classes: usize,
+    /// Replaces the bundled breached-password list with a larger one
+    pub breached_list_file: Option<String>,
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        Self {
+            min_length: 10,
+            min_character_classes: 3,
+            breached_list_file: None,
+        }
+    }
+}
+
+impl PasswordPolicyConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+
+        let config = Self {
+            min_length: parse_var("PASSWORD_MIN_LENGTH", defaults.min_length)?,
+            min_character_classes: parse_var(
+                "PASSWORD_MIN_CHARACTER_CLASSES",
+                defaults.min_character_classes,
+            )?,
+            breached_list_file: std::env::var("PASSWORD_BREACHED_LIST_FILE")
+                .ok()
+                .filter(|path| !path.trim().is_empty()),
+        };
+
+        if config.min_length == 0 {
+            return Err("PASSWORD_MIN_LENGTH must be at least 1".to_string());
+        }
+        if config.min_character_classes > 4 {
+            return Err("PASSWORD_MIN_CHARACTER_CLASSES must be between 0 and 4".to_string());
+        }
+
+        Ok(config)
+    }
+}
+
+pub fn init() -> AdHoc {
+    AdHoc::on_ignite("Password Policy Configuration", |rocket| async {
+        match PasswordPolicyConfig::from_env() {
+            Ok(config) => rocket.manage(config),
+            Err(e) => {
+                panic!("Failed to initialize password policy configuration: {}", e);
+            }
+        }
+    })
+}
@@ -0,0 +1,114 @@
+use rocket::fairing::AdHoc;
+
+/// Where rate limit counters are kept.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimitBackend {
+    /// Per-process counters; limits apply to each instance separately
+    Memory,
+    /// Counters shared by every instance through MongoDB
+    Mongo,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    pub backend: RateLimitBackend,
+    /// Login attempts allowed per IP address per minute
+    pub login_per_minute: u32,
+    /// Anonymous tokens issued per IP address per minute
+    pub anonymous_per_minute: u32,
+    /// User directory searches per IP address per minute
+    pub user_search_per_minute: u32,
+    /// Login links emailed per address per hour
+    pub magic_link_per_hour: u32,
+    /// Password reset requests per address per hour
+    pub password_reset_per_hour: u32,
+    /// Verification emails resent per address per hour
+    pub verification_email_per_hour: u32,
+    /// Invitation requests per user per tournament per hour
+    pub invitations_per_hour: u32,
+    /// Failed logins on an account before it is locked
+    pub lockout_threshold: u32,
+    /// First lockout; each further failure doubles it
+    pub lockout_secs: u64,
+    pub lockout_max_secs: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            backend: RateLimitBackend::Memory,
+            login_per_minute: 10,
+            anonymous_per_minute: 20,
+            user_search_per_minute: 30,
+            magic_link_per_hour: 5,
+            password_reset_per_hour: 5,
+            verification_email_per_hour: 5,
+            invitations_per_hour: 10,
+            lockout_threshold: 5,
+            lockout_secs: 30,
+            lockout_max_secs: 900,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+
+        let backend = match std::env::var("RATE_LIMIT_BACKEND").as_deref() {
+            Err(_) | Ok("memory") => RateLimitBackend::Memory,
+            Ok("mongo") => RateLimitBackend::Mongo,
+            Ok(other) => return Err(format!("Unknown RATE_LIMIT_BACKEND: {}", other)),
+        };
+
+        Ok(Self {
+            backend,
+            login_per_minute: parse_var("RATE_LIMIT_LOGIN_PER_MINUTE", defaults.login_per_minute)?,
+            anonymous_per_minute: parse_var(
+                "RATE_LIMIT_ANONYMOUS_PER_MINUTE",
+                defaults.anonymous_per_minute,
+            )?,
+            user_search_per_minute: parse_var(
+                "RATE_LIMIT_USER_SEARCH_PER_MINUTE",
+                defaults.user_search_per_minute,
+            )?,
+            magic_link_per_hour: parse_var(
+                "RATE_LIMIT_MAGIC_LINK_PER_HOUR",
+                defaults.magic_link_per_hour,
+            )?,
+            password_reset_per_hour: parse_var(
+                "RATE_LIMIT_PASSWORD_RESET_PER_HOUR",
+                defaults.password_reset_per_hour,
+            )?,
+            verification_email_per_hour: parse_var(
+                "RATE_LIMIT_VERIFICATION_EMAIL_PER_HOUR",
+                defaults.verification_email_per_hour,
+            )?,
+            invitations_per_hour: parse_var(
+                "RATE_LIMIT_INVITATIONS_PER_HOUR",
+                defaults.invitations_per_hour,
+            )?,
+            lockout_threshold: parse_var("LOGIN_LOCKOUT_THRESHOLD", defaults.lockout_threshold)?,
+            lockout_secs: parse_var("LOGIN_LOCKOUT_SECS", defaults.lockout_secs)?,
+            lockout_max_secs: parse_var("LOGIN_LOCKOUT_MAX_SECS", defaults.lockout_max_secs)?,
+        })
+    }
+}
+
+pub(crate) fn parse_var<T: std::str::FromStr>(name: &str, default: T) -> Result<T, String> {
+    match std::env::var(name) {
+        Ok(value) => value.parse().map_err(|_| format!("Invalid {} value", name)),
+        Err(_) => Ok(default),
+    }
+}
+
+pub fn init() -> AdHoc {
+    AdHoc::on_ignite("Rate Limit Configuration", |rocket| async {
+        match RateLimitConfig::from_env() {
+            Ok(config) => rocket.manage(config),
+            Err(e) => {
+                panic!("Failed to initialize rate limit configuration: {}", e);
+            }
+        }
+    })
+}
@@ -1,17 +1,58 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use rocket::fairing::AdHoc;
 
+use crate::config::account_deletion::AccountDeletionConfig;
+use crate::config::admin::AdminConfig;
 use crate::config::database::MongoDB;
+use crate::config::export::ExportConfig;
+use crate::config::display_name::DisplayNameConfig;
+use crate::config::invite_code::InviteCodeConfig;
 use crate::config::jwt::JwtConfig;
+use crate::config::mail::MailConfig;
+use crate::config::oidc::OidcConfig;
+use crate::config::password::PasswordHashConfig;
+use crate::config::password_policy::PasswordPolicyConfig;
+use crate::config::rate_limit::{RateLimitBackend, RateLimitConfig};
 use crate::config::s3::S3Config;
-use crate::modules::auth::service::{AuthConfig, AuthService, AuthServiceImpl};
+use crate::modules::account_deletion::service::{
+    self as account_deletion, AccountDeletionService, AccountDeletionServiceImpl,
+};
+use crate::modules::admin::repository::AuditLogRepositoryImpl;
+use crate::modules::admin::service::{AdminService, AdminServiceImpl};
+use crate::modules::api_keys::repository::ApiKeyRepositoryImpl;
+use crate::modules::api_keys::service::{ApiKeyService, ApiKeyServiceImpl};
+use crate::modules::auth::keys::JwtKeys;
+use crate::modules::auth::oidc::{HttpOidcProvider, OidcProvider};
+use crate::modules::auth::repository::{
+    OidcStateRepositoryImpl, OneTimeTokenRepositoryImpl, RefreshTokenRepositoryImpl,
+    UserIdentityRepositoryImpl,
+};
+use crate::modules::auth::service::{
+    AuthConfig, AuthService, AuthServiceImpl, OidcService, OidcServiceImpl,
+};
+use crate::modules::exports::repository::ExportRepositoryImpl;
+use crate::modules::exports::service::{self as exports, ExportService, ExportServiceImpl};
+use crate::modules::exports::storage::S3ArchiveStorage;
 use crate::modules::images::repository::ImageRepositoryImpl;
 use crate::modules::images::service::{ImageService, ImageServiceConfig, ImageServiceImpl};
+use crate::modules::invitations::repository::InvitationRepositoryImpl;
+use crate::modules::invitations::service::{InvitationService, InvitationServiceImpl};
+use crate::modules::mail::service as mail;
 use crate::modules::opponents::repository::OpponentRepositoryImpl;
 use crate::modules::opponents::service::{OpponentService, OpponentServiceImpl};
+use crate::modules::rate_limit::repository::{
+    InMemoryRateLimitRepository, RateLimitRepository, RateLimitRepositoryImpl,
+};
+use crate::modules::rate_limit::service::{RateLimitService, RateLimitServiceImpl};
+use crate::modules::tournaments::display_name::DisplayNameFilter;
+use crate::modules::tournaments::invite_code::InviteCodeGenerator;
 use crate::modules::tournaments::repository::{InviteRepositoryImpl, TournamentRepositoryImpl};
 use crate::modules::tournaments::service::{TournamentService, TournamentServiceImpl};
+use crate::modules::users::hasher::Argon2Hasher;
+use crate::modules::users::password_policy::PasswordPolicy;
+use crate::modules::users::profile::{ProfileService, ProfileServiceImpl};
 use crate::modules::users::repository::UserRepositoryImpl;
 use crate::modules::users::service::{UserService, UserServiceImpl};
 use crate::modules::websocket::broadcaster::TournamentBroadcaster;
@@ -27,29 +68,127 @@ pub fn init() -> AdHoc {
         let s3_config = rocket
             .state::<S3Config>()
             .expect("S3Config must be initialized before services");
+        let mail_config = rocket
+            .state::<MailConfig>()
+            .expect("MailConfig must be initialized before services");
+        let oidc_config = rocket
+            .state::<OidcConfig>()
+            .expect("OidcConfig must be initialized before services");
+        let rate_limit_config = rocket
+            .state::<RateLimitConfig>()
+            .expect("RateLimitConfig must be initialized before services");
+        let admin_config = rocket
+            .state::<AdminConfig>()
+            .expect("AdminConfig must be initialized before services");
+        let password_config = rocket
+            .state::<PasswordHashConfig>()
+            .expect("PasswordHashConfig must be initialized before services");
+        let password_policy_config = rocket
+            .state::<PasswordPolicyConfig>()
+            .expect("PasswordPolicyConfig must be initialized before services");
+        let sweep_interval_secs = rocket
+            .state::<AccountDeletionConfig>()
+            .expect("AccountDeletionConfig must be initialized before services")
+            .sweep_interval_secs;
+        let invite_code_config = rocket
+            .state::<InviteCodeConfig>()
+            .expect("InviteCodeConfig must be initialized before services");
+        let display_name_config = rocket
+            .state::<DisplayNameConfig>()
+            .expect("DisplayNameConfig must be initialized before services");
+        let export_retention_secs = rocket
+            .state::<ExportConfig>()
+            .expect("ExportConfig must be initialized before services")
+            .retention_secs();
 
         let user_repo = Arc::new(UserRepositoryImpl::new(&mongodb.db));
         let tournament_repo = Arc::new(TournamentRepositoryImpl::new(&mongodb.db));
         let opponent_repo = Arc::new(OpponentRepositoryImpl::new(&mongodb.db));
         let image_repo = Arc::new(ImageRepositoryImpl::new(&mongodb.db));
+        let refresh_token_repo = Arc::new(RefreshTokenRepositoryImpl::new(&mongodb.db));
+        let one_time_token_repo = Arc::new(OneTimeTokenRepositoryImpl::new(&mongodb.db));
+        let mailer = mail::from_config(mail_config)
+            .unwrap_or_else(|e| panic!("Failed to initialize mailer: {}", e));
 
-        let user_service: Arc<dyn UserService + Send + Sync> =
-            Arc::new(UserServiceImpl::new(user_repo));
+        let password_hasher = Arc::new(
+            Argon2Hasher::new(password_config)
+                .unwrap_or_else(|e| panic!("Failed to initialize password hasher: {}", e)),
+        );
+        let password_policy = Arc::new(
+            PasswordPolicy::new(password_policy_config)
+                .unwrap_or_else(|e| panic!("Failed to initialize password policy: {}", e)),
+        );
+        let user_service: Arc<dyn UserService + Send + Sync> = Arc::new(UserServiceImpl::new(
+            user_repo.clone(),
+            password_hasher,
+            password_policy,
+        ));
+        let jwt_keys = Arc::new(
+            JwtKeys::from_config(jwt_config)
+                .unwrap_or_else(|e| panic!("Failed to load JWT keys: {}", e)),
+        );
         let auth_service = Arc::new(AuthServiceImpl::new(
             user_service.clone(),
+            refresh_token_repo.clone(),
+            one_time_token_repo,
+            mailer.clone(),
             AuthConfig {
-                jwt_secret: jwt_config.secret.clone(),
+                keys: jwt_keys.clone(),
+                app_url: mail_config.app_url.clone(),
             },
         ));
+        let oidc_providers: HashMap<String, Arc<dyn OidcProvider>> = oidc_config
+            .providers
+            .iter()
+            .map(|provider| {
+                let client: Arc<dyn OidcProvider> =
+                    Arc::new(HttpOidcProvider::new(provider.clone()));
+                (provider.name.clone(), client)
+            })
+            .collect();
+        let identity_repo = Arc::new(UserIdentityRepositoryImpl::new(&mongodb.db));
+        let oidc_service = Arc::new(OidcServiceImpl::new(
+            oidc_providers,
+            Arc::new(OidcStateRepositoryImpl::new(&mongodb.db)),
+            identity_repo.clone(),
+            user_service.clone(),
+            auth_service.clone() as Arc<dyn AuthService + Send + Sync>,
+        ));
+        let api_key_repo = Arc::new(ApiKeyRepositoryImpl::new(&mongodb.db));
+        let api_key_service = Arc::new(ApiKeyServiceImpl::new(
+            api_key_repo.clone(),
+            user_service.clone(),
+        ));
         let invite_repo = Arc::new(InviteRepositoryImpl::new(&mongodb.db));
         let broadcaster = Arc::new(TournamentBroadcaster::new());
+        let display_names = DisplayNameFilter::new(display_name_config)
+            .unwrap_or_else(|e| panic!("Failed to initialize display name filter: {}", e));
         let tournament_service = Arc::new(TournamentServiceImpl::new(
-            tournament_repo,
+            tournament_repo.clone(),
             invite_repo,
             auth_service.clone() as Arc<dyn AuthService + Send + Sync>,
             Arc::clone(&broadcaster),
+            InviteCodeGenerator::new(invite_code_config),
+            display_names,
+            mail_config.app_url.clone(),
+        ));
+        let invitation_service = Arc::new(InvitationServiceImpl::new(
+            Arc::new(InvitationRepositoryImpl::new(&mongodb.db)),
+            tournament_repo.clone(),
+            user_repo.clone(),
+            mailer,
+            Arc::clone(&broadcaster),
+            mail_config.app_url.clone(),
+        ));
+        let opponent_service = Arc::new(OpponentServiceImpl::new(opponent_repo.clone()));
+        let rate_limit_repo: Arc<dyn RateLimitRepository> = match rate_limit_config.backend {
+            RateLimitBackend::Memory => Arc::new(InMemoryRateLimitRepository::new()),
+            RateLimitBackend::Mongo => Arc::new(RateLimitRepositoryImpl::new(&mongodb.db)),
+        };
+        let rate_limit_service = Arc::new(RateLimitServiceImpl::new(
+            rate_limit_repo,
+            rate_limit_config.clone(),
         ));
-        let opponent_service = Arc::new(OpponentServiceImpl::new(opponent_repo));
         let image_service = Arc::new(ImageServiceImpl::new(
             image_repo,
             ImageServiceConfig {
@@ -60,13 +199,67 @@ pub fn init() -> AdHoc {
                 endpoint_url: s3_config.endpoint_url.clone(),
             },
         ));
+        let profile_service = Arc::new(ProfileServiceImpl::new(
+            user_repo.clone(),
+            tournament_repo.clone(),
+            opponent_repo.clone(),
+            image_service.clone() as Arc<dyn ImageService + Send + Sync>,
+        ));
+        let export_service: Arc<dyn ExportService + Send + Sync> =
+            Arc::new(ExportServiceImpl::new(
+                Arc::new(ExportRepositoryImpl::new(&mongodb.db)),
+                Arc::new(S3ArchiveStorage::new(s3_config)),
+                user_repo.clone(),
+                tournament_repo,
+                opponent_repo.clone(),
+                image_service.clone() as Arc<dyn ImageService + Send + Sync>,
+                export_retention_secs,
+            ));
+        let account_deletion_service: Arc<dyn AccountDeletionService + Send + Sync> =
+            Arc::new(AccountDeletionServiceImpl::new(
+                user_repo.clone(),
+                tournament_service.clone() as Arc<dyn TournamentService + Send + Sync>,
+                opponent_repo,
+                image_service.clone() as Arc<dyn ImageService + Send + Sync>,
+                refresh_token_repo,
+                api_key_repo,
+                identity_repo,
+            ));
+        let admin_service = Arc::new(AdminServiceImpl::new(
+            user_repo,
+            Arc::new(AuditLogRepositoryImpl::new(&mongodb.db)),
+            auth_service.clone() as Arc<dyn AuthService + Send + Sync>,
+            tournament_service.clone() as Arc<dyn TournamentService + Send + Sync>,
+            account_deletion_service.clone(),
+        ));
+        if let Err(e) = admin_service
+            .promote_admins(&admin_config.bootstrap_emails)
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to promote ADMIN_EMAILS accounts");
+        }
 
         rocket
             .manage(user_service)
+            .manage(profile_service as Arc<dyn ProfileService + Send + Sync>)
             .manage(auth_service as Arc<dyn AuthService + Send + Sync>)
+            .manage(oidc_service as Arc<dyn OidcService + Send + Sync>)
+            .manage(admin_service as Arc<dyn AdminService + Send + Sync>)
+            .manage(api_key_service as Arc<dyn ApiKeyService + Send + Sync>)
             .manage(tournament_service as Arc<dyn TournamentService + Send + Sync>)
+            .manage(invitation_service as Arc<dyn InvitationService + Send + Sync>)
             .manage(opponent_service as Arc<dyn OpponentService + Send + Sync>)
             .manage(image_service as Arc<dyn ImageService + Send + Sync>)
+            .manage(rate_limit_service as Arc<dyn RateLimitService + Send + Sync>)
+            .attach(account_deletion::sweeper(
+                account_deletion_service.clone(),
+                sweep_interval_secs,
+            ))
+            .manage(account_deletion_service)
+            .attach(exports::recovery(export_service.clone()))
+            .attach(exports::sweeper(export_service.clone()))
+            .manage(export_service)
+            .manage(jwt_keys)
             .attach(TournamentBroadcaster::cleanup_fairing(Arc::clone(&broadcaster)))
             .manage(broadcaster)
     })
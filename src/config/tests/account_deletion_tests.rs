@@ -0,0 +1,57 @@
+use crate::config::account_deletion::AccountDeletionConfig;
+use serial_test::serial;
+use std::env;
+
+fn setup() {
+    env::remove_var("ACCOUNT_DELETION_GRACE_DAYS");
+    env::remove_var("ACCOUNT_DELETION_SWEEP_SECS");
+}
+
+#[test]
+#[serial]
+fn test_account_deletion_config_defaults() {
+    // Arrange
+    setup();
+
+    // Act
+    let result = AccountDeletionConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config, AccountDeletionConfig::default());
+    assert_eq!(config.grace_period_secs(), 14 * 86400);
+}
+
+#[test]
+#[serial]
+fn test_account_deletion_config_from_env() {
+    // Arrange
+    setup();
+    env::set_var("ACCOUNT_DELETION_GRACE_DAYS", "0");
+    env::set_var("ACCOUNT_DELETION_SWEEP_SECS", "60");
+
+    // Act
+    let result = AccountDeletionConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config.grace_period_days, 0);
+    assert_eq!(config.sweep_interval_secs, 60);
+}
+
+#[test]
+#[serial]
+fn test_account_deletion_config_rejects_zero_interval() {
+    // Arrange
+    setup();
+    env::set_var("ACCOUNT_DELETION_SWEEP_SECS", "0");
+
+    // Act
+    let result = AccountDeletionConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "ACCOUNT_DELETION_SWEEP_SECS must be at least 1"
+    );
+}
@@ -0,0 +1,51 @@
+use crate::config::admin::AdminConfig;
+use serial_test::serial;
+use std::env;
+
+fn setup() {
+    env::remove_var("ADMIN_EMAILS");
+}
+
+#[test]
+#[serial]
+fn test_admin_config_defaults_to_no_admins() {
+    // Arrange
+    setup();
+
+    // Act
+    let result = AdminConfig::from_env();
+
+    // Assert
+    assert!(result.unwrap().bootstrap_emails.is_empty());
+}
+
+#[test]
+#[serial]
+fn test_admin_config_parses_emails() {
+    // Arrange
+    setup();
+    env::set_var("ADMIN_EMAILS", " ada@example.com, ,grace@example.com ");
+
+    // Act
+    let result = AdminConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap().bootstrap_emails,
+        vec!["ada@example.com", "grace@example.com"]
+    );
+}
+
+#[test]
+#[serial]
+fn test_admin_config_rejects_invalid_email() {
+    // Arrange
+    setup();
+    env::set_var("ADMIN_EMAILS", "ada");
+
+    // Act
+    let result = AdminConfig::from_env();
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invalid ADMIN_EMAILS entry: ada");
+}
@@ -0,0 +1,57 @@
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+
+use crate::config::client_info::{self, ClientInfo};
+
+#[get("/test")]
+fn test_route(client: ClientInfo) -> String {
+    format!(
+        "{}|{}",
+        client.user_agent.unwrap_or_default(),
+        client.ip_address.unwrap_or_default()
+    )
+}
+
+async fn create_client() -> Client {
+    let rocket = rocket::build()
+        .attach(client_info::init())
+        .mount("/", routes![test_route]);
+    Client::tracked(rocket).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_captures_user_agent() {
+    let client = create_client().await;
+    let response = client
+        .get("/test")
+        .header(Header::new("User-Agent", "TestBrowser/1.0"))
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Ok);
+    let body = response.into_string().await.unwrap();
+    assert!(body.starts_with("TestBrowser/1.0|"));
+}
+
+#[tokio::test]
+async fn test_captures_forwarded_ip() {
+    let client = create_client().await;
+    let response = client
+        .get("/test")
+        .header(Header::new("X-Real-IP", "203.0.113.7"))
+        .dispatch()
+        .await;
+
+    let body = response.into_string().await.unwrap();
+    assert!(body.ends_with("|203.0.113.7"));
+}
+
+#[tokio::test]
+async fn test_missing_user_agent_is_empty() {
+    let client = create_client().await;
+    let response = client.get("/test").dispatch().await;
+
+    assert_eq!(response.status(), Status::Ok);
+    let body = response.into_string().await.unwrap();
+    assert!(body.starts_with('|'));
+}
@@ -0,0 +1,63 @@
+use crate::config::display_name::DisplayNameConfig;
+use serial_test::serial;
+use std::env;
+
+fn setup() {
+    env::remove_var("DISPLAY_NAME_MAX_LENGTH");
+    env::remove_var("DISPLAY_NAME_BLOCKLIST_FILE");
+    env::remove_var("DISPLAY_NAME_BLOCKED_TERMS");
+}
+
+#[test]
+#[serial]
+fn test_display_name_config_defaults() {
+    // Arrange
+    setup();
+
+    // Act
+    let result = DisplayNameConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config, DisplayNameConfig::default());
+    assert_eq!(config.max_length, 32);
+    assert!(config.blocklist_file.is_none());
+    assert!(config.blocked_terms.is_empty());
+}
+
+#[test]
+#[serial]
+fn test_display_name_config_from_env() {
+    // Arrange
+    setup();
+    env::set_var("DISPLAY_NAME_MAX_LENGTH", "48");
+    env::set_var("DISPLAY_NAME_BLOCKLIST_FILE", "/etc/torvi/blocklist.txt");
+    env::set_var("DISPLAY_NAME_BLOCKED_TERMS", " quiz master, ,host ");
+
+    // Act
+    let result = DisplayNameConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config.max_length, 48);
+    assert_eq!(
+        config.blocklist_file.as_deref(),
+        Some("/etc/torvi/blocklist.txt")
+    );
+    assert_eq!(config.blocked_terms, vec!["quiz master", "host"]);
+}
+
+#[test]
+#[serial]
+fn test_display_name_config_max_length_out_of_range() {
+    // Arrange
+    setup();
+    env::set_var("DISPLAY_NAME_MAX_LENGTH", "4");
+
+    // Act
+    let result = DisplayNameConfig::from_env();
+
+    // Assert
+    assert!(result.unwrap_err().contains("DISPLAY_NAME_MAX_LENGTH"));
+    setup();
+}
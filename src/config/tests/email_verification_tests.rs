@@ -0,0 +1,69 @@
+use crate::config::email_verification::EmailVerificationConfig;
+use serial_test::serial;
+use std::env;
+
+fn setup() {
+    env::remove_var("REQUIRE_VERIFIED_EMAIL");
+}
+
+#[test]
+#[serial]
+fn test_email_verification_config_defaults_to_not_required() {
+    // Arrange
+    setup();
+
+    // Act
+    let result = EmailVerificationConfig::from_env();
+
+    // Assert
+    assert_eq!(result.unwrap(), EmailVerificationConfig::default());
+}
+
+#[test]
+#[serial]
+fn test_email_verification_config_parses_actions() {
+    // Arrange
+    setup();
+    env::set_var("REQUIRE_VERIFIED_EMAIL", "tournaments, invites");
+
+    // Act
+    let result = EmailVerificationConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert!(config.required_for_tournaments);
+    assert!(config.required_for_invites);
+}
+
+#[test]
+#[serial]
+fn test_email_verification_config_single_action() {
+    // Arrange
+    setup();
+    env::set_var("REQUIRE_VERIFIED_EMAIL", "invites");
+
+    // Act
+    let result = EmailVerificationConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert!(!config.required_for_tournaments);
+    assert!(config.required_for_invites);
+}
+
+#[test]
+#[serial]
+fn test_email_verification_config_unknown_action() {
+    // Arrange
+    setup();
+    env::set_var("REQUIRE_VERIFIED_EMAIL", "tournaments,votes");
+
+    // Act
+    let result = EmailVerificationConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Unknown REQUIRE_VERIFIED_EMAIL value: votes"
+    );
+}
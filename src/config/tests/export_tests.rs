@@ -0,0 +1,53 @@
+use crate::config::export::ExportConfig;
+use serial_test::serial;
+use std::env;
+
+fn setup() {
+    env::remove_var("EXPORT_RETENTION_HOURS");
+}
+
+#[test]
+#[serial]
+fn test_export_config_defaults() {
+    // Arrange
+    setup();
+
+    // Act
+    let result = ExportConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config, ExportConfig::default());
+    assert_eq!(config.retention_secs(), 24 * 3600);
+}
+
+#[test]
+#[serial]
+fn test_export_config_from_env() {
+    // Arrange
+    setup();
+    env::set_var("EXPORT_RETENTION_HOURS", "72");
+
+    // Act
+    let result = ExportConfig::from_env();
+
+    // Assert
+    assert_eq!(result.unwrap().retention_hours, 72);
+}
+
+#[test]
+#[serial]
+fn test_export_config_rejects_zero_retention() {
+    // Arrange
+    setup();
+    env::set_var("EXPORT_RETENTION_HOURS", "0");
+
+    // Act
+    let result = ExportConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "EXPORT_RETENTION_HOURS must be at least 1"
+    );
+}
@@ -0,0 +1,133 @@
+use crate::config::invite_code::{InviteCodeConfig, InviteCodeFormat, DEFAULT_ALPHABET};
+use serial_test::serial;
+use std::env;
+
+fn setup() {
+    env::remove_var("INVITE_CODE_FORMAT");
+    env::remove_var("INVITE_CODE_ALPHABET");
+    env::remove_var("INVITE_CODE_LENGTH");
+    env::remove_var("INVITE_CODE_WORDS");
+}
+
+#[test]
+#[serial]
+fn test_invite_code_config_defaults() {
+    // Arrange
+    setup();
+
+    // Act
+    let result = InviteCodeConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config, InviteCodeConfig::default());
+    assert_eq!(config.format, InviteCodeFormat::Random);
+    assert_eq!(config.alphabet, DEFAULT_ALPHABET);
+    assert_eq!(config.length, 8);
+}
+
+#[test]
+#[serial]
+fn test_invite_code_config_from_env() {
+    // Arrange
+    setup();
+    env::set_var("INVITE_CODE_FORMAT", "words");
+    env::set_var("INVITE_CODE_ALPHABET", "abcdefghjkmnpqrstuvwxyz");
+    env::set_var("INVITE_CODE_LENGTH", "10");
+    env::set_var("INVITE_CODE_WORDS", "4");
+
+    // Act
+    let result = InviteCodeConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config.format, InviteCodeFormat::Words);
+    assert_eq!(config.alphabet, "abcdefghjkmnpqrstuvwxyz");
+    assert_eq!(config.length, 10);
+    assert_eq!(config.word_count, 4);
+}
+
+#[test]
+#[serial]
+fn test_invite_code_config_unknown_format() {
+    // Arrange
+    setup();
+    env::set_var("INVITE_CODE_FORMAT", "emoji");
+
+    // Act
+    let result = InviteCodeConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Unknown INVITE_CODE_FORMAT value: emoji"
+    );
+}
+
+#[test]
+#[serial]
+fn test_invite_code_config_rejects_symbols() {
+    // Arrange
+    setup();
+    env::set_var("INVITE_CODE_ALPHABET", "ABCDEFGHJK/?");
+
+    // Act
+    let result = InviteCodeConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "INVITE_CODE_ALPHABET may only contain letters and digits"
+    );
+}
+
+#[test]
+#[serial]
+fn test_invite_code_config_rejects_repeated_characters() {
+    // Arrange
+    setup();
+    env::set_var("INVITE_CODE_ALPHABET", "ABCDEFGHJKA");
+
+    // Act
+    let result = InviteCodeConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "INVITE_CODE_ALPHABET must not repeat characters"
+    );
+}
+
+#[test]
+#[serial]
+fn test_invite_code_config_rejects_small_alphabet() {
+    // Arrange
+    setup();
+    env::set_var("INVITE_CODE_ALPHABET", "ABC");
+
+    // Act
+    let result = InviteCodeConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "INVITE_CODE_ALPHABET needs at least 10 characters"
+    );
+}
+
+#[test]
+#[serial]
+fn test_invite_code_config_rejects_short_codes() {
+    // Arrange
+    setup();
+    env::set_var("INVITE_CODE_LENGTH", "4");
+
+    // Act
+    let result = InviteCodeConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "INVITE_CODE_LENGTH must be between 6 and 32"
+    );
+}
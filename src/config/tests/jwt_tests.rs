@@ -4,6 +4,8 @@ use std::env;
 
 fn setup() {
     env::remove_var("JWT_SECRET");
+    env::remove_var("JWT_KEYS_DIR");
+    env::remove_var("JWT_ACTIVE_KEY_ID");
 }
 
 #[test]
@@ -19,7 +21,7 @@ fn test_jwt_config_success() {
     // Assert
     assert!(result.is_ok());
     let config = result.unwrap();
-    assert_eq!(config.secret, "test-secret-key");
+    assert_eq!(config.secret.as_deref(), Some("test-secret-key"));
 }
 
 #[test]
@@ -35,7 +37,7 @@ fn test_jwt_config_missing_secret() {
     assert!(result.is_err());
     assert_eq!(
         result.unwrap_err(),
-        "Missing JWT_SECRET environment variable"
+        "Missing JWT_SECRET or JWT_KEYS_DIR environment variable"
     );
 }
 
@@ -52,7 +54,7 @@ fn test_jwt_config_empty_secret() {
     // Assert
     assert!(result.is_ok());
     let config = result.unwrap();
-    assert_eq!(config.secret, "");
+    assert_eq!(config.secret.as_deref(), Some(""));
 }
 
 #[test]
@@ -68,7 +70,7 @@ fn test_jwt_config_whitespace_secret() {
     // Assert
     assert!(result.is_ok());
     let config = result.unwrap();
-    assert_eq!(config.secret, "  test-secret-key  ");
+    assert_eq!(config.secret.as_deref(), Some("  test-secret-key  "));
 }
 
 #[test]
@@ -84,7 +86,25 @@ fn test_jwt_config_special_characters() {
     // Assert
     assert!(result.is_ok());
     let config = result.unwrap();
-    assert_eq!(config.secret, "test@secret#key$123");
+    assert_eq!(config.secret.as_deref(), Some("test@secret#key$123"));
+}
+
+#[test]
+#[serial]
+fn test_jwt_config_keys_dir_without_secret() {
+    // Arrange
+    setup();
+    env::set_var("JWT_KEYS_DIR", "/etc/torvi/jwt");
+    env::set_var("JWT_ACTIVE_KEY_ID", "2024-06");
+
+    // Act
+    let result = JwtConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config.secret, None);
+    assert_eq!(config.keys_dir.as_deref(), Some("/etc/torvi/jwt"));
+    assert_eq!(config.active_key_id.as_deref(), Some("2024-06"));
 }
 
 #[cfg(test)]
@@ -114,7 +134,7 @@ mod rocket_tests {
             .rocket()
             .state::<JwtConfig>()
             .expect("JWT config should be managed");
-        assert_eq!(config.secret, "test-secret-key");
+        assert_eq!(config.secret.as_deref(), Some("test-secret-key"));
     }
 
     #[tokio::test]
@@ -144,7 +164,7 @@ fn test_jwt_config_unicode_characters() {
     // Assert
     assert!(result.is_ok());
     let config = result.unwrap();
-    assert_eq!(config.secret, "测试密钥🔑");
+    assert_eq!(config.secret.as_deref(), Some("测试密钥🔑"));
 }
 
 #[test]
@@ -161,7 +181,7 @@ fn test_jwt_config_long_secret() {
     // Assert
     assert!(result.is_ok());
     let config = result.unwrap();
-    assert_eq!(config.secret, long_secret);
+    assert_eq!(config.secret.as_deref(), Some(long_secret.as_str()));
 }
 
 #[test]
@@ -177,5 +197,5 @@ fn test_jwt_config_newline_characters() {
     // Assert
     assert!(result.is_ok());
     let config = result.unwrap();
-    assert_eq!(config.secret, "test\nsecret\nkey");
+    assert_eq!(config.secret.as_deref(), Some("test\nsecret\nkey"));
 }
@@ -0,0 +1,160 @@
+use crate::config::mail::{MailConfig, MailTransport};
+use serial_test::serial;
+use std::env;
+
+fn setup() {
+    env::remove_var("MAIL_TRANSPORT");
+    env::remove_var("MAIL_FROM");
+    env::remove_var("MAIL_DIR");
+    env::remove_var("APP_URL");
+    env::remove_var("SMTP_HOST");
+    env::remove_var("SMTP_PORT");
+    env::remove_var("SMTP_USERNAME");
+    env::remove_var("SMTP_PASSWORD");
+    env::remove_var("ROCKET_PROFILE");
+}
+
+#[test]
+#[serial]
+fn test_mail_config_defaults_to_log() {
+    // Arrange
+    setup();
+
+    // Act
+    let result = MailConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config.transport, MailTransport::Log);
+    assert_eq!(config.app_url, "http://localhost:3000");
+}
+
+#[test]
+#[serial]
+fn test_mail_config_falls_back_to_log_in_release() {
+    // Arrange
+    setup();
+    env::set_var("ROCKET_PROFILE", "release");
+
+    // Act
+    let result = MailConfig::from_env();
+
+    // Assert
+    assert_eq!(result.unwrap().transport, MailTransport::Log);
+    env::remove_var("ROCKET_PROFILE");
+}
+
+#[test]
+#[serial]
+fn test_mail_config_explicit_log_allowed_in_release() {
+    // Arrange
+    setup();
+    env::set_var("ROCKET_PROFILE", "release");
+    env::set_var("MAIL_TRANSPORT", "log");
+
+    // Act
+    let result = MailConfig::from_env();
+
+    // Assert
+    assert_eq!(result.unwrap().transport, MailTransport::Log);
+    env::remove_var("ROCKET_PROFILE");
+}
+
+#[test]
+#[serial]
+fn test_mail_config_smtp() {
+    // Arrange
+    setup();
+    env::set_var("MAIL_TRANSPORT", "smtp");
+    env::set_var("SMTP_HOST", "smtp.example.com");
+    env::set_var("SMTP_PORT", "2525");
+    env::set_var("SMTP_USERNAME", "mailer");
+    env::set_var("SMTP_PASSWORD", "secret");
+    env::set_var("MAIL_FROM", "Torvi <hello@example.com>");
+
+    // Act
+    let result = MailConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(
+        config.transport,
+        MailTransport::Smtp {
+            host: "smtp.example.com".to_string(),
+            port: 2525,
+            username: Some("mailer".to_string()),
+            password: Some("secret".to_string()),
+        }
+    );
+    assert_eq!(config.from, "Torvi <hello@example.com>");
+}
+
+#[test]
+#[serial]
+fn test_mail_config_smtp_missing_host() {
+    // Arrange
+    setup();
+    env::set_var("MAIL_TRANSPORT", "smtp");
+
+    // Act
+    let result = MailConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Missing SMTP_HOST environment variable"
+    );
+}
+
+#[test]
+#[serial]
+fn test_mail_config_smtp_invalid_port() {
+    // Arrange
+    setup();
+    env::set_var("MAIL_TRANSPORT", "smtp");
+    env::set_var("SMTP_HOST", "smtp.example.com");
+    env::set_var("SMTP_PORT", "not-a-port");
+
+    // Act
+    let result = MailConfig::from_env();
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invalid SMTP_PORT value");
+}
+
+#[test]
+#[serial]
+fn test_mail_config_file_transport() {
+    // Arrange
+    setup();
+    env::set_var("MAIL_TRANSPORT", "file");
+    env::set_var("MAIL_DIR", "/tmp/torvi-mail");
+    env::set_var("APP_URL", "https://torvi.example.com/");
+
+    // Act
+    let result = MailConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(
+        config.transport,
+        MailTransport::File {
+            dir: "/tmp/torvi-mail".to_string()
+        }
+    );
+    assert_eq!(config.app_url, "https://torvi.example.com");
+}
+
+#[test]
+#[serial]
+fn test_mail_config_unknown_transport() {
+    // Arrange
+    setup();
+    env::set_var("MAIL_TRANSPORT", "pigeon");
+
+    // Act
+    let result = MailConfig::from_env();
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Unknown MAIL_TRANSPORT: pigeon");
+}
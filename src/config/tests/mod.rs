@@ -1,7 +1,19 @@
+mod account_deletion_tests;
+mod admin_tests;
+mod client_info_tests;
 mod cors_tests;
 mod database_tests;
+mod display_name_tests;
+mod email_verification_tests;
+mod export_tests;
+mod invite_code_tests;
 mod jwt_tests;
 mod logging_tests;
+mod mail_tests;
+mod oidc_tests;
+mod password_policy_tests;
+mod password_tests;
+mod rate_limit_tests;
 mod request_id_tests;
 mod s3_tests;
 mod security_tests;
@@ -0,0 +1,123 @@
+use crate::config::oidc::OidcConfig;
+use serial_test::serial;
+use std::env;
+
+fn setup() {
+    env::remove_var("OIDC_PROVIDERS");
+    env::remove_var("APP_URL");
+    for provider in ["COMPANY", "MOCK_IDP"] {
+        for key in [
+            "ISSUER",
+            "CLIENT_ID",
+            "CLIENT_SECRET",
+            "SCOPES",
+            "REDIRECT_URL",
+        ] {
+            env::remove_var(format!("OIDC_{}_{}", provider, key));
+        }
+    }
+}
+
+#[test]
+#[serial]
+fn test_oidc_config_defaults_to_no_providers() {
+    // Arrange
+    setup();
+
+    // Act
+    let result = OidcConfig::from_env();
+
+    // Assert
+    assert!(result.unwrap().providers.is_empty());
+}
+
+#[test]
+#[serial]
+fn test_oidc_config_reads_provider() {
+    // Arrange
+    setup();
+    env::set_var("OIDC_PROVIDERS", "company");
+    env::set_var("OIDC_COMPANY_ISSUER", "https://sso.example.com");
+    env::set_var("OIDC_COMPANY_CLIENT_ID", "torvi");
+    env::set_var("OIDC_COMPANY_CLIENT_SECRET", "secret");
+    env::set_var("OIDC_COMPANY_SCOPES", "email profile");
+    env::set_var("APP_URL", "https://torvi.example.com/");
+
+    // Act
+    let result = OidcConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config.providers.len(), 1);
+    let provider = &config.providers[0];
+    assert_eq!(provider.name, "company");
+    assert_eq!(provider.issuer, "https://sso.example.com");
+    assert_eq!(provider.client_id, "torvi");
+    assert_eq!(provider.client_secret.as_deref(), Some("secret"));
+    assert_eq!(provider.scopes, vec!["openid", "email", "profile"]);
+    assert_eq!(
+        provider.redirect_url,
+        "https://torvi.example.com/auth/oidc/company/callback"
+    );
+}
+
+#[test]
+#[serial]
+fn test_oidc_config_public_client_with_custom_redirect() {
+    // Arrange
+    setup();
+    env::set_var("OIDC_PROVIDERS", "mock-idp");
+    env::set_var("OIDC_MOCK_IDP_ISSUER", "http://localhost:8080");
+    env::set_var("OIDC_MOCK_IDP_CLIENT_ID", "torvi-dev");
+    env::set_var(
+        "OIDC_MOCK_IDP_REDIRECT_URL",
+        "http://localhost:5173/login/callback",
+    );
+
+    // Act
+    let result = OidcConfig::from_env();
+
+    // Assert
+    let provider = &result.unwrap().providers[0];
+    assert_eq!(provider.client_secret, None);
+    assert_eq!(provider.scopes, vec!["openid", "email", "profile"]);
+    assert_eq!(
+        provider.redirect_url,
+        "http://localhost:5173/login/callback"
+    );
+}
+
+#[test]
+#[serial]
+fn test_oidc_config_missing_issuer() {
+    // Arrange
+    setup();
+    env::set_var("OIDC_PROVIDERS", "company");
+    env::set_var("OIDC_COMPANY_CLIENT_ID", "torvi");
+
+    // Act
+    let result = OidcConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Missing OIDC_COMPANY_ISSUER environment variable"
+    );
+}
+
+#[test]
+#[serial]
+fn test_oidc_config_invalid_provider_name() {
+    // Arrange
+    setup();
+    env::set_var("OIDC_PROVIDERS", "Company SSO");
+
+    // Act
+    let result = OidcConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Invalid OIDC provider name: Company SSO"
+    );
+}
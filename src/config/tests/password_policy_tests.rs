@@ -0,0 +1,82 @@
+use crate::config::password_policy::PasswordPolicyConfig;
+use serial_test::serial;
+use std::env;
+
+fn setup() {
+    env::remove_var("PASSWORD_MIN_LENGTH");
+    env::remove_var("PASSWORD_MIN_CHARACTER_CLASSES");
+    env::remove_var("PASSWORD_BREACHED_LIST_FILE");
+}
+
+#[test]
+#[serial]
+fn test_password_policy_config_defaults() {
+    // Arrange
+    setup();
+
+    // Act
+    let result = PasswordPolicyConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config, PasswordPolicyConfig::default());
+    assert_eq!(config.min_length, 10);
+    assert_eq!(config.min_character_classes, 3);
+    assert_eq!(config.breached_list_file, None);
+}
+
+#[test]
+#[serial]
+fn test_password_policy_config_from_env() {
+    // Arrange
+    setup();
+    env::set_var("PASSWORD_MIN_LENGTH", "14");
+    env::set_var("PASSWORD_MIN_CHARACTER_CLASSES", "2");
+    env::set_var("PASSWORD_BREACHED_LIST_FILE", "/data/pwned-passwords.txt");
+
+    // Act
+    let result = PasswordPolicyConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config.min_length, 14);
+    assert_eq!(config.min_character_classes, 2);
+    assert_eq!(
+        config.breached_list_file.as_deref(),
+        Some("/data/pwned-passwords.txt")
+    );
+}
+
+#[test]
+#[serial]
+fn test_password_policy_config_too_many_classes() {
+    // Arrange
+    setup();
+    env::set_var("PASSWORD_MIN_CHARACTER_CLASSES", "5");
+
+    // Act
+    let result = PasswordPolicyConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "PASSWORD_MIN_CHARACTER_CLASSES must be between 0 and 4"
+    );
+}
+
+#[test]
+#[serial]
+fn test_password_policy_config_zero_length() {
+    // Arrange
+    setup();
+    env::set_var("PASSWORD_MIN_LENGTH", "0");
+
+    // Act
+    let result = PasswordPolicyConfig::from_env();
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "PASSWORD_MIN_LENGTH must be at least 1"
+    );
+}
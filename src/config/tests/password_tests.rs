@@ -0,0 +1,73 @@
+use crate::config::password::PasswordHashConfig;
+use serial_test::serial;
+use std::env;
+
+fn setup() {
+    env::remove_var("ARGON2_MEMORY_KIB");
+    env::remove_var("ARGON2_ITERATIONS");
+    env::remove_var("ARGON2_PARALLELISM");
+}
+
+#[test]
+#[serial]
+fn test_password_hash_config_defaults() {
+    // Arrange
+    setup();
+
+    // Act
+    let result = PasswordHashConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config, PasswordHashConfig::default());
+    assert_eq!(config.memory_kib, 19456);
+    assert_eq!(config.iterations, 2);
+    assert_eq!(config.parallelism, 1);
+}
+
+#[test]
+#[serial]
+fn test_password_hash_config_from_env() {
+    // Arrange
+    setup();
+    env::set_var("ARGON2_MEMORY_KIB", "65536");
+    env::set_var("ARGON2_ITERATIONS", "3");
+    env::set_var("ARGON2_PARALLELISM", "4");
+
+    // Act
+    let result = PasswordHashConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config.memory_kib, 65536);
+    assert_eq!(config.iterations, 3);
+    assert_eq!(config.parallelism, 4);
+}
+
+#[test]
+#[serial]
+fn test_password_hash_config_invalid_number() {
+    // Arrange
+    setup();
+    env::set_var("ARGON2_ITERATIONS", "many");
+
+    // Act
+    let result = PasswordHashConfig::from_env();
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invalid ARGON2_ITERATIONS value");
+}
+
+#[test]
+#[serial]
+fn test_password_hash_config_rejects_weak_params() {
+    // Arrange
+    setup();
+    env::set_var("ARGON2_ITERATIONS", "0");
+
+    // Act
+    let result = PasswordHashConfig::from_env();
+
+    // Assert
+    assert!(result.unwrap_err().starts_with("Invalid Argon2 parameters"));
+}
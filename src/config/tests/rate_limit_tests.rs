@@ -0,0 +1,93 @@
+use crate::config::rate_limit::{RateLimitBackend, RateLimitConfig};
+use serial_test::serial;
+use std::env;
+
+fn setup() {
+    env::remove_var("RATE_LIMIT_BACKEND");
+    env::remove_var("RATE_LIMIT_LOGIN_PER_MINUTE");
+    env::remove_var("RATE_LIMIT_ANONYMOUS_PER_MINUTE");
+    env::remove_var("RATE_LIMIT_USER_SEARCH_PER_MINUTE");
+    env::remove_var("RATE_LIMIT_MAGIC_LINK_PER_HOUR");
+    env::remove_var("RATE_LIMIT_PASSWORD_RESET_PER_HOUR");
+    env::remove_var("RATE_LIMIT_VERIFICATION_EMAIL_PER_HOUR");
+    env::remove_var("RATE_LIMIT_INVITATIONS_PER_HOUR");
+    env::remove_var("LOGIN_LOCKOUT_THRESHOLD");
+    env::remove_var("LOGIN_LOCKOUT_SECS");
+    env::remove_var("LOGIN_LOCKOUT_MAX_SECS");
+}
+
+#[test]
+#[serial]
+fn test_rate_limit_config_defaults() {
+    // Arrange
+    setup();
+
+    // Act
+    let result = RateLimitConfig::from_env();
+
+    // Assert
+    assert_eq!(result.unwrap(), RateLimitConfig::default());
+}
+
+#[test]
+#[serial]
+fn test_rate_limit_config_from_env() {
+    // Arrange
+    setup();
+    env::set_var("RATE_LIMIT_BACKEND", "mongo");
+    env::set_var("RATE_LIMIT_LOGIN_PER_MINUTE", "3");
+    env::set_var("RATE_LIMIT_ANONYMOUS_PER_MINUTE", "7");
+    env::set_var("RATE_LIMIT_USER_SEARCH_PER_MINUTE", "12");
+    env::set_var("RATE_LIMIT_MAGIC_LINK_PER_HOUR", "4");
+    env::set_var("RATE_LIMIT_PASSWORD_RESET_PER_HOUR", "3");
+    env::set_var("RATE_LIMIT_VERIFICATION_EMAIL_PER_HOUR", "2");
+    env::set_var("RATE_LIMIT_INVITATIONS_PER_HOUR", "6");
+    env::set_var("LOGIN_LOCKOUT_THRESHOLD", "2");
+    env::set_var("LOGIN_LOCKOUT_SECS", "10");
+    env::set_var("LOGIN_LOCKOUT_MAX_SECS", "60");
+
+    // Act
+    let result = RateLimitConfig::from_env();
+
+    // Assert
+    let config = result.unwrap();
+    assert_eq!(config.backend, RateLimitBackend::Mongo);
+    assert_eq!(config.login_per_minute, 3);
+    assert_eq!(config.anonymous_per_minute, 7);
+    assert_eq!(config.user_search_per_minute, 12);
+    assert_eq!(config.magic_link_per_hour, 4);
+    assert_eq!(config.password_reset_per_hour, 3);
+    assert_eq!(config.verification_email_per_hour, 2);
+    assert_eq!(config.invitations_per_hour, 6);
+    assert_eq!(config.lockout_threshold, 2);
+    assert_eq!(config.lockout_secs, 10);
+    assert_eq!(config.lockout_max_secs, 60);
+}
+
+#[test]
+#[serial]
+fn test_rate_limit_config_unknown_backend() {
+    // Arrange
+    setup();
+    env::set_var("RATE_LIMIT_BACKEND", "redis");
+
+    // Act
+    let result = RateLimitConfig::from_env();
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Unknown RATE_LIMIT_BACKEND: redis");
+}
+
+#[test]
+#[serial]
+fn test_rate_limit_config_invalid_number() {
+    // Arrange
+    setup();
+    env::set_var("LOGIN_LOCKOUT_SECS", "soon");
+
+    // Act
+    let result = RateLimitConfig::from_env();
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invalid LOGIN_LOCKOUT_SECS value");
+}
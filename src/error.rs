@@ -5,10 +5,16 @@ use rocket::Request;
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::modules::tournaments::model::MembershipError;
+use crate::modules::users::password_policy::PasswordPolicyError;
+
 #[derive(Serialize)]
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
+    /// Machine-readable specifics, such as each failed password rule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
 }
 
 #[derive(Error, Debug)]
@@ -25,9 +31,17 @@ pub enum Error {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    /// Carries the number of seconds to send in `Retry-After`
+    #[error("Too many requests, retry in {0} seconds")]
+    TooManyRequests(u64),
+
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    /// Lists every password rule that failed in the response `details`
+    #[error("Validation error: {0}")]
+    PasswordPolicy(PasswordPolicyError),
+
     #[error("Database error: {0}")]
     DatabaseError(String),
 
@@ -38,10 +52,13 @@ pub enum Error {
 impl Error {
     fn status(&self) -> Status {
         match self {
-            Error::BadRequest(_) | Error::ValidationError(_) => Status::BadRequest,
+            Error::BadRequest(_) | Error::ValidationError(_) | Error::PasswordPolicy(_) => {
+                Status::BadRequest
+            }
             Error::Unauthorized(_) => Status::Unauthorized,
             Error::Forbidden(_) => Status::Forbidden,
             Error::NotFound(_) => Status::NotFound,
+            Error::TooManyRequests(_) => Status::TooManyRequests,
             Error::DatabaseError(_) | Error::Internal(_) => Status::InternalServerError,
         }
     }
@@ -50,11 +67,41 @@ impl Error {
 impl<'r, 'o: 'r> Responder<'r, 'o> for Error {
     fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
         let status = self.status();
+        let retry_after = match self {
+            Error::TooManyRequests(secs) => Some(secs),
+            _ => None,
+        };
+        let details = match &self {
+            Error::PasswordPolicy(e) => Some(serde_json::json!({ "violations": e.violations })),
+            _ => None,
+        };
         let body = ErrorResponse {
             error: status.reason_lossy().to_string(),
             message: self.to_string(),
+            details,
         };
-        (status, Json(body)).respond_to(req)
+        let mut response = (status, Json(body)).respond_to(req)?;
+        if let Some(secs) = retry_after {
+            response.set_raw_header("Retry-After", secs.to_string());
+        }
+        Ok(response)
+    }
+}
+
+impl From<PasswordPolicyError> for Error {
+    fn from(e: PasswordPolicyError) -> Self {
+        Error::PasswordPolicy(e)
+    }
+}
+
+impl From<MembershipError> for Error {
+    fn from(e: MembershipError) -> Self {
+        match e {
+            MembershipError::NotFound(message) => Error::NotFound(message),
+            MembershipError::Forbidden(message) => Error::Forbidden(message),
+            MembershipError::Invalid(message) => Error::BadRequest(message),
+            MembershipError::Internal(message) => Error::Internal(message),
+        }
     }
 }
 
@@ -67,6 +114,7 @@ impl From<mongodb::error::Error> for Error {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modules::users::password_policy::{PasswordRule, PasswordViolation};
 
     #[test]
     fn test_bad_request_status() {
@@ -92,12 +140,46 @@ mod tests {
         assert_eq!(error.status(), Status::NotFound);
     }
 
+    #[test]
+    fn test_too_many_requests_status() {
+        let error = Error::TooManyRequests(30);
+        assert_eq!(error.status(), Status::TooManyRequests);
+        assert_eq!(error.to_string(), "Too many requests, retry in 30 seconds");
+    }
+
     #[test]
     fn test_validation_error_status() {
         let error = Error::ValidationError("invalid field".to_string());
         assert_eq!(error.status(), Status::BadRequest);
     }
 
+    #[test]
+    fn test_password_policy_status() {
+        let error = Error::PasswordPolicy(PasswordPolicyError {
+            violations: vec![PasswordViolation {
+                rule: PasswordRule::MinLength,
+                message: "Password must be at least 10 characters".to_string(),
+            }],
+        });
+        assert_eq!(error.status(), Status::BadRequest);
+        assert_eq!(
+            error.to_string(),
+            "Validation error: Password must be at least 10 characters"
+        );
+    }
+
+    #[test]
+    fn test_membership_error_statuses() {
+        let status = |e: MembershipError| Error::from(e).status();
+        assert_eq!(status(MembershipError::NotFound("gone".into())), Status::NotFound);
+        assert_eq!(status(MembershipError::Forbidden("no".into())), Status::Forbidden);
+        assert_eq!(status(MembershipError::Invalid("bad".into())), Status::BadRequest);
+        assert_eq!(
+            status(MembershipError::Internal("failed".into())),
+            Status::InternalServerError
+        );
+    }
+
     #[test]
     fn test_database_error_status() {
         let error = Error::DatabaseError("connection failed".to_string());
@@ -121,6 +203,7 @@ mod tests {
         let response = ErrorResponse {
             error: "Bad Request".to_string(),
             message: "invalid input".to_string(),
+            details: None,
         };
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("Bad Request"));
@@ -16,20 +16,40 @@ fn rocket() -> _ {
 
     rocket::build()
         .attach(config::request_id::init())
+        .attach(config::client_info::init())
         .attach(config::security::init())
         .attach(config::cors::init())
         .attach(config::database::init())
         .attach(config::s3::init())
         .attach(config::jwt::init())
+        .attach(config::mail::init())
+        .attach(config::email_verification::init())
+        .attach(config::oidc::init())
+        .attach(config::rate_limit::init())
+        .attach(config::admin::init())
+        .attach(config::account_deletion::init())
+        .attach(config::export::init())
+        .attach(config::invite_code::init())
+        .attach(config::display_name::init())
+        .attach(config::password::init())
+        .attach(config::password_policy::init())
         .attach(config::indices::init())
         .attach(config::services::init())
         .mount("/health", modules::health::routes())
         .mount("/api/opponents", modules::opponents::routes())
         .mount("/api/tournaments", modules::tournaments::routes())
+        .mount("/api/tournaments", modules::invitations::tournament_routes())
+        .mount("/api/invitations", modules::invitations::routes())
         .mount("/api/users", modules::users::routes())
+        .mount("/api/users/me/api-keys", modules::api_keys::routes())
+        .mount("/api/users/me/deletion", modules::account_deletion::routes())
+        .mount("/api/users/me/export", modules::exports::routes())
         .mount("/api/images", modules::images::routes())
         .mount("/api/auth", modules::auth::routes())
+        .mount("/api/admin", modules::admin::routes())
+        .mount("/.well-known", modules::auth::well_known_routes())
         .mount("/ws", modules::websocket::routes())
         .mount("/", rocket::fs::FileServer::from("web/landing/out").rank(10))
         .mount("/", spa::routes())
+        .register("/", modules::rate_limit::catchers())
 }
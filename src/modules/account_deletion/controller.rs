@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::common::guards::AuthenticatedUser;
+use crate::error::Error;
+use crate::modules::account_deletion::model::AccountDeletionResponse;
+use crate::modules::account_deletion::service::AccountDeletionService;
+
+pub(crate) fn deletion_error(e: String) -> Error {
+    if e == "User not found" || e == "Account is not scheduled for deletion" {
+        Error::NotFound(e)
+    } else if e == "Account deletion is already in progress" {
+        Error::BadRequest(e)
+    } else {
+        Error::Internal(e)
+    }
+}
+
+#[get("/")]
+pub async fn status(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn AccountDeletionService + Send + Sync>>,
+) -> Result<Json<AccountDeletionResponse>, Error> {
+    let deletion = service
+        .status(&auth.user_id)
+        .await
+        .map_err(deletion_error)?
+        .ok_or_else(|| Error::NotFound("Account is not scheduled for deletion".to_string()))?;
+
+    Ok(Json(AccountDeletionResponse::from(deletion)))
+}
+
+#[delete("/")]
+pub async fn cancel(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn AccountDeletionService + Send + Sync>>,
+) -> Result<Json<serde_json::Value>, Error> {
+    service
+        .cancel(&auth.user_id)
+        .await
+        .map_err(deletion_error)?;
+
+    Ok(Json(
+        serde_json::json!({ "message": "Account deletion cancelled" }),
+    ))
+}
+
+pub fn routes() -> Vec<rocket::Route> {
+    routes![status, cancel]
+}
@@ -0,0 +1,8 @@
+pub mod controller;
+pub mod model;
+pub mod service;
+
+pub use controller::routes;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,130 @@
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::common::json::serialize_datetime;
+
+/// Wait before retrying a failed deletion, doubled after every failure
+const RETRY_DELAY_SECS: i64 = 60;
+const MAX_RETRY_DELAY_SECS: i64 = 24 * 60 * 60;
+
+/// What happens to the tournaments a deleted account owns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OwnedTournamentPolicy {
+    /// Hand each tournament to its highest-ranking organizer. Tournaments
+    /// without another organizer are deleted.
+    #[default]
+    Transfer,
+    Delete,
+}
+
+impl std::str::FromStr for OwnedTournamentPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "transfer" => Ok(Self::Transfer),
+            "delete" => Ok(Self::Delete),
+            _ => Err(format!("Unknown tournament policy: {}", value)),
+        }
+    }
+}
+
+/// Parts of an account removed once the grace period is over. Each step can
+/// be run again safely, so a failed deletion resumes where it stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletionStep {
+    /// Owned tournaments are transferred or deleted, memberships dropped
+    Tournaments,
+    /// Participation in other tournaments is kept under an anonymous name
+    Votes,
+    /// Opponents and uploaded images no remaining tournament uses
+    Content,
+    /// Sessions, API keys and linked identity provider accounts
+    Credentials,
+}
+
+impl DeletionStep {
+    pub const ALL: [DeletionStep; 4] = [
+        DeletionStep::Tournaments,
+        DeletionStep::Votes,
+        DeletionStep::Content,
+        DeletionStep::Credentials,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeletionStep::Tournaments => "tournaments",
+            DeletionStep::Votes => "votes",
+            DeletionStep::Content => "content",
+            DeletionStep::Credentials => "credentials",
+        }
+    }
+}
+
+/// Pending deletion, stored on the user until the account is gone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountDeletion {
+    pub requested_at: DateTime,
+    /// End of the grace period; the deletion can be undone until then.
+    /// Moved back after every failed attempt.
+    pub scheduled_for: DateTime,
+    pub tournament_policy: OwnedTournamentPolicy,
+    #[serde(default)]
+    pub completed_steps: Vec<DeletionStep>,
+    #[serde(default)]
+    pub attempts: u32,
+    pub last_error: Option<String>,
+}
+
+impl AccountDeletion {
+    pub fn new(tournament_policy: OwnedTournamentPolicy, grace_period_secs: i64) -> Self {
+        let now = DateTime::now();
+        Self {
+            requested_at: now,
+            scheduled_for: DateTime::from_millis(now.timestamp_millis() + grace_period_secs * 1000),
+            tournament_policy,
+            completed_steps: Vec::new(),
+            attempts: 0,
+            last_error: None,
+        }
+    }
+
+    /// Once started, a deletion can no longer be undone.
+    pub fn is_started(&self) -> bool {
+        self.attempts > 0
+    }
+
+    /// Postpones the next attempt after a failure, so accounts that keep
+    /// failing do not take up every sweep.
+    pub fn back_off(&mut self) {
+        let exponent = self.attempts.saturating_sub(1).min(20);
+        let delay = (RETRY_DELAY_SECS << exponent).min(MAX_RETRY_DELAY_SECS);
+        self.scheduled_for =
+            DateTime::from_millis(DateTime::now().timestamp_millis() + delay * 1000);
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountDeletionResponse {
+    #[serde(serialize_with = "serialize_datetime")]
+    pub requested_at: DateTime,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub scheduled_for: DateTime,
+    pub tournament_policy: OwnedTournamentPolicy,
+    pub in_progress: bool,
+    pub completed_steps: Vec<DeletionStep>,
+}
+
+impl From<AccountDeletion> for AccountDeletionResponse {
+    fn from(deletion: AccountDeletion) -> Self {
+        Self {
+            requested_at: deletion.requested_at,
+            scheduled_for: deletion.scheduled_for,
+            tournament_policy: deletion.tournament_policy,
+            in_progress: deletion.is_started(),
+            completed_steps: deletion.completed_steps,
+        }
+    }
+}
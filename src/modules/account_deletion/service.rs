@@ -0,0 +1,282 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use rocket::fairing::AdHoc;
+use tokio::time::interval;
+
+use crate::modules::account_deletion::model::{
+    AccountDeletion, DeletionStep, OwnedTournamentPolicy,
+};
+use crate::modules::api_keys::repository::ApiKeyRepository;
+use crate::modules::auth::repository::{RefreshTokenRepository, UserIdentityRepository};
+use crate::modules::images::service::ImageService;
+use crate::modules::opponents::repository::OpponentRepository;
+use crate::modules::tournaments::service::TournamentService;
+use crate::modules::users::model::User;
+use crate::modules::users::repository::UserRepository;
+
+/// Accounts deleted per sweep; the rest wait for the next one
+const SWEEP_BATCH_SIZE: i64 = 50;
+const OPPONENT_PAGE_SIZE: i64 = 100;
+
+#[async_trait]
+pub trait AccountDeletionService: Send + Sync {
+    /// Schedules the account for deletion once the grace period is over.
+    /// Asking again only changes the tournament policy.
+    async fn schedule(
+        &self,
+        user_id: &ObjectId,
+        policy: OwnedTournamentPolicy,
+        grace_period_secs: i64,
+    ) -> Result<AccountDeletion, String>;
+    async fn status(&self, user_id: &ObjectId) -> Result<Option<AccountDeletion>, String>;
+    /// Undoes a scheduled deletion that has not started yet.
+    async fn cancel(&self, user_id: &ObjectId) -> Result<(), String>;
+    /// Deletes the account, skipping the steps an earlier attempt completed.
+    /// Succeeds if the account is already gone.
+    async fn run(&self, user_id: &ObjectId) -> Result<(), String>;
+    /// Deletes the accounts whose grace period is over and returns how many
+    /// were deleted. Failed deletions are retried on the next call.
+    async fn process_due(&self) -> Result<usize, String>;
+}
+
+pub struct AccountDeletionServiceImpl {
+    user_repository: Arc<dyn UserRepository>,
+    tournament_service: Arc<dyn TournamentService + Send + Sync>,
+    opponent_repository: Arc<dyn OpponentRepository>,
+    image_service: Arc<dyn ImageService + Send + Sync>,
+    refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+    api_key_repository: Arc<dyn ApiKeyRepository>,
+    identity_repository: Arc<dyn UserIdentityRepository>,
+}
+
+impl AccountDeletionServiceImpl {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        tournament_service: Arc<dyn TournamentService + Send + Sync>,
+        opponent_repository: Arc<dyn OpponentRepository>,
+        image_service: Arc<dyn ImageService + Send + Sync>,
+        refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+        api_key_repository: Arc<dyn ApiKeyRepository>,
+        identity_repository: Arc<dyn UserIdentityRepository>,
+    ) -> Self {
+        Self {
+            user_repository,
+            tournament_service,
+            opponent_repository,
+            image_service,
+            refresh_token_repository,
+            api_key_repository,
+            identity_repository,
+        }
+    }
+
+    async fn find_user(&self, user_id: &ObjectId) -> Result<User, String> {
+        self.user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| "User not found".to_string())
+    }
+
+    async fn save(&self, user: &mut User, deletion: Option<AccountDeletion>) -> Result<(), String> {
+        user.deletion = deletion;
+        user.updated_at = DateTime::now();
+        self.user_repository.update(user).await
+    }
+
+    async fn run_step(
+        &self,
+        step: DeletionStep,
+        user_id: &ObjectId,
+        policy: OwnedTournamentPolicy,
+    ) -> Result<(), String> {
+        match step {
+            DeletionStep::Tournaments => {
+                self.tournament_service
+                    .release_tournaments(user_id, policy)
+                    .await
+            }
+            DeletionStep::Votes => self.tournament_service.anonymize_participant(user_id).await,
+            DeletionStep::Content => self.delete_content(user_id).await,
+            DeletionStep::Credentials => {
+                self.refresh_token_repository
+                    .revoke_all_for_user(user_id)
+                    .await?;
+                self.api_key_repository.delete_all_for_user(user_id).await?;
+                self.identity_repository.delete_for_user(user_id).await
+            }
+        }
+    }
+
+    /// Deletes the user's opponents and images, except those a remaining
+    /// tournament still shows.
+    async fn delete_content(&self, user_id: &ObjectId) -> Result<(), String> {
+        let mut opponents = Vec::new();
+        let mut cursor = None;
+        loop {
+            let mut page = self
+                .opponent_repository
+                .find_by_creator(user_id, cursor, OPPONENT_PAGE_SIZE)
+                .await?;
+            let has_more = page.len() as i64 > OPPONENT_PAGE_SIZE;
+            page.truncate(OPPONENT_PAGE_SIZE as usize);
+            cursor = page.last().and_then(|o| o.id);
+            opponents.extend(page);
+            if !has_more {
+                break;
+            }
+        }
+
+        let opponent_ids: Vec<ObjectId> = opponents.iter().filter_map(|o| o.id).collect();
+        let in_use = self
+            .tournament_service
+            .opponents_in_use(&opponent_ids)
+            .await?;
+
+        let mut kept_images = HashSet::new();
+        for opponent in &opponents {
+            let Some(id) = opponent.id else { continue };
+            if in_use.contains(&id) {
+                kept_images.insert(opponent.image.image_id);
+            } else {
+                self.opponent_repository.delete(&id).await?;
+            }
+        }
+
+        for image in self.image_service.find_by_creator(user_id).await? {
+            match image.id {
+                Some(id) if !kept_images.contains(&id) => {
+                    self.image_service.delete_image(&id, user_id).await?
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AccountDeletionService for AccountDeletionServiceImpl {
+    async fn schedule(
+        &self,
+        user_id: &ObjectId,
+        policy: OwnedTournamentPolicy,
+        grace_period_secs: i64,
+    ) -> Result<AccountDeletion, String> {
+        let mut user = self.find_user(user_id).await?;
+        let deletion = match user.deletion.clone() {
+            Some(deletion) if deletion.is_started() => {
+                return Err("Account deletion is already in progress".to_string());
+            }
+            Some(deletion) => AccountDeletion {
+                tournament_policy: policy,
+                ..deletion
+            },
+            None => AccountDeletion::new(policy, grace_period_secs),
+        };
+
+        self.save(&mut user, Some(deletion.clone())).await?;
+        Ok(deletion)
+    }
+
+    async fn status(&self, user_id: &ObjectId) -> Result<Option<AccountDeletion>, String> {
+        Ok(self.find_user(user_id).await?.deletion)
+    }
+
+    async fn cancel(&self, user_id: &ObjectId) -> Result<(), String> {
+        let mut user = self.find_user(user_id).await?;
+        match &user.deletion {
+            None => Err("Account is not scheduled for deletion".to_string()),
+            Some(deletion) if deletion.is_started() => {
+                Err("Account deletion is already in progress".to_string())
+            }
+            Some(_) => self.save(&mut user, None).await,
+        }
+    }
+
+    async fn run(&self, user_id: &ObjectId) -> Result<(), String> {
+        let Some(mut user) = self.user_repository.find_by_id(user_id).await? else {
+            return Ok(());
+        };
+        let mut deletion = user
+            .deletion
+            .clone()
+            .ok_or_else(|| "Account is not scheduled for deletion".to_string())?;
+
+        // Counting the attempt first also rules out undoing it from now on
+        deletion.attempts += 1;
+        deletion.last_error = None;
+        self.save(&mut user, Some(deletion.clone())).await?;
+
+        for step in DeletionStep::ALL {
+            if deletion.completed_steps.contains(&step) {
+                continue;
+            }
+
+            if let Err(e) = self
+                .run_step(step, user_id, deletion.tournament_policy)
+                .await
+            {
+                let message = format!("Account deletion failed at {}: {}", step.as_str(), e);
+                deletion.last_error = Some(message.clone());
+                deletion.back_off();
+                if let Err(e) = self.save(&mut user, Some(deletion)).await {
+                    tracing::warn!(error = %e, "Failed to record account deletion error");
+                }
+                return Err(message);
+            }
+
+            deletion.completed_steps.push(step);
+            self.save(&mut user, Some(deletion.clone())).await?;
+        }
+
+        match self.user_repository.delete(user_id).await {
+            Err(e) if e != "User not found" => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    async fn process_due(&self) -> Result<usize, String> {
+        let due = self
+            .user_repository
+            .find_due_for_deletion(DateTime::now(), SWEEP_BATCH_SIZE)
+            .await?;
+
+        let mut deleted = 0;
+        for user_id in due.iter().filter_map(|u| u.id) {
+            match self.run(&user_id).await {
+                Ok(()) => {
+                    tracing::info!(user_id = %user_id, "Deleted account");
+                    deleted += 1;
+                }
+                Err(e) => tracing::warn!(user_id = %user_id, error = %e, "Account deletion failed"),
+            }
+        }
+
+        Ok(deleted)
+    }
+}
+
+/// Periodically deletes the accounts whose grace period is over.
+pub fn sweeper(
+    service: Arc<dyn AccountDeletionService + Send + Sync>,
+    interval_secs: u64,
+) -> AdHoc {
+    AdHoc::on_liftoff("Account Deletion Sweeper", move |_| {
+        Box::pin(async move {
+            tokio::spawn(async move {
+                let mut tick = interval(Duration::from_secs(interval_secs));
+                loop {
+                    tick.tick().await;
+                    if let Err(e) = service.process_due().await {
+                        tracing::warn!(error = %e, "Failed to look for accounts to delete");
+                    }
+                }
+            });
+        })
+    })
+}
@@ -0,0 +1,2 @@
+mod model_tests;
+mod service_tests;
@@ -0,0 +1,72 @@
+use mongodb::bson::DateTime;
+
+use crate::modules::account_deletion::model::{
+    AccountDeletion, AccountDeletionResponse, DeletionStep, OwnedTournamentPolicy,
+};
+
+#[test]
+fn test_policy_parses_query_values() {
+    // Act
+    let transfer = "transfer".parse::<OwnedTournamentPolicy>();
+    let delete = "delete".parse::<OwnedTournamentPolicy>();
+    let unknown = "archive".parse::<OwnedTournamentPolicy>();
+
+    // Assert
+    assert_eq!(transfer.unwrap(), OwnedTournamentPolicy::Transfer);
+    assert_eq!(delete.unwrap(), OwnedTournamentPolicy::Delete);
+    assert_eq!(unknown.unwrap_err(), "Unknown tournament policy: archive");
+}
+
+#[test]
+fn test_new_deletion_is_scheduled_after_grace_period() {
+    // Act
+    let deletion = AccountDeletion::new(OwnedTournamentPolicy::Delete, 86400);
+
+    // Assert
+    let delay =
+        deletion.scheduled_for.timestamp_millis() - deletion.requested_at.timestamp_millis();
+    assert_eq!(delay, 86_400_000);
+    assert!(!deletion.is_started());
+    assert!(deletion.completed_steps.is_empty());
+}
+
+#[test]
+fn test_response_reports_progress() {
+    // Arrange
+    let mut deletion = AccountDeletion::new(OwnedTournamentPolicy::Transfer, 0);
+    deletion.attempts = 1;
+    deletion.completed_steps = vec![DeletionStep::Tournaments];
+
+    // Act
+    let json = serde_json::to_value(AccountDeletionResponse::from(deletion)).unwrap();
+
+    // Assert
+    assert_eq!(json["tournament_policy"], "transfer");
+    assert_eq!(json["in_progress"], true);
+    assert_eq!(json["completed_steps"], serde_json::json!(["tournaments"]));
+}
+
+#[test]
+fn test_back_off_doubles_delay_up_to_a_day() {
+    // Arrange
+    let mut deletion = AccountDeletion::new(OwnedTournamentPolicy::Transfer, 0);
+    let delay = |deletion: &AccountDeletion| {
+        (deletion.scheduled_for.timestamp_millis() - DateTime::now().timestamp_millis()) / 1000
+    };
+
+    // Act
+    deletion.attempts = 1;
+    deletion.back_off();
+    let first = delay(&deletion);
+    deletion.attempts = 3;
+    deletion.back_off();
+    let third = delay(&deletion);
+    deletion.attempts = 40;
+    deletion.back_off();
+    let capped = delay(&deletion);
+
+    // Assert
+    assert!((59..=60).contains(&first));
+    assert!((239..=240).contains(&third));
+    assert!((86_399..=86_400).contains(&capped));
+}
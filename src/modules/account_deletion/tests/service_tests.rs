@@ -0,0 +1,623 @@
+use async_trait::async_trait;
+use mockall::mock;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use std::sync::{Arc, Mutex};
+
+use crate::common::pagination::{PaginatedResponse, PaginationParams};
+use crate::modules::account_deletion::model::{
+    AccountDeletion, DeletionStep, OwnedTournamentPolicy,
+};
+use crate::modules::account_deletion::service::{
+    AccountDeletionService, AccountDeletionServiceImpl,
+};
+use crate::modules::api_keys::model::ApiKey;
+use crate::modules::api_keys::repository::ApiKeyRepository;
+use crate::modules::auth::model::{RefreshToken, UserIdentity};
+use crate::modules::auth::repository::{RefreshTokenRepository, UserIdentityRepository};
+use crate::modules::images::model::Image;
+use crate::modules::images::service::ImageService;
+use crate::modules::opponents::model::Opponent;
+use crate::modules::opponents::repository::OpponentRepository;
+use crate::modules::tournaments::model::{
+    CreateInviteDto, CreateTournamentDto, GrantMemberDto, InviteRedemptionResponse,
+    InviteResponse, InviteSummaryResponse, JoinTournamentDto, JoinTournamentResponse, Match,
+    MembershipError,
+    Tournament, TournamentMemberResponse, TournamentResponse, TournamentUserResponse,
+    UpdateParticipantDto, UpdateTournamentDto, VoteMatchDto, VoterId,
+};
+use crate::modules::tournaments::service::TournamentService;
+use crate::modules::users::model::{User, UserRole};
+use crate::modules::users::repository::UserRepository;
+
+mock! {
+    UserRepo {}
+
+    #[async_trait]
+    impl UserRepository for UserRepo {
+        async fn create(&self, user: &User) -> Result<(), String>;
+        async fn find_by_email(&self, email: &str) -> Result<Option<User>, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<User>, String>;
+        async fn update(&self, user: &User) -> Result<(), String>;
+        async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn remove_recovery_code(&self, id: &ObjectId, code_hash: &str) -> Result<bool, String>;
+        async fn search(&self, query: Option<String>, role: Option<UserRole>, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<User>, String>;
+        async fn find_due_for_deletion(&self, now: DateTime, limit: i64) -> Result<Vec<User>, String>;
+        async fn find_discoverable(&self, query: &str, exclude: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<User>, String>;
+    }
+}
+
+mock! {
+    Tournaments {}
+
+    #[async_trait]
+    impl TournamentService for Tournaments {
+        async fn create_tournament(&self, tournament_dto: CreateTournamentDto, created_by: ObjectId) -> Result<Tournament, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Tournament>, String>;
+        async fn find_by_creator(&self, user_id: &ObjectId, params: PaginationParams) -> Result<PaginatedResponse<TournamentResponse>, String>;
+        async fn update_tournament(&self, id: &ObjectId, dto: UpdateTournamentDto, user_id: &ObjectId) -> Result<Tournament, String>;
+        async fn delete_tournament(&self, id: &ObjectId, user_id: &ObjectId) -> Result<(), String>;
+        async fn force_complete_tournament(&self, id: &ObjectId) -> Result<Tournament, String>;
+        async fn force_delete_tournament(&self, id: &ObjectId) -> Result<(), String>;
+        async fn pause_tournament(&self, id: &ObjectId, user_id: &ObjectId) -> Result<Tournament, String>;
+        async fn resume_tournament(&self, id: &ObjectId, user_id: &ObjectId) -> Result<Tournament, String>;
+        async fn get_match_detail(&self, tournament_id: &ObjectId, match_id: &str) -> Result<Match, String>;
+        async fn vote_match(&self, vote_dto: VoteMatchDto, voter_id: VoterId) -> Result<Tournament, String>;
+        async fn create_invite(&self, tournament_id: &ObjectId, dto: CreateInviteDto, created_by: ObjectId) -> Result<InviteResponse, String>;
+        async fn list_invites(&self, tournament_id: &ObjectId, user_id: &ObjectId) -> Result<Vec<InviteSummaryResponse>, MembershipError>;
+        async fn revoke_invite(&self, tournament_id: &ObjectId, code: &str, user_id: &ObjectId) -> Result<(), MembershipError>;
+        async fn invite_joins(&self, tournament_id: &ObjectId, code: &str, user_id: &ObjectId) -> Result<Vec<InviteRedemptionResponse>, MembershipError>;
+        async fn invite_qr_code(&self, tournament_id: &ObjectId, code: &str, user_id: &ObjectId) -> Result<Vec<u8>, MembershipError>;
+        async fn join_tournament(&self, tournament_id: &ObjectId, dto: JoinTournamentDto) -> Result<JoinTournamentResponse, String>;
+        async fn join_by_code(&self, dto: JoinTournamentDto) -> Result<JoinTournamentResponse, String>;
+        async fn claim_anonymous_session(&self, tournament_id: &ObjectId, session_id: &str, user_id: ObjectId, name: String) -> Result<(), String>;
+        async fn update_participant(&self, tournament_id: &ObjectId, voter_id: &str, dto: UpdateParticipantDto, user_id: &ObjectId) -> Result<TournamentUserResponse, MembershipError>;
+        async fn kick_participant(&self, tournament_id: &ObjectId, voter_id: &str, user_id: &ObjectId) -> Result<(), MembershipError>;
+        async fn grant_member(&self, tournament_id: &ObjectId, dto: GrantMemberDto, granted_by: &ObjectId) -> Result<Vec<TournamentMemberResponse>, MembershipError>;
+        async fn revoke_member(&self, tournament_id: &ObjectId, member_id: &ObjectId, revoked_by: &ObjectId) -> Result<Vec<TournamentMemberResponse>, MembershipError>;
+        async fn transfer_ownership(&self, tournament_id: &ObjectId, new_owner: &ObjectId, user_id: &ObjectId) -> Result<Tournament, MembershipError>;
+        async fn release_tournaments(&self, user_id: &ObjectId, policy: OwnedTournamentPolicy) -> Result<(), String>;
+        async fn anonymize_participant(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn opponents_in_use(&self, opponent_ids: &[ObjectId]) -> Result<Vec<ObjectId>, String>;
+    }
+}
+
+mock! {
+    OpponentRepo {}
+
+    #[async_trait]
+    impl OpponentRepository for OpponentRepo {
+        async fn create(&self, opponent: &Opponent) -> Result<Opponent, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Opponent>, String>;
+        async fn find_by_creator(&self, user_id: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<Opponent>, String>;
+        async fn update(&self, opponent: &Opponent) -> Result<(), String>;
+        async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn find_ids_by_creator(&self, user_id: &ObjectId) -> Result<Vec<ObjectId>, String>;
+    }
+}
+
+mock! {
+    Images {}
+
+    #[async_trait]
+    impl ImageService for Images {
+        async fn upload_image(&self, file_data: Vec<u8>, filename: String, content_type: String, created_by: ObjectId) -> Result<Image, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Image>, String>;
+        async fn delete_image(&self, id: &ObjectId, user_id: &ObjectId) -> Result<(), String>;
+        async fn find_by_creator(&self, user_id: &ObjectId) -> Result<Vec<Image>, String>;
+        async fn upload_square_variants(&self, file_data: Vec<u8>, sizes: &[u32], created_by: ObjectId) -> Result<Vec<Image>, String>;
+    }
+}
+
+mock! {
+    RefreshTokenRepo {}
+
+    #[async_trait]
+    impl RefreshTokenRepository for RefreshTokenRepo {
+        async fn create(&self, token: &RefreshToken) -> Result<(), String>;
+        async fn find_by_jti(&self, jti: &str) -> Result<Option<RefreshToken>, String>;
+        async fn mark_rotated(&self, jti: &str) -> Result<bool, String>;
+        async fn revoke_family(&self, family_id: &str) -> Result<(), String>;
+        async fn revoke_all_for_user(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn find_active_by_user(&self, user_id: &ObjectId) -> Result<Vec<RefreshToken>, String>;
+        async fn revoke_family_for_user(&self, user_id: &ObjectId, family_id: &str) -> Result<bool, String>;
+    }
+}
+
+mock! {
+    ApiKeyRepo {}
+
+    #[async_trait]
+    impl ApiKeyRepository for ApiKeyRepo {
+        async fn create(&self, api_key: &ApiKey) -> Result<(), String>;
+        async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, String>;
+        async fn find_by_user(&self, user_id: &ObjectId) -> Result<Vec<ApiKey>, String>;
+        async fn find_for_user(&self, user_id: &ObjectId, id: &ObjectId) -> Result<Option<ApiKey>, String>;
+        async fn update(&self, api_key: &ApiKey) -> Result<(), String>;
+        async fn delete_for_user(&self, user_id: &ObjectId, id: &ObjectId) -> Result<bool, String>;
+        async fn delete_all_for_user(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn touch(&self, id: &ObjectId, used_at: DateTime) -> Result<(), String>;
+    }
+}
+
+mock! {
+    IdentityRepo {}
+
+    #[async_trait]
+    impl UserIdentityRepository for IdentityRepo {
+        async fn create(&self, identity: &UserIdentity) -> Result<(), String>;
+        async fn find_by_subject(&self, provider: &str, subject: &str) -> Result<Option<UserIdentity>, String>;
+        async fn delete_for_user(&self, user_id: &ObjectId) -> Result<(), String>;
+    }
+}
+
+struct Mocks {
+    users: MockUserRepo,
+    tournaments: MockTournaments,
+    opponents: MockOpponentRepo,
+    images: MockImages,
+    refresh_tokens: MockRefreshTokenRepo,
+    api_keys: MockApiKeyRepo,
+    identities: MockIdentityRepo,
+}
+
+impl Mocks {
+    fn new() -> Self {
+        Self {
+            users: MockUserRepo::new(),
+            tournaments: MockTournaments::new(),
+            opponents: MockOpponentRepo::new(),
+            images: MockImages::new(),
+            refresh_tokens: MockRefreshTokenRepo::new(),
+            api_keys: MockApiKeyRepo::new(),
+            identities: MockIdentityRepo::new(),
+        }
+    }
+
+    /// Expects every step to succeed and records the order they ran in.
+    fn expect_all_steps(&mut self, calls: &Arc<Mutex<Vec<&'static str>>>) {
+        let log = calls.clone();
+        self.tournaments
+            .expect_release_tournaments()
+            .returning(move |_, _| {
+                log.lock().unwrap().push("tournaments");
+                Ok(())
+            });
+        let log = calls.clone();
+        self.tournaments
+            .expect_anonymize_participant()
+            .returning(move |_| {
+                log.lock().unwrap().push("votes");
+                Ok(())
+            });
+        self.opponents
+            .expect_find_by_creator()
+            .returning(|_, _, _| Ok(vec![]));
+        self.tournaments
+            .expect_opponents_in_use()
+            .returning(|_| Ok(vec![]));
+        let log = calls.clone();
+        self.images.expect_find_by_creator().returning(move |_| {
+            log.lock().unwrap().push("content");
+            Ok(vec![])
+        });
+        let log = calls.clone();
+        self.refresh_tokens
+            .expect_revoke_all_for_user()
+            .returning(move |_| {
+                log.lock().unwrap().push("credentials");
+                Ok(())
+            });
+        self.api_keys
+            .expect_delete_all_for_user()
+            .returning(|_| Ok(()));
+        self.identities
+            .expect_delete_for_user()
+            .returning(|_| Ok(()));
+    }
+
+    fn service(self) -> AccountDeletionServiceImpl {
+        AccountDeletionServiceImpl::new(
+            Arc::new(self.users),
+            Arc::new(self.tournaments),
+            Arc::new(self.opponents),
+            Arc::new(self.images),
+            Arc::new(self.refresh_tokens),
+            Arc::new(self.api_keys),
+            Arc::new(self.identities),
+        )
+    }
+}
+
+fn create_test_user(deletion: Option<AccountDeletion>) -> User {
+    let mut user = User::new(
+        "leaving@example.com".to_string(),
+        "Leaving User".to_string(),
+        "hash".to_string(),
+    );
+    user.id = Some(ObjectId::new());
+    user.deletion = deletion;
+    user
+}
+
+/// Stores every saved version of the user.
+fn record_updates(users: &mut MockUserRepo) -> Arc<Mutex<Vec<User>>> {
+    let saved = Arc::new(Mutex::new(Vec::new()));
+    let captured = saved.clone();
+    users.expect_update().returning(move |user| {
+        captured.lock().unwrap().push(user.clone());
+        Ok(())
+    });
+    saved
+}
+
+fn create_test_opponent(user_id: ObjectId) -> Opponent {
+    let mut opponent = Opponent::new(
+        "Opponent".to_string(),
+        user_id,
+        ObjectId::new(),
+        "https://cdn.example.com/opponent.webp".to_string(),
+    )
+    .unwrap();
+    opponent.id = Some(ObjectId::new());
+    opponent
+}
+
+fn create_test_image(id: ObjectId, user_id: ObjectId) -> Image {
+    let mut image = Image::new(
+        "https://cdn.example.com/image.webp".to_string(),
+        "image/webp".to_string(),
+        1024,
+        "image.webp".to_string(),
+        user_id,
+    );
+    image.id = Some(id);
+    image
+}
+
+#[tokio::test]
+async fn test_schedule_sets_grace_period() {
+    // Arrange
+    let user = create_test_user(None);
+    let user_id = user.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    let saved = record_updates(&mut mocks.users);
+    let service = mocks.service();
+
+    // Act
+    let result = service
+        .schedule(&user_id, OwnedTournamentPolicy::Delete, 3600)
+        .await;
+
+    // Assert
+    let deletion = result.unwrap();
+    assert_eq!(deletion.tournament_policy, OwnedTournamentPolicy::Delete);
+    assert_eq!(
+        deletion.scheduled_for.timestamp_millis() - deletion.requested_at.timestamp_millis(),
+        3_600_000
+    );
+    assert_eq!(saved.lock().unwrap()[0].deletion, Some(deletion));
+}
+
+#[tokio::test]
+async fn test_schedule_again_keeps_date_and_changes_policy() {
+    // Arrange
+    let existing = AccountDeletion::new(OwnedTournamentPolicy::Transfer, 3600);
+    let user = create_test_user(Some(existing.clone()));
+    let user_id = user.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    record_updates(&mut mocks.users);
+    let service = mocks.service();
+
+    // Act
+    let result = service
+        .schedule(&user_id, OwnedTournamentPolicy::Delete, 60)
+        .await;
+
+    // Assert
+    let deletion = result.unwrap();
+    assert_eq!(deletion.scheduled_for, existing.scheduled_for);
+    assert_eq!(deletion.tournament_policy, OwnedTournamentPolicy::Delete);
+}
+
+#[tokio::test]
+async fn test_cancel_clears_pending_deletion() {
+    // Arrange
+    let user = create_test_user(Some(AccountDeletion::new(
+        OwnedTournamentPolicy::Transfer,
+        3600,
+    )));
+    let user_id = user.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    let saved = record_updates(&mut mocks.users);
+    let service = mocks.service();
+
+    // Act
+    let result = service.cancel(&user_id).await;
+
+    // Assert
+    assert!(result.is_ok());
+    assert_eq!(saved.lock().unwrap()[0].deletion, None);
+}
+
+#[tokio::test]
+async fn test_cancel_refuses_started_deletion() {
+    // Arrange
+    let mut deletion = AccountDeletion::new(OwnedTournamentPolicy::Transfer, 0);
+    deletion.attempts = 1;
+    let user = create_test_user(Some(deletion));
+    let user_id = user.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    mocks.users.expect_update().never();
+    let service = mocks.service();
+
+    // Act
+    let result = service.cancel(&user_id).await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Account deletion is already in progress"
+    );
+}
+
+#[tokio::test]
+async fn test_cancel_without_pending_deletion() {
+    // Arrange
+    let user = create_test_user(None);
+    let user_id = user.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    let service = mocks.service();
+
+    // Act
+    let result = service.cancel(&user_id).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Account is not scheduled for deletion");
+}
+
+#[tokio::test]
+async fn test_run_completes_every_step_then_deletes_user() {
+    // Arrange
+    let user = create_test_user(Some(AccountDeletion::new(
+        OwnedTournamentPolicy::Transfer,
+        0,
+    )));
+    let user_id = user.id.unwrap();
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    let saved = record_updates(&mut mocks.users);
+    mocks
+        .users
+        .expect_delete()
+        .with(mockall::predicate::eq(user_id))
+        .times(1)
+        .returning(|_| Ok(()));
+    mocks.expect_all_steps(&calls);
+    let service = mocks.service();
+
+    // Act
+    let result = service.run(&user_id).await;
+
+    // Assert
+    assert!(result.is_ok());
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec!["tournaments", "votes", "content", "credentials"]
+    );
+    let last = saved
+        .lock()
+        .unwrap()
+        .last()
+        .unwrap()
+        .deletion
+        .clone()
+        .unwrap();
+    assert_eq!(last.completed_steps, DeletionStep::ALL.to_vec());
+    assert_eq!(last.attempts, 1);
+}
+
+#[tokio::test]
+async fn test_run_resumes_after_completed_steps() {
+    // Arrange
+    let mut deletion = AccountDeletion::new(OwnedTournamentPolicy::Transfer, 0);
+    deletion.attempts = 1;
+    deletion.completed_steps = vec![DeletionStep::Tournaments, DeletionStep::Votes];
+    let user = create_test_user(Some(deletion));
+    let user_id = user.id.unwrap();
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    record_updates(&mut mocks.users);
+    mocks.users.expect_delete().times(1).returning(|_| Ok(()));
+    mocks.expect_all_steps(&calls);
+    let service = mocks.service();
+
+    // Act
+    let result = service.run(&user_id).await;
+
+    // Assert
+    assert!(result.is_ok());
+    assert_eq!(*calls.lock().unwrap(), vec!["content", "credentials"]);
+}
+
+#[tokio::test]
+async fn test_run_failure_records_error_and_keeps_user() {
+    // Arrange
+    let user = create_test_user(Some(AccountDeletion::new(OwnedTournamentPolicy::Delete, 0)));
+    let user_id = user.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    let saved = record_updates(&mut mocks.users);
+    mocks.users.expect_delete().never();
+    mocks
+        .tournaments
+        .expect_release_tournaments()
+        .with(
+            mockall::predicate::eq(user_id),
+            mockall::predicate::eq(OwnedTournamentPolicy::Delete),
+        )
+        .returning(|_, _| Ok(()));
+    mocks
+        .tournaments
+        .expect_anonymize_participant()
+        .returning(|_| Err("Error updating tournament: timeout".to_string()));
+    mocks.images.expect_find_by_creator().never();
+    let service = mocks.service();
+
+    // Act
+    let result = service.run(&user_id).await;
+
+    // Assert
+    let message = "Account deletion failed at votes: Error updating tournament: timeout";
+    assert_eq!(result.unwrap_err(), message);
+    let last = saved
+        .lock()
+        .unwrap()
+        .last()
+        .unwrap()
+        .deletion
+        .clone()
+        .unwrap();
+    assert_eq!(last.completed_steps, vec![DeletionStep::Tournaments]);
+    assert_eq!(last.last_error.as_deref(), Some(message));
+    assert!(last.scheduled_for > DateTime::now());
+}
+
+#[tokio::test]
+async fn test_run_for_deleted_account_succeeds() {
+    // Arrange
+    let mut mocks = Mocks::new();
+    mocks.users.expect_find_by_id().returning(|_| Ok(None));
+    mocks.users.expect_delete().never();
+    let service = mocks.service();
+
+    // Act
+    let result = service.run(&ObjectId::new()).await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_run_keeps_content_still_used_by_tournaments() {
+    // Arrange
+    let mut deletion = AccountDeletion::new(OwnedTournamentPolicy::Transfer, 0);
+    deletion.completed_steps = vec![DeletionStep::Tournaments, DeletionStep::Votes];
+    let user = create_test_user(Some(deletion));
+    let user_id = user.id.unwrap();
+
+    let used = create_test_opponent(user_id);
+    let unused = create_test_opponent(user_id);
+    let (used_id, unused_id) = (used.id.unwrap(), unused.id.unwrap());
+    let kept_image = used.image.image_id;
+    let deleted_images = [unused.image.image_id, ObjectId::new()];
+    let all_images: Vec<Image> = deleted_images
+        .iter()
+        .chain(std::iter::once(&kept_image))
+        .map(|id| create_test_image(*id, user_id))
+        .collect();
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    record_updates(&mut mocks.users);
+    mocks.users.expect_delete().returning(|_| Ok(()));
+    mocks
+        .opponents
+        .expect_find_by_creator()
+        .returning(move |_, _, _| Ok(vec![used.clone(), unused.clone()]));
+    mocks
+        .tournaments
+        .expect_opponents_in_use()
+        .withf(move |ids| ids == [used_id, unused_id])
+        .returning(move |_| Ok(vec![used_id]));
+    mocks
+        .opponents
+        .expect_delete()
+        .with(mockall::predicate::eq(unused_id))
+        .times(1)
+        .returning(|_| Ok(()));
+    mocks
+        .images
+        .expect_find_by_creator()
+        .returning(move |_| Ok(all_images.clone()));
+    mocks
+        .images
+        .expect_delete_image()
+        .withf(move |id, owner| deleted_images.contains(id) && *owner == user_id)
+        .times(2)
+        .returning(|_, _| Ok(()));
+    mocks.expect_all_steps(&calls);
+    let service = mocks.service();
+
+    // Act
+    let result = service.run(&user_id).await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_process_due_counts_deleted_accounts() {
+    // Arrange
+    let due = create_test_user(Some(AccountDeletion::new(
+        OwnedTournamentPolicy::Transfer,
+        0,
+    )));
+    let due_id = due.id.unwrap();
+    let listed = due.clone();
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_find_due_for_deletion()
+        .returning(move |_, _| Ok(vec![listed.clone()]));
+    mocks
+        .users
+        .expect_find_by_id()
+        .with(mockall::predicate::eq(due_id))
+        .returning(move |_| Ok(Some(due.clone())));
+    record_updates(&mut mocks.users);
+    mocks.users.expect_delete().times(1).returning(|_| Ok(()));
+    mocks.expect_all_steps(&calls);
+    let service = mocks.service();
+
+    // Act
+    let result = service.process_due().await;
+
+    // Assert
+    assert_eq!(result.unwrap(), 1);
+}
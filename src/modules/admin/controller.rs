@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::common::guards::{AdminUser, ModeratorUser};
+use crate::common::pagination::{PaginatedResponse, PaginationParams};
+use crate::error::Error;
+use crate::modules::admin::model::{AuditEntryResponse, SetRoleDto, SuspendUserDto};
+use crate::modules::admin::service::AdminService;
+use crate::modules::tournaments::model::TournamentResponse;
+use crate::modules::users::model::{UserResponse, UserRole};
+
+fn parse_id(id: &str, what: &str) -> Result<ObjectId, Error> {
+    ObjectId::parse_str(id).map_err(|_| Error::BadRequest(format!("Invalid {} ID", what)))
+}
+
+/// Missing targets are 404s; every other refusal is a bad request.
+fn admin_error(e: String) -> Error {
+    if e.ends_with("not found") {
+        Error::NotFound(e)
+    } else {
+        Error::BadRequest(e)
+    }
+}
+
+#[get("/users?<q>&<role>&<params..>")]
+pub async fn list_users(
+    q: Option<String>,
+    role: Option<&str>,
+    params: PaginationParams,
+    _moderator: ModeratorUser,
+    service: &State<Arc<dyn AdminService + Send + Sync>>,
+) -> Result<Json<PaginatedResponse<UserResponse>>, Error> {
+    let role = role
+        .map(str::parse::<UserRole>)
+        .transpose()
+        .map_err(Error::BadRequest)?;
+
+    let users = service
+        .list_users(q, role, params)
+        .await
+        .map_err(Error::BadRequest)?;
+
+    Ok(Json(users))
+}
+
+#[put("/users/<id>/role", data = "<role_dto>")]
+pub async fn set_role(
+    id: &str,
+    admin: AdminUser,
+    service: &State<Arc<dyn AdminService + Send + Sync>>,
+    role_dto: Json<SetRoleDto>,
+) -> Result<Json<UserResponse>, Error> {
+    let user = service
+        .set_role(&admin.user_id, &parse_id(id, "user")?, role_dto.role)
+        .await
+        .map_err(admin_error)?;
+
+    Ok(Json(user))
+}
+
+#[post("/users/<id>/suspend", data = "<suspend_dto>")]
+pub async fn suspend_user(
+    id: &str,
+    moderator: ModeratorUser,
+    service: &State<Arc<dyn AdminService + Send + Sync>>,
+    suspend_dto: Option<Json<SuspendUserDto>>,
+) -> Result<Json<UserResponse>, Error> {
+    let reason = suspend_dto.and_then(|dto| dto.into_inner().reason);
+    let user = service
+        .suspend_user(
+            &moderator.user_id,
+            moderator.role,
+            &parse_id(id, "user")?,
+            reason,
+        )
+        .await
+        .map_err(admin_error)?;
+
+    Ok(Json(user))
+}
+
+#[post("/users/<id>/unsuspend")]
+pub async fn unsuspend_user(
+    id: &str,
+    moderator: ModeratorUser,
+    service: &State<Arc<dyn AdminService + Send + Sync>>,
+) -> Result<Json<UserResponse>, Error> {
+    let user = service
+        .unsuspend_user(&moderator.user_id, moderator.role, &parse_id(id, "user")?)
+        .await
+        .map_err(admin_error)?;
+
+    Ok(Json(user))
+}
+
+#[delete("/users/<id>")]
+pub async fn delete_user(
+    id: &str,
+    admin: AdminUser,
+    service: &State<Arc<dyn AdminService + Send + Sync>>,
+) -> Result<Json<serde_json::Value>, Error> {
+    service
+        .delete_user(&admin.user_id, &parse_id(id, "user")?)
+        .await
+        .map_err(admin_error)?;
+
+    Ok(Json(
+        serde_json::json!({ "message": "User deleted successfully" }),
+    ))
+}
+
+#[post("/tournaments/<id>/complete")]
+pub async fn complete_tournament(
+    id: &str,
+    admin: AdminUser,
+    service: &State<Arc<dyn AdminService + Send + Sync>>,
+) -> Result<Json<TournamentResponse>, Error> {
+    let tournament = service
+        .force_complete_tournament(&admin.user_id, &parse_id(id, "tournament")?)
+        .await
+        .map_err(admin_error)?;
+
+    Ok(Json(tournament))
+}
+
+#[delete("/tournaments/<id>")]
+pub async fn delete_tournament(
+    id: &str,
+    admin: AdminUser,
+    service: &State<Arc<dyn AdminService + Send + Sync>>,
+) -> Result<Json<serde_json::Value>, Error> {
+    service
+        .delete_tournament(&admin.user_id, &parse_id(id, "tournament")?)
+        .await
+        .map_err(admin_error)?;
+
+    Ok(Json(
+        serde_json::json!({ "message": "Tournament deleted successfully" }),
+    ))
+}
+
+#[get("/audit-log?<params..>")]
+pub async fn audit_log(
+    params: PaginationParams,
+    _admin: AdminUser,
+    service: &State<Arc<dyn AdminService + Send + Sync>>,
+) -> Result<Json<PaginatedResponse<AuditEntryResponse>>, Error> {
+    let entries = service.audit_log(params).await.map_err(Error::BadRequest)?;
+
+    Ok(Json(entries))
+}
+
+pub fn routes() -> Vec<rocket::Route> {
+    routes![
+        list_users,
+        set_role,
+        suspend_user,
+        unsuspend_user,
+        delete_user,
+        complete_tournament,
+        delete_tournament,
+        audit_log
+    ]
+}
@@ -0,0 +1,92 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::common::json::{serialize_datetime, serialize_oid, serialize_option_oid};
+use crate::modules::users::model::UserRole;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    RoleChanged,
+    UserSuspended,
+    UserUnsuspended,
+    UserDeleted,
+    TournamentForceCompleted,
+    TournamentDeleted,
+}
+
+/// One privileged action, kept for accountability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    /// `None` for changes made by the server itself, such as `ADMIN_EMAILS`
+    pub actor_id: Option<ObjectId>,
+    pub action: AuditAction,
+    pub target_user_id: Option<ObjectId>,
+    pub target_tournament_id: Option<ObjectId>,
+    pub previous_role: Option<UserRole>,
+    pub new_role: Option<UserRole>,
+    pub reason: Option<String>,
+    pub created_at: DateTime,
+}
+
+impl AuditEntry {
+    pub fn new(actor_id: Option<ObjectId>, action: AuditAction) -> Self {
+        Self {
+            id: Some(ObjectId::new()),
+            actor_id,
+            action,
+            target_user_id: None,
+            target_tournament_id: None,
+            previous_role: None,
+            new_role: None,
+            reason: None,
+            created_at: DateTime::now(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditEntryResponse {
+    #[serde(serialize_with = "serialize_oid")]
+    pub id: ObjectId,
+    #[serde(serialize_with = "serialize_option_oid")]
+    pub actor_id: Option<ObjectId>,
+    pub action: AuditAction,
+    #[serde(serialize_with = "serialize_option_oid")]
+    pub target_user_id: Option<ObjectId>,
+    #[serde(serialize_with = "serialize_option_oid")]
+    pub target_tournament_id: Option<ObjectId>,
+    pub previous_role: Option<UserRole>,
+    pub new_role: Option<UserRole>,
+    pub reason: Option<String>,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub created_at: DateTime,
+}
+
+impl From<AuditEntry> for AuditEntryResponse {
+    fn from(entry: AuditEntry) -> Self {
+        Self {
+            id: entry.id.unwrap(),
+            actor_id: entry.actor_id,
+            action: entry.action,
+            target_user_id: entry.target_user_id,
+            target_tournament_id: entry.target_tournament_id,
+            previous_role: entry.previous_role,
+            new_role: entry.new_role,
+            reason: entry.reason,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetRoleDto {
+    pub role: UserRole,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SuspendUserDto {
+    pub reason: Option<String>,
+}
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::Database;
+
+use crate::modules::admin::model::AuditEntry;
+
+#[async_trait]
+pub trait AuditLogRepository: Send + Sync {
+    async fn create(&self, entry: &AuditEntry) -> Result<(), String>;
+    /// Newest entries first, fetching one more than `limit`.
+    async fn find(&self, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<AuditEntry>, String>;
+}
+
+pub struct AuditLogRepositoryImpl {
+    db: Database,
+}
+
+impl AuditLogRepositoryImpl {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait]
+impl AuditLogRepository for AuditLogRepositoryImpl {
+    async fn create(&self, entry: &AuditEntry) -> Result<(), String> {
+        self.db
+            .collection::<AuditEntry>("audit_log")
+            .insert_one(entry)
+            .await
+            .map_err(|e| format!("Error creating audit entry: {}", e))?;
+        Ok(())
+    }
+
+    async fn find(&self, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<AuditEntry>, String> {
+        let mut filter = doc! {};
+        if let Some(cursor_id) = cursor {
+            filter.insert("_id", doc! { "$lt": cursor_id });
+        }
+
+        self.db
+            .collection::<AuditEntry>("audit_log")
+            .find(filter)
+            .sort(doc! { "_id": -1 })
+            .limit(limit + 1)
+            .await
+            .map_err(|e| format!("Error finding audit entries: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error finding audit entries: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_log_repository_impl_creation() {
+        let _repo_fn = AuditLogRepositoryImpl::new;
+    }
+}
@@ -0,0 +1,318 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mongodb::bson::{oid::ObjectId, DateTime};
+
+use crate::common::pagination::{PaginatedResponse, PaginationParams};
+use crate::modules::account_deletion::service::AccountDeletionService;
+use crate::modules::admin::model::{AuditAction, AuditEntry, AuditEntryResponse};
+use crate::modules::admin::repository::AuditLogRepository;
+use crate::modules::auth::service::AuthService;
+use crate::modules::tournaments::model::TournamentResponse;
+use crate::modules::tournaments::service::TournamentService;
+use crate::modules::users::model::{User, UserResponse, UserRole};
+use crate::modules::users::repository::UserRepository;
+
+/// Platform moderation. Callers are expected to have checked the actor's
+/// role with the `ModeratorUser` or `AdminUser` guard; the service only
+/// enforces rules between the actor and the target.
+#[async_trait]
+pub trait AdminService: Send + Sync {
+    async fn list_users(
+        &self,
+        query: Option<String>,
+        role: Option<UserRole>,
+        params: PaginationParams,
+    ) -> Result<PaginatedResponse<UserResponse>, String>;
+    async fn set_role(
+        &self,
+        actor_id: &ObjectId,
+        user_id: &ObjectId,
+        role: UserRole,
+    ) -> Result<UserResponse, String>;
+    /// Blocks the account and signs it out everywhere. Only users with a
+    /// lower role than the actor can be suspended.
+    async fn suspend_user(
+        &self,
+        actor_id: &ObjectId,
+        actor_role: UserRole,
+        user_id: &ObjectId,
+        reason: Option<String>,
+    ) -> Result<UserResponse, String>;
+    async fn unsuspend_user(
+        &self,
+        actor_id: &ObjectId,
+        actor_role: UserRole,
+        user_id: &ObjectId,
+    ) -> Result<UserResponse, String>;
+    async fn delete_user(&self, actor_id: &ObjectId, user_id: &ObjectId) -> Result<(), String>;
+    async fn force_complete_tournament(
+        &self,
+        actor_id: &ObjectId,
+        tournament_id: &ObjectId,
+    ) -> Result<TournamentResponse, String>;
+    async fn delete_tournament(
+        &self,
+        actor_id: &ObjectId,
+        tournament_id: &ObjectId,
+    ) -> Result<(), String>;
+    async fn audit_log(
+        &self,
+        params: PaginationParams,
+    ) -> Result<PaginatedResponse<AuditEntryResponse>, String>;
+    /// Gives the admin role to the existing accounts among `emails`.
+    async fn promote_admins(&self, emails: &[String]) -> Result<(), String>;
+}
+
+pub struct AdminServiceImpl {
+    user_repository: Arc<dyn UserRepository>,
+    audit_repository: Arc<dyn AuditLogRepository>,
+    auth_service: Arc<dyn AuthService + Send + Sync>,
+    tournament_service: Arc<dyn TournamentService + Send + Sync>,
+    account_deletion_service: Arc<dyn AccountDeletionService + Send + Sync>,
+}
+
+impl AdminServiceImpl {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        audit_repository: Arc<dyn AuditLogRepository>,
+        auth_service: Arc<dyn AuthService + Send + Sync>,
+        tournament_service: Arc<dyn TournamentService + Send + Sync>,
+        account_deletion_service: Arc<dyn AccountDeletionService + Send + Sync>,
+    ) -> Self {
+        Self {
+            user_repository,
+            audit_repository,
+            auth_service,
+            tournament_service,
+            account_deletion_service,
+        }
+    }
+
+    async fn find_user(&self, user_id: &ObjectId) -> Result<User, String> {
+        self.user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| "User not found".to_string())
+    }
+
+    /// The target of a suspension, checked against the actor.
+    async fn find_subordinate(
+        &self,
+        actor_id: &ObjectId,
+        actor_role: UserRole,
+        user_id: &ObjectId,
+    ) -> Result<User, String> {
+        if actor_id == user_id {
+            return Err("You cannot suspend yourself".to_string());
+        }
+        let user = self.find_user(user_id).await?;
+        if user.role >= actor_role {
+            return Err("You can only suspend users with a lower role".to_string());
+        }
+        Ok(user)
+    }
+
+    async fn apply_role(
+        &self,
+        actor_id: Option<ObjectId>,
+        mut user: User,
+        role: UserRole,
+    ) -> Result<User, String> {
+        let previous_role = user.role;
+        if previous_role == role {
+            return Ok(user);
+        }
+
+        user.role = role;
+        user.updated_at = DateTime::now();
+        self.user_repository.update(&user).await?;
+
+        let mut entry = AuditEntry::new(actor_id, AuditAction::RoleChanged);
+        entry.target_user_id = user.id;
+        entry.previous_role = Some(previous_role);
+        entry.new_role = Some(role);
+        self.audit_repository.create(&entry).await?;
+
+        Ok(user)
+    }
+}
+
+#[async_trait]
+impl AdminService for AdminServiceImpl {
+    async fn list_users(
+        &self,
+        query: Option<String>,
+        role: Option<UserRole>,
+        params: PaginationParams,
+    ) -> Result<PaginatedResponse<UserResponse>, String> {
+        let cursor = params.cursor_oid()?;
+        let limit = params.effective_limit();
+
+        let users = self
+            .user_repository
+            .search(query, role, cursor, limit)
+            .await?;
+
+        Ok(PaginatedResponse::with_cursor(
+            users.into_iter().map(UserResponse::from).collect(),
+            limit,
+            |user| user.id.to_hex(),
+        ))
+    }
+
+    async fn set_role(
+        &self,
+        actor_id: &ObjectId,
+        user_id: &ObjectId,
+        role: UserRole,
+    ) -> Result<UserResponse, String> {
+        if actor_id == user_id {
+            return Err("You cannot change your own role".to_string());
+        }
+        let user = self.find_user(user_id).await?;
+        self.apply_role(Some(*actor_id), user, role)
+            .await
+            .map(UserResponse::from)
+    }
+
+    async fn suspend_user(
+        &self,
+        actor_id: &ObjectId,
+        actor_role: UserRole,
+        user_id: &ObjectId,
+        reason: Option<String>,
+    ) -> Result<UserResponse, String> {
+        let mut user = self.find_subordinate(actor_id, actor_role, user_id).await?;
+        if user.is_suspended() {
+            return Err("User is already suspended".to_string());
+        }
+
+        let now = DateTime::now();
+        user.suspended_at = Some(now);
+        user.updated_at = now;
+        self.user_repository.update(&user).await?;
+        self.auth_service.logout_all(user_id).await?;
+
+        let mut entry = AuditEntry::new(Some(*actor_id), AuditAction::UserSuspended);
+        entry.target_user_id = Some(*user_id);
+        entry.reason = reason
+            .map(|reason| reason.trim().to_string())
+            .filter(|reason| !reason.is_empty());
+        self.audit_repository.create(&entry).await?;
+
+        Ok(UserResponse::from(user))
+    }
+
+    async fn unsuspend_user(
+        &self,
+        actor_id: &ObjectId,
+        actor_role: UserRole,
+        user_id: &ObjectId,
+    ) -> Result<UserResponse, String> {
+        let mut user = self.find_subordinate(actor_id, actor_role, user_id).await?;
+        if !user.is_suspended() {
+            return Err("User is not suspended".to_string());
+        }
+
+        user.suspended_at = None;
+        user.updated_at = DateTime::now();
+        self.user_repository.update(&user).await?;
+
+        let mut entry = AuditEntry::new(Some(*actor_id), AuditAction::UserUnsuspended);
+        entry.target_user_id = Some(*user_id);
+        self.audit_repository.create(&entry).await?;
+
+        Ok(UserResponse::from(user))
+    }
+
+    async fn delete_user(&self, actor_id: &ObjectId, user_id: &ObjectId) -> Result<(), String> {
+        if actor_id == user_id {
+            return Err("You cannot delete yourself here".to_string());
+        }
+        let user = self.find_user(user_id).await?;
+        if user.role == UserRole::Admin {
+            return Err("Admins cannot be deleted; change their role first".to_string());
+        }
+
+        self.auth_service.logout_all(user_id).await?;
+        // Same steps as a self-service deletion, without the grace period, so
+        // the account's tournaments, content and credentials go with it
+        match &user.deletion {
+            Some(deletion) if deletion.is_started() => {}
+            deletion => {
+                let policy = deletion
+                    .as_ref()
+                    .map(|d| d.tournament_policy)
+                    .unwrap_or_default();
+                self.account_deletion_service
+                    .schedule(user_id, policy, 0)
+                    .await?;
+            }
+        }
+        self.account_deletion_service.run(user_id).await?;
+
+        let mut entry = AuditEntry::new(Some(*actor_id), AuditAction::UserDeleted);
+        entry.target_user_id = Some(*user_id);
+        self.audit_repository.create(&entry).await
+    }
+
+    async fn force_complete_tournament(
+        &self,
+        actor_id: &ObjectId,
+        tournament_id: &ObjectId,
+    ) -> Result<TournamentResponse, String> {
+        let tournament = self
+            .tournament_service
+            .force_complete_tournament(tournament_id)
+            .await?;
+
+        let mut entry = AuditEntry::new(Some(*actor_id), AuditAction::TournamentForceCompleted);
+        entry.target_tournament_id = Some(*tournament_id);
+        self.audit_repository.create(&entry).await?;
+
+        Ok(TournamentResponse::from(tournament))
+    }
+
+    async fn delete_tournament(
+        &self,
+        actor_id: &ObjectId,
+        tournament_id: &ObjectId,
+    ) -> Result<(), String> {
+        self.tournament_service
+            .force_delete_tournament(tournament_id)
+            .await?;
+
+        let mut entry = AuditEntry::new(Some(*actor_id), AuditAction::TournamentDeleted);
+        entry.target_tournament_id = Some(*tournament_id);
+        self.audit_repository.create(&entry).await
+    }
+
+    async fn audit_log(
+        &self,
+        params: PaginationParams,
+    ) -> Result<PaginatedResponse<AuditEntryResponse>, String> {
+        let cursor = params.cursor_oid()?;
+        let limit = params.effective_limit();
+
+        let entries = self.audit_repository.find(cursor, limit).await?;
+
+        Ok(PaginatedResponse::with_cursor(
+            entries.into_iter().map(AuditEntryResponse::from).collect(),
+            limit,
+            |entry| entry.id.to_hex(),
+        ))
+    }
+
+    async fn promote_admins(&self, emails: &[String]) -> Result<(), String> {
+        for email in emails {
+            match self.user_repository.find_by_email(email).await? {
+                Some(user) => {
+                    self.apply_role(None, user, UserRole::Admin).await?;
+                }
+                None => tracing::warn!(email = %email, "Admin account not found"),
+            }
+        }
+        Ok(())
+    }
+}
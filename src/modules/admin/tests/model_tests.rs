@@ -0,0 +1,57 @@
+use mongodb::bson::oid::ObjectId;
+
+use crate::modules::admin::model::{AuditAction, AuditEntry, AuditEntryResponse, SetRoleDto};
+use crate::modules::users::model::UserRole;
+
+#[test]
+fn test_audit_entry_response_serialization() {
+    // Arrange
+    let actor_id = ObjectId::new();
+    let target_id = ObjectId::new();
+    let mut entry = AuditEntry::new(Some(actor_id), AuditAction::RoleChanged);
+    entry.target_user_id = Some(target_id);
+    entry.previous_role = Some(UserRole::User);
+    entry.new_role = Some(UserRole::Admin);
+
+    // Act
+    let json = serde_json::to_value(AuditEntryResponse::from(entry)).unwrap();
+
+    // Assert
+    assert_eq!(json["action"], "role_changed");
+    assert_eq!(json["actor_id"], actor_id.to_hex());
+    assert_eq!(json["target_user_id"], target_id.to_hex());
+    assert!(json["target_tournament_id"].is_null());
+    assert_eq!(json["previous_role"], "user");
+    assert_eq!(json["new_role"], "admin");
+}
+
+#[test]
+fn test_system_audit_entry_has_no_actor() {
+    // Act
+    let entry = AuditEntry::new(None, AuditAction::RoleChanged);
+
+    // Assert
+    let json = serde_json::to_value(AuditEntryResponse::from(entry)).unwrap();
+    assert!(json["actor_id"].is_null());
+}
+
+#[test]
+fn test_set_role_dto_deserialization() {
+    // Act
+    let dto: SetRoleDto = serde_json::from_str(r#"{"role": "moderator"}"#).unwrap();
+
+    // Assert
+    assert_eq!(dto.role, UserRole::Moderator);
+    assert!(serde_json::from_str::<SetRoleDto>(r#"{"role": "root"}"#).is_err());
+}
+
+#[test]
+fn test_user_role_ordering_and_parsing() {
+    assert!(UserRole::Admin > UserRole::Moderator);
+    assert!(UserRole::Moderator > UserRole::User);
+    assert_eq!("moderator".parse::<UserRole>(), Ok(UserRole::Moderator));
+    assert_eq!(
+        "root".parse::<UserRole>(),
+        Err("Unknown role: root".to_string())
+    );
+}
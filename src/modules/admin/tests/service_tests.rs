@@ -0,0 +1,564 @@
+use async_trait::async_trait;
+use mockall::mock;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use std::sync::Arc;
+
+use crate::common::pagination::{PaginatedResponse, PaginationParams};
+use crate::config::client_info::ClientInfo;
+use crate::modules::account_deletion::model::{AccountDeletion, OwnedTournamentPolicy};
+use crate::modules::account_deletion::service::AccountDeletionService;
+use crate::modules::admin::model::{AuditAction, AuditEntry};
+use crate::modules::admin::repository::AuditLogRepository;
+use crate::modules::admin::service::{AdminService, AdminServiceImpl};
+use crate::modules::auth::model::{
+    AnonymousClaims, AnonymousTokenResponse, JwtClaims, LoginResponse, LoginResult,
+    RecoveryCodesResponse, RefreshResponse, SessionResponse, TotpEnrollmentResponse,
+};
+use crate::modules::auth::service::AuthService;
+use crate::modules::tournaments::model::{
+    CreateInviteDto, CreateTournamentDto, GrantMemberDto, InviteRedemptionResponse,
+    InviteResponse, InviteSummaryResponse, JoinTournamentDto, JoinTournamentResponse, Match,
+    MembershipError,
+    Tournament, TournamentMemberResponse, TournamentResponse, TournamentStatus,
+    TournamentUserResponse, UpdateParticipantDto, UpdateTournamentDto, VoteMatchDto, VoterId,
+};
+use crate::modules::tournaments::service::TournamentService;
+use crate::modules::users::model::{User, UserRole};
+use crate::modules::users::repository::UserRepository;
+
+mock! {
+    UserRepo {}
+
+    #[async_trait]
+    impl UserRepository for UserRepo {
+        async fn create(&self, user: &User) -> Result<(), String>;
+        async fn find_by_email(&self, email: &str) -> Result<Option<User>, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<User>, String>;
+        async fn update(&self, user: &User) -> Result<(), String>;
+        async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn remove_recovery_code(&self, id: &ObjectId, code_hash: &str) -> Result<bool, String>;
+        async fn search(&self, query: Option<String>, role: Option<UserRole>, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<User>, String>;
+        async fn find_due_for_deletion(&self, now: DateTime, limit: i64) -> Result<Vec<User>, String>;
+        async fn find_discoverable(&self, query: &str, exclude: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<User>, String>;
+    }
+}
+
+mock! {
+    AuditRepo {}
+
+    #[async_trait]
+    impl AuditLogRepository for AuditRepo {
+        async fn create(&self, entry: &AuditEntry) -> Result<(), String>;
+        async fn find(&self, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<AuditEntry>, String>;
+    }
+}
+
+mock! {
+    Auth {}
+
+    #[async_trait]
+    impl AuthService for Auth {
+        async fn login(&self, email: &str, password: &str, client: &ClientInfo) -> Result<LoginResult, String>;
+        async fn complete_mfa_login(&self, mfa_token: &str, code: &str, client: &ClientInfo) -> Result<LoginResponse, String>;
+        async fn register(&self, email: &str, name: &str, password: &str, client: &ClientInfo) -> Result<LoginResponse, String>;
+        async fn create_session(&self, user: User, client: &ClientInfo) -> Result<LoginResponse, String>;
+        fn verify_token(&self, token: &str) -> Result<JwtClaims, String>;
+        async fn refresh_tokens(&self, refresh_token: &str, client: &ClientInfo) -> Result<RefreshResponse, String>;
+        async fn logout(&self, refresh_token: &str) -> Result<(), String>;
+        async fn logout_all(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn list_sessions(&self, user_id: &ObjectId) -> Result<Vec<SessionResponse>, String>;
+        async fn revoke_session(&self, user_id: &ObjectId, session_id: &str) -> Result<(), String>;
+        async fn request_password_reset(&self, email: &str) -> Result<(), String>;
+        async fn reset_password(&self, token: &str, new_password: &str) -> Result<(), String>;
+        async fn send_verification_email(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn verify_email(&self, token: &str) -> Result<(), String>;
+        async fn request_magic_link(&self, email: &str) -> Result<(), String>;
+        async fn redeem_magic_link(&self, token: &str, client: &ClientInfo) -> Result<LoginResult, String>;
+        async fn start_totp_enrollment(&self, user_id: &ObjectId) -> Result<TotpEnrollmentResponse, String>;
+        async fn confirm_totp_enrollment(&self, user_id: &ObjectId, code: &str) -> Result<RecoveryCodesResponse, String>;
+        async fn disable_totp(&self, user_id: &ObjectId, code: &str) -> Result<(), String>;
+        async fn regenerate_recovery_codes(&self, user_id: &ObjectId, code: &str) -> Result<RecoveryCodesResponse, String>;
+        fn generate_anonymous_token(&self, tournament_id: &ObjectId, display_name: &str) -> Result<AnonymousTokenResponse, String>;
+        fn verify_anonymous_token(&self, token: &str) -> Result<AnonymousClaims, String>;
+    }
+}
+
+mock! {
+    Tournaments {}
+
+    #[async_trait]
+    impl TournamentService for Tournaments {
+        async fn create_tournament(&self, tournament_dto: CreateTournamentDto, created_by: ObjectId) -> Result<Tournament, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Tournament>, String>;
+        async fn find_by_creator(&self, user_id: &ObjectId, params: PaginationParams) -> Result<PaginatedResponse<TournamentResponse>, String>;
+        async fn update_tournament(&self, id: &ObjectId, dto: UpdateTournamentDto, user_id: &ObjectId) -> Result<Tournament, String>;
+        async fn delete_tournament(&self, id: &ObjectId, user_id: &ObjectId) -> Result<(), String>;
+        async fn force_complete_tournament(&self, id: &ObjectId) -> Result<Tournament, String>;
+        async fn force_delete_tournament(&self, id: &ObjectId) -> Result<(), String>;
+        async fn pause_tournament(&self, id: &ObjectId, user_id: &ObjectId) -> Result<Tournament, String>;
+        async fn resume_tournament(&self, id: &ObjectId, user_id: &ObjectId) -> Result<Tournament, String>;
+        async fn get_match_detail(&self, tournament_id: &ObjectId, match_id: &str) -> Result<Match, String>;
+        async fn vote_match(&self, vote_dto: VoteMatchDto, voter_id: VoterId) -> Result<Tournament, String>;
+        async fn create_invite(&self, tournament_id: &ObjectId, dto: CreateInviteDto, created_by: ObjectId) -> Result<InviteResponse, String>;
+        async fn list_invites(&self, tournament_id: &ObjectId, user_id: &ObjectId) -> Result<Vec<InviteSummaryResponse>, MembershipError>;
+        async fn revoke_invite(&self, tournament_id: &ObjectId, code: &str, user_id: &ObjectId) -> Result<(), MembershipError>;
+        async fn invite_joins(&self, tournament_id: &ObjectId, code: &str, user_id: &ObjectId) -> Result<Vec<InviteRedemptionResponse>, MembershipError>;
+        async fn invite_qr_code(&self, tournament_id: &ObjectId, code: &str, user_id: &ObjectId) -> Result<Vec<u8>, MembershipError>;
+        async fn join_tournament(&self, tournament_id: &ObjectId, dto: JoinTournamentDto) -> Result<JoinTournamentResponse, String>;
+        async fn join_by_code(&self, dto: JoinTournamentDto) -> Result<JoinTournamentResponse, String>;
+        async fn claim_anonymous_session(&self, tournament_id: &ObjectId, session_id: &str, user_id: ObjectId, name: String) -> Result<(), String>;
+        async fn update_participant(&self, tournament_id: &ObjectId, voter_id: &str, dto: UpdateParticipantDto, user_id: &ObjectId) -> Result<TournamentUserResponse, MembershipError>;
+        async fn kick_participant(&self, tournament_id: &ObjectId, voter_id: &str, user_id: &ObjectId) -> Result<(), MembershipError>;
+        async fn grant_member(&self, tournament_id: &ObjectId, dto: GrantMemberDto, granted_by: &ObjectId) -> Result<Vec<TournamentMemberResponse>, MembershipError>;
+        async fn revoke_member(&self, tournament_id: &ObjectId, member_id: &ObjectId, revoked_by: &ObjectId) -> Result<Vec<TournamentMemberResponse>, MembershipError>;
+        async fn transfer_ownership(&self, tournament_id: &ObjectId, new_owner: &ObjectId, user_id: &ObjectId) -> Result<Tournament, MembershipError>;
+        async fn release_tournaments(&self, user_id: &ObjectId, policy: OwnedTournamentPolicy) -> Result<(), String>;
+        async fn anonymize_participant(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn opponents_in_use(&self, opponent_ids: &[ObjectId]) -> Result<Vec<ObjectId>, String>;
+    }
+}
+
+mock! {
+    AccountDeletion {}
+
+    #[async_trait]
+    impl AccountDeletionService for AccountDeletion {
+        async fn schedule(&self, user_id: &ObjectId, policy: OwnedTournamentPolicy, grace_period_secs: i64) -> Result<AccountDeletion, String>;
+        async fn status(&self, user_id: &ObjectId) -> Result<Option<AccountDeletion>, String>;
+        async fn cancel(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn run(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn process_due(&self) -> Result<usize, String>;
+    }
+}
+
+fn create_test_user(role: UserRole) -> User {
+    let mut user = User::new(
+        "member@example.com".to_string(),
+        "Member".to_string(),
+        "$2b$12$hashedpassword".to_string(),
+    );
+    user.id = Some(ObjectId::new());
+    user.role = role;
+    user
+}
+
+fn create_test_tournament() -> Tournament {
+    let now = DateTime::now();
+    Tournament {
+        id: Some(ObjectId::new()),
+        name: "Test Tournament".to_string(),
+        created_by: ObjectId::new(),
+        members: vec![],
+        opponents: vec![],
+        users: vec![],
+        rounds: vec![],
+        status: TournamentStatus::Completed,
+        winner: None,
+        created_at: now,
+        updated_at: now,
+        version: 0,
+    }
+}
+
+struct Mocks {
+    users: MockUserRepo,
+    audit: MockAuditRepo,
+    auth: MockAuth,
+    tournaments: MockTournaments,
+    deletion: MockAccountDeletion,
+}
+
+impl Mocks {
+    fn new() -> Self {
+        Self {
+            users: MockUserRepo::new(),
+            audit: MockAuditRepo::new(),
+            auth: MockAuth::new(),
+            tournaments: MockTournaments::new(),
+            deletion: MockAccountDeletion::new(),
+        }
+    }
+
+    fn with_user(mut self, user: &User) -> Self {
+        let user = user.clone();
+        self.users
+            .expect_find_by_id()
+            .returning(move |_| Ok(Some(user.clone())));
+        self
+    }
+
+    fn expect_audit(mut self, action: AuditAction) -> Self {
+        self.audit
+            .expect_create()
+            .withf(move |entry| entry.action == action)
+            .times(1)
+            .returning(|_| Ok(()));
+        self
+    }
+
+    fn service(self) -> AdminServiceImpl {
+        AdminServiceImpl::new(
+            Arc::new(self.users),
+            Arc::new(self.audit),
+            Arc::new(self.auth),
+            Arc::new(self.tournaments),
+            Arc::new(self.deletion),
+        )
+    }
+}
+
+#[tokio::test]
+async fn test_set_role_records_previous_and_new_role() {
+    // Arrange
+    let admin_id = ObjectId::new();
+    let user = create_test_user(UserRole::User);
+    let user_id = user.id.unwrap();
+    let mut mocks = Mocks::new().with_user(&user);
+    mocks
+        .users
+        .expect_update()
+        .withf(|user| user.role == UserRole::Moderator)
+        .times(1)
+        .returning(|_| Ok(()));
+    mocks
+        .audit
+        .expect_create()
+        .withf(move |entry| {
+            entry.action == AuditAction::RoleChanged
+                && entry.actor_id == Some(admin_id)
+                && entry.target_user_id == Some(user_id)
+                && entry.previous_role == Some(UserRole::User)
+                && entry.new_role == Some(UserRole::Moderator)
+        })
+        .times(1)
+        .returning(|_| Ok(()));
+
+    // Act
+    let result = mocks
+        .service()
+        .set_role(&admin_id, &user_id, UserRole::Moderator)
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap().role, UserRole::Moderator);
+}
+
+#[tokio::test]
+async fn test_set_role_unchanged_is_not_audited() {
+    // Arrange
+    let user = create_test_user(UserRole::Moderator);
+    let mut mocks = Mocks::new().with_user(&user);
+    mocks.users.expect_update().times(0);
+    mocks.audit.expect_create().times(0);
+
+    // Act
+    let result = mocks
+        .service()
+        .set_role(&ObjectId::new(), &user.id.unwrap(), UserRole::Moderator)
+        .await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_set_own_role_rejected() {
+    // Arrange
+    let admin_id = ObjectId::new();
+
+    // Act
+    let result = Mocks::new()
+        .service()
+        .set_role(&admin_id, &admin_id, UserRole::User)
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "You cannot change your own role");
+}
+
+#[tokio::test]
+async fn test_suspend_user_signs_out_everywhere() {
+    // Arrange
+    let user = create_test_user(UserRole::User);
+    let user_id = user.id.unwrap();
+    let mut mocks = Mocks::new()
+        .with_user(&user)
+        .expect_audit(AuditAction::UserSuspended);
+    mocks
+        .users
+        .expect_update()
+        .withf(|user| user.suspended_at.is_some())
+        .times(1)
+        .returning(|_| Ok(()));
+    mocks
+        .auth
+        .expect_logout_all()
+        .withf(move |id| *id == user_id)
+        .times(1)
+        .returning(|_| Ok(()));
+
+    // Act
+    let result = mocks
+        .service()
+        .suspend_user(
+            &ObjectId::new(),
+            UserRole::Moderator,
+            &user_id,
+            Some("spam".to_string()),
+        )
+        .await;
+
+    // Assert
+    assert!(result.unwrap().suspended_at.is_some());
+}
+
+#[tokio::test]
+async fn test_moderator_cannot_suspend_moderator() {
+    // Arrange
+    let user = create_test_user(UserRole::Moderator);
+    let mut mocks = Mocks::new().with_user(&user);
+    mocks.users.expect_update().times(0);
+
+    // Act
+    let result = mocks
+        .service()
+        .suspend_user(
+            &ObjectId::new(),
+            UserRole::Moderator,
+            &user.id.unwrap(),
+            None,
+        )
+        .await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "You can only suspend users with a lower role"
+    );
+}
+
+#[tokio::test]
+async fn test_unsuspend_user() {
+    // Arrange
+    let mut user = create_test_user(UserRole::User);
+    user.suspended_at = Some(DateTime::now());
+    let mut mocks = Mocks::new()
+        .with_user(&user)
+        .expect_audit(AuditAction::UserUnsuspended);
+    mocks
+        .users
+        .expect_update()
+        .withf(|user| user.suspended_at.is_none())
+        .times(1)
+        .returning(|_| Ok(()));
+
+    // Act
+    let result = mocks
+        .service()
+        .unsuspend_user(&ObjectId::new(), UserRole::Admin, &user.id.unwrap())
+        .await;
+
+    // Assert
+    assert!(result.unwrap().suspended_at.is_none());
+}
+
+#[tokio::test]
+async fn test_delete_user_is_audited() {
+    // Arrange
+    let user = create_test_user(UserRole::Moderator);
+    let mut mocks = Mocks::new()
+        .with_user(&user)
+        .expect_audit(AuditAction::UserDeleted);
+    let user_id = user.id.unwrap();
+    mocks.auth.expect_logout_all().returning(|_| Ok(()));
+    mocks
+        .deletion
+        .expect_schedule()
+        .with(
+            mockall::predicate::eq(user_id),
+            mockall::predicate::eq(OwnedTournamentPolicy::Transfer),
+            mockall::predicate::eq(0),
+        )
+        .times(1)
+        .returning(|_, policy, grace| Ok(AccountDeletion::new(policy, grace)));
+    mocks
+        .deletion
+        .expect_run()
+        .with(mockall::predicate::eq(user_id))
+        .times(1)
+        .returning(|_| Ok(()));
+    mocks.users.expect_delete().never();
+
+    // Act
+    let result = mocks.service().delete_user(&ObjectId::new(), &user_id).await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_delete_user_resumes_started_deletion() {
+    // Arrange
+    let mut user = create_test_user(UserRole::User);
+    let mut deletion = AccountDeletion::new(OwnedTournamentPolicy::Delete, 0);
+    deletion.attempts = 1;
+    user.deletion = Some(deletion);
+    let mut mocks = Mocks::new()
+        .with_user(&user)
+        .expect_audit(AuditAction::UserDeleted);
+    mocks.auth.expect_logout_all().returning(|_| Ok(()));
+    mocks.deletion.expect_schedule().never();
+    mocks.deletion.expect_run().times(1).returning(|_| Ok(()));
+
+    // Act
+    let result = mocks
+        .service()
+        .delete_user(&ObjectId::new(), &user.id.unwrap())
+        .await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_delete_admin_rejected() {
+    // Arrange
+    let user = create_test_user(UserRole::Admin);
+    let mut mocks = Mocks::new().with_user(&user);
+    mocks.users.expect_delete().times(0);
+
+    // Act
+    let result = mocks
+        .service()
+        .delete_user(&ObjectId::new(), &user.id.unwrap())
+        .await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Admins cannot be deleted; change their role first"
+    );
+}
+
+#[tokio::test]
+async fn test_force_complete_tournament_is_audited() {
+    // Arrange
+    let tournament = create_test_tournament();
+    let tournament_id = tournament.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks
+        .tournaments
+        .expect_force_complete_tournament()
+        .times(1)
+        .returning(move |_| Ok(tournament.clone()));
+    mocks
+        .audit
+        .expect_create()
+        .withf(move |entry| {
+            entry.action == AuditAction::TournamentForceCompleted
+                && entry.target_tournament_id == Some(tournament_id)
+        })
+        .times(1)
+        .returning(|_| Ok(()));
+
+    // Act
+    let result = mocks
+        .service()
+        .force_complete_tournament(&ObjectId::new(), &tournament_id)
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap().id, tournament_id);
+}
+
+#[tokio::test]
+async fn test_delete_tournament_not_found_is_not_audited() {
+    // Arrange
+    let mut mocks = Mocks::new();
+    mocks
+        .tournaments
+        .expect_force_delete_tournament()
+        .returning(|_| Err("Tournament not found".to_string()));
+    mocks.audit.expect_create().times(0);
+
+    // Act
+    let result = mocks
+        .service()
+        .delete_tournament(&ObjectId::new(), &ObjectId::new())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Tournament not found");
+}
+
+#[tokio::test]
+async fn test_list_users_paginates() {
+    // Arrange
+    let users: Vec<User> = (0..3).map(|_| create_test_user(UserRole::User)).collect();
+    let last_id = users[1].id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_search()
+        .withf(|query, role, cursor, limit| {
+            query.as_deref() == Some("ada") && role.is_none() && cursor.is_none() && *limit == 2
+        })
+        .returning(move |_, _, _, _| Ok(users.clone()));
+
+    // Act
+    let result = mocks
+        .service()
+        .list_users(
+            Some("ada".to_string()),
+            None,
+            PaginationParams {
+                cursor: None,
+                limit: Some(2),
+            },
+        )
+        .await;
+
+    // Assert
+    let page = result.unwrap();
+    assert_eq!(page.data.len(), 2);
+    assert!(page.has_more);
+    assert_eq!(page.next_cursor, Some(last_id.to_hex()));
+}
+
+#[tokio::test]
+async fn test_promote_admins_uses_system_actor() {
+    // Arrange
+    let user = create_test_user(UserRole::User);
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_find_by_email()
+        .returning(move |email| match email {
+            "member@example.com" => Ok(Some(user.clone())),
+            _ => Ok(None),
+        });
+    mocks
+        .users
+        .expect_update()
+        .withf(|user| user.role == UserRole::Admin)
+        .times(1)
+        .returning(|_| Ok(()));
+    mocks
+        .audit
+        .expect_create()
+        .withf(|entry| entry.actor_id.is_none() && entry.new_role == Some(UserRole::Admin))
+        .times(1)
+        .returning(|_| Ok(()));
+
+    // Act
+    let result = mocks
+        .service()
+        .promote_admins(&[
+            "member@example.com".to_string(),
+            "missing@example.com".to_string(),
+        ])
+        .await;
+
+    // Assert
+    assert!(result.is_ok());
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::common::guards::AuthenticatedUser;
+use crate::error::Error;
+use crate::modules::api_keys::model::{
+    ApiKeyResponse, CreateApiKeyDto, CreatedApiKeyResponse, UpdateApiKeyDto,
+};
+use crate::modules::api_keys::service::ApiKeyService;
+
+fn parse_id(id: &str) -> Result<ObjectId, Error> {
+    ObjectId::parse_str(id).map_err(|_| Error::BadRequest("Invalid API key ID".to_string()))
+}
+
+#[post("/", data = "<create_dto>")]
+pub async fn create(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn ApiKeyService + Send + Sync>>,
+    create_dto: Json<CreateApiKeyDto>,
+) -> Result<Json<CreatedApiKeyResponse>, Error> {
+    let created = service
+        .create_key(&auth.user_id, create_dto.into_inner())
+        .await
+        .map_err(Error::BadRequest)?;
+
+    Ok(Json(created))
+}
+
+#[get("/")]
+pub async fn list(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn ApiKeyService + Send + Sync>>,
+) -> Result<Json<Vec<ApiKeyResponse>>, Error> {
+    let keys = service
+        .list_keys(&auth.user_id)
+        .await
+        .map_err(Error::Internal)?;
+
+    Ok(Json(keys))
+}
+
+#[get("/<id>")]
+pub async fn get_key(
+    id: &str,
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn ApiKeyService + Send + Sync>>,
+) -> Result<Json<ApiKeyResponse>, Error> {
+    let key = service
+        .get_key(&auth.user_id, &parse_id(id)?)
+        .await
+        .map_err(Error::NotFound)?;
+
+    Ok(Json(key))
+}
+
+#[put("/<id>", data = "<update_dto>")]
+pub async fn update(
+    id: &str,
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn ApiKeyService + Send + Sync>>,
+    update_dto: Json<UpdateApiKeyDto>,
+) -> Result<Json<ApiKeyResponse>, Error> {
+    let key = service
+        .update_key(&auth.user_id, &parse_id(id)?, update_dto.into_inner())
+        .await
+        .map_err(Error::BadRequest)?;
+
+    Ok(Json(key))
+}
+
+#[delete("/<id>")]
+pub async fn delete(
+    id: &str,
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn ApiKeyService + Send + Sync>>,
+) -> Result<Json<serde_json::Value>, Error> {
+    service
+        .delete_key(&auth.user_id, &parse_id(id)?)
+        .await
+        .map_err(Error::NotFound)?;
+
+    Ok(Json(
+        serde_json::json!({ "message": "API key deleted successfully" }),
+    ))
+}
+
+pub fn routes() -> Vec<rocket::Route> {
+    routes![create, list, get_key, update, delete]
+}
@@ -0,0 +1,131 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use rocket::http::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::common::json::{serialize_datetime, serialize_oid, serialize_option_datetime};
+
+/// Prefix that tells API keys apart from JWTs in the `Authorization` header.
+pub const API_KEY_PREFIX: &str = "torvi_";
+
+/// Tournament route segments that change who runs or takes part in it.
+const TOURNAMENT_ADMIN_SEGMENTS: [&str; 3] = ["members", "participants", "transfer"];
+
+/// What an API key is allowed to do. JWT sessions are not limited by scopes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiKeyScope {
+    #[serde(rename = "tournaments:read")]
+    TournamentsRead,
+    #[serde(rename = "tournaments:write")]
+    TournamentsWrite,
+    /// Managing members, ownership and participants of tournaments
+    #[serde(rename = "tournaments:admin")]
+    TournamentsAdmin,
+    #[serde(rename = "images:write")]
+    ImagesWrite,
+}
+
+impl ApiKeyScope {
+    /// Scope needed to call the route at `path`, mounted at `base`, with
+    /// `method`. `None` means API keys cannot be used there at all, e.g. for
+    /// account settings.
+    pub fn required_for(method: Method, base: &str, path: &str) -> Option<Self> {
+        match base {
+            "/api/tournaments" | "/api/opponents" if method == Method::Get => {
+                Some(Self::TournamentsRead)
+            }
+            "/api/tournaments"
+                if path
+                    .split('/')
+                    .any(|segment| TOURNAMENT_ADMIN_SEGMENTS.contains(&segment)) =>
+            {
+                Some(Self::TournamentsAdmin)
+            }
+            "/api/tournaments" | "/api/opponents" => Some(Self::TournamentsWrite),
+            "/api/images" => Some(Self::ImagesWrite),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TournamentsRead => "tournaments:read",
+            Self::TournamentsWrite => "tournaments:write",
+            Self::TournamentsAdmin => "tournaments:admin",
+            Self::ImagesWrite => "images:write",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub name: String,
+    /// First characters of the key, shown so users can tell keys apart
+    pub prefix: String,
+    /// SHA-256 of the full key
+    pub key_hash: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub expires_at: Option<DateTime>,
+    pub last_used_at: Option<DateTime>,
+    pub created_at: DateTime,
+}
+
+impl ApiKey {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= DateTime::now())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyDto {
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    /// Days until the key stops working; never expires when omitted
+    pub expires_in_days: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateApiKeyDto {
+    pub name: Option<String>,
+    pub scopes: Option<Vec<ApiKeyScope>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKeyResponse {
+    #[serde(serialize_with = "serialize_oid")]
+    pub id: ObjectId,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<ApiKeyScope>,
+    #[serde(serialize_with = "serialize_option_datetime")]
+    pub expires_at: Option<DateTime>,
+    #[serde(serialize_with = "serialize_option_datetime")]
+    pub last_used_at: Option<DateTime>,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub created_at: DateTime,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(api_key: ApiKey) -> Self {
+        Self {
+            id: api_key.id.unwrap(),
+            name: api_key.name,
+            prefix: api_key.prefix,
+            scopes: api_key.scopes,
+            expires_at: api_key.expires_at,
+            last_used_at: api_key.last_used_at,
+            created_at: api_key.created_at,
+        }
+    }
+}
+
+/// Returned once on creation; the full key cannot be retrieved again.
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+    pub key: String,
+}
@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::Database;
+
+use crate::modules::api_keys::model::ApiKey;
+
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    async fn create(&self, api_key: &ApiKey) -> Result<(), String>;
+    async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, String>;
+    async fn find_by_user(&self, user_id: &ObjectId) -> Result<Vec<ApiKey>, String>;
+    /// The key only if it belongs to the user.
+    async fn find_for_user(
+        &self,
+        user_id: &ObjectId,
+        id: &ObjectId,
+    ) -> Result<Option<ApiKey>, String>;
+    async fn update(&self, api_key: &ApiKey) -> Result<(), String>;
+    /// Deletes a key only if it belongs to the user. Returns `false` when
+    /// nothing was deleted.
+    async fn delete_for_user(&self, user_id: &ObjectId, id: &ObjectId) -> Result<bool, String>;
+    async fn delete_all_for_user(&self, user_id: &ObjectId) -> Result<(), String>;
+    async fn touch(&self, id: &ObjectId, used_at: DateTime) -> Result<(), String>;
+}
+
+pub struct ApiKeyRepositoryImpl {
+    db: Database,
+}
+
+impl ApiKeyRepositoryImpl {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for ApiKeyRepositoryImpl {
+    async fn create(&self, api_key: &ApiKey) -> Result<(), String> {
+        self.db
+            .collection::<ApiKey>("api_keys")
+            .insert_one(api_key)
+            .await
+            .map_err(|e| format!("Error creating API key: {}", e))?;
+        Ok(())
+    }
+
+    async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, String> {
+        self.db
+            .collection::<ApiKey>("api_keys")
+            .find_one(doc! { "key_hash": key_hash })
+            .await
+            .map_err(|e| format!("Error finding API key: {}", e))
+    }
+
+    async fn find_by_user(&self, user_id: &ObjectId) -> Result<Vec<ApiKey>, String> {
+        self.db
+            .collection::<ApiKey>("api_keys")
+            .find(doc! { "user_id": user_id })
+            .sort(doc! { "created_at": -1 })
+            .await
+            .map_err(|e| format!("Error finding API keys: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error finding API keys: {}", e))
+    }
+
+    async fn find_for_user(
+        &self,
+        user_id: &ObjectId,
+        id: &ObjectId,
+    ) -> Result<Option<ApiKey>, String> {
+        self.db
+            .collection::<ApiKey>("api_keys")
+            .find_one(doc! { "_id": id, "user_id": user_id })
+            .await
+            .map_err(|e| format!("Error finding API key: {}", e))
+    }
+
+    async fn update(&self, api_key: &ApiKey) -> Result<(), String> {
+        let id = api_key
+            .id
+            .as_ref()
+            .ok_or("API key must have an id to update")?;
+
+        self.db
+            .collection::<ApiKey>("api_keys")
+            .replace_one(doc! { "_id": id }, api_key)
+            .await
+            .map_err(|e| format!("Error updating API key: {}", e))?;
+        Ok(())
+    }
+
+    async fn delete_for_user(&self, user_id: &ObjectId, id: &ObjectId) -> Result<bool, String> {
+        let result = self
+            .db
+            .collection::<ApiKey>("api_keys")
+            .delete_one(doc! { "_id": id, "user_id": user_id })
+            .await
+            .map_err(|e| format!("Error deleting API key: {}", e))?;
+        Ok(result.deleted_count == 1)
+    }
+
+    async fn delete_all_for_user(&self, user_id: &ObjectId) -> Result<(), String> {
+        self.db
+            .collection::<ApiKey>("api_keys")
+            .delete_many(doc! { "user_id": user_id })
+            .await
+            .map_err(|e| format!("Error deleting API keys: {}", e))?;
+        Ok(())
+    }
+
+    async fn touch(&self, id: &ObjectId, used_at: DateTime) -> Result<(), String> {
+        self.db
+            .collection::<ApiKey>("api_keys")
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "last_used_at": used_at } },
+            )
+            .await
+            .map_err(|e| format!("Error updating API key: {}", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_repository_impl_creation() {
+        let _repo_fn = ApiKeyRepositoryImpl::new;
+    }
+}
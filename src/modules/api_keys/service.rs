@@ -0,0 +1,213 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mongodb::bson::{oid::ObjectId, DateTime};
+
+use crate::common::secrets::{generate_secret, hash_secret};
+use crate::modules::api_keys::model::{
+    ApiKey, ApiKeyResponse, ApiKeyScope, CreateApiKeyDto, CreatedApiKeyResponse, UpdateApiKeyDto,
+    API_KEY_PREFIX,
+};
+use crate::modules::api_keys::repository::ApiKeyRepository;
+use crate::modules::users::service::UserService;
+
+/// Characters of the key kept in clear text so users can tell keys apart
+const DISPLAY_PREFIX_LEN: usize = 12;
+const MAX_NAME_LEN: usize = 100;
+/// `last_used_at` is only written when it is older than this, so busy keys
+/// do not cost a write on every request
+const LAST_USED_PRECISION_SECS: i64 = 60;
+
+/// The user an API key acts for, and what it may do.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiKeyPrincipal {
+    pub user_id: ObjectId,
+    pub email: String,
+    pub scopes: Vec<ApiKeyScope>,
+}
+
+#[async_trait]
+pub trait ApiKeyService: Send + Sync {
+    /// Creates a key. The full key is only ever returned here.
+    async fn create_key(
+        &self,
+        user_id: &ObjectId,
+        dto: CreateApiKeyDto,
+    ) -> Result<CreatedApiKeyResponse, String>;
+    async fn list_keys(&self, user_id: &ObjectId) -> Result<Vec<ApiKeyResponse>, String>;
+    async fn get_key(&self, user_id: &ObjectId, id: &ObjectId) -> Result<ApiKeyResponse, String>;
+    async fn update_key(
+        &self,
+        user_id: &ObjectId,
+        id: &ObjectId,
+        dto: UpdateApiKeyDto,
+    ) -> Result<ApiKeyResponse, String>;
+    async fn delete_key(&self, user_id: &ObjectId, id: &ObjectId) -> Result<(), String>;
+    /// Resolves a key from an `Authorization` header and records its use.
+    async fn authenticate(&self, key: &str) -> Result<ApiKeyPrincipal, String>;
+}
+
+pub struct ApiKeyServiceImpl {
+    repository: Arc<dyn ApiKeyRepository>,
+    user_service: Arc<dyn UserService + Send + Sync>,
+}
+
+impl ApiKeyServiceImpl {
+    pub fn new(
+        repository: Arc<dyn ApiKeyRepository>,
+        user_service: Arc<dyn UserService + Send + Sync>,
+    ) -> Self {
+        Self {
+            repository,
+            user_service,
+        }
+    }
+
+    async fn find_key(&self, user_id: &ObjectId, id: &ObjectId) -> Result<ApiKey, String> {
+        self.repository
+            .find_for_user(user_id, id)
+            .await?
+            .ok_or_else(|| "API key not found".to_string())
+    }
+}
+
+fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("API key name is required".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!(
+            "API key name must be at most {} characters",
+            MAX_NAME_LEN
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_scopes(scopes: Vec<ApiKeyScope>) -> Result<Vec<ApiKeyScope>, String> {
+    let mut unique = Vec::with_capacity(scopes.len());
+    for scope in scopes {
+        if !unique.contains(&scope) {
+            unique.push(scope);
+        }
+    }
+    if unique.is_empty() {
+        return Err("At least one scope is required".to_string());
+    }
+    Ok(unique)
+}
+
+#[async_trait]
+impl ApiKeyService for ApiKeyServiceImpl {
+    async fn create_key(
+        &self,
+        user_id: &ObjectId,
+        dto: CreateApiKeyDto,
+    ) -> Result<CreatedApiKeyResponse, String> {
+        let name = validate_name(&dto.name)?;
+        let scopes = validate_scopes(dto.scopes)?;
+        if dto.expires_in_days == Some(0) {
+            return Err("expires_in_days must be at least 1".to_string());
+        }
+
+        let now = DateTime::now();
+        let key = format!("{}{}", API_KEY_PREFIX, generate_secret());
+        let api_key = ApiKey {
+            id: Some(ObjectId::new()),
+            user_id: *user_id,
+            name,
+            prefix: key[..DISPLAY_PREFIX_LEN].to_string(),
+            key_hash: hash_secret(&key),
+            scopes,
+            expires_at: dto.expires_in_days.map(|days| {
+                DateTime::from_millis(now.timestamp_millis() + days as i64 * 86_400_000)
+            }),
+            last_used_at: None,
+            created_at: now,
+        };
+        self.repository.create(&api_key).await?;
+
+        Ok(CreatedApiKeyResponse {
+            api_key: ApiKeyResponse::from(api_key),
+            key,
+        })
+    }
+
+    async fn list_keys(&self, user_id: &ObjectId) -> Result<Vec<ApiKeyResponse>, String> {
+        Ok(self
+            .repository
+            .find_by_user(user_id)
+            .await?
+            .into_iter()
+            .map(ApiKeyResponse::from)
+            .collect())
+    }
+
+    async fn get_key(&self, user_id: &ObjectId, id: &ObjectId) -> Result<ApiKeyResponse, String> {
+        self.find_key(user_id, id).await.map(ApiKeyResponse::from)
+    }
+
+    async fn update_key(
+        &self,
+        user_id: &ObjectId,
+        id: &ObjectId,
+        dto: UpdateApiKeyDto,
+    ) -> Result<ApiKeyResponse, String> {
+        let mut api_key = self.find_key(user_id, id).await?;
+        if let Some(name) = dto.name {
+            api_key.name = validate_name(&name)?;
+        }
+        if let Some(scopes) = dto.scopes {
+            api_key.scopes = validate_scopes(scopes)?;
+        }
+        self.repository.update(&api_key).await?;
+        Ok(ApiKeyResponse::from(api_key))
+    }
+
+    async fn delete_key(&self, user_id: &ObjectId, id: &ObjectId) -> Result<(), String> {
+        if self.repository.delete_for_user(user_id, id).await? {
+            Ok(())
+        } else {
+            Err("API key not found".to_string())
+        }
+    }
+
+    async fn authenticate(&self, key: &str) -> Result<ApiKeyPrincipal, String> {
+        let api_key = self
+            .repository
+            .find_by_hash(&hash_secret(key))
+            .await?
+            .ok_or_else(|| "Invalid API key".to_string())?;
+        if api_key.is_expired() {
+            return Err("API key has expired".to_string());
+        }
+        let user = self
+            .user_service
+            .find_by_id(&api_key.user_id)
+            .await?
+            .ok_or_else(|| "Invalid API key".to_string())?;
+        if user.is_suspended() {
+            return Err("Account is suspended".to_string());
+        }
+
+        let now = DateTime::now();
+        let stale = api_key.last_used_at.is_none_or(|last_used_at| {
+            now.timestamp_millis() - last_used_at.timestamp_millis()
+                >= LAST_USED_PRECISION_SECS * 1000
+        });
+        if stale {
+            if let Some(id) = api_key.id {
+                if let Err(e) = self.repository.touch(&id, now).await {
+                    tracing::warn!(error = %e, "Failed to record API key use");
+                }
+            }
+        }
+
+        Ok(ApiKeyPrincipal {
+            user_id: api_key.user_id,
+            email: user.email,
+            scopes: api_key.scopes,
+        })
+    }
+}
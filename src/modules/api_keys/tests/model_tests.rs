@@ -0,0 +1,127 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use rocket::http::Method;
+
+use crate::modules::api_keys::model::{ApiKey, ApiKeyResponse, ApiKeyScope, CreateApiKeyDto};
+
+fn create_test_api_key() -> ApiKey {
+    ApiKey {
+        id: Some(ObjectId::new()),
+        user_id: ObjectId::new(),
+        name: "CI bot".to_string(),
+        prefix: "torvi_0123ab".to_string(),
+        key_hash: "hash".to_string(),
+        scopes: vec![ApiKeyScope::TournamentsRead],
+        expires_at: None,
+        last_used_at: None,
+        created_at: DateTime::now(),
+    }
+}
+
+#[test]
+fn test_scope_serialization() {
+    // Act
+    let json = serde_json::to_string(&vec![
+        ApiKeyScope::TournamentsRead,
+        ApiKeyScope::TournamentsWrite,
+        ApiKeyScope::ImagesWrite,
+    ])
+    .unwrap();
+
+    // Assert
+    assert_eq!(
+        json,
+        r#"["tournaments:read","tournaments:write","images:write"]"#
+    );
+}
+
+#[test]
+fn test_create_dto_rejects_unknown_scope() {
+    // Act
+    let result =
+        serde_json::from_str::<CreateApiKeyDto>(r#"{"name": "bot", "scopes": ["users:admin"]}"#);
+
+    // Assert
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_required_scope_for_routes() {
+    assert_eq!(
+        ApiKeyScope::required_for(Method::Get, "/api/tournaments", "/api/tournaments/<id>"),
+        Some(ApiKeyScope::TournamentsRead)
+    );
+    assert_eq!(
+        ApiKeyScope::required_for(Method::Post, "/api/tournaments", "/api/tournaments"),
+        Some(ApiKeyScope::TournamentsWrite)
+    );
+    assert_eq!(
+        ApiKeyScope::required_for(Method::Delete, "/api/opponents", "/api/opponents/<id>"),
+        Some(ApiKeyScope::TournamentsWrite)
+    );
+    assert_eq!(
+        ApiKeyScope::required_for(Method::Post, "/api/images", "/api/images/upload"),
+        Some(ApiKeyScope::ImagesWrite)
+    );
+    assert_eq!(
+        ApiKeyScope::required_for(Method::Get, "/api/users", "/api/users/me"),
+        None
+    );
+    assert_eq!(
+        ApiKeyScope::required_for(Method::Post, "/api/users", "/api/users/me/api-keys"),
+        None
+    );
+}
+
+#[test]
+fn test_membership_routes_require_admin_scope() {
+    for (method, path) in [
+        (Method::Post, "/api/tournaments/<id>/members"),
+        (Method::Delete, "/api/tournaments/<id>/members/<user_id>"),
+        (Method::Put, "/api/tournaments/<id>/participants/<voter_id>"),
+        (Method::Delete, "/api/tournaments/<id>/participants/<voter_id>"),
+        (Method::Post, "/api/tournaments/<id>/transfer"),
+    ] {
+        assert_eq!(
+            ApiKeyScope::required_for(method, "/api/tournaments", path),
+            Some(ApiKeyScope::TournamentsAdmin)
+        );
+    }
+    assert_eq!(
+        ApiKeyScope::required_for(Method::Get, "/api/tournaments", "/api/tournaments/<id>/members"),
+        Some(ApiKeyScope::TournamentsRead)
+    );
+}
+
+#[test]
+fn test_api_key_expiry() {
+    // Arrange
+    let mut api_key = create_test_api_key();
+
+    // Assert
+    assert!(!api_key.is_expired());
+    api_key.expires_at = Some(DateTime::from_millis(
+        DateTime::now().timestamp_millis() - 1000,
+    ));
+    assert!(api_key.is_expired());
+    api_key.expires_at = Some(DateTime::from_millis(
+        DateTime::now().timestamp_millis() + 60_000,
+    ));
+    assert!(!api_key.is_expired());
+}
+
+#[test]
+fn test_api_key_response_hides_hash() {
+    // Arrange
+    let api_key = create_test_api_key();
+
+    // Act
+    let json = serde_json::to_value(ApiKeyResponse::from(api_key.clone())).unwrap();
+
+    // Assert
+    assert_eq!(json["id"], api_key.id.unwrap().to_hex());
+    assert_eq!(json["prefix"], "torvi_0123ab");
+    assert_eq!(json["scopes"][0], "tournaments:read");
+    assert!(json["expires_at"].is_null());
+    assert!(json.get("key_hash").is_none());
+    assert!(json.get("user_id").is_none());
+}
@@ -0,0 +1,329 @@
+use async_trait::async_trait;
+use mockall::mock;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use std::sync::{Arc, Mutex};
+
+use crate::common::secrets::hash_secret;
+use crate::modules::api_keys::model::{
+    ApiKey, ApiKeyScope, CreateApiKeyDto, UpdateApiKeyDto, API_KEY_PREFIX,
+};
+use crate::modules::api_keys::repository::ApiKeyRepository;
+use crate::modules::api_keys::service::{ApiKeyService, ApiKeyServiceImpl};
+use crate::modules::users::model::{TwoFactor, UpdateUserDto, User};
+use crate::modules::users::service::UserService;
+
+mock! {
+    ApiKeyRepo {}
+
+    #[async_trait]
+    impl ApiKeyRepository for ApiKeyRepo {
+        async fn create(&self, api_key: &ApiKey) -> Result<(), String>;
+        async fn find_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, String>;
+        async fn find_by_user(&self, user_id: &ObjectId) -> Result<Vec<ApiKey>, String>;
+        async fn find_for_user(&self, user_id: &ObjectId, id: &ObjectId) -> Result<Option<ApiKey>, String>;
+        async fn update(&self, api_key: &ApiKey) -> Result<(), String>;
+        async fn delete_for_user(&self, user_id: &ObjectId, id: &ObjectId) -> Result<bool, String>;
+        async fn delete_all_for_user(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn touch(&self, id: &ObjectId, used_at: DateTime) -> Result<(), String>;
+    }
+}
+
+mock! {
+    UserService {}
+
+    #[async_trait]
+    impl UserService for UserService {
+        async fn create_user(&self, email: String, name: String, password: String) -> Result<User, String>;
+        async fn provision_user(&self, email: String, name: String) -> Result<User, String>;
+        async fn find_by_email(&self, email: &str) -> Result<Option<User>, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<User>, String>;
+        async fn verify_credentials(&self, email: &str, password: &str) -> Result<Option<User>, String>;
+        async fn update_user(&self, id: &ObjectId, dto: UpdateUserDto) -> Result<User, String>;
+        async fn mark_email_verified(&self, id: &ObjectId) -> Result<User, String>;
+        async fn update_two_factor(&self, id: &ObjectId, two_factor: TwoFactor) -> Result<User, String>;
+        async fn consume_recovery_code(&self, id: &ObjectId, code_hash: &str) -> Result<bool, String>;
+        fn check_password(&self, password: &str, email: &str, name: &str) -> Result<(), crate::modules::users::password_policy::PasswordPolicyError>;
+    }
+}
+
+fn create_test_user() -> User {
+    let mut user = User::new(
+        "bot@example.com".to_string(),
+        "Bot Owner".to_string(),
+        "$2b$12$hashedpassword".to_string(),
+    );
+    user.id = Some(ObjectId::new());
+    user
+}
+
+fn create_test_api_key(user_id: ObjectId) -> ApiKey {
+    ApiKey {
+        id: Some(ObjectId::new()),
+        user_id,
+        name: "CI bot".to_string(),
+        prefix: "torvi_0123ab".to_string(),
+        key_hash: hash_secret("torvi_secret"),
+        scopes: vec![ApiKeyScope::TournamentsRead],
+        expires_at: None,
+        last_used_at: None,
+        created_at: DateTime::now(),
+    }
+}
+
+fn service(repo: MockApiKeyRepo, user_service: MockUserService) -> ApiKeyServiceImpl {
+    ApiKeyServiceImpl::new(Arc::new(repo), Arc::new(user_service))
+}
+
+#[tokio::test]
+async fn test_create_key_stores_only_hash() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let stored = Arc::new(Mutex::new(None));
+    let captured = stored.clone();
+    let mut mock_repo = MockApiKeyRepo::new();
+    mock_repo
+        .expect_create()
+        .times(1)
+        .returning(move |api_key| {
+            *captured.lock().unwrap() = Some(api_key.clone());
+            Ok(())
+        });
+    let service = service(mock_repo, MockUserService::new());
+
+    // Act
+    let result = service
+        .create_key(
+            &user_id,
+            CreateApiKeyDto {
+                name: "  CI bot ".to_string(),
+                scopes: vec![
+                    ApiKeyScope::TournamentsWrite,
+                    ApiKeyScope::TournamentsWrite,
+                    ApiKeyScope::ImagesWrite,
+                ],
+                expires_in_days: Some(30),
+            },
+        )
+        .await;
+
+    // Assert
+    let created = result.unwrap();
+    assert!(created.key.starts_with(API_KEY_PREFIX));
+    assert!(created.key.starts_with(&created.api_key.prefix));
+    let api_key = stored.lock().unwrap().clone().unwrap();
+    assert_eq!(api_key.user_id, user_id);
+    assert_eq!(api_key.name, "CI bot");
+    assert_eq!(api_key.key_hash, hash_secret(&created.key));
+    assert_ne!(api_key.key_hash, created.key);
+    assert_eq!(
+        api_key.scopes,
+        vec![ApiKeyScope::TournamentsWrite, ApiKeyScope::ImagesWrite]
+    );
+    let expires_in =
+        api_key.expires_at.unwrap().timestamp_millis() - api_key.created_at.timestamp_millis();
+    assert_eq!(expires_in, 30 * 86_400_000);
+}
+
+#[tokio::test]
+async fn test_create_key_requires_scope() {
+    // Arrange
+    let mut mock_repo = MockApiKeyRepo::new();
+    mock_repo.expect_create().times(0);
+    let service = service(mock_repo, MockUserService::new());
+
+    // Act
+    let result = service
+        .create_key(
+            &ObjectId::new(),
+            CreateApiKeyDto {
+                name: "CI bot".to_string(),
+                scopes: vec![],
+                expires_in_days: None,
+            },
+        )
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "At least one scope is required");
+}
+
+#[tokio::test]
+async fn test_create_key_requires_name() {
+    // Arrange
+    let service = service(MockApiKeyRepo::new(), MockUserService::new());
+
+    // Act
+    let result = service
+        .create_key(
+            &ObjectId::new(),
+            CreateApiKeyDto {
+                name: "   ".to_string(),
+                scopes: vec![ApiKeyScope::TournamentsRead],
+                expires_in_days: None,
+            },
+        )
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "API key name is required");
+}
+
+#[tokio::test]
+async fn test_update_key_changes_name_and_scopes() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let api_key = create_test_api_key(user_id);
+    let id = api_key.id.unwrap();
+    let mut mock_repo = MockApiKeyRepo::new();
+    mock_repo
+        .expect_find_for_user()
+        .returning(move |_, _| Ok(Some(api_key.clone())));
+    mock_repo
+        .expect_update()
+        .withf(|api_key| {
+            api_key.name == "Deploy bot" && api_key.scopes == vec![ApiKeyScope::ImagesWrite]
+        })
+        .times(1)
+        .returning(|_| Ok(()));
+    let service = service(mock_repo, MockUserService::new());
+
+    // Act
+    let result = service
+        .update_key(
+            &user_id,
+            &id,
+            UpdateApiKeyDto {
+                name: Some("Deploy bot".to_string()),
+                scopes: Some(vec![ApiKeyScope::ImagesWrite]),
+            },
+        )
+        .await;
+
+    // Assert
+    let response = result.unwrap();
+    assert_eq!(response.name, "Deploy bot");
+    assert_eq!(response.id, id);
+}
+
+#[tokio::test]
+async fn test_get_key_of_other_user_not_found() {
+    // Arrange
+    let mut mock_repo = MockApiKeyRepo::new();
+    mock_repo.expect_find_for_user().returning(|_, _| Ok(None));
+    let service = service(mock_repo, MockUserService::new());
+
+    // Act
+    let result = service.get_key(&ObjectId::new(), &ObjectId::new()).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "API key not found");
+}
+
+#[tokio::test]
+async fn test_delete_key_not_found() {
+    // Arrange
+    let mut mock_repo = MockApiKeyRepo::new();
+    mock_repo
+        .expect_delete_for_user()
+        .times(1)
+        .returning(|_, _| Ok(false));
+    let service = service(mock_repo, MockUserService::new());
+
+    // Act
+    let result = service.delete_key(&ObjectId::new(), &ObjectId::new()).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "API key not found");
+}
+
+#[tokio::test]
+async fn test_authenticate_records_use() {
+    // Arrange
+    let user = create_test_user();
+    let api_key = create_test_api_key(user.id.unwrap());
+    let key_id = api_key.id.unwrap();
+    let mut mock_repo = MockApiKeyRepo::new();
+    mock_repo
+        .expect_find_by_hash()
+        .withf(|hash| hash == hash_secret("torvi_secret"))
+        .returning(move |_| Ok(Some(api_key.clone())));
+    mock_repo
+        .expect_touch()
+        .withf(move |id, _| *id == key_id)
+        .times(1)
+        .returning(|_, _| Ok(()));
+    let mut mock_user_service = MockUserService::new();
+    let found = user.clone();
+    mock_user_service
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(found.clone())));
+    let service = service(mock_repo, mock_user_service);
+
+    // Act
+    let result = service.authenticate("torvi_secret").await;
+
+    // Assert
+    let principal = result.unwrap();
+    assert_eq!(principal.user_id, user.id.unwrap());
+    assert_eq!(principal.email, "bot@example.com");
+    assert_eq!(principal.scopes, vec![ApiKeyScope::TournamentsRead]);
+}
+
+#[tokio::test]
+async fn test_authenticate_skips_recent_use() {
+    // Arrange
+    let user = create_test_user();
+    let mut api_key = create_test_api_key(user.id.unwrap());
+    api_key.last_used_at = Some(DateTime::now());
+    let mut mock_repo = MockApiKeyRepo::new();
+    mock_repo
+        .expect_find_by_hash()
+        .returning(move |_| Ok(Some(api_key.clone())));
+    mock_repo.expect_touch().times(0);
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    let service = service(mock_repo, mock_user_service);
+
+    // Act
+    let result = service.authenticate("torvi_secret").await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_authenticate_expired_key() {
+    // Arrange
+    let mut api_key = create_test_api_key(ObjectId::new());
+    api_key.expires_at = Some(DateTime::from_millis(
+        DateTime::now().timestamp_millis() - 1000,
+    ));
+    let mut mock_repo = MockApiKeyRepo::new();
+    mock_repo
+        .expect_find_by_hash()
+        .returning(move |_| Ok(Some(api_key.clone())));
+    mock_repo.expect_touch().times(0);
+    let service = service(mock_repo, MockUserService::new());
+
+    // Act
+    let result = service.authenticate("torvi_secret").await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "API key has expired");
+}
+
+#[tokio::test]
+async fn test_authenticate_unknown_key() {
+    // Arrange
+    let mut mock_repo = MockApiKeyRepo::new();
+    mock_repo.expect_find_by_hash().returning(|_| Ok(None));
+    let service = service(mock_repo, MockUserService::new());
+
+    // Act
+    let result = service.authenticate("torvi_unknown").await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invalid API key");
+}
@@ -1,24 +1,141 @@
 use std::sync::Arc;
 
+use jsonwebtoken::jwk::JwkSet;
+use mongodb::bson::oid::ObjectId;
 use rocket::serde::json::Json;
 use rocket::State;
 
+use crate::common::guards::AuthenticatedUser;
+use crate::config::client_info::ClientInfo;
 use crate::error::Error;
+use crate::modules::auth::keys::JwtKeys;
 use crate::modules::auth::model::{
-    AnonymousTokenRequest, AnonymousTokenResponse, LoginDto, LoginResponse, RefreshRequest,
-    RefreshResponse, RegisterDto,
+    AnonymousClaims, AnonymousTokenRequest, AnonymousTokenResponse, ForgotPasswordDto, LoginDto,
+    LoginResponse, LoginResult, MagicLinkRedeemDto, MagicLinkRequestDto, MfaLoginDto,
+    OidcAuthorizeResponse, OidcCallbackDto, OidcProvidersResponse, RecoveryCodesResponse,
+    RefreshRequest, RefreshResponse, RegisterDto, ResetPasswordDto, TotpCodeDto,
+    TotpEnrollmentResponse, VerifyEmailDto,
 };
-use crate::modules::auth::service::AuthService;
+use crate::modules::auth::service::{AuthService, OidcService};
+use crate::modules::rate_limit::guard::{AnonymousRateLimit, LoginRateLimit};
+use crate::modules::rate_limit::service::RateLimitService;
+use crate::modules::tournaments::service::TournamentService;
+use crate::modules::users::service::UserService;
+
+fn verify_anonymous_session(
+    auth_service: &Arc<dyn AuthService + Send + Sync>,
+    anonymous_token: Option<&str>,
+) -> Result<Option<AnonymousClaims>, Error> {
+    anonymous_token
+        .map(|token| {
+            auth_service
+                .verify_anonymous_token(token)
+                .map_err(|_| Error::BadRequest("Invalid anonymous token".to_string()))
+        })
+        .transpose()
+}
+
+/// Hands the votes of an anonymous session to the account that just signed
+/// in. The session has already been issued at this point, so a failure is
+/// logged rather than failing the login.
+async fn upgrade_anonymous_session(
+    tournament_service: &Arc<dyn TournamentService + Send + Sync>,
+    claims: &AnonymousClaims,
+    response: &LoginResponse,
+) {
+    let Ok(tournament_id) = ObjectId::parse_str(&claims.tournament_id) else {
+        tracing::warn!(
+            session_id = %claims.sub,
+            "Anonymous token names an invalid tournament; session not claimed"
+        );
+        return;
+    };
+
+    if let Err(e) = tournament_service
+        .claim_anonymous_session(
+            &tournament_id,
+            &claims.sub,
+            response.user.id,
+            response.user.name.clone(),
+        )
+        .await
+    {
+        tracing::error!(
+            error = %e,
+            tournament_id = %tournament_id,
+            session_id = %claims.sub,
+            "Failed to claim anonymous session after login"
+        );
+    }
+}
 
 #[post("/login", data = "<login_dto>")]
 pub async fn login(
+    _rate_limit: LoginRateLimit,
     auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    tournament_service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    rate_limit_service: &State<Arc<dyn RateLimitService + Send + Sync>>,
+    client: ClientInfo,
     login_dto: Json<LoginDto>,
+) -> Result<Json<LoginResult>, Error> {
+    let anonymous =
+        verify_anonymous_session(auth_service, login_dto.anonymous_token.as_deref())?;
+
+    rate_limit_service.check_account(&login_dto.email).await?;
+    let result = match auth_service
+        .login(&login_dto.email, &login_dto.password, &client)
+        .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            rate_limit_service.record_login_failure(&login_dto.email).await;
+            return Err(Error::Unauthorized(e));
+        }
+    };
+
+    // Failed attempts only reset once the second factor has been checked too
+    if let LoginResult::Session(response) = &result {
+        rate_limit_service.record_login_success(&login_dto.email).await;
+        if let Some(claims) = anonymous {
+            upgrade_anonymous_session(tournament_service, &claims, response).await;
+        }
+    }
+
+    Ok(Json(result))
+}
+
+#[post("/login/mfa", data = "<mfa_dto>")]
+pub async fn login_mfa(
+    _rate_limit: LoginRateLimit,
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    tournament_service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    rate_limit_service: &State<Arc<dyn RateLimitService + Send + Sync>>,
+    client: ClientInfo,
+    mfa_dto: Json<MfaLoginDto>,
 ) -> Result<Json<LoginResponse>, Error> {
-    let response = auth_service
-        .login(&login_dto.email, &login_dto.password)
+    let anonymous = verify_anonymous_session(auth_service, mfa_dto.anonymous_token.as_deref())?;
+    let challenge = auth_service
+        .verify_token(&mfa_dto.mfa_token)
+        .map_err(|_| Error::Unauthorized("Invalid MFA token".to_string()))?;
+
+    rate_limit_service.check_account(&challenge.email).await?;
+    let response = match auth_service
+        .complete_mfa_login(&mfa_dto.mfa_token, &mfa_dto.code, &client)
         .await
-        .map_err(|e| Error::Unauthorized(e))?;
+    {
+        Ok(response) => {
+            rate_limit_service.record_login_success(&challenge.email).await;
+            response
+        }
+        Err(e) => {
+            rate_limit_service.record_login_failure(&challenge.email).await;
+            return Err(Error::Unauthorized(e));
+        }
+    };
+
+    if let Some(claims) = anonymous {
+        upgrade_anonymous_session(tournament_service, &claims, &response).await;
+    }
 
     Ok(Json(response))
 }
@@ -26,34 +143,83 @@ pub async fn login(
 #[post("/refresh", data = "<refresh_dto>")]
 pub async fn refresh(
     auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    client: ClientInfo,
     refresh_dto: Json<RefreshRequest>,
 ) -> Result<Json<RefreshResponse>, Error> {
     let response = auth_service
-        .refresh_tokens(&refresh_dto.refresh_token)
+        .refresh_tokens(&refresh_dto.refresh_token, &client)
+        .await
         .map_err(|e| Error::Unauthorized(e))?;
 
     Ok(Json(response))
 }
 
+#[post("/logout", data = "<refresh_dto>")]
+pub async fn logout(
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    refresh_dto: Json<RefreshRequest>,
+) -> Result<Json<serde_json::Value>, Error> {
+    auth_service
+        .logout(&refresh_dto.refresh_token)
+        .await
+        .map_err(Error::Unauthorized)?;
+
+    Ok(Json(serde_json::json!({ "message": "Logged out successfully" })))
+}
+
+#[post("/logout-all")]
+pub async fn logout_all(
+    auth: AuthenticatedUser,
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+) -> Result<Json<serde_json::Value>, Error> {
+    auth_service
+        .logout_all(&auth.user_id)
+        .await
+        .map_err(Error::Internal)?;
+
+    Ok(Json(
+        serde_json::json!({ "message": "Logged out of all sessions successfully" }),
+    ))
+}
+
 #[post("/register", data = "<register_dto>")]
 pub async fn register(
     auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    user_service: &State<Arc<dyn UserService + Send + Sync>>,
+    tournament_service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    client: ClientInfo,
     register_dto: Json<RegisterDto>,
 ) -> Result<Json<LoginResponse>, Error> {
+    let anonymous =
+        verify_anonymous_session(auth_service, register_dto.anonymous_token.as_deref())?;
+
+    // Checked here as well so the response lists every failed rule
+    user_service.check_password(
+        &register_dto.password,
+        &register_dto.email,
+        &register_dto.name,
+    )?;
+
     let response = auth_service
         .register(
             &register_dto.email,
             &register_dto.name,
             &register_dto.password,
+            &client,
         )
         .await
         .map_err(|e| Error::BadRequest(e))?;
 
+    if let Some(claims) = anonymous {
+        upgrade_anonymous_session(tournament_service, &claims, &response).await;
+    }
+
     Ok(Json(response))
 }
 
 #[post("/anonymous", data = "<request>")]
 pub async fn anonymous_token(
+    _rate_limit: AnonymousRateLimit,
     auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
     request: Json<AnonymousTokenRequest>,
 ) -> Result<Json<AnonymousTokenResponse>, Error> {
@@ -68,6 +234,248 @@ pub async fn anonymous_token(
     Ok(Json(response))
 }
 
+#[post("/password/forgot", data = "<forgot_dto>")]
+pub async fn forgot_password(
+    _rate_limit: LoginRateLimit,
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    rate_limit_service: &State<Arc<dyn RateLimitService + Send + Sync>>,
+    forgot_dto: Json<ForgotPasswordDto>,
+) -> Result<Json<serde_json::Value>, Error> {
+    rate_limit_service
+        .check_password_reset(&forgot_dto.email)
+        .await?;
+    auth_service
+        .request_password_reset(&forgot_dto.email)
+        .await
+        .map_err(Error::Internal)?;
+
+    Ok(Json(serde_json::json!({
+        "message": "If an account exists for that email, a reset link has been sent"
+    })))
+}
+
+#[post("/password/reset", data = "<reset_dto>")]
+pub async fn reset_password(
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    user_service: &State<Arc<dyn UserService + Send + Sync>>,
+    reset_dto: Json<ResetPasswordDto>,
+) -> Result<Json<serde_json::Value>, Error> {
+    user_service.check_password(&reset_dto.password, "", "")?;
+
+    auth_service
+        .reset_password(&reset_dto.token, &reset_dto.password)
+        .await
+        .map_err(Error::BadRequest)?;
+
+    Ok(Json(serde_json::json!({ "message": "Password reset successfully" })))
+}
+
+#[post("/magic-link", data = "<magic_link_dto>")]
+pub async fn request_magic_link(
+    _rate_limit: LoginRateLimit,
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    rate_limit_service: &State<Arc<dyn RateLimitService + Send + Sync>>,
+    magic_link_dto: Json<MagicLinkRequestDto>,
+) -> Result<Json<serde_json::Value>, Error> {
+    rate_limit_service
+        .check_magic_link(&magic_link_dto.email)
+        .await?;
+    auth_service
+        .request_magic_link(&magic_link_dto.email)
+        .await
+        .map_err(Error::Internal)?;
+
+    Ok(Json(serde_json::json!({
+        "message": "If an account exists for that email, a login link has been sent"
+    })))
+}
+
+#[post("/magic-link/redeem", data = "<redeem_dto>")]
+pub async fn redeem_magic_link(
+    _rate_limit: LoginRateLimit,
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    tournament_service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    client: ClientInfo,
+    redeem_dto: Json<MagicLinkRedeemDto>,
+) -> Result<Json<LoginResult>, Error> {
+    let anonymous =
+        verify_anonymous_session(auth_service, redeem_dto.anonymous_token.as_deref())?;
+
+    let result = auth_service
+        .redeem_magic_link(&redeem_dto.token, &client)
+        .await
+        .map_err(Error::Unauthorized)?;
+
+    if let (LoginResult::Session(response), Some(claims)) = (&result, anonymous) {
+        upgrade_anonymous_session(tournament_service, &claims, response).await;
+    }
+
+    Ok(Json(result))
+}
+
+#[post("/email/verify", data = "<verify_dto>")]
+pub async fn verify_email(
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    verify_dto: Json<VerifyEmailDto>,
+) -> Result<Json<serde_json::Value>, Error> {
+    auth_service
+        .verify_email(&verify_dto.token)
+        .await
+        .map_err(Error::BadRequest)?;
+
+    Ok(Json(serde_json::json!({ "message": "Email verified successfully" })))
+}
+
+#[post("/email/resend")]
+pub async fn resend_verification_email(
+    _rate_limit: LoginRateLimit,
+    auth: AuthenticatedUser,
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    rate_limit_service: &State<Arc<dyn RateLimitService + Send + Sync>>,
+) -> Result<Json<serde_json::Value>, Error> {
+    rate_limit_service
+        .check_verification_email(&auth.email)
+        .await?;
+    auth_service
+        .send_verification_email(&auth.user_id)
+        .await
+        .map_err(Error::BadRequest)?;
+
+    Ok(Json(serde_json::json!({ "message": "Verification email sent" })))
+}
+
+#[post("/totp/enroll")]
+pub async fn totp_enroll(
+    auth: AuthenticatedUser,
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+) -> Result<Json<TotpEnrollmentResponse>, Error> {
+    let response = auth_service
+        .start_totp_enrollment(&auth.user_id)
+        .await
+        .map_err(Error::BadRequest)?;
+
+    Ok(Json(response))
+}
+
+#[post("/totp/confirm", data = "<code_dto>")]
+pub async fn totp_confirm(
+    auth: AuthenticatedUser,
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    code_dto: Json<TotpCodeDto>,
+) -> Result<Json<RecoveryCodesResponse>, Error> {
+    let response = auth_service
+        .confirm_totp_enrollment(&auth.user_id, &code_dto.code)
+        .await
+        .map_err(Error::BadRequest)?;
+
+    Ok(Json(response))
+}
+
+#[post("/totp/disable", data = "<code_dto>")]
+pub async fn totp_disable(
+    auth: AuthenticatedUser,
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    code_dto: Json<TotpCodeDto>,
+) -> Result<Json<serde_json::Value>, Error> {
+    auth_service
+        .disable_totp(&auth.user_id, &code_dto.code)
+        .await
+        .map_err(Error::BadRequest)?;
+
+    Ok(Json(serde_json::json!({ "message": "Two-factor authentication disabled" })))
+}
+
+#[post("/totp/recovery-codes", data = "<code_dto>")]
+pub async fn totp_recovery_codes(
+    auth: AuthenticatedUser,
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    code_dto: Json<TotpCodeDto>,
+) -> Result<Json<RecoveryCodesResponse>, Error> {
+    let response = auth_service
+        .regenerate_recovery_codes(&auth.user_id, &code_dto.code)
+        .await
+        .map_err(Error::BadRequest)?;
+
+    Ok(Json(response))
+}
+
+#[get("/oidc/providers")]
+pub async fn oidc_providers(
+    oidc_service: &State<Arc<dyn OidcService + Send + Sync>>,
+) -> Json<OidcProvidersResponse> {
+    Json(OidcProvidersResponse {
+        providers: oidc_service.providers(),
+    })
+}
+
+#[get("/oidc/<provider>/authorize")]
+pub async fn oidc_authorize(
+    oidc_service: &State<Arc<dyn OidcService + Send + Sync>>,
+    provider: &str,
+) -> Result<Json<OidcAuthorizeResponse>, Error> {
+    let authorization_url = oidc_service
+        .authorization_url(provider)
+        .await
+        .map_err(Error::BadRequest)?;
+
+    Ok(Json(OidcAuthorizeResponse { authorization_url }))
+}
+
+#[post("/oidc/<provider>/callback", data = "<callback_dto>")]
+pub async fn oidc_callback(
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    oidc_service: &State<Arc<dyn OidcService + Send + Sync>>,
+    tournament_service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    client: ClientInfo,
+    provider: &str,
+    callback_dto: Json<OidcCallbackDto>,
+) -> Result<Json<LoginResponse>, Error> {
+    let anonymous =
+        verify_anonymous_session(auth_service, callback_dto.anonymous_token.as_deref())?;
+
+    let response = oidc_service
+        .login(provider, &callback_dto.code, &callback_dto.state, &client)
+        .await
+        .map_err(Error::Unauthorized)?;
+
+    if let Some(claims) = anonymous {
+        upgrade_anonymous_session(tournament_service, &claims, &response).await;
+    }
+
+    Ok(Json(response))
+}
+
+/// Public keys for verifying tokens issued by this server.
+#[get("/jwks.json")]
+pub fn jwks(keys: &State<Arc<JwtKeys>>) -> Json<JwkSet> {
+    Json(keys.jwks().clone())
+}
+
 pub fn routes() -> Vec<rocket::Route> {
-    routes![login, refresh, register, anonymous_token]
+    routes![
+        login,
+        login_mfa,
+        refresh,
+        logout,
+        logout_all,
+        register,
+        anonymous_token,
+        forgot_password,
+        reset_password,
+        request_magic_link,
+        redeem_magic_link,
+        verify_email,
+        resend_verification_email,
+        totp_enroll,
+        totp_confirm,
+        totp_disable,
+        totp_recovery_codes,
+        oidc_providers,
+        oidc_authorize,
+        oidc_callback
+    ]
+}
+
+pub fn well_known_routes() -> Vec<rocket::Route> {
+    routes![jwks]
 }
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::errors::{Error as JwtError, ErrorKind};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+    OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+};
+use jsonwebtoken::Validation;
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header};
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPrivateKey;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::config::jwt::JwtConfig;
+
+struct VerificationKey {
+    algorithm: Algorithm,
+    key: DecodingKey,
+}
+
+/// Keys used to sign and verify Torvi tokens. New tokens are signed with the
+/// active key and carry its `kid`; every other configured key still verifies,
+/// so switching the active key does not invalidate tokens already issued.
+pub struct JwtKeys {
+    signing_kid: Option<String>,
+    signing_algorithm: Algorithm,
+    signing_key: EncodingKey,
+    verification: HashMap<String, VerificationKey>,
+    /// HS256 secret for tokens issued without a `kid`
+    legacy: Option<DecodingKey>,
+    jwks: JwkSet,
+}
+
+impl JwtKeys {
+    /// Signs and verifies with a single shared HS256 secret. Nothing is
+    /// published in the JWKS.
+    pub fn from_secret(secret: &str) -> Self {
+        Self {
+            signing_kid: None,
+            signing_algorithm: Algorithm::HS256,
+            signing_key: EncodingKey::from_secret(secret.as_bytes()),
+            verification: HashMap::new(),
+            legacy: Some(DecodingKey::from_secret(secret.as_bytes())),
+            jwks: JwkSet { keys: Vec::new() },
+        }
+    }
+
+    /// Builds the key set from `(kid, private key PEM)` pairs.
+    /// `legacy_secret` keeps HS256 tokens without a `kid` valid.
+    pub fn from_pem_keys(
+        keys: &[(String, String)],
+        active_key_id: &str,
+        legacy_secret: Option<&str>,
+    ) -> Result<Self, String> {
+        let mut verification = HashMap::new();
+        let mut published = Vec::new();
+        let mut signing = None;
+
+        for (kid, pem) in keys {
+            let (algorithm, encoding_key, jwk) = parse_private_key(kid, pem)?;
+            let key = DecodingKey::from_jwk(&jwk)
+                .map_err(|e| format!("Invalid JWT key {}: {}", kid, e))?;
+
+            if kid == active_key_id {
+                signing = Some((algorithm, encoding_key));
+            }
+            verification.insert(kid.clone(), VerificationKey { algorithm, key });
+            published.push(jwk);
+        }
+
+        let (signing_algorithm, signing_key) =
+            signing.ok_or_else(|| format!("Unknown active JWT key: {}", active_key_id))?;
+
+        Ok(Self {
+            signing_kid: Some(active_key_id.to_string()),
+            signing_algorithm,
+            signing_key,
+            verification,
+            legacy: legacy_secret.map(|secret| DecodingKey::from_secret(secret.as_bytes())),
+            jwks: JwkSet { keys: published },
+        })
+    }
+
+    /// Loads every `<kid>.pem` file from `JWT_KEYS_DIR`, or falls back to
+    /// the HS256 secret when no directory is configured.
+    pub fn from_config(config: &JwtConfig) -> Result<Self, String> {
+        let Some(dir) = &config.keys_dir else {
+            return Ok(Self::from_secret(
+                config.secret.as_deref().unwrap_or_default(),
+            ));
+        };
+
+        let mut keys = Vec::new();
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| format!("Error reading JWT keys directory: {}", e))?;
+        for entry in entries {
+            let path = entry
+                .map_err(|e| format!("Error reading JWT keys directory: {}", e))?
+                .path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("pem") {
+                continue;
+            }
+            let kid = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .ok_or_else(|| format!("Invalid JWT key file name: {}", path.display()))?
+                .to_string();
+            let pem = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Error reading JWT key {}: {}", kid, e))?;
+            keys.push((kid, pem));
+        }
+        keys.sort();
+
+        let active_key_id = match (&config.active_key_id, keys.as_slice()) {
+            (Some(kid), _) => kid.clone(),
+            (None, [(kid, _)]) => kid.clone(),
+            (None, []) => return Err("No JWT keys found in JWT_KEYS_DIR".to_string()),
+            (None, _) => {
+                return Err(
+                    "JWT_ACTIVE_KEY_ID is required when JWT_KEYS_DIR holds several keys"
+                        .to_string(),
+                )
+            }
+        };
+
+        Self::from_pem_keys(&keys, &active_key_id, config.secret.as_deref())
+    }
+
+    pub fn encode<T: Serialize>(&self, claims: &T) -> Result<String, JwtError> {
+        let mut header = Header::new(self.signing_algorithm);
+        header.kid = self.signing_kid.clone();
+        encode(&header, claims, &self.signing_key)
+    }
+
+    /// Verifies the signature with the key named by the token's `kid` and
+    /// checks expiry.
+    pub fn decode<T: DeserializeOwned>(&self, token: &str) -> Result<T, JwtError> {
+        let header = decode_header(token)?;
+        let (algorithm, key) = match &header.kid {
+            Some(kid) => self
+                .verification
+                .get(kid)
+                .map(|verification| (verification.algorithm, &verification.key)),
+            None => self.legacy.as_ref().map(|key| (Algorithm::HS256, key)),
+        }
+        .ok_or_else(|| JwtError::from(ErrorKind::InvalidToken))?;
+
+        decode::<T>(token, key, &Validation::new(algorithm)).map(|data| data.claims)
+    }
+
+    /// Public keys for `/.well-known/jwks.json`.
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+}
+
+/// Reads an RSA (PKCS#1 or PKCS#8) or Ed25519 (PKCS#8) private key and
+/// returns its signing key and public JWK.
+fn parse_private_key(kid: &str, pem: &str) -> Result<(Algorithm, EncodingKey, Jwk), String> {
+    let invalid = |e: jsonwebtoken::errors::Error| format!("Invalid JWT key {}: {}", kid, e);
+
+    if let Ok(rsa) =
+        RsaPrivateKey::from_pkcs8_pem(pem).or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem))
+    {
+        let encoding_key = EncodingKey::from_rsa_pem(pem.as_bytes()).map_err(invalid)?;
+        let parameters = AlgorithmParameters::RSA(RSAKeyParameters {
+            key_type: RSAKeyType::RSA,
+            n: URL_SAFE_NO_PAD.encode(rsa.n().to_bytes_be()),
+            e: URL_SAFE_NO_PAD.encode(rsa.e().to_bytes_be()),
+        });
+        return Ok((
+            Algorithm::RS256,
+            encoding_key,
+            public_jwk(kid, KeyAlgorithm::RS256, parameters),
+        ));
+    }
+
+    let ed25519 = ed25519_dalek::SigningKey::from_pkcs8_pem(pem).map_err(|_| {
+        format!(
+            "Invalid JWT key {}: expected an RSA or Ed25519 private key",
+            kid
+        )
+    })?;
+    let encoding_key = EncodingKey::from_ed_pem(pem.as_bytes()).map_err(invalid)?;
+    let parameters = AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+        key_type: OctetKeyPairType::OctetKeyPair,
+        curve: EllipticCurve::Ed25519,
+        x: URL_SAFE_NO_PAD.encode(ed25519.verifying_key().to_bytes()),
+    });
+    Ok((
+        Algorithm::EdDSA,
+        encoding_key,
+        public_jwk(kid, KeyAlgorithm::EdDSA, parameters),
+    ))
+}
+
+fn public_jwk(kid: &str, algorithm: KeyAlgorithm, parameters: AlgorithmParameters) -> Jwk {
+    Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(algorithm),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: parameters,
+    }
+}
@@ -1,8 +1,12 @@
 pub mod controller;
+pub mod keys;
 pub mod model;
+pub mod oidc;
+pub mod repository;
 pub mod service;
+pub mod totp;
 
-pub use controller::routes;
+pub use controller::{routes, well_known_routes};
 
 #[cfg(test)]
 mod tests;
@@ -1,12 +1,15 @@
-use crate::common::json::{deserialize_oid, serialize_oid};
-use crate::modules::users::model::User;
-use mongodb::bson::oid::ObjectId;
+use crate::common::json::{deserialize_oid, serialize_datetime, serialize_oid};
+use crate::modules::users::model::{User, UserRole};
+use mongodb::bson::{oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
 pub struct LoginDto {
     pub email: String,
     pub password: String,
+    /// Anonymous session to upgrade into the logged-in account
+    #[serde(default)]
+    pub anonymous_token: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -17,11 +20,55 @@ pub struct LoginResponse {
     pub user: AuthUserResponse,
 }
 
+/// Sent by `/login` in place of tokens when the account has two-factor
+/// authentication on. The token is exchanged at `/login/mfa`.
+#[derive(Debug, Serialize)]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    pub mfa_token: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginResult {
+    Session(LoginResponse),
+    MfaRequired(MfaChallengeResponse),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MfaLoginDto {
+    pub mfa_token: String,
+    /// Current TOTP code or an unused recovery code
+    pub code: String,
+    #[serde(default)]
+    pub anonymous_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TotpEnrollmentResponse {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TotpCodeDto {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RegisterDto {
     pub email: String,
     pub name: String,
     pub password: String,
+    /// Anonymous session to upgrade into the new account
+    #[serde(default)]
+    pub anonymous_token: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -30,14 +77,20 @@ pub struct AuthUserResponse {
     pub id: ObjectId,
     pub email: String,
     pub name: String,
+    pub email_verified: bool,
+    pub two_factor_enabled: bool,
+    pub role: UserRole,
 }
 
 impl From<User> for AuthUserResponse {
     fn from(user: User) -> Self {
         Self {
             id: user.id.expect("User must have an id"),
+            two_factor_enabled: user.two_factor_enabled(),
             email: user.email,
             name: user.name,
+            email_verified: user.email_verified,
+            role: user.role,
         }
     }
 }
@@ -51,6 +104,82 @@ pub struct JwtClaims {
     pub token_type: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshClaims {
+    pub sub: String,
+    pub email: String,
+    pub exp: usize,
+    pub iat: usize,
+    pub token_type: String,
+    #[serde(default)]
+    pub jti: String,
+    /// Token family: every refresh token rotated from the same login shares it
+    #[serde(default)]
+    pub fid: String,
+}
+
+/// Server-side record of an issued refresh token, keyed by its `jti`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshToken {
+    #[serde(rename = "_id")]
+    pub jti: String,
+    pub user_id: ObjectId,
+    pub family_id: String,
+    pub expires_at: DateTime,
+    pub created_at: DateTime,
+    pub rotated_at: Option<DateTime>,
+    pub revoked_at: Option<DateTime>,
+    /// When the login that started this token family happened
+    pub session_started_at: DateTime,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl RefreshToken {
+    pub fn new(jti: String, user_id: ObjectId, family_id: String, expires_at: DateTime) -> Self {
+        let now = DateTime::now();
+        Self {
+            jti,
+            user_id,
+            family_id,
+            expires_at,
+            created_at: now,
+            rotated_at: None,
+            revoked_at: None,
+            session_started_at: now,
+            user_agent: None,
+            ip_address: None,
+        }
+    }
+}
+
+/// A login session, i.e. one refresh token family, as shown to its owner.
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub created_at: DateTime,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub last_used_at: DateTime,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub expires_at: DateTime,
+}
+
+impl From<RefreshToken> for SessionResponse {
+    fn from(token: RefreshToken) -> Self {
+        Self {
+            id: token.family_id,
+            user_agent: token.user_agent,
+            ip_address: token.ip_address,
+            created_at: token.session_started_at,
+            last_used_at: token.created_at,
+            expires_at: token.expires_at,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AnonymousClaims {
     pub sub: String,
@@ -88,6 +217,170 @@ pub struct RefreshResponse {
     pub token_type: String,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TokenPurpose {
+    #[serde(rename = "password_reset")]
+    PasswordReset,
+    #[serde(rename = "email_verification")]
+    EmailVerification,
+    #[serde(rename = "magic_link")]
+    MagicLink,
+}
+
+impl TokenPurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenPurpose::PasswordReset => "password_reset",
+            TokenPurpose::EmailVerification => "email_verification",
+            TokenPurpose::MagicLink => "magic_link",
+        }
+    }
+}
+
+/// Single-use token sent by email. Only the SHA-256 of the emailed token is
+/// stored, together with the address it was sent to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OneTimeToken {
+    #[serde(rename = "_id")]
+    pub token_hash: String,
+    pub user_id: ObjectId,
+    pub email: String,
+    pub purpose: TokenPurpose,
+    pub expires_at: DateTime,
+    pub created_at: DateTime,
+    pub used_at: Option<DateTime>,
+}
+
+impl OneTimeToken {
+    pub fn new(
+        token_hash: String,
+        user_id: ObjectId,
+        email: String,
+        purpose: TokenPurpose,
+        expires_at: DateTime,
+    ) -> Self {
+        Self {
+            token_hash,
+            user_id,
+            email,
+            purpose,
+            expires_at,
+            created_at: DateTime::now(),
+            used_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ForgotPasswordDto {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordDto {
+    pub token: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailDto {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MagicLinkRequestDto {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MagicLinkRedeemDto {
+    pub token: String,
+    /// Anonymous session to upgrade into the logged-in account
+    #[serde(default)]
+    pub anonymous_token: Option<String>,
+}
+
+/// Pending OIDC login, keyed by the `state` sent to the identity provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcLoginState {
+    #[serde(rename = "_id")]
+    pub state: String,
+    pub provider: String,
+    pub nonce: String,
+    pub code_verifier: String,
+    pub expires_at: DateTime,
+    pub created_at: DateTime,
+}
+
+impl OidcLoginState {
+    pub fn new(
+        state: String,
+        provider: String,
+        nonce: String,
+        code_verifier: String,
+        expires_at: DateTime,
+    ) -> Self {
+        Self {
+            state,
+            provider,
+            nonce,
+            code_verifier,
+            expires_at,
+            created_at: DateTime::now(),
+        }
+    }
+}
+
+/// External account at an identity provider linked to a user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserIdentity {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub provider: String,
+    /// The provider's `sub` claim
+    pub subject: String,
+    pub email: Option<String>,
+    pub created_at: DateTime,
+}
+
+impl UserIdentity {
+    pub fn new(
+        user_id: ObjectId,
+        provider: String,
+        subject: String,
+        email: Option<String>,
+    ) -> Self {
+        Self {
+            id: None,
+            user_id,
+            provider,
+            subject,
+            email,
+            created_at: DateTime::now(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct OidcProvidersResponse {
+    pub providers: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OidcAuthorizeResponse {
+    pub authorization_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OidcCallbackDto {
+    pub code: String,
+    pub state: String,
+    /// Anonymous session to upgrade into the logged-in account
+    #[serde(default)]
+    pub anonymous_token: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,265 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use reqwest::Url;
+use serde::{Deserialize, Deserializer};
+use sha2::{Digest, Sha256};
+use tokio::sync::OnceCell;
+
+use crate::config::oidc::OidcProviderConfig;
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Asymmetric algorithms accepted for ID tokens. HMAC is rejected because
+/// it would make the client secret a signing key.
+const ALLOWED_ALGORITHMS: [Algorithm; 8] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::EdDSA,
+];
+
+/// User information taken from a validated ID token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OidcIdentity {
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+}
+
+#[async_trait]
+pub trait OidcProvider: Send + Sync {
+    async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> Result<String, String>;
+    /// Redeems an authorization code and validates the returned ID token.
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> Result<OidcIdentity, String>;
+}
+
+/// S256 PKCE challenge for a code verifier (RFC 7636).
+pub fn code_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_flag")]
+    email_verified: bool,
+    name: Option<String>,
+}
+
+/// Some providers send `email_verified` as the string `"true"`.
+fn deserialize_flag<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        String(String),
+    }
+
+    Ok(match Flag::deserialize(deserializer)? {
+        Flag::Bool(value) => value,
+        Flag::String(value) => value == "true",
+    })
+}
+
+/// Provider reached over HTTP. Endpoints come from the issuer's discovery
+/// document, fetched on first use.
+pub struct HttpOidcProvider {
+    config: OidcProviderConfig,
+    http: reqwest::Client,
+    metadata: OnceCell<ProviderMetadata>,
+}
+
+impl HttpOidcProvider {
+    pub fn new(config: OidcProviderConfig) -> Self {
+        Self {
+            config,
+            http: reqwest::Client::new(),
+            metadata: OnceCell::new(),
+        }
+    }
+
+    async fn metadata(&self) -> Result<&ProviderMetadata, String> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer.trim_end_matches('/')
+                );
+                let metadata: ProviderMetadata = self
+                    .http
+                    .get(&url)
+                    .timeout(HTTP_TIMEOUT)
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|e| format!("Error fetching provider metadata: {}", e))?
+                    .json()
+                    .await
+                    .map_err(|e| format!("Invalid provider metadata: {}", e))?;
+
+                if metadata.issuer != self.config.issuer {
+                    return Err("Provider metadata issuer does not match".to_string());
+                }
+                Ok(metadata)
+            })
+            .await
+    }
+
+    async fn validate_id_token(
+        &self,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<IdTokenClaims, String> {
+        let metadata = self.metadata().await?;
+
+        let header = decode_header(id_token).map_err(|e| format!("Invalid ID token: {}", e))?;
+        if !ALLOWED_ALGORITHMS.contains(&header.alg) {
+            return Err("Unsupported ID token algorithm".to_string());
+        }
+
+        let jwks: JwkSet = self
+            .http
+            .get(&metadata.jwks_uri)
+            .timeout(HTTP_TIMEOUT)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Error fetching provider keys: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid provider keys: {}", e))?;
+
+        let jwk = match &header.kid {
+            Some(kid) => jwks.find(kid),
+            None => jwks.keys.first(),
+        }
+        .ok_or_else(|| "No matching provider key for ID token".to_string())?;
+        let key = DecodingKey::from_jwk(jwk).map_err(|e| format!("Invalid provider key: {}", e))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_audience(&[&self.config.client_id]);
+
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|e| format!("Invalid ID token: {}", e))?
+            .claims;
+
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err("ID token nonce does not match".to_string());
+        }
+        Ok(claims)
+    }
+}
+
+#[async_trait]
+impl OidcProvider for HttpOidcProvider {
+    async fn authorization_url(
+        &self,
+        state: &str,
+        nonce: &str,
+        code_challenge: &str,
+    ) -> Result<String, String> {
+        let metadata = self.metadata().await?;
+        let scope = self.config.scopes.join(" ");
+
+        let url = Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            [
+                ("response_type", "code"),
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", self.config.redirect_url.as_str()),
+                ("scope", scope.as_str()),
+                ("state", state),
+                ("nonce", nonce),
+                ("code_challenge", code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| format!("Invalid authorization endpoint: {}", e))?;
+
+        Ok(url.to_string())
+    }
+
+    async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> Result<OidcIdentity, String> {
+        let metadata = self.metadata().await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.config.redirect_url.as_str()),
+            ("client_id", self.config.client_id.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(secret) = &self.config.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+
+        let response: TokenResponse = self
+            .http
+            .post(&metadata.token_endpoint)
+            .timeout(HTTP_TIMEOUT)
+            .form(&form)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Error exchanging authorization code: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid token response: {}", e))?;
+
+        let id_token = response
+            .id_token
+            .ok_or_else(|| "Token response has no ID token".to_string())?;
+        let claims = self.validate_id_token(&id_token, nonce).await?;
+
+        Ok(OidcIdentity {
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified,
+            name: claims.name,
+        })
+    }
+}
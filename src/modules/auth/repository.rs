@@ -0,0 +1,324 @@
+use crate::modules::auth::model::{
+    OidcLoginState, OneTimeToken, RefreshToken, TokenPurpose, UserIdentity,
+};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::Database;
+
+#[async_trait]
+pub trait RefreshTokenRepository: Send + Sync {
+    async fn create(&self, token: &RefreshToken) -> Result<(), String>;
+    async fn find_by_jti(&self, jti: &str) -> Result<Option<RefreshToken>, String>;
+    /// Atomically marks an unused token as rotated. Returns `false` when the
+    /// token was already rotated or revoked.
+    async fn mark_rotated(&self, jti: &str) -> Result<bool, String>;
+    async fn revoke_family(&self, family_id: &str) -> Result<(), String>;
+    async fn revoke_all_for_user(&self, user_id: &ObjectId) -> Result<(), String>;
+    /// Live tokens of a user: one per active session.
+    async fn find_active_by_user(&self, user_id: &ObjectId) -> Result<Vec<RefreshToken>, String>;
+    /// Revokes a family only if it belongs to the user. Returns `false` when
+    /// nothing was revoked.
+    async fn revoke_family_for_user(
+        &self,
+        user_id: &ObjectId,
+        family_id: &str,
+    ) -> Result<bool, String>;
+}
+
+pub struct RefreshTokenRepositoryImpl {
+    db: Database,
+}
+
+impl RefreshTokenRepositoryImpl {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait]
+impl RefreshTokenRepository for RefreshTokenRepositoryImpl {
+    async fn create(&self, token: &RefreshToken) -> Result<(), String> {
+        self.db
+            .collection::<RefreshToken>("refresh_tokens")
+            .insert_one(token)
+            .await
+            .map_err(|e| format!("Error creating refresh token: {}", e))?;
+        Ok(())
+    }
+
+    async fn find_by_jti(&self, jti: &str) -> Result<Option<RefreshToken>, String> {
+        self.db
+            .collection::<RefreshToken>("refresh_tokens")
+            .find_one(doc! { "_id": jti })
+            .await
+            .map_err(|e| format!("Error finding refresh token: {}", e))
+    }
+
+    async fn mark_rotated(&self, jti: &str) -> Result<bool, String> {
+        let result = self
+            .db
+            .collection::<RefreshToken>("refresh_tokens")
+            .update_one(
+                doc! { "_id": jti, "rotated_at": null, "revoked_at": null },
+                doc! { "$set": { "rotated_at": DateTime::now() } },
+            )
+            .await
+            .map_err(|e| format!("Error rotating refresh token: {}", e))?;
+        Ok(result.modified_count == 1)
+    }
+
+    async fn revoke_family(&self, family_id: &str) -> Result<(), String> {
+        self.db
+            .collection::<RefreshToken>("refresh_tokens")
+            .update_many(
+                doc! { "family_id": family_id, "revoked_at": null },
+                doc! { "$set": { "revoked_at": DateTime::now() } },
+            )
+            .await
+            .map_err(|e| format!("Error revoking refresh tokens: {}", e))?;
+        Ok(())
+    }
+
+    async fn revoke_all_for_user(&self, user_id: &ObjectId) -> Result<(), String> {
+        self.db
+            .collection::<RefreshToken>("refresh_tokens")
+            .update_many(
+                doc! { "user_id": user_id, "revoked_at": null },
+                doc! { "$set": { "revoked_at": DateTime::now() } },
+            )
+            .await
+            .map_err(|e| format!("Error revoking refresh tokens: {}", e))?;
+        Ok(())
+    }
+
+    async fn find_active_by_user(&self, user_id: &ObjectId) -> Result<Vec<RefreshToken>, String> {
+        let cursor = self
+            .db
+            .collection::<RefreshToken>("refresh_tokens")
+            .find(doc! {
+                "user_id": user_id,
+                "rotated_at": null,
+                "revoked_at": null,
+                "expires_at": { "$gt": DateTime::now() },
+            })
+            .sort(doc! { "created_at": -1 })
+            .await
+            .map_err(|e| format!("Error finding sessions: {}", e))?;
+
+        cursor
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error collecting sessions: {}", e))
+    }
+
+    async fn revoke_family_for_user(
+        &self,
+        user_id: &ObjectId,
+        family_id: &str,
+    ) -> Result<bool, String> {
+        let result = self
+            .db
+            .collection::<RefreshToken>("refresh_tokens")
+            .update_many(
+                doc! { "user_id": user_id, "family_id": family_id, "revoked_at": null },
+                doc! { "$set": { "revoked_at": DateTime::now() } },
+            )
+            .await
+            .map_err(|e| format!("Error revoking session: {}", e))?;
+        Ok(result.modified_count > 0)
+    }
+}
+
+#[async_trait]
+pub trait OneTimeTokenRepository: Send + Sync {
+    async fn create(&self, token: &OneTimeToken) -> Result<(), String>;
+    /// Atomically marks an unused, unexpired token as used and returns it.
+    async fn consume(
+        &self,
+        token_hash: &str,
+        purpose: TokenPurpose,
+    ) -> Result<Option<OneTimeToken>, String>;
+    async fn invalidate_for_user(
+        &self,
+        user_id: &ObjectId,
+        purpose: TokenPurpose,
+    ) -> Result<(), String>;
+}
+
+pub struct OneTimeTokenRepositoryImpl {
+    db: Database,
+}
+
+impl OneTimeTokenRepositoryImpl {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait]
+impl OneTimeTokenRepository for OneTimeTokenRepositoryImpl {
+    async fn create(&self, token: &OneTimeToken) -> Result<(), String> {
+        self.db
+            .collection::<OneTimeToken>("one_time_tokens")
+            .insert_one(token)
+            .await
+            .map_err(|e| format!("Error creating one-time token: {}", e))?;
+        Ok(())
+    }
+
+    async fn consume(
+        &self,
+        token_hash: &str,
+        purpose: TokenPurpose,
+    ) -> Result<Option<OneTimeToken>, String> {
+        self.db
+            .collection::<OneTimeToken>("one_time_tokens")
+            .find_one_and_update(
+                doc! {
+                    "_id": token_hash,
+                    "purpose": purpose.as_str(),
+                    "used_at": null,
+                    "expires_at": { "$gt": DateTime::now() },
+                },
+                doc! { "$set": { "used_at": DateTime::now() } },
+            )
+            .await
+            .map_err(|e| format!("Error consuming one-time token: {}", e))
+    }
+
+    async fn invalidate_for_user(
+        &self,
+        user_id: &ObjectId,
+        purpose: TokenPurpose,
+    ) -> Result<(), String> {
+        self.db
+            .collection::<OneTimeToken>("one_time_tokens")
+            .update_many(
+                doc! { "user_id": user_id, "purpose": purpose.as_str(), "used_at": null },
+                doc! { "$set": { "used_at": DateTime::now() } },
+            )
+            .await
+            .map_err(|e| format!("Error invalidating one-time tokens: {}", e))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+pub trait OidcStateRepository: Send + Sync {
+    async fn create(&self, state: &OidcLoginState) -> Result<(), String>;
+    /// Removes and returns an unexpired login state so it can be used once.
+    async fn consume(&self, state: &str) -> Result<Option<OidcLoginState>, String>;
+}
+
+pub struct OidcStateRepositoryImpl {
+    db: Database,
+}
+
+impl OidcStateRepositoryImpl {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait]
+impl OidcStateRepository for OidcStateRepositoryImpl {
+    async fn create(&self, state: &OidcLoginState) -> Result<(), String> {
+        self.db
+            .collection::<OidcLoginState>("oidc_login_states")
+            .insert_one(state)
+            .await
+            .map_err(|e| format!("Error creating OIDC login state: {}", e))?;
+        Ok(())
+    }
+
+    async fn consume(&self, state: &str) -> Result<Option<OidcLoginState>, String> {
+        self.db
+            .collection::<OidcLoginState>("oidc_login_states")
+            .find_one_and_delete(doc! {
+                "_id": state,
+                "expires_at": { "$gt": DateTime::now() },
+            })
+            .await
+            .map_err(|e| format!("Error consuming OIDC login state: {}", e))
+    }
+}
+
+#[async_trait]
+pub trait UserIdentityRepository: Send + Sync {
+    async fn create(&self, identity: &UserIdentity) -> Result<(), String>;
+    async fn find_by_subject(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<UserIdentity>, String>;
+    async fn delete_for_user(&self, user_id: &ObjectId) -> Result<(), String>;
+}
+
+pub struct UserIdentityRepositoryImpl {
+    db: Database,
+}
+
+impl UserIdentityRepositoryImpl {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait]
+impl UserIdentityRepository for UserIdentityRepositoryImpl {
+    async fn create(&self, identity: &UserIdentity) -> Result<(), String> {
+        self.db
+            .collection::<UserIdentity>("user_identities")
+            .insert_one(identity)
+            .await
+            .map_err(|e| format!("Error creating user identity: {}", e))?;
+        Ok(())
+    }
+
+    async fn find_by_subject(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<UserIdentity>, String> {
+        self.db
+            .collection::<UserIdentity>("user_identities")
+            .find_one(doc! { "provider": provider, "subject": subject })
+            .await
+            .map_err(|e| format!("Error finding user identity: {}", e))
+    }
+
+    async fn delete_for_user(&self, user_id: &ObjectId) -> Result<(), String> {
+        self.db
+            .collection::<UserIdentity>("user_identities")
+            .delete_many(doc! { "user_id": user_id })
+            .await
+            .map_err(|e| format!("Error deleting user identities: {}", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_token_repository_impl_creation() {
+        let _repo_fn = RefreshTokenRepositoryImpl::new;
+    }
+
+    #[test]
+    fn test_one_time_token_repository_impl_creation() {
+        let _repo_fn = OneTimeTokenRepositoryImpl::new;
+    }
+
+    #[test]
+    fn test_oidc_state_repository_impl_creation() {
+        let _repo_fn = OidcStateRepositoryImpl::new;
+    }
+
+    #[test]
+    fn test_user_identity_repository_impl_creation() {
+        let _repo_fn = UserIdentityRepositoryImpl::new;
+    }
+}
@@ -1,34 +1,120 @@
+use crate::common::secrets::{generate_secret, hash_secret};
+use crate::config::client_info::ClientInfo;
+use crate::modules::auth::keys::JwtKeys;
 use crate::modules::auth::model::{
     AnonymousClaims, AnonymousTokenResponse, AuthUserResponse, JwtClaims, LoginResponse,
-    RefreshResponse,
+    LoginResult, MfaChallengeResponse, OidcLoginState, OneTimeToken, RecoveryCodesResponse,
+    RefreshClaims, RefreshResponse, RefreshToken, SessionResponse, TokenPurpose,
+    TotpEnrollmentResponse, UserIdentity,
 };
+use crate::modules::auth::oidc::{code_challenge, OidcIdentity, OidcProvider};
+use crate::modules::auth::totp;
+use crate::modules::auth::repository::{
+    OidcStateRepository, OneTimeTokenRepository, RefreshTokenRepository, UserIdentityRepository,
+};
+use crate::modules::mail::model::EmailMessage;
+use crate::modules::mail::service::Mailer;
+use crate::modules::users::model::{TwoFactor, UpdateUserDto, User};
 use crate::modules::users::service::UserService;
 use async_trait::async_trait;
 use chrono::Utc;
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
-use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
 const ACCESS_TOKEN_DURATION_SECS: i64 = 900; // 15 minutes
 const REFRESH_TOKEN_DURATION_SECS: i64 = 604800; // 7 days
 const ANONYMOUS_TOKEN_DURATION_SECS: i64 = 86400; // 24 hours
+const PASSWORD_RESET_TOKEN_DURATION_SECS: i64 = 3600; // 1 hour
+const EMAIL_VERIFICATION_TOKEN_DURATION_SECS: i64 = 172800; // 48 hours
+const MAGIC_LINK_TOKEN_DURATION_SECS: i64 = 900; // 15 minutes
+const OIDC_LOGIN_STATE_DURATION_SECS: i64 = 600; // 10 minutes
+const MFA_CHALLENGE_DURATION_SECS: i64 = 300; // 5 minutes
 
 pub struct AuthConfig {
-    pub jwt_secret: String,
+    /// Signs issued tokens and verifies presented ones
+    pub keys: Arc<JwtKeys>,
+    /// Base URL of the web app for links sent by email
+    pub app_url: String,
 }
 
 #[async_trait]
 pub trait AuthService: Send + Sync {
-    async fn login(&self, email: &str, password: &str) -> Result<LoginResponse, String>;
+    /// Checks the password. Accounts with two-factor authentication get an
+    /// MFA challenge instead of tokens.
+    async fn login(
+        &self,
+        email: &str,
+        password: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginResult, String>;
+    /// Finishes a login started by `login` with a TOTP or recovery code.
+    async fn complete_mfa_login(
+        &self,
+        mfa_token: &str,
+        code: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginResponse, String>;
     async fn register(
         &self,
         email: &str,
         name: &str,
         password: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginResponse, String>;
+    /// Starts a session for a user authenticated by other means, such as an
+    /// external identity provider.
+    async fn create_session(
+        &self,
+        user: User,
+        client: &ClientInfo,
     ) -> Result<LoginResponse, String>;
     fn verify_token(&self, token: &str) -> Result<JwtClaims, String>;
-    fn refresh_tokens(&self, refresh_token: &str) -> Result<RefreshResponse, String>;
+    async fn refresh_tokens(
+        &self,
+        refresh_token: &str,
+        client: &ClientInfo,
+    ) -> Result<RefreshResponse, String>;
+    async fn logout(&self, refresh_token: &str) -> Result<(), String>;
+    async fn logout_all(&self, user_id: &ObjectId) -> Result<(), String>;
+    async fn list_sessions(&self, user_id: &ObjectId) -> Result<Vec<SessionResponse>, String>;
+    async fn revoke_session(&self, user_id: &ObjectId, session_id: &str) -> Result<(), String>;
+    /// Emails a reset link when the account exists. Succeeds silently
+    /// otherwise so the endpoint cannot be used to probe for accounts.
+    async fn request_password_reset(&self, email: &str) -> Result<(), String>;
+    async fn reset_password(&self, token: &str, new_password: &str) -> Result<(), String>;
+    async fn send_verification_email(&self, user_id: &ObjectId) -> Result<(), String>;
+    async fn verify_email(&self, token: &str) -> Result<(), String>;
+    /// Emails a single-use login link when the account exists and is active.
+    /// Succeeds silently otherwise, like `request_password_reset`.
+    async fn request_magic_link(&self, email: &str) -> Result<(), String>;
+    /// Signs in with a login link. Accounts with two-factor authentication
+    /// still have to complete the MFA challenge.
+    async fn redeem_magic_link(
+        &self,
+        token: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginResult, String>;
+    /// Generates a TOTP secret. Two-factor authentication is only turned on
+    /// once a code from it is confirmed.
+    async fn start_totp_enrollment(
+        &self,
+        user_id: &ObjectId,
+    ) -> Result<TotpEnrollmentResponse, String>;
+    /// Turns two-factor authentication on and returns the recovery codes.
+    async fn confirm_totp_enrollment(
+        &self,
+        user_id: &ObjectId,
+        code: &str,
+    ) -> Result<RecoveryCodesResponse, String>;
+    async fn disable_totp(&self, user_id: &ObjectId, code: &str) -> Result<(), String>;
+    /// Replaces every recovery code with a fresh set.
+    async fn regenerate_recovery_codes(
+        &self,
+        user_id: &ObjectId,
+        code: &str,
+    ) -> Result<RecoveryCodesResponse, String>;
     fn generate_anonymous_token(
         &self,
         tournament_id: &ObjectId,
@@ -39,13 +125,25 @@ pub trait AuthService: Send + Sync {
 
 pub struct AuthServiceImpl {
     user_service: Arc<dyn UserService + Send + Sync>,
+    refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+    one_time_token_repository: Arc<dyn OneTimeTokenRepository>,
+    mailer: Arc<dyn Mailer>,
     config: AuthConfig,
 }
 
 impl AuthServiceImpl {
-    pub fn new(user_service: Arc<dyn UserService + Send + Sync>, config: AuthConfig) -> Self {
+    pub fn new(
+        user_service: Arc<dyn UserService + Send + Sync>,
+        refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+        one_time_token_repository: Arc<dyn OneTimeTokenRepository>,
+        mailer: Arc<dyn Mailer>,
+        config: AuthConfig,
+    ) -> Self {
         Self {
             user_service,
+            refresh_token_repository,
+            one_time_token_repository,
+            mailer,
             config,
         }
     }
@@ -60,34 +158,208 @@ impl AuthServiceImpl {
             token_type: "access".to_string(),
         };
 
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.config.jwt_secret.as_bytes()),
-        )
-        .map_err(|e| format!("Failed to create token: {}", e))
+        self.config
+            .keys
+            .encode(&claims)
+            .map_err(|e| format!("Failed to create token: {}", e))
     }
 
     pub fn generate_refresh_token(
         &self,
         user_id: String,
         email: String,
+        jti: String,
+        family_id: String,
     ) -> Result<String, String> {
         let now = Utc::now().timestamp() as usize;
-        let claims = JwtClaims {
+        let claims = RefreshClaims {
             sub: user_id,
             email,
             exp: now + REFRESH_TOKEN_DURATION_SECS as usize,
             iat: now,
             token_type: "refresh".to_string(),
+            jti,
+            fid: family_id,
+        };
+
+        self.config
+            .keys
+            .encode(&claims)
+            .map_err(|e| format!("Failed to create refresh token: {}", e))
+    }
+
+    fn verify_refresh_token(&self, token: &str) -> Result<RefreshClaims, String> {
+        let claims = self
+            .config
+            .keys
+            .decode::<RefreshClaims>(token)
+            .map_err(|e| format!("Invalid token: {}", e))?;
+
+        if claims.token_type != "refresh" {
+            return Err("Invalid token type: expected refresh token".to_string());
+        }
+
+        Ok(claims)
+    }
+
+    /// Signs a refresh token and records it in the store. Passing the token
+    /// being rotated keeps its session; `None` starts a new one.
+    async fn issue_refresh_token(
+        &self,
+        user_id: String,
+        email: String,
+        client: &ClientInfo,
+        previous: Option<&RefreshToken>,
+    ) -> Result<String, String> {
+        let user_oid =
+            ObjectId::parse_str(&user_id).map_err(|_| "Invalid user ID".to_string())?;
+        let jti = Uuid::new_v4().to_string();
+        let family_id = previous
+            .map(|token| token.family_id.clone())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        let token = self.generate_refresh_token(user_id, email, jti.clone(), family_id.clone())?;
+
+        let expires_at = DateTime::from_millis(
+            (Utc::now().timestamp() + REFRESH_TOKEN_DURATION_SECS) * 1000,
+        );
+        let mut record = RefreshToken::new(jti, user_oid, family_id, expires_at);
+        if let Some(previous) = previous {
+            record.session_started_at = previous.session_started_at;
+        }
+        record.user_agent = client.user_agent.clone();
+        record.ip_address = client.ip_address.clone();
+
+        self.refresh_token_repository.create(&record).await?;
+
+        Ok(token)
+    }
+
+    /// Stores a new single-use token for `user`, invalidating earlier ones
+    /// with the same purpose, and returns the secret to email.
+    async fn issue_one_time_token(
+        &self,
+        user: &User,
+        purpose: TokenPurpose,
+        duration_secs: i64,
+    ) -> Result<String, String> {
+        let user_id = user.id.ok_or("User has no id")?;
+
+        // Only the most recent link stays valid
+        self.one_time_token_repository
+            .invalidate_for_user(&user_id, purpose)
+            .await?;
+
+        let token = generate_secret();
+        let expires_at =
+            DateTime::from_millis((Utc::now().timestamp() + duration_secs) * 1000);
+        self.one_time_token_repository
+            .create(&OneTimeToken::new(
+                hash_secret(&token),
+                user_id,
+                user.email.clone(),
+                purpose,
+                expires_at,
+            ))
+            .await?;
+
+        Ok(token)
+    }
+
+    async fn send_verification_link(&self, user: &User) -> Result<(), String> {
+        let token = self
+            .issue_one_time_token(
+                user,
+                TokenPurpose::EmailVerification,
+                EMAIL_VERIFICATION_TOKEN_DURATION_SECS,
+            )
+            .await?;
+
+        let link = format!("{}/verify-email?token={}", self.config.app_url, token);
+        self.mailer
+            .send(&EmailMessage::new(
+                &user.email,
+                "Confirm your Torvi email address",
+                format!(
+                    "Hi {},\n\n\
+                     Please confirm your email address by opening the link below. \
+                     It expires in 48 hours.\n\n\
+                     {}",
+                    user.name, link
+                ),
+            ))
+            .await
+    }
+
+    fn generate_mfa_token(&self, user: &User) -> Result<String, String> {
+        let now = Utc::now().timestamp() as usize;
+        let claims = JwtClaims {
+            sub: user.id.map(|id| id.to_string()).unwrap_or_default(),
+            email: user.email.clone(),
+            exp: now + MFA_CHALLENGE_DURATION_SECS as usize,
+            iat: now,
+            token_type: "mfa".to_string(),
+        };
+
+        self.config
+            .keys
+            .encode(&claims)
+            .map_err(|e| format!("Failed to create MFA token: {}", e))
+    }
+
+    async fn find_user(&self, user_id: &ObjectId) -> Result<User, String> {
+        self.user_service
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| "User not found".to_string())
+    }
+
+    /// Accepts a TOTP code from the confirmed secret, once per time step, or
+    /// an unused recovery code.
+    async fn verify_second_factor(&self, user: &User, code: &str) -> Result<bool, String> {
+        let (Some(user_id), Some(secret)) = (user.id, user.two_factor.secret.as_deref()) else {
+            return Ok(false);
+        };
+
+        let now = Utc::now().timestamp() as u64;
+        if let Some(step) = totp::verify_code(secret, code, now)? {
+            if user.two_factor.last_used_step >= Some(step) {
+                return Ok(false);
+            }
+            let two_factor = TwoFactor {
+                last_used_step: Some(step),
+                ..user.two_factor.clone()
+            };
+            self.user_service
+                .update_two_factor(&user_id, two_factor)
+                .await?;
+            return Ok(true);
+        }
+
+        self.user_service
+            .consume_recovery_code(&user_id, &totp::hash_recovery_code(code))
+            .await
+    }
+
+    /// Stores a new set of recovery codes and returns them in clear.
+    async fn replace_recovery_codes(
+        &self,
+        user_id: &ObjectId,
+        two_factor: TwoFactor,
+    ) -> Result<RecoveryCodesResponse, String> {
+        let recovery_codes = totp::generate_recovery_codes();
+        let two_factor = TwoFactor {
+            recovery_codes: recovery_codes
+                .iter()
+                .map(|code| totp::hash_recovery_code(code))
+                .collect(),
+            ..two_factor
         };
+        self.user_service
+            .update_two_factor(user_id, two_factor)
+            .await?;
 
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.config.jwt_secret.as_bytes()),
-        )
-        .map_err(|e| format!("Failed to create refresh token: {}", e))
+        Ok(RecoveryCodesResponse { recovery_codes })
     }
 }
 
@@ -98,36 +370,79 @@ impl AuthService for AuthServiceImpl {
         email: &str,
         name: &str,
         password: &str,
+        client: &ClientInfo,
     ) -> Result<LoginResponse, String> {
         let user = self
             .user_service
             .create_user(email.to_string(), name.to_string(), password.to_string())
             .await?;
 
-        let user_id = user
-            .id
-            .as_ref()
-            .map(|id| id.to_string())
-            .unwrap_or_default();
-
-        let access_token = self.generate_token(user_id.clone(), user.email.clone())?;
-        let refresh_token = self.generate_refresh_token(user_id, user.email.clone())?;
+        // The account is usable either way; the user can ask for a new link
+        if let Err(e) = self.send_verification_link(&user).await {
+            tracing::warn!(error = %e, "Failed to send verification email");
+        }
 
-        Ok(LoginResponse {
-            access_token,
-            refresh_token,
-            token_type: "Bearer".to_string(),
-            user: AuthUserResponse::from(user),
-        })
+        self.create_session(user, client).await
     }
 
-    async fn login(&self, email: &str, password: &str) -> Result<LoginResponse, String> {
+    async fn login(
+        &self,
+        email: &str,
+        password: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginResult, String> {
         let user = self
             .user_service
             .verify_credentials(email, password)
             .await?
             .ok_or_else(|| "Invalid credentials".to_string())?;
 
+        if user.is_suspended() {
+            return Err("Account is suspended".to_string());
+        }
+
+        if user.two_factor_enabled() {
+            return Ok(LoginResult::MfaRequired(MfaChallengeResponse {
+                mfa_required: true,
+                mfa_token: self.generate_mfa_token(&user)?,
+            }));
+        }
+
+        self.create_session(user, client)
+            .await
+            .map(LoginResult::Session)
+    }
+
+    async fn complete_mfa_login(
+        &self,
+        mfa_token: &str,
+        code: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginResponse, String> {
+        let claims = self.verify_token(mfa_token)?;
+        if claims.token_type != "mfa" {
+            return Err("Invalid token type: expected MFA token".to_string());
+        }
+        let user_id =
+            ObjectId::parse_str(&claims.sub).map_err(|_| "Invalid MFA token".to_string())?;
+        let user = self.find_user(&user_id).await?;
+
+        if !self.verify_second_factor(&user, code).await? {
+            return Err("Invalid authentication code".to_string());
+        }
+
+        self.create_session(user, client).await
+    }
+
+    async fn create_session(
+        &self,
+        user: User,
+        client: &ClientInfo,
+    ) -> Result<LoginResponse, String> {
+        if user.is_suspended() {
+            return Err("Account is suspended".to_string());
+        }
+
         let user_id = user
             .id
             .as_ref()
@@ -135,7 +450,9 @@ impl AuthService for AuthServiceImpl {
             .unwrap_or_default();
 
         let access_token = self.generate_token(user_id.clone(), user.email.clone())?;
-        let refresh_token = self.generate_refresh_token(user_id, user.email.clone())?;
+        let refresh_token = self
+            .issue_refresh_token(user_id, user.email.clone(), client, None)
+            .await?;
 
         Ok(LoginResponse {
             access_token,
@@ -146,25 +463,43 @@ impl AuthService for AuthServiceImpl {
     }
 
     fn verify_token(&self, token: &str) -> Result<JwtClaims, String> {
-        let decoded = decode::<JwtClaims>(
-            token,
-            &DecodingKey::from_secret(self.config.jwt_secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map_err(|e| format!("Invalid token: {}", e))?;
-
-        Ok(decoded.claims)
+        self.config
+            .keys
+            .decode::<JwtClaims>(token)
+            .map_err(|e| format!("Invalid token: {}", e))
     }
 
-    fn refresh_tokens(&self, refresh_token: &str) -> Result<RefreshResponse, String> {
-        let claims = self.verify_token(refresh_token)?;
+    async fn refresh_tokens(
+        &self,
+        refresh_token: &str,
+        client: &ClientInfo,
+    ) -> Result<RefreshResponse, String> {
+        let claims = self.verify_refresh_token(refresh_token)?;
+
+        let stored = self
+            .refresh_token_repository
+            .find_by_jti(&claims.jti)
+            .await?
+            .ok_or_else(|| "Refresh token has been revoked".to_string())?;
 
-        if claims.token_type != "refresh" {
-            return Err("Invalid token type: expected refresh token".to_string());
+        if stored.revoked_at.is_some() {
+            return Err("Refresh token has been revoked".to_string());
+        }
+
+        // A rotated token coming back means it was copied: kill the session
+        if stored.rotated_at.is_some()
+            || !self.refresh_token_repository.mark_rotated(&claims.jti).await?
+        {
+            self.refresh_token_repository
+                .revoke_family(&stored.family_id)
+                .await?;
+            return Err("Refresh token reuse detected".to_string());
         }
 
         let access_token = self.generate_token(claims.sub.clone(), claims.email.clone())?;
-        let new_refresh = self.generate_refresh_token(claims.sub, claims.email)?;
+        let new_refresh = self
+            .issue_refresh_token(claims.sub, claims.email, client, Some(&stored))
+            .await?;
 
         Ok(RefreshResponse {
             access_token,
@@ -173,6 +508,295 @@ impl AuthService for AuthServiceImpl {
         })
     }
 
+    async fn logout(&self, refresh_token: &str) -> Result<(), String> {
+        let claims = self.verify_refresh_token(refresh_token)?;
+        self.refresh_token_repository
+            .revoke_family(&claims.fid)
+            .await
+    }
+
+    async fn logout_all(&self, user_id: &ObjectId) -> Result<(), String> {
+        self.refresh_token_repository
+            .revoke_all_for_user(user_id)
+            .await
+    }
+
+    async fn list_sessions(&self, user_id: &ObjectId) -> Result<Vec<SessionResponse>, String> {
+        let tokens = self
+            .refresh_token_repository
+            .find_active_by_user(user_id)
+            .await?;
+
+        Ok(tokens.into_iter().map(SessionResponse::from).collect())
+    }
+
+    async fn revoke_session(&self, user_id: &ObjectId, session_id: &str) -> Result<(), String> {
+        if !self
+            .refresh_token_repository
+            .revoke_family_for_user(user_id, session_id)
+            .await?
+        {
+            return Err("Session not found".to_string());
+        }
+
+        Ok(())
+    }
+
+    async fn request_password_reset(&self, email: &str) -> Result<(), String> {
+        let user = match self.user_service.find_by_email(email.trim()).await? {
+            Some(user) => user,
+            None => return Ok(()),
+        };
+
+        let token = self
+            .issue_one_time_token(
+                &user,
+                TokenPurpose::PasswordReset,
+                PASSWORD_RESET_TOKEN_DURATION_SECS,
+            )
+            .await?;
+
+        let link = format!("{}/reset-password?token={}", self.config.app_url, token);
+        self.mailer
+            .send(&EmailMessage::new(
+                &user.email,
+                "Reset your Torvi password",
+                format!(
+                    "Hi {},\n\n\
+                     Use the link below to choose a new password. It expires in 1 hour.\n\n\
+                     {}\n\n\
+                     If you did not ask for this, you can ignore this email.",
+                    user.name, link
+                ),
+            ))
+            .await
+    }
+
+    async fn reset_password(&self, token: &str, new_password: &str) -> Result<(), String> {
+        // Validate before consuming so a weak password does not burn the link.
+        // Rules about the email and name are applied once the user is known.
+        self.user_service
+            .check_password(new_password, "", "")
+            .map_err(|e| e.to_string())?;
+
+        let reset = self
+            .one_time_token_repository
+            .consume(&hash_secret(token), TokenPurpose::PasswordReset)
+            .await?
+            .ok_or_else(|| "Invalid or expired reset token".to_string())?;
+
+        self.user_service
+            .update_user(
+                &reset.user_id,
+                UpdateUserDto {
+                    email: None,
+                    name: None,
+                    password: Some(new_password.to_string()),
+                    bio: None,
+                    links: None,
+                    privacy: None,
+                },
+            )
+            .await?;
+
+        // Sessions opened with the old password are no longer trusted
+        self.refresh_token_repository
+            .revoke_all_for_user(&reset.user_id)
+            .await
+    }
+
+    async fn send_verification_email(&self, user_id: &ObjectId) -> Result<(), String> {
+        let user = self
+            .user_service
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| "User not found".to_string())?;
+
+        if user.email_verified {
+            return Err("Email already verified".to_string());
+        }
+
+        self.send_verification_link(&user).await
+    }
+
+    async fn verify_email(&self, token: &str) -> Result<(), String> {
+        let verification = self
+            .one_time_token_repository
+            .consume(&hash_secret(token), TokenPurpose::EmailVerification)
+            .await?
+            .ok_or_else(|| "Invalid or expired verification token".to_string())?;
+
+        let user = self
+            .user_service
+            .find_by_id(&verification.user_id)
+            .await?
+            .ok_or_else(|| "User not found".to_string())?;
+
+        // The address changed after the link was sent
+        if user.email != verification.email {
+            return Err("Invalid or expired verification token".to_string());
+        }
+
+        self.user_service
+            .mark_email_verified(&verification.user_id)
+            .await?;
+        Ok(())
+    }
+
+    async fn request_magic_link(&self, email: &str) -> Result<(), String> {
+        let user = match self.user_service.find_by_email(email.trim()).await? {
+            Some(user) if !user.is_suspended() => user,
+            _ => return Ok(()),
+        };
+
+        let token = self
+            .issue_one_time_token(&user, TokenPurpose::MagicLink, MAGIC_LINK_TOKEN_DURATION_SECS)
+            .await?;
+
+        let link = format!("{}/magic-link?token={}", self.config.app_url, token);
+        self.mailer
+            .send(&EmailMessage::new(
+                &user.email,
+                "Your Torvi login link",
+                format!(
+                    "Hi {},\n\n\
+                     Use the link below to log in. It expires in 15 minutes and works once.\n\n\
+                     {}\n\n\
+                     If you did not ask for this, you can ignore this email.",
+                    user.name, link
+                ),
+            ))
+            .await
+    }
+
+    async fn redeem_magic_link(
+        &self,
+        token: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginResult, String> {
+        let link = self
+            .one_time_token_repository
+            .consume(&hash_secret(token), TokenPurpose::MagicLink)
+            .await?
+            .ok_or_else(|| "Invalid or expired login link".to_string())?;
+
+        let mut user = self
+            .user_service
+            .find_by_id(&link.user_id)
+            .await?
+            .ok_or_else(|| "Invalid or expired login link".to_string())?;
+
+        // The address changed after the link was sent
+        if user.email != link.email {
+            return Err("Invalid or expired login link".to_string());
+        }
+
+        if user.is_suspended() {
+            return Err("Account is suspended".to_string());
+        }
+
+        // Following the link proves the address belongs to the user
+        if !user.email_verified {
+            user = self.user_service.mark_email_verified(&link.user_id).await?;
+        }
+
+        if user.two_factor_enabled() {
+            return Ok(LoginResult::MfaRequired(MfaChallengeResponse {
+                mfa_required: true,
+                mfa_token: self.generate_mfa_token(&user)?,
+            }));
+        }
+
+        self.create_session(user, client)
+            .await
+            .map(LoginResult::Session)
+    }
+
+    async fn start_totp_enrollment(
+        &self,
+        user_id: &ObjectId,
+    ) -> Result<TotpEnrollmentResponse, String> {
+        let user = self.find_user(user_id).await?;
+        if user.two_factor_enabled() {
+            return Err("Two-factor authentication is already enabled".to_string());
+        }
+
+        let secret = totp::generate_secret();
+        let otpauth_uri = totp::otpauth_uri(&secret, &user.email)?;
+        let two_factor = TwoFactor {
+            pending_secret: Some(secret.clone()),
+            ..user.two_factor
+        };
+        self.user_service
+            .update_two_factor(user_id, two_factor)
+            .await?;
+
+        Ok(TotpEnrollmentResponse {
+            secret,
+            otpauth_uri,
+        })
+    }
+
+    async fn confirm_totp_enrollment(
+        &self,
+        user_id: &ObjectId,
+        code: &str,
+    ) -> Result<RecoveryCodesResponse, String> {
+        let user = self.find_user(user_id).await?;
+        if user.two_factor_enabled() {
+            return Err("Two-factor authentication is already enabled".to_string());
+        }
+        let secret = user
+            .two_factor
+            .pending_secret
+            .ok_or_else(|| "No two-factor enrollment in progress".to_string())?;
+
+        let now = Utc::now().timestamp() as u64;
+        let step = totp::verify_code(&secret, code, now)?
+            .ok_or_else(|| "Invalid authentication code".to_string())?;
+
+        let two_factor = TwoFactor {
+            secret: Some(secret),
+            pending_secret: None,
+            recovery_codes: Vec::new(),
+            last_used_step: Some(step),
+        };
+        self.replace_recovery_codes(user_id, two_factor).await
+    }
+
+    async fn disable_totp(&self, user_id: &ObjectId, code: &str) -> Result<(), String> {
+        let user = self.find_user(user_id).await?;
+        if !user.two_factor_enabled() {
+            return Err("Two-factor authentication is not enabled".to_string());
+        }
+        if !self.verify_second_factor(&user, code).await? {
+            return Err("Invalid authentication code".to_string());
+        }
+
+        self.user_service
+            .update_two_factor(user_id, TwoFactor::default())
+            .await?;
+        Ok(())
+    }
+
+    async fn regenerate_recovery_codes(
+        &self,
+        user_id: &ObjectId,
+        code: &str,
+    ) -> Result<RecoveryCodesResponse, String> {
+        let user = self.find_user(user_id).await?;
+        if !user.two_factor_enabled() {
+            return Err("Two-factor authentication is not enabled".to_string());
+        }
+        if !self.verify_second_factor(&user, code).await? {
+            return Err("Invalid authentication code".to_string());
+        }
+
+        // Re-read so the step recorded by the check above is kept
+        let user = self.find_user(user_id).await?;
+        self.replace_recovery_codes(user_id, user.two_factor).await
+    }
+
     fn generate_anonymous_token(
         &self,
         tournament_id: &ObjectId,
@@ -189,12 +813,11 @@ impl AuthService for AuthServiceImpl {
             token_type: "anonymous".to_string(),
         };
 
-        let token = encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.config.jwt_secret.as_bytes()),
-        )
-        .map_err(|e| format!("Failed to create anonymous token: {}", e))?;
+        let token = self
+            .config
+            .keys
+            .encode(&claims)
+            .map_err(|e| format!("Failed to create anonymous token: {}", e))?;
 
         Ok(AnonymousTokenResponse {
             access_token: token,
@@ -205,27 +828,317 @@ impl AuthService for AuthServiceImpl {
     }
 
     fn verify_anonymous_token(&self, token: &str) -> Result<AnonymousClaims, String> {
-        let decoded = decode::<AnonymousClaims>(
-            token,
-            &DecodingKey::from_secret(self.config.jwt_secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map_err(|e| format!("Invalid anonymous token: {}", e))?;
-
-        if decoded.claims.token_type != "anonymous" {
+        let claims = self
+            .config
+            .keys
+            .decode::<AnonymousClaims>(token)
+            .map_err(|e| format!("Invalid anonymous token: {}", e))?;
+
+        if claims.token_type != "anonymous" {
             return Err("Invalid token type: expected anonymous token".to_string());
         }
 
-        Ok(decoded.claims)
+        Ok(claims)
+    }
+}
+
+#[async_trait]
+pub trait OidcService: Send + Sync {
+    /// Names of the configured identity providers.
+    fn providers(&self) -> Vec<String>;
+    /// Starts a login and returns the provider URL to send the browser to.
+    async fn authorization_url(&self, provider: &str) -> Result<String, String>;
+    /// Completes a login from the provider's redirect. The external identity
+    /// is linked to the user with the same verified email, or to a new user.
+    async fn login(
+        &self,
+        provider: &str,
+        code: &str,
+        state: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginResponse, String>;
+}
+
+pub struct OidcServiceImpl {
+    providers: HashMap<String, Arc<dyn OidcProvider>>,
+    state_repository: Arc<dyn OidcStateRepository>,
+    identity_repository: Arc<dyn UserIdentityRepository>,
+    user_service: Arc<dyn UserService + Send + Sync>,
+    auth_service: Arc<dyn AuthService + Send + Sync>,
+}
+
+impl OidcServiceImpl {
+    pub fn new(
+        providers: HashMap<String, Arc<dyn OidcProvider>>,
+        state_repository: Arc<dyn OidcStateRepository>,
+        identity_repository: Arc<dyn UserIdentityRepository>,
+        user_service: Arc<dyn UserService + Send + Sync>,
+        auth_service: Arc<dyn AuthService + Send + Sync>,
+    ) -> Self {
+        Self {
+            providers,
+            state_repository,
+            identity_repository,
+            user_service,
+            auth_service,
+        }
+    }
+
+    fn provider(&self, name: &str) -> Result<&Arc<dyn OidcProvider>, String> {
+        self.providers
+            .get(name)
+            .ok_or_else(|| "Unknown identity provider".to_string())
+    }
+
+    async fn find_or_link_user(
+        &self,
+        provider: &str,
+        identity: OidcIdentity,
+    ) -> Result<User, String> {
+        if let Some(linked) = self
+            .identity_repository
+            .find_by_subject(provider, &identity.subject)
+            .await?
+        {
+            return self
+                .user_service
+                .find_by_id(&linked.user_id)
+                .await?
+                .ok_or_else(|| "User not found".to_string());
+        }
+
+        let email = identity
+            .email
+            .clone()
+            .ok_or_else(|| "Identity provider did not return an email address".to_string())?;
+
+        let user = match self.user_service.find_by_email(&email).await? {
+            Some(user) => {
+                // Otherwise anyone able to claim the address at the provider
+                // could take over the account
+                if !identity.email_verified {
+                    return Err(
+                        "Email address is not verified by the identity provider".to_string()
+                    );
+                }
+                // An unverified local account may have been registered by
+                // someone else, who would keep its password after linking
+                if !user.email_verified {
+                    return Err(
+                        "Verify the email address of your existing account before linking"
+                            .to_string(),
+                    );
+                }
+                user
+            }
+            None => {
+                let name = identity
+                    .name
+                    .clone()
+                    .filter(|name| !name.trim().is_empty())
+                    .unwrap_or_else(|| email.split('@').next().unwrap_or_default().to_string());
+                self.user_service.provision_user(email.clone(), name).await?
+            }
+        };
+        let user_id = user.id.ok_or("User has no id")?;
+
+        self.identity_repository
+            .create(&UserIdentity::new(
+                user_id,
+                provider.to_string(),
+                identity.subject,
+                Some(email),
+            ))
+            .await?;
+
+        if identity.email_verified && !user.email_verified {
+            return self.user_service.mark_email_verified(&user_id).await;
+        }
+        Ok(user)
+    }
+}
+
+#[async_trait]
+impl OidcService for OidcServiceImpl {
+    fn providers(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.providers.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    async fn authorization_url(&self, provider: &str) -> Result<String, String> {
+        let provider_client = self.provider(provider)?;
+
+        let state = generate_secret();
+        let nonce = generate_secret();
+        let code_verifier = generate_secret();
+        let url = provider_client
+            .authorization_url(&state, &nonce, &code_challenge(&code_verifier))
+            .await?;
+
+        let expires_at = DateTime::from_millis(
+            (Utc::now().timestamp() + OIDC_LOGIN_STATE_DURATION_SECS) * 1000,
+        );
+        self.state_repository
+            .create(&OidcLoginState::new(
+                state,
+                provider.to_string(),
+                nonce,
+                code_verifier,
+                expires_at,
+            ))
+            .await?;
+
+        Ok(url)
+    }
+
+    async fn login(
+        &self,
+        provider: &str,
+        code: &str,
+        state: &str,
+        client: &ClientInfo,
+    ) -> Result<LoginResponse, String> {
+        let provider_client = self.provider(provider)?;
+
+        let login_state = self
+            .state_repository
+            .consume(state)
+            .await?
+            .filter(|login_state| login_state.provider == provider)
+            .ok_or_else(|| "Invalid or expired login state".to_string())?;
+
+        let identity = provider_client
+            .exchange_code(code, &login_state.code_verifier, &login_state.nonce)
+            .await?;
+        let user = self.find_or_link_user(provider, identity).await?;
+
+        self.auth_service.create_session(user, client).await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modules::mail::service::LogMailer;
     use crate::modules::users::model::User;
     use async_trait::async_trait;
     use mongodb::bson::oid::ObjectId;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct InMemoryRefreshTokens {
+        tokens: Mutex<Vec<RefreshToken>>,
+    }
+
+    #[async_trait]
+    impl RefreshTokenRepository for InMemoryRefreshTokens {
+        async fn create(&self, token: &RefreshToken) -> Result<(), String> {
+            self.tokens.lock().unwrap().push(token.clone());
+            Ok(())
+        }
+
+        async fn find_by_jti(&self, jti: &str) -> Result<Option<RefreshToken>, String> {
+            Ok(self
+                .tokens
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|t| t.jti == jti)
+                .cloned())
+        }
+
+        async fn mark_rotated(&self, jti: &str) -> Result<bool, String> {
+            let mut tokens = self.tokens.lock().unwrap();
+            match tokens
+                .iter_mut()
+                .find(|t| t.jti == jti && t.rotated_at.is_none() && t.revoked_at.is_none())
+            {
+                Some(token) => {
+                    token.rotated_at = Some(DateTime::now());
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+
+        async fn revoke_family(&self, family_id: &str) -> Result<(), String> {
+            for token in self.tokens.lock().unwrap().iter_mut() {
+                if token.family_id == family_id {
+                    token.revoked_at = Some(DateTime::now());
+                }
+            }
+            Ok(())
+        }
+
+        async fn revoke_all_for_user(&self, user_id: &ObjectId) -> Result<(), String> {
+            for token in self.tokens.lock().unwrap().iter_mut() {
+                if token.user_id == *user_id {
+                    token.revoked_at = Some(DateTime::now());
+                }
+            }
+            Ok(())
+        }
+
+        async fn find_active_by_user(
+            &self,
+            user_id: &ObjectId,
+        ) -> Result<Vec<RefreshToken>, String> {
+            Ok(self
+                .tokens
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|t| {
+                    t.user_id == *user_id && t.rotated_at.is_none() && t.revoked_at.is_none()
+                })
+                .cloned()
+                .collect())
+        }
+
+        async fn revoke_family_for_user(
+            &self,
+            user_id: &ObjectId,
+            family_id: &str,
+        ) -> Result<bool, String> {
+            let mut revoked = false;
+            for token in self.tokens.lock().unwrap().iter_mut() {
+                if token.user_id == *user_id
+                    && token.family_id == family_id
+                    && token.revoked_at.is_none()
+                {
+                    token.revoked_at = Some(DateTime::now());
+                    revoked = true;
+                }
+            }
+            Ok(revoked)
+        }
+    }
+
+    struct UnusedOneTimeTokens;
+
+    #[async_trait]
+    impl OneTimeTokenRepository for UnusedOneTimeTokens {
+        async fn create(&self, _token: &OneTimeToken) -> Result<(), String> {
+            unimplemented!()
+        }
+
+        async fn consume(
+            &self,
+            _token_hash: &str,
+            _purpose: TokenPurpose,
+        ) -> Result<Option<OneTimeToken>, String> {
+            unimplemented!()
+        }
+
+        async fn invalidate_for_user(
+            &self,
+            _user_id: &ObjectId,
+            _purpose: TokenPurpose,
+        ) -> Result<(), String> {
+            unimplemented!()
+        }
+    }
 
     struct MockUserService {
         user: Option<User>,
@@ -242,6 +1155,10 @@ mod tests {
             Err("not implemented".to_string())
         }
 
+        async fn provision_user(&self, _email: String, _name: String) -> Result<User, String> {
+            Err("not implemented".to_string())
+        }
+
         async fn find_by_email(&self, _email: &str) -> Result<Option<User>, String> {
             Ok(self.user.clone())
         }
@@ -269,12 +1186,43 @@ mod tests {
             Err("not implemented".to_string())
         }
 
-        async fn delete_user(
+        async fn mark_email_verified(
             &self,
             _id: &mongodb::bson::oid::ObjectId,
-        ) -> Result<(), String> {
+        ) -> Result<User, String> {
             Err("not implemented".to_string())
         }
+        async fn update_two_factor(
+            &self,
+            _id: &mongodb::bson::oid::ObjectId,
+            _two_factor: crate::modules::users::model::TwoFactor,
+        ) -> Result<User, String> {
+            Err("not implemented".to_string())
+        }
+
+        async fn consume_recovery_code(
+            &self,
+            _id: &mongodb::bson::oid::ObjectId,
+            _code_hash: &str,
+        ) -> Result<bool, String> {
+            Ok(false)
+        }
+
+        fn check_password(
+            &self,
+            _password: &str,
+            _email: &str,
+            _name: &str,
+        ) -> Result<(), crate::modules::users::password_policy::PasswordPolicyError> {
+            Ok(())
+        }
+    }
+
+    fn expect_session(result: LoginResult) -> LoginResponse {
+        match result {
+            LoginResult::Session(response) => response,
+            LoginResult::MfaRequired(_) => panic!("expected a session, got an MFA challenge"),
+        }
     }
 
     fn create_test_user() -> User {
@@ -291,8 +1239,12 @@ mod tests {
     fn test_generate_token_includes_exp_and_iat() {
         let service = AuthServiceImpl::new(
             Arc::new(MockUserService { user: None }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
             AuthConfig {
-                jwt_secret: "test_secret_key_for_testing".to_string(),
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
             },
         );
 
@@ -315,48 +1267,381 @@ mod tests {
     fn test_generate_refresh_token() {
         let service = AuthServiceImpl::new(
             Arc::new(MockUserService { user: None }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
             AuthConfig {
-                jwt_secret: "test_secret_key_for_testing".to_string(),
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
             },
         );
 
         let token = service
-            .generate_refresh_token("user123".to_string(), "test@test.com".to_string())
+            .generate_refresh_token(
+                "user123".to_string(),
+                "test@test.com".to_string(),
+                "jti-1".to_string(),
+                "family-1".to_string(),
+            )
             .unwrap();
 
-        let claims = service.verify_token(&token).unwrap();
+        let claims = service.verify_refresh_token(&token).unwrap();
         assert_eq!(claims.token_type, "refresh");
+        assert_eq!(claims.jti, "jti-1");
+        assert_eq!(claims.fid, "family-1");
         let now = Utc::now().timestamp() as usize;
         assert!(claims.exp > now + 86400); // at least 1 day
     }
 
-    #[test]
-    fn test_refresh_tokens_with_valid_refresh_token() {
+    #[tokio::test]
+    async fn test_refresh_tokens_with_valid_refresh_token() {
+        let user = create_test_user();
         let service = AuthServiceImpl::new(
-            Arc::new(MockUserService { user: None }),
+            Arc::new(MockUserService {
+                user: Some(user.clone()),
+            }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
             AuthConfig {
-                jwt_secret: "test_secret_key_for_testing".to_string(),
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
             },
         );
 
-        let refresh = service
-            .generate_refresh_token("user123".to_string(), "test@test.com".to_string())
+        let login = service
+            .login("test@test.com", "password", &ClientInfo::default())
+            .await
+            .map(expect_session)
             .unwrap();
 
-        let result = service.refresh_tokens(&refresh);
+        let result = service.refresh_tokens(&login.refresh_token, &ClientInfo::default()).await;
         assert!(result.is_ok());
         let response = result.unwrap();
         assert!(!response.access_token.is_empty());
         assert!(!response.refresh_token.is_empty());
+        assert_ne!(response.refresh_token, login.refresh_token);
         assert_eq!(response.token_type, "Bearer");
     }
 
-    #[test]
-    fn test_refresh_tokens_rejects_access_token() {
+    #[tokio::test]
+    async fn test_refresh_tokens_rotation_keeps_family() {
+        let user = create_test_user();
+        let service = AuthServiceImpl::new(
+            Arc::new(MockUserService {
+                user: Some(user.clone()),
+            }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
+            AuthConfig {
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
+            },
+        );
+
+        let login = service
+            .login("test@test.com", "password", &ClientInfo::default())
+            .await
+            .map(expect_session)
+            .unwrap();
+        let rotated = service
+            .refresh_tokens(&login.refresh_token, &ClientInfo::default())
+            .await
+            .unwrap();
+
+        let first = service.verify_refresh_token(&login.refresh_token).unwrap();
+        let second = service.verify_refresh_token(&rotated.refresh_token).unwrap();
+        assert_eq!(first.fid, second.fid);
+        assert_ne!(first.jti, second.jti);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_tokens_reuse_revokes_family() {
+        let user = create_test_user();
+        let service = AuthServiceImpl::new(
+            Arc::new(MockUserService {
+                user: Some(user.clone()),
+            }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
+            AuthConfig {
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
+            },
+        );
+
+        let login = service
+            .login("test@test.com", "password", &ClientInfo::default())
+            .await
+            .map(expect_session)
+            .unwrap();
+        let rotated = service
+            .refresh_tokens(&login.refresh_token, &ClientInfo::default())
+            .await
+            .unwrap();
+
+        // Replaying the already rotated token is treated as theft
+        let replay = service.refresh_tokens(&login.refresh_token, &ClientInfo::default()).await;
+        assert_eq!(replay.unwrap_err(), "Refresh token reuse detected");
+
+        // ...and the legitimate successor is revoked along with it
+        let result = service.refresh_tokens(&rotated.refresh_token, &ClientInfo::default()).await;
+        assert_eq!(result.unwrap_err(), "Refresh token has been revoked");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_tokens_rejects_unknown_token() {
+        let service = AuthServiceImpl::new(
+            Arc::new(MockUserService { user: None }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
+            AuthConfig {
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
+            },
+        );
+
+        let refresh = service
+            .generate_refresh_token(
+                ObjectId::new().to_string(),
+                "test@test.com".to_string(),
+                "never-stored".to_string(),
+                "family-1".to_string(),
+            )
+            .unwrap();
+
+        let result = service.refresh_tokens(&refresh, &ClientInfo::default()).await;
+        assert_eq!(result.unwrap_err(), "Refresh token has been revoked");
+    }
+
+    #[tokio::test]
+    async fn test_logout_revokes_session() {
+        let user = create_test_user();
+        let service = AuthServiceImpl::new(
+            Arc::new(MockUserService {
+                user: Some(user.clone()),
+            }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
+            AuthConfig {
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
+            },
+        );
+
+        let login = service
+            .login("test@test.com", "password", &ClientInfo::default())
+            .await
+            .map(expect_session)
+            .unwrap();
+        let other = service
+            .login("test@test.com", "password", &ClientInfo::default())
+            .await
+            .map(expect_session)
+            .unwrap();
+
+        service.logout(&login.refresh_token).await.unwrap();
+
+        assert!(service
+            .refresh_tokens(&login.refresh_token, &ClientInfo::default())
+            .await
+            .is_err());
+        assert!(service.refresh_tokens(&other.refresh_token, &ClientInfo::default()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_logout_all_revokes_every_session() {
+        let user = create_test_user();
+        let user_id = user.id.unwrap();
+        let service = AuthServiceImpl::new(
+            Arc::new(MockUserService { user: Some(user) }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
+            AuthConfig {
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
+            },
+        );
+
+        let login = service
+            .login("test@test.com", "password", &ClientInfo::default())
+            .await
+            .map(expect_session)
+            .unwrap();
+        let other = service
+            .login("test@test.com", "password", &ClientInfo::default())
+            .await
+            .map(expect_session)
+            .unwrap();
+
+        service.logout_all(&user_id).await.unwrap();
+
+        assert!(service
+            .refresh_tokens(&login.refresh_token, &ClientInfo::default())
+            .await
+            .is_err());
+        assert!(service
+            .refresh_tokens(&other.refresh_token, &ClientInfo::default())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_list_sessions_records_client_info() {
+        let user = create_test_user();
+        let user_id = user.id.unwrap();
+        let service = AuthServiceImpl::new(
+            Arc::new(MockUserService { user: Some(user) }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
+            AuthConfig {
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
+            },
+        );
+        let client = ClientInfo {
+            user_agent: Some("TestBrowser/1.0".to_string()),
+            ip_address: Some("203.0.113.7".to_string()),
+        };
+
+        service
+            .login("test@test.com", "password", &client)
+            .await
+            .map(expect_session)
+            .unwrap();
+
+        let sessions = service.list_sessions(&user_id).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].user_agent.as_deref(), Some("TestBrowser/1.0"));
+        assert_eq!(sessions[0].ip_address.as_deref(), Some("203.0.113.7"));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_keeps_session_and_updates_last_used() {
+        let user = create_test_user();
+        let user_id = user.id.unwrap();
+        let service = AuthServiceImpl::new(
+            Arc::new(MockUserService { user: Some(user) }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
+            AuthConfig {
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
+            },
+        );
+
+        let login = service
+            .login("test@test.com", "password", &ClientInfo::default())
+            .await
+            .map(expect_session)
+            .unwrap();
+        let before = service.list_sessions(&user_id).await.unwrap();
+
+        let client = ClientInfo {
+            user_agent: Some("TestBrowser/2.0".to_string()),
+            ip_address: None,
+        };
+        service
+            .refresh_tokens(&login.refresh_token, &client)
+            .await
+            .unwrap();
+
+        let after = service.list_sessions(&user_id).await.unwrap();
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[0].id, before[0].id);
+        assert_eq!(after[0].created_at, before[0].created_at);
+        assert_eq!(after[0].user_agent.as_deref(), Some("TestBrowser/2.0"));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_session() {
+        let user = create_test_user();
+        let user_id = user.id.unwrap();
+        let service = AuthServiceImpl::new(
+            Arc::new(MockUserService { user: Some(user) }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
+            AuthConfig {
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
+            },
+        );
+
+        let login = service
+            .login("test@test.com", "password", &ClientInfo::default())
+            .await
+            .map(expect_session)
+            .unwrap();
+        let other = service
+            .login("test@test.com", "password", &ClientInfo::default())
+            .await
+            .map(expect_session)
+            .unwrap();
+        let session_id = service
+            .verify_refresh_token(&login.refresh_token)
+            .unwrap()
+            .fid;
+
+        service.revoke_session(&user_id, &session_id).await.unwrap();
+
+        let sessions = service.list_sessions(&user_id).await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert!(service
+            .refresh_tokens(&login.refresh_token, &ClientInfo::default())
+            .await
+            .is_err());
+        assert!(service
+            .refresh_tokens(&other.refresh_token, &ClientInfo::default())
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_session_of_other_user_is_not_found() {
+        let user = create_test_user();
+        let service = AuthServiceImpl::new(
+            Arc::new(MockUserService { user: Some(user) }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
+            AuthConfig {
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
+            },
+        );
+
+        let login = service
+            .login("test@test.com", "password", &ClientInfo::default())
+            .await
+            .map(expect_session)
+            .unwrap();
+        let session_id = service
+            .verify_refresh_token(&login.refresh_token)
+            .unwrap()
+            .fid;
+
+        let result = service.revoke_session(&ObjectId::new(), &session_id).await;
+        assert_eq!(result.unwrap_err(), "Session not found");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_tokens_rejects_access_token() {
         let service = AuthServiceImpl::new(
             Arc::new(MockUserService { user: None }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
             AuthConfig {
-                jwt_secret: "test_secret_key_for_testing".to_string(),
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
             },
         );
 
@@ -364,21 +1649,25 @@ mod tests {
             .generate_token("user123".to_string(), "test@test.com".to_string())
             .unwrap();
 
-        let result = service.refresh_tokens(&access);
+        let result = service.refresh_tokens(&access, &ClientInfo::default()).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Invalid token type"));
     }
 
-    #[test]
-    fn test_refresh_tokens_rejects_invalid_token() {
+    #[tokio::test]
+    async fn test_refresh_tokens_rejects_invalid_token() {
         let service = AuthServiceImpl::new(
             Arc::new(MockUserService { user: None }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
             AuthConfig {
-                jwt_secret: "test_secret_key_for_testing".to_string(),
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
             },
         );
 
-        let result = service.refresh_tokens("invalid_token");
+        let result = service.refresh_tokens("invalid_token", &ClientInfo::default()).await;
         assert!(result.is_err());
     }
 
@@ -386,8 +1675,12 @@ mod tests {
     fn test_verify_invalid_token_fails() {
         let service = AuthServiceImpl::new(
             Arc::new(MockUserService { user: None }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
             AuthConfig {
-                jwt_secret: "test_secret".to_string(),
+                keys: Arc::new(JwtKeys::from_secret("test_secret")),
+                app_url: "http://localhost:3000".to_string(),
             },
         );
 
@@ -402,14 +1695,18 @@ mod tests {
             Arc::new(MockUserService {
                 user: Some(user.clone()),
             }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
             AuthConfig {
-                jwt_secret: "test_secret_key_for_testing".to_string(),
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
             },
         );
 
-        let result = service.login("test@test.com", "password").await;
+        let result = service.login("test@test.com", "password", &ClientInfo::default()).await;
         assert!(result.is_ok());
-        let response = result.unwrap();
+        let response = expect_session(result.unwrap());
         assert_eq!(response.token_type, "Bearer");
         assert!(!response.access_token.is_empty());
         assert!(!response.refresh_token.is_empty());
@@ -419,12 +1716,16 @@ mod tests {
     async fn test_login_invalid_credentials() {
         let service = AuthServiceImpl::new(
             Arc::new(MockUserService { user: None }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
             AuthConfig {
-                jwt_secret: "test_secret".to_string(),
+                keys: Arc::new(JwtKeys::from_secret("test_secret")),
+                app_url: "http://localhost:3000".to_string(),
             },
         );
 
-        let result = service.login("test@test.com", "wrong").await;
+        let result = service.login("test@test.com", "wrong", &ClientInfo::default()).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Invalid credentials"));
     }
@@ -433,8 +1734,12 @@ mod tests {
     fn test_generate_anonymous_token_has_session_id() {
         let service = AuthServiceImpl::new(
             Arc::new(MockUserService { user: None }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
             AuthConfig {
-                jwt_secret: "test_secret_key_for_testing".to_string(),
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
             },
         );
 
@@ -452,8 +1757,12 @@ mod tests {
     fn test_verify_anonymous_token_invalid() {
         let service = AuthServiceImpl::new(
             Arc::new(MockUserService { user: None }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
             AuthConfig {
-                jwt_secret: "test_secret_key_for_testing".to_string(),
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
             },
         );
 
@@ -465,8 +1774,12 @@ mod tests {
     fn test_verify_anonymous_token_roundtrip() {
         let service = AuthServiceImpl::new(
             Arc::new(MockUserService { user: None }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
             AuthConfig {
-                jwt_secret: "test_secret_key_for_testing".to_string(),
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
             },
         );
 
@@ -488,8 +1801,12 @@ mod tests {
     fn test_verify_anonymous_token_rejects_access_token() {
         let service = AuthServiceImpl::new(
             Arc::new(MockUserService { user: None }),
+            Arc::new(InMemoryRefreshTokens::default()),
+            Arc::new(UnusedOneTimeTokens),
+            Arc::new(LogMailer),
             AuthConfig {
-                jwt_secret: "test_secret_key_for_testing".to_string(),
+                keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+                app_url: "http://localhost:3000".to_string(),
             },
         );
 
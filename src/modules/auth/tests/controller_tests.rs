@@ -13,7 +13,9 @@ async fn setup_rocket() -> Rocket<Build> {
         .expect("Failed to initialize MongoDB for testing");
 
     let jwt_config = JwtConfig {
-        secret: "test_secret_key".to_string(),
+        secret: Some("test_secret_key".to_string()),
+        keys_dir: None,
+        active_key_id: None,
     };
 
     rocket::build()
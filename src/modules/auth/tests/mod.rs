@@ -1,3 +1,6 @@
 mod controller_tests;
+mod keys_tests;
 mod model_tests;
+mod oidc_tests;
 mod service_tests;
+mod totp_tests;
@@ -3,9 +3,10 @@ use serde_json;
 
 use crate::modules::auth::model::{
     AnonymousClaims, AnonymousTokenRequest, AnonymousTokenResponse, AuthUserResponse, JwtClaims,
-    LoginDto, LoginResponse, RefreshRequest, RefreshResponse, RegisterDto,
+    LoginDto, LoginResponse, LoginResult, MfaChallengeResponse, RefreshRequest, RefreshResponse,
+    RegisterDto, TokenPurpose,
 };
-use crate::modules::users::model::User;
+use crate::modules::users::model::{PrivacySettings, TwoFactor, User, UserRole};
 
 #[test]
 fn test_auth_user_response_from_user() {
@@ -16,6 +17,15 @@ fn test_auth_user_response_from_user() {
         email: "test@example.com".to_string(),
         name: "Test User".to_string(),
         password: "hashed_password".to_string(),
+        email_verified: true,
+        two_factor: TwoFactor::default(),
+        role: UserRole::User,
+        suspended_at: None,
+        deletion: None,
+        bio: None,
+        links: Vec::new(),
+        avatar: Vec::new(),
+        privacy: PrivacySettings::default(),
         created_at: DateTime::now(),
         updated_at: DateTime::now(),
     };
@@ -27,6 +37,26 @@ fn test_auth_user_response_from_user() {
     assert_eq!(auth_response.id, user_id);
     assert_eq!(auth_response.email, user.email);
     assert_eq!(auth_response.name, user.name);
+    assert!(auth_response.email_verified);
+    assert!(!auth_response.two_factor_enabled);
+}
+
+#[test]
+fn test_login_result_mfa_challenge_serialization() {
+    // Arrange
+    let result = LoginResult::MfaRequired(MfaChallengeResponse {
+        mfa_required: true,
+        mfa_token: "challenge".to_string(),
+    });
+
+    // Act
+    let json = serde_json::to_value(&result).unwrap();
+
+    // Assert
+    assert_eq!(
+        json,
+        serde_json::json!({ "mfa_required": true, "mfa_token": "challenge" })
+    );
 }
 
 #[test]
@@ -43,6 +73,18 @@ fn test_login_dto_deserialization() {
     // Assert
     assert_eq!(login_dto.email, "test@example.com");
     assert_eq!(login_dto.password, "password123");
+    assert!(login_dto.anonymous_token.is_none());
+}
+
+#[test]
+fn test_login_dto_with_anonymous_token() {
+    let json = r#"{
+        "email": "test@example.com",
+        "password": "password123",
+        "anonymous_token": "anon.jwt.token"
+    }"#;
+    let dto: LoginDto = serde_json::from_str(json).unwrap();
+    assert_eq!(dto.anonymous_token.as_deref(), Some("anon.jwt.token"));
 }
 
 #[test]
@@ -57,6 +99,9 @@ fn test_login_response_serialization() {
             id: user_id,
             email: "test@example.com".to_string(),
             name: "Test User".to_string(),
+            email_verified: false,
+            two_factor_enabled: false,
+            role: UserRole::User,
         },
     };
 
@@ -124,6 +169,7 @@ fn test_register_dto_deserialization() {
     assert_eq!(dto.email, "new@example.com");
     assert_eq!(dto.name, "New User");
     assert_eq!(dto.password, "password123");
+    assert!(dto.anonymous_token.is_none());
 }
 
 #[test]
@@ -171,3 +217,16 @@ fn test_anonymous_token_response_serialization() {
     assert!(json.contains("session-uuid-123"));
     assert!(json.contains("Player 1"));
 }
+
+#[test]
+fn test_token_purpose_serialization() {
+    assert_eq!(
+        serde_json::to_string(&TokenPurpose::PasswordReset).unwrap(),
+        "\"password_reset\""
+    );
+    assert_eq!(
+        serde_json::to_string(&TokenPurpose::EmailVerification).unwrap(),
+        "\"email_verification\""
+    );
+    assert_eq!(TokenPurpose::EmailVerification.as_str(), "email_verification");
+}
@@ -1,12 +1,29 @@
+use crate::common::secrets::hash_secret;
+use crate::config::client_info::ClientInfo;
 use crate::modules::auth::{
-    model::JwtClaims,
-    service::{AuthConfig, AuthService, AuthServiceImpl},
+    keys::JwtKeys,
+    model::{
+        JwtClaims, LoginResult, OidcLoginState, OneTimeToken, RefreshToken, TokenPurpose,
+        UserIdentity,
+    },
+    oidc::{code_challenge, OidcIdentity, OidcProvider},
+    repository::{
+        OidcStateRepository, OneTimeTokenRepository, RefreshTokenRepository,
+        UserIdentityRepository,
+    },
+    service::{AuthConfig, AuthService, AuthServiceImpl, OidcService, OidcServiceImpl},
+};
+use crate::modules::mail::{model::EmailMessage, service::Mailer};
+use crate::modules::users::{
+    model::{PrivacySettings, TwoFactor, User, UserRole},
+    password_policy::{PasswordPolicyError, PasswordRule, PasswordViolation},
+    service::UserService,
 };
-use crate::modules::users::{model::User, service::UserService};
 use async_trait::async_trait;
 use mockall::mock;
 use mongodb::bson::{oid::ObjectId, DateTime};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 mock! {
     UserService {}
@@ -19,20 +36,105 @@ mock! {
             name: String,
             password: String,
         ) -> Result<User, String>;
+        async fn provision_user(&self, email: String, name: String) -> Result<User, String>;
         async fn find_by_email(&self, email: &str) -> Result<Option<User>, String>;
         async fn find_by_id(&self, id: &ObjectId) -> Result<Option<User>, String>;
         async fn verify_credentials(&self, email: &str, password: &str) -> Result<Option<User>, String>;
         async fn update_user(&self, id: &ObjectId, dto: crate::modules::users::model::UpdateUserDto) -> Result<User, String>;
-        async fn delete_user(&self, id: &ObjectId) -> Result<(), String>;
+        async fn mark_email_verified(&self, id: &ObjectId) -> Result<User, String>;
+        async fn update_two_factor(&self, id: &ObjectId, two_factor: crate::modules::users::model::TwoFactor) -> Result<User, String>;
+        async fn consume_recovery_code(&self, id: &ObjectId, code_hash: &str) -> Result<bool, String>;
+        fn check_password(&self, password: &str, email: &str, name: &str) -> Result<(), PasswordPolicyError>;
+    }
+}
+
+mock! {
+    RefreshTokenRepo {}
+
+    #[async_trait]
+    impl RefreshTokenRepository for RefreshTokenRepo {
+        async fn create(&self, token: &RefreshToken) -> Result<(), String>;
+        async fn find_by_jti(&self, jti: &str) -> Result<Option<RefreshToken>, String>;
+        async fn mark_rotated(&self, jti: &str) -> Result<bool, String>;
+        async fn revoke_family(&self, family_id: &str) -> Result<(), String>;
+        async fn revoke_all_for_user(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn find_active_by_user(&self, user_id: &ObjectId) -> Result<Vec<RefreshToken>, String>;
+        async fn revoke_family_for_user(&self, user_id: &ObjectId, family_id: &str) -> Result<bool, String>;
+    }
+}
+
+mock! {
+    OneTimeTokenRepo {}
+
+    #[async_trait]
+    impl OneTimeTokenRepository for OneTimeTokenRepo {
+        async fn create(&self, token: &OneTimeToken) -> Result<(), String>;
+        async fn consume(&self, token_hash: &str, purpose: TokenPurpose) -> Result<Option<OneTimeToken>, String>;
+        async fn invalidate_for_user(&self, user_id: &ObjectId, purpose: TokenPurpose) -> Result<(), String>;
+    }
+}
+
+mock! {
+    Mailer {}
+
+    #[async_trait]
+    impl Mailer for Mailer {
+        async fn send(&self, message: &EmailMessage) -> Result<(), String>;
+    }
+}
+
+mock! {
+    OidcProvider {}
+
+    #[async_trait]
+    impl OidcProvider for OidcProvider {
+        async fn authorization_url(&self, state: &str, nonce: &str, code_challenge: &str) -> Result<String, String>;
+        async fn exchange_code(&self, code: &str, code_verifier: &str, nonce: &str) -> Result<OidcIdentity, String>;
+    }
+}
+
+mock! {
+    OidcStateRepo {}
+
+    #[async_trait]
+    impl OidcStateRepository for OidcStateRepo {
+        async fn create(&self, state: &OidcLoginState) -> Result<(), String>;
+        async fn consume(&self, state: &str) -> Result<Option<OidcLoginState>, String>;
+    }
+}
+
+mock! {
+    UserIdentityRepo {}
+
+    #[async_trait]
+    impl UserIdentityRepository for UserIdentityRepo {
+        async fn create(&self, identity: &UserIdentity) -> Result<(), String>;
+        async fn find_by_subject(&self, provider: &str, subject: &str) -> Result<Option<UserIdentity>, String>;
+        async fn delete_for_user(&self, user_id: &ObjectId) -> Result<(), String>;
     }
 }
 
+fn refresh_token_repo() -> MockRefreshTokenRepo {
+    let mut repo = MockRefreshTokenRepo::new();
+    repo.expect_create().returning(|_| Ok(()));
+    repo
+}
+
 fn create_test_user() -> User {
     User {
         id: Some(ObjectId::new()),
         email: "test@example.com".to_string(),
         name: "Test User".to_string(),
         password: "hashed_password".to_string(),
+        email_verified: false,
+        two_factor: TwoFactor::default(),
+        role: UserRole::User,
+        suspended_at: None,
+        deletion: None,
+        bio: None,
+        links: Vec::new(),
+        avatar: Vec::new(),
+        privacy: PrivacySettings::default(),
         created_at: DateTime::now(),
         updated_at: DateTime::now(),
     }
@@ -56,16 +158,23 @@ async fn test_login_success() {
 
     let auth_service = AuthServiceImpl::new(
         Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
         AuthConfig {
-            jwt_secret: "test_secret".to_string(),
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
         },
     );
 
     // Act
-    let result = auth_service
-        .login("test@example.com", "password123")
+    let LoginResult::Session(result) = auth_service
+        .login("test@example.com", "password123", &ClientInfo::default())
         .await
-        .unwrap();
+        .unwrap()
+    else {
+        panic!("expected a session");
+    };
 
     // Assert
     assert_eq!(result.token_type, "Bearer");
@@ -91,14 +200,18 @@ async fn test_login_invalid_credentials() {
 
     let auth_service = AuthServiceImpl::new(
         Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
         AuthConfig {
-            jwt_secret: "test_secret".to_string(),
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
         },
     );
 
     // Act
     let result = auth_service
-        .login("test@example.com", "wrong_password")
+        .login("test@example.com", "wrong_password", &ClientInfo::default())
         .await;
 
     // Assert
@@ -111,8 +224,12 @@ fn test_verify_token_success() {
     // Arrange
     let auth_service = AuthServiceImpl::new(
         Arc::new(MockUserService::new()),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
         AuthConfig {
-            jwt_secret: "test_secret".to_string(),
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
         },
     );
 
@@ -135,8 +252,12 @@ fn test_verify_token_invalid() {
     // Arrange
     let auth_service = AuthServiceImpl::new(
         Arc::new(MockUserService::new()),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
         AuthConfig {
-            jwt_secret: "test_secret".to_string(),
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
         },
     );
 
@@ -153,8 +274,12 @@ fn test_generate_token() {
     // Arrange
     let auth_service = AuthServiceImpl::new(
         Arc::new(MockUserService::new()),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
         AuthConfig {
-            jwt_secret: "test_secret".to_string(),
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
         },
     );
 
@@ -187,16 +312,39 @@ async fn test_register_success() {
         .times(1)
         .returning(move |_, _, _| Ok(test_user_clone.clone()));
 
+    let mut mock_token_repo = MockOneTimeTokenRepo::new();
+    mock_token_repo
+        .expect_invalidate_for_user()
+        .withf(|_, purpose| *purpose == TokenPurpose::EmailVerification)
+        .times(1)
+        .returning(|_, _| Ok(()));
+    mock_token_repo
+        .expect_create()
+        .withf(|token| token.purpose == TokenPurpose::EmailVerification)
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let mut mock_mailer = MockMailer::new();
+    mock_mailer
+        .expect_send()
+        .withf(|message| message.body.contains("http://localhost:3000/verify-email?token="))
+        .times(1)
+        .returning(|_| Ok(()));
+
     let auth_service = AuthServiceImpl::new(
         Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(mock_token_repo),
+        Arc::new(mock_mailer),
         AuthConfig {
-            jwt_secret: "test_secret_key_for_testing".to_string(),
+            keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+            app_url: "http://localhost:3000".to_string(),
         },
     );
 
     // Act
     let result = auth_service
-        .register("new@example.com", "New User", "password123")
+        .register("new@example.com", "New User", "password123", &ClientInfo::default())
         .await;
 
     // Assert
@@ -206,6 +354,49 @@ async fn test_register_success() {
     assert!(!response.refresh_token.is_empty());
     assert_eq!(response.token_type, "Bearer");
     assert_eq!(response.user.email, test_user.email);
+    assert!(!response.user.email_verified);
+}
+
+#[tokio::test]
+async fn test_register_succeeds_when_verification_email_fails() {
+    // Arrange
+    let mut mock_user_service = MockUserService::new();
+    let test_user = create_test_user();
+    mock_user_service
+        .expect_create_user()
+        .times(1)
+        .returning(move |_, _, _| Ok(test_user.clone()));
+
+    let mut mock_token_repo = MockOneTimeTokenRepo::new();
+    mock_token_repo
+        .expect_invalidate_for_user()
+        .returning(|_, _| Ok(()));
+    mock_token_repo.expect_create().returning(|_| Ok(()));
+
+    let mut mock_mailer = MockMailer::new();
+    mock_mailer
+        .expect_send()
+        .times(1)
+        .returning(|_| Err("Error sending email: connection refused".to_string()));
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(mock_token_repo),
+        Arc::new(mock_mailer),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service
+        .register("new@example.com", "New User", "password123", &ClientInfo::default())
+        .await;
+
+    // Assert
+    assert!(result.is_ok());
 }
 
 #[tokio::test]
@@ -219,14 +410,18 @@ async fn test_register_duplicate_email() {
 
     let auth_service = AuthServiceImpl::new(
         Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
         AuthConfig {
-            jwt_secret: "test_secret".to_string(),
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
         },
     );
 
     // Act
     let result = auth_service
-        .register("existing@example.com", "User", "password123")
+        .register("existing@example.com", "User", "password123", &ClientInfo::default())
         .await;
 
     // Assert
@@ -247,13 +442,19 @@ async fn test_register_short_password() {
 
     let auth_service = AuthServiceImpl::new(
         Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
         AuthConfig {
-            jwt_secret: "test_secret".to_string(),
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
         },
     );
 
     // Act
-    let result = auth_service.register("test@example.com", "User", "short").await;
+    let result = auth_service
+        .register("test@example.com", "User", "short", &ClientInfo::default())
+        .await;
 
     // Assert
     assert!(result.is_err());
@@ -265,8 +466,12 @@ fn test_generate_anonymous_token() {
     // Arrange
     let auth_service = AuthServiceImpl::new(
         Arc::new(MockUserService::new()),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
         AuthConfig {
-            jwt_secret: "test_secret_key_for_testing".to_string(),
+            keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+            app_url: "http://localhost:3000".to_string(),
         },
     );
     let tournament_id = ObjectId::new();
@@ -288,8 +493,12 @@ fn test_verify_anonymous_token_success() {
     // Arrange
     let auth_service = AuthServiceImpl::new(
         Arc::new(MockUserService::new()),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
         AuthConfig {
-            jwt_secret: "test_secret_key_for_testing".to_string(),
+            keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+            app_url: "http://localhost:3000".to_string(),
         },
     );
     let tournament_id = ObjectId::new();
@@ -314,8 +523,12 @@ fn test_verify_anonymous_token_rejects_access_token() {
     // Arrange
     let auth_service = AuthServiceImpl::new(
         Arc::new(MockUserService::new()),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
         AuthConfig {
-            jwt_secret: "test_secret_key_for_testing".to_string(),
+            keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+            app_url: "http://localhost:3000".to_string(),
         },
     );
     let access = auth_service
@@ -334,8 +547,12 @@ fn test_anonymous_token_contains_tournament_id() {
     // Arrange
     let auth_service = AuthServiceImpl::new(
         Arc::new(MockUserService::new()),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
         AuthConfig {
-            jwt_secret: "test_secret_key_for_testing".to_string(),
+            keys: Arc::new(JwtKeys::from_secret("test_secret_key_for_testing")),
+            app_url: "http://localhost:3000".to_string(),
         },
     );
     let tournament_id = ObjectId::new();
@@ -351,3 +568,1475 @@ fn test_anonymous_token_contains_tournament_id() {
     // Assert
     assert_eq!(claims.tournament_id, tournament_id.to_string());
 }
+
+#[tokio::test]
+async fn test_request_password_reset_unknown_email_sends_nothing() {
+    // Arrange
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_email()
+        .times(1)
+        .returning(|_| Ok(None));
+
+    let mut mock_mailer = MockMailer::new();
+    mock_mailer.expect_send().times(0);
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(mock_mailer),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service
+        .request_password_reset("nobody@example.com")
+        .await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_request_password_reset_emails_link_and_stores_hash() {
+    // Arrange
+    let test_user = create_test_user();
+    let user_id = test_user.id.unwrap();
+    let test_user_clone = test_user.clone();
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_email()
+        .with(mockall::predicate::eq("test@example.com"))
+        .times(1)
+        .returning(move |_| Ok(Some(test_user_clone.clone())));
+
+    let stored_hash = Arc::new(Mutex::new(String::new()));
+    let stored_hash_clone = Arc::clone(&stored_hash);
+    let mut mock_reset_repo = MockOneTimeTokenRepo::new();
+    mock_reset_repo
+        .expect_invalidate_for_user()
+        .with(
+            mockall::predicate::eq(user_id),
+            mockall::predicate::eq(TokenPurpose::PasswordReset),
+        )
+        .times(1)
+        .returning(|_, _| Ok(()));
+    mock_reset_repo
+        .expect_create()
+        .times(1)
+        .returning(move |token| {
+            *stored_hash_clone.lock().unwrap() = token.token_hash.clone();
+            Ok(())
+        });
+
+    let sent = Arc::new(Mutex::new(None));
+    let sent_clone = Arc::clone(&sent);
+    let mut mock_mailer = MockMailer::new();
+    mock_mailer.expect_send().times(1).returning(move |message| {
+        *sent_clone.lock().unwrap() = Some(message.clone());
+        Ok(())
+    });
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(mock_reset_repo),
+        Arc::new(mock_mailer),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "https://torvi.example.com".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service.request_password_reset("test@example.com").await;
+
+    // Assert
+    assert!(result.is_ok());
+    let message = sent.lock().unwrap().clone().unwrap();
+    assert_eq!(message.to, "test@example.com");
+
+    let prefix = "https://torvi.example.com/reset-password?token=";
+    let start = message.body.find(prefix).unwrap() + prefix.len();
+    let token: String = message.body[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_hexdigit())
+        .collect();
+    assert_eq!(*stored_hash.lock().unwrap(), hash_secret(&token));
+    assert_ne!(*stored_hash.lock().unwrap(), token);
+}
+
+#[tokio::test]
+async fn test_reset_password_success() {
+    // Arrange
+    let test_user = create_test_user();
+    let user_id = test_user.id.unwrap();
+    let test_user_clone = test_user.clone();
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_check_password()
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+    mock_user_service
+        .expect_update_user()
+        .withf(move |id, dto| *id == user_id && dto.password.as_deref() == Some("newpassword123"))
+        .times(1)
+        .returning(move |_, _| Ok(test_user_clone.clone()));
+
+    let mut mock_reset_repo = MockOneTimeTokenRepo::new();
+    mock_reset_repo
+        .expect_consume()
+        .with(
+            mockall::predicate::eq(hash_secret("reset-token")),
+            mockall::predicate::eq(TokenPurpose::PasswordReset),
+        )
+        .times(1)
+        .returning(move |hash, purpose| {
+            Ok(Some(OneTimeToken::new(
+                hash.to_string(),
+                user_id,
+                "test@example.com".to_string(),
+                purpose,
+                DateTime::now(),
+            )))
+        });
+
+    let mut mock_refresh_repo = MockRefreshTokenRepo::new();
+    mock_refresh_repo
+        .expect_revoke_all_for_user()
+        .with(mockall::predicate::eq(user_id))
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(mock_refresh_repo),
+        Arc::new(mock_reset_repo),
+        Arc::new(MockMailer::new()),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service
+        .reset_password("reset-token", "newpassword123")
+        .await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_reset_password_invalid_token() {
+    // Arrange
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_check_password()
+        .returning(|_, _, _| Ok(()));
+    mock_user_service.expect_update_user().times(0);
+
+    let mut mock_reset_repo = MockOneTimeTokenRepo::new();
+    mock_reset_repo
+        .expect_consume()
+        .times(1)
+        .returning(|_, _| Ok(None));
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(mock_reset_repo),
+        Arc::new(MockMailer::new()),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service
+        .reset_password("used-or-expired", "newpassword123")
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invalid or expired reset token");
+}
+
+#[tokio::test]
+async fn test_reset_password_short_password_keeps_token() {
+    // Arrange
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_check_password()
+        .withf(|password, _, _| password == "short")
+        .times(1)
+        .returning(|_, _, _| {
+            Err(PasswordPolicyError {
+                violations: vec![PasswordViolation {
+                    rule: PasswordRule::MinLength,
+                    message: "Password must be at least 10 characters".to_string(),
+                }],
+            })
+        });
+    let mut mock_reset_repo = MockOneTimeTokenRepo::new();
+    mock_reset_repo.expect_consume().times(0);
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(mock_reset_repo),
+        Arc::new(MockMailer::new()),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service.reset_password("reset-token", "short").await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Password must be at least 10 characters"
+    );
+}
+
+#[tokio::test]
+async fn test_send_verification_email_already_verified() {
+    // Arrange
+    let mut test_user = create_test_user();
+    test_user.email_verified = true;
+    let user_id = test_user.id.unwrap();
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(test_user.clone())));
+
+    let mut mock_mailer = MockMailer::new();
+    mock_mailer.expect_send().times(0);
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(mock_mailer),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service.send_verification_email(&user_id).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Email already verified");
+}
+
+#[tokio::test]
+async fn test_send_verification_email_emails_link_and_stores_hash() {
+    // Arrange
+    let test_user = create_test_user();
+    let user_id = test_user.id.unwrap();
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .with(mockall::predicate::eq(user_id))
+        .times(1)
+        .returning(move |_| Ok(Some(test_user.clone())));
+
+    let stored = Arc::new(Mutex::new(None));
+    let stored_clone = Arc::clone(&stored);
+    let mut mock_token_repo = MockOneTimeTokenRepo::new();
+    mock_token_repo
+        .expect_invalidate_for_user()
+        .with(
+            mockall::predicate::eq(user_id),
+            mockall::predicate::eq(TokenPurpose::EmailVerification),
+        )
+        .times(1)
+        .returning(|_, _| Ok(()));
+    mock_token_repo
+        .expect_create()
+        .times(1)
+        .returning(move |token| {
+            *stored_clone.lock().unwrap() = Some(token.clone());
+            Ok(())
+        });
+
+    let sent = Arc::new(Mutex::new(None));
+    let sent_clone = Arc::clone(&sent);
+    let mut mock_mailer = MockMailer::new();
+    mock_mailer.expect_send().times(1).returning(move |message| {
+        *sent_clone.lock().unwrap() = Some(message.clone());
+        Ok(())
+    });
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(mock_token_repo),
+        Arc::new(mock_mailer),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "https://torvi.example.com".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service.send_verification_email(&user_id).await;
+
+    // Assert
+    assert!(result.is_ok());
+    let message = sent.lock().unwrap().clone().unwrap();
+    assert_eq!(message.to, "test@example.com");
+
+    let prefix = "https://torvi.example.com/verify-email?token=";
+    let start = message.body.find(prefix).unwrap() + prefix.len();
+    let token: String = message.body[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_hexdigit())
+        .collect();
+    let stored = stored.lock().unwrap().clone().unwrap();
+    assert_eq!(stored.token_hash, hash_secret(&token));
+    assert_eq!(stored.email, "test@example.com");
+    assert_eq!(stored.purpose, TokenPurpose::EmailVerification);
+}
+
+#[tokio::test]
+async fn test_verify_email_success() {
+    // Arrange
+    let test_user = create_test_user();
+    let user_id = test_user.id.unwrap();
+    let test_user_clone = test_user.clone();
+
+    let mut mock_token_repo = MockOneTimeTokenRepo::new();
+    mock_token_repo
+        .expect_consume()
+        .with(
+            mockall::predicate::eq(hash_secret("verify-token")),
+            mockall::predicate::eq(TokenPurpose::EmailVerification),
+        )
+        .times(1)
+        .returning(move |hash, purpose| {
+            Ok(Some(OneTimeToken::new(
+                hash.to_string(),
+                user_id,
+                "test@example.com".to_string(),
+                purpose,
+                DateTime::now(),
+            )))
+        });
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(test_user_clone.clone())));
+    mock_user_service
+        .expect_mark_email_verified()
+        .with(mockall::predicate::eq(user_id))
+        .times(1)
+        .returning(move |_| {
+            let mut user = test_user.clone();
+            user.email_verified = true;
+            Ok(user)
+        });
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(mock_token_repo),
+        Arc::new(MockMailer::new()),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service.verify_email("verify-token").await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_verify_email_invalid_token() {
+    // Arrange
+    let mut mock_token_repo = MockOneTimeTokenRepo::new();
+    mock_token_repo
+        .expect_consume()
+        .times(1)
+        .returning(|_, _| Ok(None));
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service.expect_mark_email_verified().times(0);
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(mock_token_repo),
+        Arc::new(MockMailer::new()),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service.verify_email("used-or-expired").await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Invalid or expired verification token"
+    );
+}
+
+#[tokio::test]
+async fn test_verify_email_rejects_token_for_previous_address() {
+    // Arrange
+    let mut test_user = create_test_user();
+    test_user.email = "changed@example.com".to_string();
+    let user_id = test_user.id.unwrap();
+
+    let mut mock_token_repo = MockOneTimeTokenRepo::new();
+    mock_token_repo
+        .expect_consume()
+        .times(1)
+        .returning(move |hash, purpose| {
+            Ok(Some(OneTimeToken::new(
+                hash.to_string(),
+                user_id,
+                "test@example.com".to_string(),
+                purpose,
+                DateTime::now(),
+            )))
+        });
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(test_user.clone())));
+    mock_user_service.expect_mark_email_verified().times(0);
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(mock_token_repo),
+        Arc::new(MockMailer::new()),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service.verify_email("verify-token").await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Invalid or expired verification token"
+    );
+}
+
+#[tokio::test]
+async fn test_request_magic_link_skips_suspended_account() {
+    // Arrange
+    let mut test_user = create_test_user();
+    test_user.suspended_at = Some(DateTime::now());
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_email()
+        .times(1)
+        .returning(move |_| Ok(Some(test_user.clone())));
+
+    let mut mock_token_repo = MockOneTimeTokenRepo::new();
+    mock_token_repo.expect_create().times(0);
+    let mut mock_mailer = MockMailer::new();
+    mock_mailer.expect_send().times(0);
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(mock_token_repo),
+        Arc::new(mock_mailer),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service.request_magic_link("test@example.com").await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_request_magic_link_emails_link_and_stores_hash() {
+    // Arrange
+    let test_user = create_test_user();
+    let user_id = test_user.id.unwrap();
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_email()
+        .with(mockall::predicate::eq("test@example.com"))
+        .times(1)
+        .returning(move |_| Ok(Some(test_user.clone())));
+
+    let stored = Arc::new(Mutex::new(None));
+    let stored_clone = Arc::clone(&stored);
+    let mut mock_token_repo = MockOneTimeTokenRepo::new();
+    mock_token_repo
+        .expect_invalidate_for_user()
+        .with(
+            mockall::predicate::eq(user_id),
+            mockall::predicate::eq(TokenPurpose::MagicLink),
+        )
+        .times(1)
+        .returning(|_, _| Ok(()));
+    mock_token_repo
+        .expect_create()
+        .times(1)
+        .returning(move |token| {
+            *stored_clone.lock().unwrap() = Some(token.clone());
+            Ok(())
+        });
+
+    let sent = Arc::new(Mutex::new(None));
+    let sent_clone = Arc::clone(&sent);
+    let mut mock_mailer = MockMailer::new();
+    mock_mailer.expect_send().times(1).returning(move |message| {
+        *sent_clone.lock().unwrap() = Some(message.clone());
+        Ok(())
+    });
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(mock_token_repo),
+        Arc::new(mock_mailer),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "https://torvi.example.com".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service.request_magic_link(" test@example.com ").await;
+
+    // Assert
+    assert!(result.is_ok());
+    let message = sent.lock().unwrap().clone().unwrap();
+    assert_eq!(message.to, "test@example.com");
+
+    let prefix = "https://torvi.example.com/magic-link?token=";
+    let start = message.body.find(prefix).unwrap() + prefix.len();
+    let token: String = message.body[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_hexdigit())
+        .collect();
+    let stored = stored.lock().unwrap().clone().unwrap();
+    assert_eq!(stored.token_hash, hash_secret(&token));
+    assert_eq!(stored.purpose, TokenPurpose::MagicLink);
+    let ttl_ms = stored.expires_at.timestamp_millis() - DateTime::now().timestamp_millis();
+    assert!(ttl_ms > 14 * 60 * 1000 && ttl_ms <= 15 * 60 * 1000);
+}
+
+fn magic_link_token_repo(user_id: ObjectId) -> MockOneTimeTokenRepo {
+    let mut repo = MockOneTimeTokenRepo::new();
+    repo.expect_consume()
+        .with(
+            mockall::predicate::eq(hash_secret("login-token")),
+            mockall::predicate::eq(TokenPurpose::MagicLink),
+        )
+        .times(1)
+        .returning(move |hash, purpose| {
+            Ok(Some(OneTimeToken::new(
+                hash.to_string(),
+                user_id,
+                "test@example.com".to_string(),
+                purpose,
+                DateTime::now(),
+            )))
+        });
+    repo
+}
+
+#[tokio::test]
+async fn test_redeem_magic_link_starts_session_and_verifies_email() {
+    // Arrange
+    let test_user = create_test_user();
+    let user_id = test_user.id.unwrap();
+    let test_user_clone = test_user.clone();
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .with(mockall::predicate::eq(user_id))
+        .times(1)
+        .returning(move |_| Ok(Some(test_user_clone.clone())));
+    mock_user_service
+        .expect_mark_email_verified()
+        .with(mockall::predicate::eq(user_id))
+        .times(1)
+        .returning(move |_| {
+            let mut user = test_user.clone();
+            user.email_verified = true;
+            Ok(user)
+        });
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_token_repo()),
+        Arc::new(magic_link_token_repo(user_id)),
+        Arc::new(MockMailer::new()),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service
+        .redeem_magic_link("login-token", &ClientInfo::default())
+        .await;
+
+    // Assert
+    let LoginResult::Session(response) = result.unwrap() else {
+        panic!("expected a session");
+    };
+    assert_eq!(response.token_type, "Bearer");
+    assert_eq!(response.user.email, "test@example.com");
+    assert!(response.user.email_verified);
+}
+
+#[tokio::test]
+async fn test_redeem_magic_link_invalid_token() {
+    // Arrange
+    let mut mock_token_repo = MockOneTimeTokenRepo::new();
+    mock_token_repo
+        .expect_consume()
+        .times(1)
+        .returning(|_, _| Ok(None));
+    let mut refresh_repo = MockRefreshTokenRepo::new();
+    refresh_repo.expect_create().times(0);
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(MockUserService::new()),
+        Arc::new(refresh_repo),
+        Arc::new(mock_token_repo),
+        Arc::new(MockMailer::new()),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service
+        .redeem_magic_link("used-or-expired", &ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invalid or expired login link");
+}
+
+#[tokio::test]
+async fn test_redeem_magic_link_rejects_suspended_account() {
+    // Arrange
+    let mut test_user = create_test_user();
+    test_user.suspended_at = Some(DateTime::now());
+    let user_id = test_user.id.unwrap();
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(test_user.clone())));
+    let mut refresh_repo = MockRefreshTokenRepo::new();
+    refresh_repo.expect_create().times(0);
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_repo),
+        Arc::new(magic_link_token_repo(user_id)),
+        Arc::new(MockMailer::new()),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service
+        .redeem_magic_link("login-token", &ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Account is suspended");
+}
+
+#[tokio::test]
+async fn test_redeem_magic_link_with_two_factor_returns_challenge() {
+    // Arrange
+    let mut test_user = two_factor_user();
+    test_user.email_verified = true;
+    let user_id = test_user.id.unwrap();
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(test_user.clone())));
+    mock_user_service.expect_mark_email_verified().times(0);
+    let mut refresh_repo = MockRefreshTokenRepo::new();
+    refresh_repo.expect_create().times(0);
+
+    let auth_service = AuthServiceImpl::new(
+        Arc::new(mock_user_service),
+        Arc::new(refresh_repo),
+        Arc::new(magic_link_token_repo(user_id)),
+        Arc::new(MockMailer::new()),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    );
+
+    // Act
+    let result = auth_service
+        .redeem_magic_link("login-token", &ClientInfo::default())
+        .await;
+
+    // Assert
+    let LoginResult::MfaRequired(challenge) = result.unwrap() else {
+        panic!("expected an MFA challenge");
+    };
+    let claims = auth_service.verify_token(&challenge.mfa_token).unwrap();
+    assert_eq!(claims.token_type, "mfa");
+}
+
+const TOTP_SECRET: &str = "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP";
+
+fn two_factor_user() -> User {
+    User {
+        two_factor: TwoFactor {
+            secret: Some(TOTP_SECRET.to_string()),
+            ..TwoFactor::default()
+        },
+        ..create_test_user()
+    }
+}
+
+fn current_code() -> String {
+    let totp = totp_rs::TOTP::new(
+        totp_rs::Algorithm::SHA1,
+        6,
+        0,
+        30,
+        totp_rs::Secret::Encoded(TOTP_SECRET.to_string())
+            .to_bytes()
+            .unwrap(),
+        None,
+        String::new(),
+    )
+    .unwrap();
+    totp.generate(chrono::Utc::now().timestamp() as u64)
+}
+
+fn two_factor_auth_service(
+    user_service: MockUserService,
+    refresh_token_repo: MockRefreshTokenRepo,
+) -> AuthServiceImpl {
+    AuthServiceImpl::new(
+        Arc::new(user_service),
+        Arc::new(refresh_token_repo),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    )
+}
+
+fn mfa_token(user: &User) -> String {
+    let now = chrono::Utc::now().timestamp() as usize;
+    JwtKeys::from_secret("test_secret")
+        .encode(&JwtClaims {
+            sub: user.id.unwrap().to_hex(),
+            email: user.email.clone(),
+            exp: now + 300,
+            iat: now,
+            token_type: "mfa".to_string(),
+        })
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_login_with_two_factor_returns_challenge() {
+    // Arrange
+    let user = two_factor_user();
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_verify_credentials()
+        .returning(move |_, _| Ok(Some(user.clone())));
+    let mut refresh_token_repo = MockRefreshTokenRepo::new();
+    refresh_token_repo.expect_create().times(0);
+    let auth_service = two_factor_auth_service(mock_user_service, refresh_token_repo);
+
+    // Act
+    let result = auth_service
+        .login("test@example.com", "password123", &ClientInfo::default())
+        .await;
+
+    // Assert
+    let LoginResult::MfaRequired(challenge) = result.unwrap() else {
+        panic!("expected an MFA challenge");
+    };
+    assert!(challenge.mfa_required);
+    let claims = auth_service.verify_token(&challenge.mfa_token).unwrap();
+    assert_eq!(claims.token_type, "mfa");
+    assert_eq!(claims.email, "test@example.com");
+}
+
+#[tokio::test]
+async fn test_complete_mfa_login_with_totp_code() {
+    // Arrange
+    let user = two_factor_user();
+    let token = mfa_token(&user);
+    let found = user.clone();
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(found.clone())));
+    let updated = user.clone();
+    mock_user_service
+        .expect_update_two_factor()
+        .withf(|_, two_factor| two_factor.last_used_step.is_some())
+        .times(1)
+        .returning(move |_, _| Ok(updated.clone()));
+    mock_user_service.expect_consume_recovery_code().times(0);
+    let auth_service = two_factor_auth_service(mock_user_service, refresh_token_repo());
+
+    // Act
+    let result = auth_service
+        .complete_mfa_login(&token, &current_code(), &ClientInfo::default())
+        .await;
+
+    // Assert
+    let response = result.unwrap();
+    assert!(!response.refresh_token.is_empty());
+    assert!(response.user.two_factor_enabled);
+}
+
+#[tokio::test]
+async fn test_complete_mfa_login_rejects_replayed_code() {
+    // Arrange
+    let mut user = two_factor_user();
+    user.two_factor.last_used_step = Some(chrono::Utc::now().timestamp() / 30 + 1);
+    let token = mfa_token(&user);
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    mock_user_service.expect_update_two_factor().times(0);
+    mock_user_service
+        .expect_consume_recovery_code()
+        .returning(|_, _| Ok(false));
+    let auth_service = two_factor_auth_service(mock_user_service, MockRefreshTokenRepo::new());
+
+    // Act
+    let result = auth_service
+        .complete_mfa_login(&token, &current_code(), &ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invalid authentication code");
+}
+
+#[tokio::test]
+async fn test_complete_mfa_login_with_recovery_code() {
+    // Arrange
+    let user = two_factor_user();
+    let token = mfa_token(&user);
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    mock_user_service
+        .expect_consume_recovery_code()
+        .withf(|_, hash| hash == hash_secret("abcde12345"))
+        .times(1)
+        .returning(|_, _| Ok(true));
+    let auth_service = two_factor_auth_service(mock_user_service, refresh_token_repo());
+
+    // Act
+    let result = auth_service
+        .complete_mfa_login(&token, "ABCDE-12345", &ClientInfo::default())
+        .await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_complete_mfa_login_rejects_access_token() {
+    // Arrange
+    let user = two_factor_user();
+    let auth_service =
+        two_factor_auth_service(MockUserService::new(), MockRefreshTokenRepo::new());
+    let access_token = auth_service
+        .generate_token(user.id.unwrap().to_hex(), user.email.clone())
+        .unwrap();
+
+    // Act
+    let result = auth_service
+        .complete_mfa_login(&access_token, &current_code(), &ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Invalid token type: expected MFA token"
+    );
+}
+
+#[tokio::test]
+async fn test_start_totp_enrollment_stores_pending_secret() {
+    // Arrange
+    let user = create_test_user();
+    let user_id = user.id.unwrap();
+    let found = user.clone();
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(found.clone())));
+    let pending = Arc::new(Mutex::new(None));
+    let captured = pending.clone();
+    mock_user_service
+        .expect_update_two_factor()
+        .times(1)
+        .returning(move |_, two_factor| {
+            assert!(two_factor.secret.is_none());
+            *captured.lock().unwrap() = two_factor.pending_secret;
+            Ok(user.clone())
+        });
+    let auth_service = two_factor_auth_service(mock_user_service, MockRefreshTokenRepo::new());
+
+    // Act
+    let result = auth_service.start_totp_enrollment(&user_id).await;
+
+    // Assert
+    let enrollment = result.unwrap();
+    assert_eq!(pending.lock().unwrap().as_deref(), Some(enrollment.secret.as_str()));
+    assert!(enrollment.otpauth_uri.starts_with("otpauth://totp/Torvi:test%40example.com?"));
+    assert!(enrollment
+        .otpauth_uri
+        .contains(&format!("secret={}", enrollment.secret)));
+}
+
+#[tokio::test]
+async fn test_start_totp_enrollment_already_enabled() {
+    // Arrange
+    let user = two_factor_user();
+    let user_id = user.id.unwrap();
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    let auth_service = two_factor_auth_service(mock_user_service, MockRefreshTokenRepo::new());
+
+    // Act
+    let result = auth_service.start_totp_enrollment(&user_id).await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Two-factor authentication is already enabled"
+    );
+}
+
+#[tokio::test]
+async fn test_confirm_totp_enrollment_enables_and_returns_recovery_codes() {
+    // Arrange
+    let mut user = create_test_user();
+    user.two_factor.pending_secret = Some(TOTP_SECRET.to_string());
+    let user_id = user.id.unwrap();
+    let found = user.clone();
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(found.clone())));
+    let stored = Arc::new(Mutex::new(None));
+    let captured = stored.clone();
+    mock_user_service
+        .expect_update_two_factor()
+        .times(1)
+        .returning(move |_, two_factor| {
+            *captured.lock().unwrap() = Some(two_factor);
+            Ok(user.clone())
+        });
+    let auth_service = two_factor_auth_service(mock_user_service, MockRefreshTokenRepo::new());
+
+    // Act
+    let result = auth_service
+        .confirm_totp_enrollment(&user_id, &current_code())
+        .await;
+
+    // Assert
+    let codes = result.unwrap().recovery_codes;
+    assert_eq!(codes.len(), 10);
+    let two_factor = stored.lock().unwrap().clone().unwrap();
+    assert_eq!(two_factor.secret.as_deref(), Some(TOTP_SECRET));
+    assert!(two_factor.pending_secret.is_none());
+    assert!(two_factor.last_used_step.is_some());
+    assert_eq!(
+        two_factor.recovery_codes,
+        codes
+            .iter()
+            .map(|code| hash_secret(&code.replace('-', "")))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn test_confirm_totp_enrollment_invalid_code() {
+    // Arrange
+    let mut user = create_test_user();
+    user.two_factor.pending_secret = Some(TOTP_SECRET.to_string());
+    let user_id = user.id.unwrap();
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user.clone())));
+    mock_user_service.expect_update_two_factor().times(0);
+    let auth_service = two_factor_auth_service(mock_user_service, MockRefreshTokenRepo::new());
+
+    // Act
+    let result = auth_service.confirm_totp_enrollment(&user_id, "000000x").await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invalid authentication code");
+}
+
+#[tokio::test]
+async fn test_disable_totp_clears_two_factor() {
+    // Arrange
+    let user = two_factor_user();
+    let user_id = user.id.unwrap();
+    let found = user.clone();
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(found.clone())));
+    mock_user_service
+        .expect_consume_recovery_code()
+        .times(1)
+        .returning(|_, _| Ok(true));
+    mock_user_service
+        .expect_update_two_factor()
+        .withf(|_, two_factor| *two_factor == TwoFactor::default())
+        .times(1)
+        .returning(move |_, _| Ok(user.clone()));
+    let auth_service = two_factor_auth_service(mock_user_service, MockRefreshTokenRepo::new());
+
+    // Act
+    let result = auth_service.disable_totp(&user_id, "abcde-12345").await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+fn session_auth_service() -> Arc<dyn AuthService + Send + Sync> {
+    Arc::new(AuthServiceImpl::new(
+        Arc::new(MockUserService::new()),
+        Arc::new(refresh_token_repo()),
+        Arc::new(MockOneTimeTokenRepo::new()),
+        Arc::new(MockMailer::new()),
+        AuthConfig {
+            keys: Arc::new(JwtKeys::from_secret("test_secret")),
+            app_url: "http://localhost:3000".to_string(),
+        },
+    ))
+}
+
+fn oidc_service(
+    provider: MockOidcProvider,
+    state_repo: MockOidcStateRepo,
+    identity_repo: MockUserIdentityRepo,
+    user_service: MockUserService,
+) -> OidcServiceImpl {
+    let mut providers: HashMap<String, Arc<dyn OidcProvider>> = HashMap::new();
+    providers.insert("company".to_string(), Arc::new(provider));
+    OidcServiceImpl::new(
+        providers,
+        Arc::new(state_repo),
+        Arc::new(identity_repo),
+        Arc::new(user_service),
+        session_auth_service(),
+    )
+}
+
+fn pending_login_state() -> MockOidcStateRepo {
+    let mut state_repo = MockOidcStateRepo::new();
+    state_repo
+        .expect_consume()
+        .with(mockall::predicate::eq("login-state"))
+        .times(1)
+        .returning(|state| {
+            Ok(Some(OidcLoginState::new(
+                state.to_string(),
+                "company".to_string(),
+                "login-nonce".to_string(),
+                "login-verifier".to_string(),
+                DateTime::now(),
+            )))
+        });
+    state_repo
+}
+
+fn external_identity(email_verified: bool) -> MockOidcProvider {
+    let mut provider = MockOidcProvider::new();
+    provider
+        .expect_exchange_code()
+        .with(
+            mockall::predicate::eq("auth-code"),
+            mockall::predicate::eq("login-verifier"),
+            mockall::predicate::eq("login-nonce"),
+        )
+        .times(1)
+        .returning(move |_, _, _| {
+            Ok(OidcIdentity {
+                subject: "external-1".to_string(),
+                email: Some("test@example.com".to_string()),
+                email_verified,
+                name: Some("SSO User".to_string()),
+            })
+        });
+    provider
+}
+
+#[tokio::test]
+async fn test_oidc_authorization_url_stores_pkce_state() {
+    // Arrange
+    let sent = Arc::new(Mutex::new(None));
+    let sent_clone = Arc::clone(&sent);
+    let mut provider = MockOidcProvider::new();
+    provider
+        .expect_authorization_url()
+        .times(1)
+        .returning(move |state, nonce, challenge| {
+            *sent_clone.lock().unwrap() =
+                Some((state.to_string(), nonce.to_string(), challenge.to_string()));
+            Ok("https://sso.example.com/authorize?state=x".to_string())
+        });
+
+    let stored = Arc::new(Mutex::new(None));
+    let stored_clone = Arc::clone(&stored);
+    let mut state_repo = MockOidcStateRepo::new();
+    state_repo.expect_create().times(1).returning(move |state| {
+        *stored_clone.lock().unwrap() = Some(state.clone());
+        Ok(())
+    });
+
+    let service = oidc_service(
+        provider,
+        state_repo,
+        MockUserIdentityRepo::new(),
+        MockUserService::new(),
+    );
+
+    // Act
+    let result = service.authorization_url("company").await;
+
+    // Assert
+    assert_eq!(result.unwrap(), "https://sso.example.com/authorize?state=x");
+    let (state, nonce, challenge) = sent.lock().unwrap().clone().unwrap();
+    let stored = stored.lock().unwrap().clone().unwrap();
+    assert_eq!(stored.state, state);
+    assert_eq!(stored.nonce, nonce);
+    assert_eq!(stored.provider, "company");
+    assert_eq!(code_challenge(&stored.code_verifier), challenge);
+}
+
+#[tokio::test]
+async fn test_oidc_authorization_url_unknown_provider() {
+    // Arrange
+    let service = oidc_service(
+        MockOidcProvider::new(),
+        MockOidcStateRepo::new(),
+        MockUserIdentityRepo::new(),
+        MockUserService::new(),
+    );
+
+    // Act
+    let result = service.authorization_url("other").await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Unknown identity provider");
+}
+
+#[tokio::test]
+async fn test_oidc_login_invalid_state() {
+    // Arrange
+    let mut provider = MockOidcProvider::new();
+    provider.expect_exchange_code().times(0);
+    let mut state_repo = MockOidcStateRepo::new();
+    state_repo.expect_consume().times(1).returning(|_| Ok(None));
+
+    let service = oidc_service(
+        provider,
+        state_repo,
+        MockUserIdentityRepo::new(),
+        MockUserService::new(),
+    );
+
+    // Act
+    let result = service
+        .login("company", "auth-code", "unknown-state", &ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invalid or expired login state");
+}
+
+#[tokio::test]
+async fn test_oidc_login_with_linked_identity() {
+    // Arrange
+    let test_user = create_test_user();
+    let user_id = test_user.id.unwrap();
+
+    let mut identity_repo = MockUserIdentityRepo::new();
+    identity_repo
+        .expect_find_by_subject()
+        .with(
+            mockall::predicate::eq("company"),
+            mockall::predicate::eq("external-1"),
+        )
+        .times(1)
+        .returning(move |provider, subject| {
+            Ok(Some(UserIdentity::new(
+                user_id,
+                provider.to_string(),
+                subject.to_string(),
+                None,
+            )))
+        });
+    identity_repo.expect_create().times(0);
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_id()
+        .with(mockall::predicate::eq(user_id))
+        .times(1)
+        .returning(move |_| Ok(Some(test_user.clone())));
+
+    let service = oidc_service(
+        external_identity(true),
+        pending_login_state(),
+        identity_repo,
+        mock_user_service,
+    );
+
+    // Act
+    let result = service
+        .login("company", "auth-code", "login-state", &ClientInfo::default())
+        .await;
+
+    // Assert
+    let response = result.unwrap();
+    assert_eq!(response.user.id, user_id);
+    assert!(!response.access_token.is_empty());
+    assert!(!response.refresh_token.is_empty());
+}
+
+#[tokio::test]
+async fn test_oidc_login_links_existing_user_by_verified_email() {
+    // Arrange
+    let mut test_user = create_test_user();
+    test_user.email_verified = true;
+    let user_id = test_user.id.unwrap();
+
+    let mut identity_repo = MockUserIdentityRepo::new();
+    identity_repo
+        .expect_find_by_subject()
+        .times(1)
+        .returning(|_, _| Ok(None));
+    identity_repo
+        .expect_create()
+        .withf(move |identity| {
+            identity.user_id == user_id
+                && identity.provider == "company"
+                && identity.subject == "external-1"
+        })
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_email()
+        .with(mockall::predicate::eq("test@example.com"))
+        .times(1)
+        .returning(move |_| Ok(Some(test_user.clone())));
+    mock_user_service.expect_provision_user().times(0);
+    mock_user_service.expect_mark_email_verified().times(0);
+
+    let service = oidc_service(
+        external_identity(true),
+        pending_login_state(),
+        identity_repo,
+        mock_user_service,
+    );
+
+    // Act
+    let result = service
+        .login("company", "auth-code", "login-state", &ClientInfo::default())
+        .await;
+
+    // Assert
+    let response = result.unwrap();
+    assert_eq!(response.user.id, user_id);
+    assert!(response.user.email_verified);
+}
+
+#[tokio::test]
+async fn test_oidc_login_refuses_unverified_email_of_existing_user() {
+    // Arrange
+    let test_user = create_test_user();
+
+    let mut identity_repo = MockUserIdentityRepo::new();
+    identity_repo
+        .expect_find_by_subject()
+        .times(1)
+        .returning(|_, _| Ok(None));
+    identity_repo.expect_create().times(0);
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_email()
+        .times(1)
+        .returning(move |_| Ok(Some(test_user.clone())));
+
+    let service = oidc_service(
+        external_identity(false),
+        pending_login_state(),
+        identity_repo,
+        mock_user_service,
+    );
+
+    // Act
+    let result = service
+        .login("company", "auth-code", "login-state", &ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Email address is not verified by the identity provider"
+    );
+}
+
+#[tokio::test]
+async fn test_oidc_login_refuses_to_link_unverified_local_account() {
+    // Arrange
+    let test_user = create_test_user();
+
+    let mut identity_repo = MockUserIdentityRepo::new();
+    identity_repo
+        .expect_find_by_subject()
+        .times(1)
+        .returning(|_, _| Ok(None));
+    identity_repo.expect_create().times(0);
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_email()
+        .times(1)
+        .returning(move |_| Ok(Some(test_user.clone())));
+    mock_user_service.expect_mark_email_verified().times(0);
+
+    let service = oidc_service(
+        external_identity(true),
+        pending_login_state(),
+        identity_repo,
+        mock_user_service,
+    );
+
+    // Act
+    let result = service
+        .login("company", "auth-code", "login-state", &ClientInfo::default())
+        .await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Verify the email address of your existing account before linking"
+    );
+}
+
+#[tokio::test]
+async fn test_oidc_login_creates_user_for_new_identity() {
+    // Arrange
+    let test_user = create_test_user();
+    let user_id = test_user.id.unwrap();
+
+    let mut identity_repo = MockUserIdentityRepo::new();
+    identity_repo
+        .expect_find_by_subject()
+        .times(1)
+        .returning(|_, _| Ok(None));
+    identity_repo
+        .expect_create()
+        .withf(move |identity| identity.user_id == user_id)
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let mut mock_user_service = MockUserService::new();
+    mock_user_service
+        .expect_find_by_email()
+        .times(1)
+        .returning(|_| Ok(None));
+    mock_user_service
+        .expect_provision_user()
+        .withf(|email, name| email == "test@example.com" && name == "SSO User")
+        .times(1)
+        .returning(move |_, _| Ok(test_user.clone()));
+    mock_user_service.expect_mark_email_verified().times(0);
+
+    let service = oidc_service(
+        external_identity(false),
+        pending_login_state(),
+        identity_repo,
+        mock_user_service,
+    );
+
+    // Act
+    let result = service
+        .login("company", "auth-code", "login-state", &ClientInfo::default())
+        .await;
+
+    // Assert
+    let response = result.unwrap();
+    assert_eq!(response.user.id, user_id);
+    assert!(!response.user.email_verified);
+}
@@ -0,0 +1,71 @@
+use crate::common::secrets::hash_secret;
+use crate::modules::auth::totp::{
+    generate_recovery_codes, generate_secret, hash_recovery_code, otpauth_uri, verify_code,
+};
+
+/// RFC 6238 test secret "12345678901234567890" in base32
+const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+#[test]
+fn test_verify_code_matches_rfc_6238_vector() {
+    // The RFC lists 94287082 for T = 59; six digits keep the last six
+    let step = verify_code(RFC_SECRET, "287082", 59).unwrap();
+    assert_eq!(step, Some(1));
+}
+
+#[test]
+fn test_verify_code_allows_one_step_of_drift() {
+    assert_eq!(verify_code(RFC_SECRET, "287082", 59 + 30).unwrap(), Some(1));
+    assert_eq!(verify_code(RFC_SECRET, "287082", 59 - 30).unwrap(), Some(1));
+    assert_eq!(verify_code(RFC_SECRET, "287082", 59 + 60).unwrap(), None);
+}
+
+#[test]
+fn test_verify_code_rejects_wrong_code() {
+    assert_eq!(verify_code(RFC_SECRET, "123456", 59).unwrap(), None);
+}
+
+#[test]
+fn test_generate_secret_is_usable() {
+    let secret = generate_secret();
+
+    assert_eq!(secret.len(), 32);
+    assert!(verify_code(&secret, "000000", 0).is_ok());
+    assert_ne!(secret, generate_secret());
+}
+
+#[test]
+fn test_otpauth_uri() {
+    let uri = otpauth_uri(RFC_SECRET, "ada@example.com").unwrap();
+
+    assert_eq!(
+        uri,
+        format!(
+            "otpauth://totp/Torvi:ada%40example.com?secret={}&issuer=Torvi",
+            RFC_SECRET
+        )
+    );
+}
+
+#[test]
+fn test_recovery_codes_are_unique_and_formatted() {
+    let codes = generate_recovery_codes();
+
+    assert_eq!(codes.len(), 10);
+    assert!(codes
+        .iter()
+        .all(|code| code.len() == 11 && code.as_bytes()[5] == b'-'));
+    let mut unique = codes.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), codes.len());
+}
+
+#[test]
+fn test_hash_recovery_code_ignores_case_and_dashes() {
+    assert_eq!(hash_recovery_code("ABCDE-12345"), hash_secret("abcde12345"));
+    assert_eq!(
+        hash_recovery_code(" abcde12345 "),
+        hash_secret("abcde12345")
+    );
+}
@@ -0,0 +1,75 @@
+use rand::RngCore;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use crate::common::secrets::hash_secret;
+
+const ISSUER: &str = "Torvi";
+const SECRET_BYTES: usize = 20;
+const DIGITS: usize = 6;
+const STEP_SECS: u64 = 30;
+/// Steps accepted on either side of the current one, for clock drift
+const ALLOWED_DRIFT_STEPS: u64 = 1;
+const RECOVERY_CODE_COUNT: usize = 10;
+
+fn totp(secret: &str, account: &str) -> Result<TOTP, String> {
+    let bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| format!("Invalid TOTP secret: {:?}", e))?;
+    TOTP::new(
+        Algorithm::SHA1,
+        DIGITS,
+        0,
+        STEP_SECS,
+        bytes,
+        Some(ISSUER.to_string()),
+        account.to_string(),
+    )
+    .map_err(|e| format!("Invalid TOTP parameters: {}", e))
+}
+
+/// New random base32 secret.
+pub fn generate_secret() -> String {
+    let mut bytes = vec![0u8; SECRET_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    Secret::Raw(bytes).to_encoded().to_string()
+}
+
+/// `otpauth://` URI for authenticator apps, usually shown as a QR code.
+pub fn otpauth_uri(secret: &str, account: &str) -> Result<String, String> {
+    Ok(totp(secret, account)?.get_url())
+}
+
+/// Time step of the code if it is valid at `now` (Unix seconds).
+pub fn verify_code(secret: &str, code: &str, now: u64) -> Result<Option<i64>, String> {
+    let totp = totp(secret, "")?;
+    let code = code.trim();
+    let current = now / STEP_SECS;
+
+    Ok(
+        (current.saturating_sub(ALLOWED_DRIFT_STEPS)..=current + ALLOWED_DRIFT_STEPS)
+            .find(|step| totp.check(code, step * STEP_SECS))
+            .map(|step| step as i64),
+    )
+}
+
+/// Ten single-use codes formatted as `xxxxx-xxxxx`.
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; 5];
+            rand::thread_rng().fill_bytes(&mut bytes);
+            let code = hex::encode(bytes);
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+/// Stored digest of a recovery code. Case and dashes are ignored.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    hash_secret(&normalized)
+}
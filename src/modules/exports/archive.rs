@@ -0,0 +1,49 @@
+use std::io::{Cursor, Write};
+
+use chrono::Utc;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Builds a zip archive of deflated files in memory.
+pub struct ArchiveWriter {
+    zip: ZipWriter<Cursor<Vec<u8>>>,
+    options: SimpleFileOptions,
+}
+
+impl Default for ArchiveWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArchiveWriter {
+    pub fn new() -> Self {
+        let mut options =
+            SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        if let Ok(now) = zip::DateTime::try_from(Utc::now().naive_utc()) {
+            options = options.last_modified_time(now);
+        }
+
+        Self {
+            zip: ZipWriter::new(Cursor::new(Vec::new())),
+            options,
+        }
+    }
+
+    pub fn add_file(&mut self, name: &str, contents: &[u8]) -> Result<(), String> {
+        self.zip
+            .start_file(name, self.options)
+            .map_err(|e| format!("Failed to add {} to the archive: {}", name, e))?;
+        self.zip
+            .write_all(contents)
+            .map_err(|e| format!("Failed to add {} to the archive: {}", name, e))
+    }
+
+    /// Writes the central directory and returns the archive.
+    pub fn finish(self) -> Result<Vec<u8>, String> {
+        self.zip
+            .finish()
+            .map(Cursor::into_inner)
+            .map_err(|e| format!("Failed to finish the archive: {}", e))
+    }
+}
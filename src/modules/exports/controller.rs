@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use mongodb::bson::oid::ObjectId;
+use rocket::http::Header;
+use rocket::response::status::Accepted;
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::common::guards::AuthenticatedUser;
+use crate::error::Error;
+use crate::modules::exports::model::{ExportJobResponse, ExportStatus};
+use crate::modules::exports::service::{self as exports, ExportService};
+
+fn parse_id(id: &str) -> Result<ObjectId, Error> {
+    ObjectId::parse_str(id).map_err(|_| Error::BadRequest("Invalid export ID".to_string()))
+}
+
+fn export_error(e: String) -> Error {
+    if e == "Export not found" {
+        Error::NotFound(e)
+    } else if e == "Export is not ready yet" || e == "Export failed" {
+        Error::BadRequest(e)
+    } else {
+        Error::Internal(e)
+    }
+}
+
+#[derive(Responder)]
+#[response(content_type = "application/zip")]
+pub struct ExportArchive {
+    data: Vec<u8>,
+    disposition: Header<'static>,
+}
+
+#[post("/")]
+pub async fn request(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn ExportService + Send + Sync>>,
+) -> Result<Accepted<Json<ExportJobResponse>>, Error> {
+    let job = service
+        .request_export(&auth.user_id)
+        .await
+        .map_err(export_error)?;
+
+    if job.status == ExportStatus::Pending {
+        if let Some(id) = job.id {
+            exports::spawn_run(service.inner().clone(), id);
+        }
+    }
+
+    Ok(Accepted(Json(ExportJobResponse::from(job))))
+}
+
+#[get("/<id>")]
+pub async fn status(
+    id: &str,
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn ExportService + Send + Sync>>,
+) -> Result<Json<ExportJobResponse>, Error> {
+    let job = service
+        .get_job(&auth.user_id, &parse_id(id)?)
+        .await
+        .map_err(export_error)?;
+
+    Ok(Json(ExportJobResponse::from(job)))
+}
+
+#[get("/<id>/download")]
+pub async fn download(
+    id: &str,
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn ExportService + Send + Sync>>,
+) -> Result<ExportArchive, Error> {
+    let id = parse_id(id)?;
+    let data = service
+        .download(&auth.user_id, &id)
+        .await
+        .map_err(export_error)?;
+
+    Ok(ExportArchive {
+        data,
+        disposition: Header::new(
+            "Content-Disposition",
+            format!("attachment; filename=\"torvi-export-{}.zip\"", id),
+        ),
+    })
+}
+
+pub fn routes() -> Vec<rocket::Route> {
+    routes![request, status, download]
+}
@@ -0,0 +1,11 @@
+pub mod archive;
+pub mod controller;
+pub mod model;
+pub mod repository;
+pub mod service;
+pub mod storage;
+
+pub use controller::routes;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,142 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::common::json::{serialize_datetime, serialize_oid, serialize_option_datetime};
+use crate::modules::tournaments::model::{Tournament, VoterId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A personal data export. Once `expires_at` has passed the job is removed
+/// by a TTL index and its archive by the export sweeper.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportJob {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub status: ExportStatus,
+    pub error: Option<String>,
+    /// Storage key of the finished archive
+    #[serde(default)]
+    pub archive_key: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub created_at: DateTime,
+    pub started_at: Option<DateTime>,
+    pub completed_at: Option<DateTime>,
+    pub expires_at: DateTime,
+}
+
+impl ExportJob {
+    pub fn new(user_id: ObjectId, retention_secs: i64) -> Self {
+        let now = DateTime::now();
+        Self {
+            id: None,
+            user_id,
+            status: ExportStatus::Pending,
+            error: None,
+            archive_key: None,
+            size_bytes: None,
+            created_at: now,
+            started_at: None,
+            completed_at: None,
+            expires_at: secs_after(now, retention_secs),
+        }
+    }
+}
+
+pub fn secs_after(from: DateTime, retention_secs: i64) -> DateTime {
+    DateTime::from_millis(from.timestamp_millis() + retention_secs * 1000)
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportJobResponse {
+    #[serde(serialize_with = "serialize_oid")]
+    pub id: ObjectId,
+    pub status: ExportStatus,
+    pub error: Option<String>,
+    pub size_bytes: Option<i64>,
+    /// Set once the archive is ready
+    pub download_url: Option<String>,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub created_at: DateTime,
+    #[serde(serialize_with = "serialize_option_datetime")]
+    pub completed_at: Option<DateTime>,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub expires_at: DateTime,
+}
+
+impl From<ExportJob> for ExportJobResponse {
+    fn from(job: ExportJob) -> Self {
+        let id = job
+            .id
+            .expect("Export job must have an id to convert to response");
+        Self {
+            id,
+            status: job.status,
+            error: job.error,
+            size_bytes: job.size_bytes,
+            download_url: (job.status == ExportStatus::Completed)
+                .then(|| format!("/api/users/me/export/{}/download", id)),
+            created_at: job.created_at,
+            completed_at: job.completed_at,
+            expires_at: job.expires_at,
+        }
+    }
+}
+
+/// A tournament the user took part in, as written to `participations.json`.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ParticipationExport {
+    #[serde(serialize_with = "serialize_oid")]
+    pub tournament_id: ObjectId,
+    pub tournament_name: String,
+    /// Name the user appeared under in the tournament
+    pub display_name: String,
+    pub votes: Vec<VoteExport>,
+}
+
+impl ParticipationExport {
+    /// What `voter_id` did in the tournament, or `None` if they did not take part.
+    pub fn from_tournament(tournament: &Tournament, voter_id: &VoterId) -> Option<Self> {
+        let user = tournament.users.iter().find(|u| u.voter_id == *voter_id)?;
+        let votes = tournament
+            .rounds
+            .iter()
+            .flat_map(|round| {
+                round.matches.iter().flat_map(move |m| {
+                    m.votes
+                        .iter()
+                        .filter(|(_, voters)| voters.contains(voter_id))
+                        .filter_map(move |(opponent_id, _)| {
+                            Some(VoteExport {
+                                round_number: round.round_number,
+                                match_id: m.match_id.clone(),
+                                opponent_id: ObjectId::parse_str(opponent_id).ok()?,
+                            })
+                        })
+                })
+            })
+            .collect();
+
+        Some(Self {
+            tournament_id: tournament.id?,
+            tournament_name: tournament.name.clone(),
+            display_name: user.name.clone(),
+            votes,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct VoteExport {
+    pub round_number: i32,
+    pub match_id: String,
+    #[serde(serialize_with = "serialize_oid")]
+    pub opponent_id: ObjectId,
+}
@@ -0,0 +1,200 @@
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::ReturnDocument;
+use mongodb::Database;
+
+use crate::modules::exports::model::ExportJob;
+
+#[async_trait]
+pub trait ExportRepository: Send + Sync {
+    async fn create(&self, job: &ExportJob) -> Result<ObjectId, String>;
+    /// The job, if it belongs to the user and has not expired.
+    async fn find_for_user(
+        &self,
+        user_id: &ObjectId,
+        id: &ObjectId,
+    ) -> Result<Option<ExportJob>, String>;
+    async fn find_active_for_user(&self, user_id: &ObjectId) -> Result<Option<ExportJob>, String>;
+    /// Pending jobs, and running jobs started before `stale_before` whose
+    /// worker is assumed lost.
+    async fn find_unfinished(&self, stale_before: DateTime) -> Result<Vec<ExportJob>, String>;
+    /// Marks the job as running unless another worker already has it.
+    /// Returns `None` when the job could not be claimed.
+    async fn claim(
+        &self,
+        id: &ObjectId,
+        stale_before: DateTime,
+    ) -> Result<Option<ExportJob>, String>;
+    async fn complete(
+        &self,
+        id: &ObjectId,
+        archive_key: &str,
+        size_bytes: i64,
+        expires_at: DateTime,
+    ) -> Result<(), String>;
+    async fn fail(&self, id: &ObjectId, error: &str, expires_at: DateTime) -> Result<(), String>;
+    /// The archive key of a completed, unexpired job that belongs to the user.
+    async fn find_archive_key(
+        &self,
+        user_id: &ObjectId,
+        id: &ObjectId,
+    ) -> Result<Option<String>, String>;
+}
+
+pub struct ExportRepositoryImpl {
+    db: Database,
+}
+
+impl ExportRepositoryImpl {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+
+    fn unfinished_filter(stale_before: DateTime) -> mongodb::bson::Document {
+        doc! {
+            "$or": [
+                { "status": "pending" },
+                { "status": "running", "started_at": { "$lt": stale_before } },
+            ]
+        }
+    }
+}
+
+#[async_trait]
+impl ExportRepository for ExportRepositoryImpl {
+    async fn create(&self, job: &ExportJob) -> Result<ObjectId, String> {
+        let result = self
+            .db
+            .collection::<ExportJob>("export_jobs")
+            .insert_one(job)
+            .await
+            .map_err(|e| format!("Error creating export job: {}", e))?;
+        result
+            .inserted_id
+            .as_object_id()
+            .ok_or_else(|| "Error creating export job: missing id".to_string())
+    }
+
+    async fn find_for_user(
+        &self,
+        user_id: &ObjectId,
+        id: &ObjectId,
+    ) -> Result<Option<ExportJob>, String> {
+        self.db
+            .collection::<ExportJob>("export_jobs")
+            .find_one(doc! {
+                "_id": id,
+                "user_id": user_id,
+                "expires_at": { "$gt": DateTime::now() },
+            })
+            .await
+            .map_err(|e| format!("Error finding export job: {}", e))
+    }
+
+    async fn find_active_for_user(&self, user_id: &ObjectId) -> Result<Option<ExportJob>, String> {
+        self.db
+            .collection::<ExportJob>("export_jobs")
+            .find_one(doc! {
+                "user_id": user_id,
+                "status": { "$in": ["pending", "running"] },
+                "expires_at": { "$gt": DateTime::now() },
+            })
+            .await
+            .map_err(|e| format!("Error finding export job: {}", e))
+    }
+
+    async fn find_unfinished(&self, stale_before: DateTime) -> Result<Vec<ExportJob>, String> {
+        self.db
+            .collection::<ExportJob>("export_jobs")
+            .find(Self::unfinished_filter(stale_before))
+            .await
+            .map_err(|e| format!("Error finding export jobs: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error finding export jobs: {}", e))
+    }
+
+    async fn claim(
+        &self,
+        id: &ObjectId,
+        stale_before: DateTime,
+    ) -> Result<Option<ExportJob>, String> {
+        let mut filter = Self::unfinished_filter(stale_before);
+        filter.insert("_id", id);
+
+        self.db
+            .collection::<ExportJob>("export_jobs")
+            .find_one_and_update(
+                filter,
+                doc! { "$set": {
+                    "status": "running",
+                    "started_at": DateTime::now(),
+                } },
+            )
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(|e| format!("Error claiming export job: {}", e))
+    }
+
+    async fn complete(
+        &self,
+        id: &ObjectId,
+        archive_key: &str,
+        size_bytes: i64,
+        expires_at: DateTime,
+    ) -> Result<(), String> {
+        self.db
+            .collection::<ExportJob>("export_jobs")
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": {
+                    "status": "completed",
+                    "archive_key": archive_key,
+                    "size_bytes": size_bytes,
+                    "completed_at": DateTime::now(),
+                    "expires_at": expires_at,
+                } },
+            )
+            .await
+            .map_err(|e| format!("Error completing export job: {}", e))?;
+        Ok(())
+    }
+
+    async fn fail(&self, id: &ObjectId, error: &str, expires_at: DateTime) -> Result<(), String> {
+        self.db
+            .collection::<ExportJob>("export_jobs")
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": {
+                    "status": "failed",
+                    "error": error,
+                    "completed_at": DateTime::now(),
+                    "expires_at": expires_at,
+                } },
+            )
+            .await
+            .map_err(|e| format!("Error updating export job: {}", e))?;
+        Ok(())
+    }
+
+    async fn find_archive_key(
+        &self,
+        user_id: &ObjectId,
+        id: &ObjectId,
+    ) -> Result<Option<String>, String> {
+        let job = self
+            .db
+            .collection::<ExportJob>("export_jobs")
+            .find_one(doc! {
+                "_id": id,
+                "user_id": user_id,
+                "status": "completed",
+                "expires_at": { "$gt": DateTime::now() },
+            })
+            .await
+            .map_err(|e| format!("Error finding export archive: {}", e))?;
+
+        Ok(job.and_then(|job| job.archive_key))
+    }
+}
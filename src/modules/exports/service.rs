@@ -0,0 +1,283 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use rocket::fairing::AdHoc;
+use serde::Serialize;
+use tokio::time::interval;
+
+use crate::modules::exports::archive::ArchiveWriter;
+use crate::modules::exports::model::{secs_after, ExportJob, ExportStatus, ParticipationExport};
+use crate::modules::exports::repository::ExportRepository;
+use crate::modules::exports::storage::{archive_key, ArchiveStorage};
+use crate::modules::images::model::ImageResponse;
+use crate::modules::images::service::ImageService;
+use crate::modules::opponents::model::OpponentResponse;
+use crate::modules::opponents::repository::OpponentRepository;
+use crate::modules::tournaments::model::{TournamentResponse, VoterId};
+use crate::modules::tournaments::repository::TournamentRepository;
+use crate::modules::users::model::UserResponse;
+use crate::modules::users::repository::UserRepository;
+
+/// A running job not finished after this long is assumed lost and restarted
+const STALE_JOB_SECS: i64 = 900;
+/// How often expired archives are deleted from storage
+const PURGE_INTERVAL_SECS: u64 = 3600;
+const OPPONENT_PAGE_SIZE: i64 = 100;
+
+#[async_trait]
+pub trait ExportService: Send + Sync {
+    /// Queues an export of the user's data, or returns the one already
+    /// queued or running. The caller starts it with [`ExportService::run`].
+    async fn request_export(&self, user_id: &ObjectId) -> Result<ExportJob, String>;
+    async fn get_job(&self, user_id: &ObjectId, id: &ObjectId) -> Result<ExportJob, String>;
+    async fn download(&self, user_id: &ObjectId, id: &ObjectId) -> Result<Vec<u8>, String>;
+    /// Builds the archive of a queued job. Does nothing when another worker
+    /// already claimed the job.
+    async fn run(&self, id: &ObjectId) -> Result<(), String>;
+    /// Runs the jobs a restart interrupted and returns how many succeeded.
+    async fn resume_unfinished(&self) -> Result<usize, String>;
+    /// Deletes the archives past their retention and returns how many were
+    /// deleted.
+    async fn purge_expired(&self) -> Result<usize, String>;
+}
+
+pub struct ExportServiceImpl {
+    export_repository: Arc<dyn ExportRepository>,
+    archive_storage: Arc<dyn ArchiveStorage>,
+    user_repository: Arc<dyn UserRepository>,
+    tournament_repository: Arc<dyn TournamentRepository>,
+    opponent_repository: Arc<dyn OpponentRepository>,
+    image_service: Arc<dyn ImageService + Send + Sync>,
+    retention_secs: i64,
+}
+
+impl ExportServiceImpl {
+    pub fn new(
+        export_repository: Arc<dyn ExportRepository>,
+        archive_storage: Arc<dyn ArchiveStorage>,
+        user_repository: Arc<dyn UserRepository>,
+        tournament_repository: Arc<dyn TournamentRepository>,
+        opponent_repository: Arc<dyn OpponentRepository>,
+        image_service: Arc<dyn ImageService + Send + Sync>,
+        retention_secs: i64,
+    ) -> Self {
+        Self {
+            export_repository,
+            archive_storage,
+            user_repository,
+            tournament_repository,
+            opponent_repository,
+            image_service,
+            retention_secs,
+        }
+    }
+
+    fn stale_before() -> DateTime {
+        secs_after(DateTime::now(), -STALE_JOB_SECS)
+    }
+
+    async fn build_archive(&self, user_id: &ObjectId) -> Result<Vec<u8>, String> {
+        let user = self
+            .user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| "User not found".to_string())?;
+
+        let tournaments: Vec<TournamentResponse> = self
+            .tournament_repository
+            .find_by_organizer(user_id)
+            .await?
+            .into_iter()
+            .map(TournamentResponse::from)
+            .collect();
+
+        let voter_id = VoterId::Registered(*user_id);
+        let participations: Vec<ParticipationExport> = self
+            .tournament_repository
+            .find_by_participant(&voter_id)
+            .await?
+            .iter()
+            .filter_map(|t| ParticipationExport::from_tournament(t, &voter_id))
+            .collect();
+
+        let mut opponents = Vec::new();
+        let mut cursor = None;
+        loop {
+            let mut page = self
+                .opponent_repository
+                .find_by_creator(user_id, cursor, OPPONENT_PAGE_SIZE)
+                .await?;
+            let has_more = page.len() as i64 > OPPONENT_PAGE_SIZE;
+            page.truncate(OPPONENT_PAGE_SIZE as usize);
+            cursor = page.last().and_then(|o| o.id);
+            opponents.extend(page.into_iter().map(OpponentResponse::from));
+            if !has_more {
+                break;
+            }
+        }
+
+        let images: Vec<ImageResponse> = self
+            .image_service
+            .find_by_creator(user_id)
+            .await?
+            .into_iter()
+            .map(ImageResponse::from)
+            .collect();
+
+        let mut archive = ArchiveWriter::new();
+        add_json(&mut archive, "profile.json", &UserResponse::from(user))?;
+        add_json(&mut archive, "tournaments.json", &tournaments)?;
+        add_json(&mut archive, "opponents.json", &opponents)?;
+        add_json(&mut archive, "images.json", &images)?;
+        add_json(&mut archive, "participations.json", &participations)?;
+        archive.finish()
+    }
+
+    /// Builds the archive and stores it, returning its key and size.
+    async fn store_archive(
+        &self,
+        job: &ExportJob,
+        expires_at: DateTime,
+    ) -> Result<(String, i64), String> {
+        let id = job.id.ok_or("Export job has no id")?;
+        let archive = self.build_archive(&job.user_id).await?;
+        let size = archive.len() as i64;
+        let key = archive_key(&id);
+        self.archive_storage.put(&key, archive, expires_at).await?;
+        Ok((key, size))
+    }
+}
+
+fn add_json<T: Serialize>(
+    archive: &mut ArchiveWriter,
+    name: &str,
+    value: &T,
+) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+    archive.add_file(name, &json)
+}
+
+#[async_trait]
+impl ExportService for ExportServiceImpl {
+    async fn request_export(&self, user_id: &ObjectId) -> Result<ExportJob, String> {
+        if let Some(job) = self.export_repository.find_active_for_user(user_id).await? {
+            return Ok(job);
+        }
+
+        let mut job = ExportJob::new(*user_id, self.retention_secs);
+        job.id = Some(self.export_repository.create(&job).await?);
+        Ok(job)
+    }
+
+    async fn get_job(&self, user_id: &ObjectId, id: &ObjectId) -> Result<ExportJob, String> {
+        self.export_repository
+            .find_for_user(user_id, id)
+            .await?
+            .ok_or_else(|| "Export not found".to_string())
+    }
+
+    async fn download(&self, user_id: &ObjectId, id: &ObjectId) -> Result<Vec<u8>, String> {
+        if let Some(key) = self.export_repository.find_archive_key(user_id, id).await? {
+            return self.archive_storage.get(&key).await;
+        }
+
+        match self.get_job(user_id, id).await?.status {
+            ExportStatus::Failed => Err("Export failed".to_string()),
+            _ => Err("Export is not ready yet".to_string()),
+        }
+    }
+
+    async fn run(&self, id: &ObjectId) -> Result<(), String> {
+        let Some(job) = self
+            .export_repository
+            .claim(id, Self::stale_before())
+            .await?
+        else {
+            return Ok(());
+        };
+
+        // Retention starts once the archive can be downloaded
+        let expires_at = secs_after(DateTime::now(), self.retention_secs);
+        match self.store_archive(&job, expires_at).await {
+            Ok((key, size)) => {
+                self.export_repository
+                    .complete(id, &key, size, expires_at)
+                    .await
+            }
+            Err(e) => {
+                let message = format!("Export failed: {}", e);
+                self.export_repository
+                    .fail(id, &message, expires_at)
+                    .await?;
+                Err(message)
+            }
+        }
+    }
+
+    async fn resume_unfinished(&self) -> Result<usize, String> {
+        let jobs = self
+            .export_repository
+            .find_unfinished(Self::stale_before())
+            .await?;
+
+        let mut completed = 0;
+        for id in jobs.iter().filter_map(|job| job.id) {
+            match self.run(&id).await {
+                Ok(()) => completed += 1,
+                Err(e) => tracing::warn!(export_id = %id, error = %e, "Export job failed"),
+            }
+        }
+        Ok(completed)
+    }
+
+    async fn purge_expired(&self) -> Result<usize, String> {
+        let before = secs_after(DateTime::now(), -self.retention_secs);
+        self.archive_storage.delete_stored_before(before).await
+    }
+}
+
+/// Builds the archive of a queued job in the background.
+pub fn spawn_run(service: Arc<dyn ExportService + Send + Sync>, id: ObjectId) {
+    tokio::spawn(async move {
+        if let Err(e) = service.run(&id).await {
+            tracing::warn!(export_id = %id, error = %e, "Export job failed");
+        }
+    });
+}
+
+/// Periodically deletes the archives past their retention.
+pub fn sweeper(service: Arc<dyn ExportService + Send + Sync>) -> AdHoc {
+    AdHoc::on_liftoff("Export Archive Sweeper", move |_| {
+        Box::pin(async move {
+            tokio::spawn(async move {
+                let mut tick = interval(Duration::from_secs(PURGE_INTERVAL_SECS));
+                loop {
+                    tick.tick().await;
+                    match service.purge_expired().await {
+                        Ok(0) => {}
+                        Ok(count) => tracing::info!(count, "Deleted expired export archives"),
+                        Err(e) => tracing::warn!(error = %e, "Failed to delete expired exports"),
+                    }
+                }
+            });
+        })
+    })
+}
+
+/// Picks up the export jobs left unfinished by the previous run.
+pub fn recovery(service: Arc<dyn ExportService + Send + Sync>) -> AdHoc {
+    AdHoc::on_liftoff("Export Job Recovery", move |_| {
+        Box::pin(async move {
+            tokio::spawn(async move {
+                match service.resume_unfinished().await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::info!(count, "Resumed export jobs"),
+                    Err(e) => tracing::warn!(error = %e, "Failed to resume export jobs"),
+                }
+            });
+        })
+    })
+}
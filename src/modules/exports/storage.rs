@@ -0,0 +1,133 @@
+use async_trait::async_trait;
+use aws_credential_types::Credentials;
+use aws_sdk_s3::config::{BehaviorVersion, Region};
+use aws_sdk_s3::primitives::{ByteStream, DateTime as S3DateTime};
+use aws_sdk_s3::Client;
+use mongodb::bson::{oid::ObjectId, DateTime};
+
+use crate::config::s3::S3Config;
+
+/// Export archives are kept apart from images so they can be swept by prefix
+const KEY_PREFIX: &str = "exports/";
+
+pub fn archive_key(job_id: &ObjectId) -> String {
+    format!("{}{}.zip", KEY_PREFIX, job_id)
+}
+
+/// Where finished export archives are kept until they expire.
+#[async_trait]
+pub trait ArchiveStorage: Send + Sync {
+    async fn put(&self, key: &str, archive: Vec<u8>, expires_at: DateTime) -> Result<(), String>;
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String>;
+    /// Deletes the archives stored before `before` and returns how many
+    /// were deleted.
+    async fn delete_stored_before(&self, before: DateTime) -> Result<usize, String>;
+}
+
+/// Keeps archives in the image bucket under `exports/`.
+pub struct S3ArchiveStorage {
+    client: Client,
+    bucket: String,
+}
+
+impl S3ArchiveStorage {
+    pub fn new(config: &S3Config) -> Self {
+        let credentials = Credentials::new(
+            &config.access_key_id,
+            &config.secret_access_key,
+            None,
+            None,
+            "torvi",
+        );
+
+        let mut builder = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new(config.region.clone()))
+            .credentials_provider(credentials);
+
+        if let Some(ref endpoint) = config.endpoint_url {
+            builder = builder.endpoint_url(endpoint).force_path_style(true);
+        }
+
+        Self {
+            client: Client::from_conf(builder.build()),
+            bucket: config.bucket.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl ArchiveStorage for S3ArchiveStorage {
+    async fn put(&self, key: &str, archive: Vec<u8>, expires_at: DateTime) -> Result<(), String> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(ByteStream::from(archive))
+            .content_type("application/zip")
+            .expires(S3DateTime::from_millis(expires_at.timestamp_millis()))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to upload export archive: {}", e))?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String> {
+        let object = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to download export archive: {}", e))?;
+
+        let data = object
+            .body
+            .collect()
+            .await
+            .map_err(|e| format!("Failed to download export archive: {}", e))?;
+        Ok(data.into_bytes().to_vec())
+    }
+
+    async fn delete_stored_before(&self, before: DateTime) -> Result<usize, String> {
+        let mut deleted = 0;
+        let mut continuation_token = None;
+        loop {
+            let page = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(KEY_PREFIX)
+                .set_continuation_token(continuation_token)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to list export archives: {}", e))?;
+
+            for object in page.contents() {
+                let expired = object
+                    .last_modified()
+                    .and_then(|modified| modified.to_millis().ok())
+                    .is_some_and(|millis| millis < before.timestamp_millis());
+                let Some(key) = object.key().filter(|_| expired) else {
+                    continue;
+                };
+
+                self.client
+                    .delete_object()
+                    .bucket(&self.bucket)
+                    .key(key)
+                    .send()
+                    .await
+                    .map_err(|e| format!("Failed to delete export archive: {}", e))?;
+                deleted += 1;
+            }
+
+            continuation_token = page.next_continuation_token().map(str::to_string);
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,48 @@
+use std::io::{Cursor, Read};
+
+use zip::{CompressionMethod, ZipArchive};
+
+use crate::modules::exports::archive::ArchiveWriter;
+
+/// Reads every file back in archive order.
+fn read_entries(data: Vec<u8>) -> Vec<(String, Vec<u8>)> {
+    let mut archive = ZipArchive::new(Cursor::new(data)).unwrap();
+    (0..archive.len())
+        .map(|i| {
+            let mut file = archive.by_index(i).unwrap();
+            assert_eq!(file.compression(), CompressionMethod::Deflated);
+            let mut contents = Vec::new();
+            file.read_to_end(&mut contents).unwrap();
+            (file.name().to_string(), contents)
+        })
+        .collect()
+}
+
+#[test]
+fn test_archive_round_trip() {
+    // Arrange
+    let mut archive = ArchiveWriter::new();
+    let profile = br#"{"name": "Jane"}"#.repeat(100);
+
+    // Act
+    archive.add_file("profile.json", &profile).unwrap();
+    archive.add_file("empty.json", b"").unwrap();
+    let data = archive.finish().unwrap();
+
+    // Assert
+    assert!(data.len() < profile.len());
+    let entries = read_entries(data);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0], ("profile.json".to_string(), profile.clone()));
+    assert_eq!(entries[1], ("empty.json".to_string(), Vec::new()));
+}
+
+#[test]
+fn test_empty_archive() {
+    // Act
+    let data = ArchiveWriter::new().finish().unwrap();
+
+    // Assert
+    assert_eq!(data.len(), 22);
+    assert!(read_entries(data).is_empty());
+}
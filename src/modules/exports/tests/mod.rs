@@ -0,0 +1,3 @@
+mod archive_tests;
+mod model_tests;
+mod service_tests;
@@ -0,0 +1,105 @@
+use mongodb::bson::oid::ObjectId;
+
+use crate::modules::exports::model::{
+    ExportJob, ExportJobResponse, ExportStatus, ParticipationExport, VoteExport,
+};
+use crate::modules::tournaments::model::{Match, Round, Tournament, TournamentUser, VoterId};
+
+#[test]
+fn test_new_job_is_pending_until_retention_ends() {
+    // Act
+    let job = ExportJob::new(ObjectId::new(), 3600);
+
+    // Assert
+    assert_eq!(job.status, ExportStatus::Pending);
+    assert_eq!(
+        job.expires_at.timestamp_millis() - job.created_at.timestamp_millis(),
+        3_600_000
+    );
+}
+
+#[test]
+fn test_response_links_download_once_completed() {
+    // Arrange
+    let id = ObjectId::new();
+    let mut job = ExportJob::new(ObjectId::new(), 3600);
+    job.id = Some(id);
+
+    // Act
+    let pending = serde_json::to_value(ExportJobResponse::from(job.clone())).unwrap();
+    job.status = ExportStatus::Completed;
+    let completed = serde_json::to_value(ExportJobResponse::from(job)).unwrap();
+
+    // Assert
+    assert_eq!(pending["status"], "pending");
+    assert!(pending["download_url"].is_null());
+    assert_eq!(
+        completed["download_url"],
+        format!("/api/users/me/export/{}/download", id)
+    );
+}
+
+#[test]
+fn test_participation_lists_votes_of_voter() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let voter = VoterId::Registered(user_id);
+    let other = VoterId::Anonymous("guest".to_string());
+    let (first, second) = (ObjectId::new(), ObjectId::new());
+    let mut match_instance = Match {
+        match_id: "match-1".to_string(),
+        opponent1: first,
+        opponent2: second,
+        votes: Default::default(),
+        winner: None,
+        match_date: mongodb::bson::DateTime::now(),
+    };
+    match_instance
+        .votes
+        .insert(first.to_string(), vec![voter.clone()]);
+    match_instance
+        .votes
+        .insert(second.to_string(), vec![other.clone()]);
+    let mut tournament = Tournament::new(
+        "Snacks".to_string(),
+        ObjectId::new(),
+        vec![],
+        vec![],
+        Round {
+            round_number: 1,
+            matches: vec![match_instance],
+            automatic_winners: vec![],
+        },
+    );
+    tournament.id = Some(ObjectId::new());
+    tournament.users = vec![
+        TournamentUser {
+            voter_id: voter.clone(),
+            name: "Jane".to_string(),
+            muted: false,
+        },
+        TournamentUser {
+            voter_id: other,
+            name: "Guest".to_string(),
+            muted: false,
+        },
+    ];
+
+    // Act
+    let participation = ParticipationExport::from_tournament(&tournament, &voter).unwrap();
+    let missing =
+        ParticipationExport::from_tournament(&tournament, &VoterId::Registered(ObjectId::new()));
+
+    // Assert
+    assert_eq!(participation.tournament_name, "Snacks");
+    assert_eq!(participation.display_name, "Jane");
+    assert_eq!(
+        participation.votes,
+        vec![VoteExport {
+            round_number: 1,
+            match_id: "match-1".to_string(),
+            opponent_id: first,
+        }]
+    );
+    assert!(missing.is_none());
+}
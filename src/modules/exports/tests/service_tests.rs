@@ -0,0 +1,485 @@
+use async_trait::async_trait;
+use mockall::mock;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+
+use crate::modules::exports::model::{ExportJob, ExportStatus};
+use crate::modules::exports::repository::ExportRepository;
+use crate::modules::exports::service::{ExportService, ExportServiceImpl};
+use crate::modules::exports::storage::ArchiveStorage;
+use crate::modules::images::model::Image;
+use crate::modules::images::service::ImageService;
+use crate::modules::opponents::model::Opponent;
+use crate::modules::opponents::repository::OpponentRepository;
+use crate::modules::tournaments::model::{Tournament, TournamentUser, VoterId};
+use crate::modules::tournaments::repository::TournamentRepository;
+use crate::modules::users::model::{User, UserRole};
+use crate::modules::users::repository::UserRepository;
+
+mock! {
+    ExportRepo {}
+
+    #[async_trait]
+    impl ExportRepository for ExportRepo {
+        async fn create(&self, job: &ExportJob) -> Result<ObjectId, String>;
+        async fn find_for_user(&self, user_id: &ObjectId, id: &ObjectId) -> Result<Option<ExportJob>, String>;
+        async fn find_active_for_user(&self, user_id: &ObjectId) -> Result<Option<ExportJob>, String>;
+        async fn find_unfinished(&self, stale_before: DateTime) -> Result<Vec<ExportJob>, String>;
+        async fn claim(&self, id: &ObjectId, stale_before: DateTime) -> Result<Option<ExportJob>, String>;
+        async fn complete(&self, id: &ObjectId, archive_key: &str, size_bytes: i64, expires_at: DateTime) -> Result<(), String>;
+        async fn fail(&self, id: &ObjectId, error: &str, expires_at: DateTime) -> Result<(), String>;
+        async fn find_archive_key(&self, user_id: &ObjectId, id: &ObjectId) -> Result<Option<String>, String>;
+    }
+}
+
+mock! {
+    Storage {}
+
+    #[async_trait]
+    impl ArchiveStorage for Storage {
+        async fn put(&self, key: &str, archive: Vec<u8>, expires_at: DateTime) -> Result<(), String>;
+        async fn get(&self, key: &str) -> Result<Vec<u8>, String>;
+        async fn delete_stored_before(&self, before: DateTime) -> Result<usize, String>;
+    }
+}
+
+mock! {
+    UserRepo {}
+
+    #[async_trait]
+    impl UserRepository for UserRepo {
+        async fn create(&self, user: &User) -> Result<(), String>;
+        async fn find_by_email(&self, email: &str) -> Result<Option<User>, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<User>, String>;
+        async fn update(&self, user: &User) -> Result<(), String>;
+        async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn remove_recovery_code(&self, id: &ObjectId, code_hash: &str) -> Result<bool, String>;
+        async fn search(&self, query: Option<String>, role: Option<UserRole>, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<User>, String>;
+        async fn find_due_for_deletion(&self, now: DateTime, limit: i64) -> Result<Vec<User>, String>;
+        async fn find_discoverable(&self, query: &str, exclude: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<User>, String>;
+    }
+}
+
+mock! {
+    TournamentRepo {}
+
+    #[async_trait]
+    impl TournamentRepository for TournamentRepo {
+        async fn create(&self, tournament: Tournament) -> Result<(), String>;
+        async fn update(&self, tournament: &Tournament) -> Result<bool, String>;
+        async fn add_participant(&self, id: &ObjectId, user: &TournamentUser) -> Result<Option<Tournament>, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Tournament>, String>;
+        async fn find_by_creator(&self, user_id: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<Tournament>, String>;
+        async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn find_by_organizer(&self, user_id: &ObjectId) -> Result<Vec<Tournament>, String>;
+        async fn find_by_participant(&self, voter_id: &VoterId) -> Result<Vec<Tournament>, String>;
+        async fn find_used_opponents(&self, opponent_ids: &[ObjectId]) -> Result<Vec<ObjectId>, String>;
+        async fn count_by_organizer(&self, user_id: &ObjectId) -> Result<u64, String>;
+        async fn count_by_participant(&self, voter_id: &VoterId) -> Result<u64, String>;
+        async fn count_won_by(&self, opponent_ids: &[ObjectId]) -> Result<u64, String>;
+    }
+}
+
+mock! {
+    OpponentRepo {}
+
+    #[async_trait]
+    impl OpponentRepository for OpponentRepo {
+        async fn create(&self, opponent: &Opponent) -> Result<Opponent, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Opponent>, String>;
+        async fn find_by_creator(&self, user_id: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<Opponent>, String>;
+        async fn update(&self, opponent: &Opponent) -> Result<(), String>;
+        async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn find_ids_by_creator(&self, user_id: &ObjectId) -> Result<Vec<ObjectId>, String>;
+    }
+}
+
+mock! {
+    Images {}
+
+    #[async_trait]
+    impl ImageService for Images {
+        async fn upload_image(&self, file_data: Vec<u8>, filename: String, content_type: String, created_by: ObjectId) -> Result<Image, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Image>, String>;
+        async fn delete_image(&self, id: &ObjectId, user_id: &ObjectId) -> Result<(), String>;
+        async fn find_by_creator(&self, user_id: &ObjectId) -> Result<Vec<Image>, String>;
+        async fn upload_square_variants(&self, file_data: Vec<u8>, sizes: &[u32], created_by: ObjectId) -> Result<Vec<Image>, String>;
+    }
+}
+
+struct Mocks {
+    exports: MockExportRepo,
+    storage: MockStorage,
+    users: MockUserRepo,
+    tournaments: MockTournamentRepo,
+    opponents: MockOpponentRepo,
+    images: MockImages,
+}
+
+impl Mocks {
+    fn new() -> Self {
+        Self {
+            exports: MockExportRepo::new(),
+            storage: MockStorage::new(),
+            users: MockUserRepo::new(),
+            tournaments: MockTournamentRepo::new(),
+            opponents: MockOpponentRepo::new(),
+            images: MockImages::new(),
+        }
+    }
+
+    /// A user without tournaments, opponents or images.
+    fn expect_empty_account(&mut self, user_id: ObjectId) {
+        self.users.expect_find_by_id().returning(move |_| {
+            let mut user = User::new(
+                "jane@example.com".to_string(),
+                "Jane".to_string(),
+                "hash".to_string(),
+            );
+            user.id = Some(user_id);
+            Ok(Some(user))
+        });
+        self.tournaments
+            .expect_find_by_organizer()
+            .returning(|_| Ok(vec![]));
+        self.tournaments
+            .expect_find_by_participant()
+            .returning(|_| Ok(vec![]));
+        self.opponents
+            .expect_find_by_creator()
+            .returning(|_, _, _| Ok(vec![]));
+        self.images
+            .expect_find_by_creator()
+            .returning(|_| Ok(vec![]));
+    }
+
+    fn service(self) -> ExportServiceImpl {
+        ExportServiceImpl::new(
+            Arc::new(self.exports),
+            Arc::new(self.storage),
+            Arc::new(self.users),
+            Arc::new(self.tournaments),
+            Arc::new(self.opponents),
+            Arc::new(self.images),
+            3600,
+        )
+    }
+}
+
+fn create_test_job(user_id: ObjectId, status: ExportStatus) -> ExportJob {
+    let mut job = ExportJob::new(user_id, 3600);
+    job.id = Some(ObjectId::new());
+    job.status = status;
+    job
+}
+
+fn file_names(archive: Vec<u8>) -> Vec<String> {
+    let archive = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+    archive.file_names().map(str::to_string).collect()
+}
+
+#[tokio::test]
+async fn test_request_export_queues_job() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let job_id = ObjectId::new();
+    let mut mocks = Mocks::new();
+    mocks
+        .exports
+        .expect_find_active_for_user()
+        .returning(|_| Ok(None));
+    mocks
+        .exports
+        .expect_create()
+        .withf(move |job| job.user_id == user_id && job.status == ExportStatus::Pending)
+        .times(1)
+        .returning(move |_| Ok(job_id));
+    let service = mocks.service();
+
+    // Act
+    let result = service.request_export(&user_id).await;
+
+    // Assert
+    let job = result.unwrap();
+    assert_eq!(job.id, Some(job_id));
+    assert_eq!(job.status, ExportStatus::Pending);
+}
+
+#[tokio::test]
+async fn test_request_export_returns_running_job() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let running = create_test_job(user_id, ExportStatus::Running);
+    let running_id = running.id;
+    let mut mocks = Mocks::new();
+    mocks
+        .exports
+        .expect_find_active_for_user()
+        .returning(move |_| Ok(Some(running.clone())));
+    mocks.exports.expect_create().never();
+    let service = mocks.service();
+
+    // Act
+    let result = service.request_export(&user_id).await;
+
+    // Assert
+    assert_eq!(result.unwrap().id, running_id);
+}
+
+#[tokio::test]
+async fn test_run_stores_archive() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let job = create_test_job(user_id, ExportStatus::Running);
+    let job_id = job.id.unwrap();
+    let stored = Arc::new(Mutex::new(None));
+    let captured = stored.clone();
+    let mut mocks = Mocks::new();
+    mocks
+        .exports
+        .expect_claim()
+        .returning(move |_, _| Ok(Some(job.clone())));
+    let key = format!("exports/{}.zip", job_id);
+    let stored_key = key.clone();
+    mocks
+        .storage
+        .expect_put()
+        .withf(move |key, _, expires_at| key == stored_key && *expires_at > DateTime::now())
+        .times(1)
+        .returning(move |_, archive, _| {
+            *captured.lock().unwrap() = Some(archive);
+            Ok(())
+        });
+    let completed_key = key.clone();
+    mocks
+        .exports
+        .expect_complete()
+        .withf(move |id, key, size, _| *id == job_id && key == completed_key && *size > 0)
+        .times(1)
+        .returning(|_, _, _, _| Ok(()));
+    mocks.expect_empty_account(user_id);
+    let service = mocks.service();
+
+    // Act
+    let result = service.run(&job_id).await;
+
+    // Assert
+    assert!(result.is_ok());
+    let archive = stored.lock().unwrap().take().unwrap();
+    let names = file_names(archive);
+    for name in [
+        "profile.json",
+        "tournaments.json",
+        "opponents.json",
+        "images.json",
+        "participations.json",
+    ] {
+        assert!(names.iter().any(|n| n == name), "missing {}", name);
+    }
+}
+
+#[tokio::test]
+async fn test_run_records_upload_failure() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let job = create_test_job(user_id, ExportStatus::Running);
+    let job_id = job.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks
+        .exports
+        .expect_claim()
+        .returning(move |_, _| Ok(Some(job.clone())));
+    mocks
+        .storage
+        .expect_put()
+        .returning(|_, _, _| Err("Failed to upload export archive: timeout".to_string()));
+    mocks
+        .exports
+        .expect_fail()
+        .withf(|_, error, _| error == "Export failed: Failed to upload export archive: timeout")
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+    mocks.exports.expect_complete().never();
+    mocks.expect_empty_account(user_id);
+    let service = mocks.service();
+
+    // Act
+    let result = service.run(&job_id).await;
+
+    // Assert
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_run_skips_claimed_job() {
+    // Arrange
+    let mut mocks = Mocks::new();
+    mocks.exports.expect_claim().returning(|_, _| Ok(None));
+    mocks.exports.expect_complete().never();
+    mocks.users.expect_find_by_id().never();
+    let service = mocks.service();
+
+    // Act
+    let result = service.run(&ObjectId::new()).await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_run_records_failure() {
+    // Arrange
+    let job = create_test_job(ObjectId::new(), ExportStatus::Running);
+    let job_id = job.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks
+        .exports
+        .expect_claim()
+        .returning(move |_, _| Ok(Some(job.clone())));
+    mocks
+        .users
+        .expect_find_by_id()
+        .returning(|_| Err("Error finding user: timeout".to_string()));
+    mocks
+        .exports
+        .expect_fail()
+        .withf(|_, error, _| error == "Export failed: Error finding user: timeout")
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+    mocks.exports.expect_complete().never();
+    let service = mocks.service();
+
+    // Act
+    let result = service.run(&job_id).await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Export failed: Error finding user: timeout"
+    );
+}
+
+#[tokio::test]
+async fn test_download_reads_archive_from_storage() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let job_id = ObjectId::new();
+    let mut mocks = Mocks::new();
+    mocks
+        .exports
+        .expect_find_archive_key()
+        .returning(move |_, id| Ok(Some(format!("exports/{}.zip", id))));
+    mocks
+        .storage
+        .expect_get()
+        .withf(move |key| key == format!("exports/{}.zip", job_id))
+        .times(1)
+        .returning(|_| Ok(b"zip".to_vec()));
+    let service = mocks.service();
+
+    // Act
+    let result = service.download(&user_id, &job_id).await;
+
+    // Assert
+    assert_eq!(result.unwrap(), b"zip".to_vec());
+}
+
+#[tokio::test]
+async fn test_download_before_completion() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let job = create_test_job(user_id, ExportStatus::Running);
+    let job_id = job.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks
+        .exports
+        .expect_find_archive_key()
+        .returning(|_, _| Ok(None));
+    mocks
+        .exports
+        .expect_find_for_user()
+        .returning(move |_, _| Ok(Some(job.clone())));
+    let service = mocks.service();
+
+    // Act
+    let result = service.download(&user_id, &job_id).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Export is not ready yet");
+}
+
+#[tokio::test]
+async fn test_download_unknown_export() {
+    // Arrange
+    let mut mocks = Mocks::new();
+    mocks
+        .exports
+        .expect_find_archive_key()
+        .returning(|_, _| Ok(None));
+    mocks
+        .exports
+        .expect_find_for_user()
+        .returning(|_, _| Ok(None));
+    let service = mocks.service();
+
+    // Act
+    let result = service.download(&ObjectId::new(), &ObjectId::new()).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Export not found");
+}
+
+#[tokio::test]
+async fn test_resume_unfinished_runs_each_job() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let pending = create_test_job(user_id, ExportStatus::Pending);
+    let listed = pending.clone();
+    let mut mocks = Mocks::new();
+    mocks
+        .exports
+        .expect_find_unfinished()
+        .returning(move |_| Ok(vec![listed.clone()]));
+    mocks
+        .exports
+        .expect_claim()
+        .times(1)
+        .returning(move |_, _| Ok(Some(pending.clone())));
+    mocks.storage.expect_put().returning(|_, _, _| Ok(()));
+    mocks
+        .exports
+        .expect_complete()
+        .times(1)
+        .returning(|_, _, _, _| Ok(()));
+    mocks.expect_empty_account(user_id);
+    let service = mocks.service();
+
+    // Act
+    let result = service.resume_unfinished().await;
+
+    // Assert
+    assert_eq!(result.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_purge_expired_deletes_archives_past_retention() {
+    // Arrange
+    let mut mocks = Mocks::new();
+    mocks
+        .storage
+        .expect_delete_stored_before()
+        .withf(|before| {
+            let age = DateTime::now().timestamp_millis() - before.timestamp_millis();
+            (3_600_000..3_610_000).contains(&age)
+        })
+        .times(1)
+        .returning(|_| Ok(2));
+    let service = mocks.service();
+
+    // Act
+    let result = service.purge_expired().await;
+
+    // Assert
+    assert_eq!(result.unwrap(), 2);
+}
@@ -8,6 +8,7 @@ pub trait ImageRepository: Send + Sync {
     async fn save(&self, image: &Image) -> Result<(), String>;
     async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Image>, String>;
     async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+    async fn find_by_creator(&self, user_id: &ObjectId) -> Result<Vec<Image>, String>;
 }
 
 pub struct ImageRepositoryImpl {
@@ -52,6 +53,19 @@ impl ImageRepository for ImageRepositoryImpl {
         }
         Ok(())
     }
+
+    async fn find_by_creator(&self, user_id: &ObjectId) -> Result<Vec<Image>, String> {
+        use futures::TryStreamExt;
+
+        self.db
+            .collection::<Image>("images")
+            .find(doc! { "created_by": user_id })
+            .await
+            .map_err(|e| format!("Error finding images: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error collecting images: {}", e))
+    }
 }
 
 #[cfg(test)]
@@ -32,6 +32,28 @@ pub trait ImageService: Send + Sync {
     ) -> Result<Image, String>;
     async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Image>, String>;
     async fn delete_image(&self, id: &ObjectId, user_id: &ObjectId) -> Result<(), String>;
+    async fn find_by_creator(&self, user_id: &ObjectId) -> Result<Vec<Image>, String>;
+    /// Stores a centered square crop of the image once per size, as used
+    /// for avatars. Returns the images in the order of `sizes`.
+    async fn upload_square_variants(
+        &self,
+        file_data: Vec<u8>,
+        sizes: &[u32],
+        created_by: ObjectId,
+    ) -> Result<Vec<Image>, String>;
+}
+
+/// Crops the image to a centered square and scales it down to `size`
+/// pixels. Smaller images are not enlarged.
+pub fn square_variant(img: &DynamicImage, size: u32) -> DynamicImage {
+    let (width, height) = img.dimensions();
+    let side = width.min(height);
+    let square = img.crop_imm((width - side) / 2, (height - side) / 2, side, side);
+
+    if side <= size {
+        return square;
+    }
+    square.resize_exact(size, size, image::imageops::FilterType::Lanczos3)
 }
 
 pub struct ImageServiceImpl {
@@ -53,7 +75,11 @@ impl ImageServiceImpl {
 
         let img = self.resize_image(img);
 
-        let encoder = webp::Encoder::from_image(&img)
+        self.encode_webp(&img)
+    }
+
+    fn encode_webp(&self, img: &DynamicImage) -> Result<Vec<u8>, String> {
+        let encoder = webp::Encoder::from_image(img)
             .map_err(|e| format!("Failed to create WebP encoder: {}", e))?;
         let memory = encoder.encode(WEBP_QUALITY);
 
@@ -191,4 +217,54 @@ impl ImageService for ImageServiceImpl {
         self.delete_from_s3(&image.filename).await?;
         self.image_repository.delete(id).await
     }
+    async fn find_by_creator(&self, user_id: &ObjectId) -> Result<Vec<Image>, String> {
+        self.image_repository.find_by_creator(user_id).await
+    }
+
+    async fn upload_square_variants(
+        &self,
+        file_data: Vec<u8>,
+        sizes: &[u32],
+        created_by: ObjectId,
+    ) -> Result<Vec<Image>, String> {
+        let img = image::load_from_memory(&file_data)
+            .map_err(|e| format!("Failed to load image: {}", e))?;
+        let key_prefix = Uuid::new_v4();
+
+        let mut images: Vec<Image> = Vec::new();
+        for &size in sizes {
+            let result = async {
+                let data = self.encode_webp(&square_variant(&img, size))?;
+                let key = format!("{}-{}.webp", key_prefix, size);
+                let url = self.upload_to_s3(data.clone(), &key).await?;
+
+                let mut image = Image::new(
+                    url,
+                    "image/webp".to_string(),
+                    data.len() as i64,
+                    key,
+                    created_by,
+                );
+                image.id = Some(ObjectId::new());
+                self.image_repository.save(&image).await?;
+                Ok::<_, String>(image)
+            }
+            .await;
+
+            match result {
+                Ok(image) => images.push(image),
+                Err(e) => {
+                    // Do not leave the sizes already stored behind
+                    for image in &images {
+                        if let Err(e) = self.delete_image(&image.id.unwrap(), &created_by).await {
+                            tracing::warn!(error = %e, "Failed to clean up image variant");
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(images)
+    }
 }
@@ -14,7 +14,9 @@ async fn setup_rocket() -> Rocket<Build> {
         .expect("Failed to initialize MongoDB for testing");
 
     let jwt_config = JwtConfig {
-        secret: "test_secret".to_string(),
+        secret: Some("test_secret".to_string()),
+        keys_dir: None,
+        active_key_id: None,
     };
 
     let s3_config = S3Config {
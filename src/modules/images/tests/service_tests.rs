@@ -1,5 +1,6 @@
 use crate::config::s3::S3Config;
 use crate::modules::images::model::Image;
+use crate::modules::images::service::square_variant;
 use mongodb::bson::oid::ObjectId;
 use std::io::Cursor;
 
@@ -93,6 +94,26 @@ fn test_image_resize_logic() {
     assert_eq!(new_height, 512);
 }
 
+#[test]
+fn test_square_variant_crops_and_scales() {
+    let img = image::DynamicImage::new_rgba8(200, 100);
+
+    let variant = square_variant(&img, 64);
+
+    assert_eq!(variant.width(), 64);
+    assert_eq!(variant.height(), 64);
+}
+
+#[test]
+fn test_square_variant_does_not_enlarge() {
+    let img = image::DynamicImage::new_rgba8(40, 60);
+
+    let variant = square_variant(&img, 128);
+
+    assert_eq!(variant.width(), 40);
+    assert_eq!(variant.height(), 40);
+}
+
 // Integration tests - require AWS S3 and MongoDB
 #[tokio::test]
 #[ignore]
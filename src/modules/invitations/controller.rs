@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use mongodb::bson::oid::ObjectId;
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::common::guards::AuthenticatedUser;
+use crate::config::email_verification::EmailVerificationConfig;
+use crate::error::Error;
+use crate::modules::invitations::model::{CreateInvitationsDto, InvitationResponse};
+use crate::modules::invitations::service::InvitationService;
+use crate::modules::rate_limit::service::RateLimitService;
+use crate::modules::tournaments::controller::ensure_email_verified;
+use crate::modules::users::service::UserService;
+
+fn parse_id(id: &str, what: &str) -> Result<ObjectId, Error> {
+    ObjectId::parse_str(id).map_err(|_| Error::BadRequest(format!("Invalid {} ID", what)))
+}
+
+fn invitation_error(e: String) -> Error {
+    if e.ends_with("not found") {
+        Error::NotFound(e)
+    } else if e.starts_with("You ") {
+        Error::Forbidden(e)
+    } else if e.starts_with("Error ") {
+        Error::Internal(e)
+    } else {
+        Error::BadRequest(e)
+    }
+}
+
+#[post("/<id>/invitations", data = "<invitations_dto>")]
+pub async fn invite(
+    id: &str,
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn InvitationService + Send + Sync>>,
+    user_service: &State<Arc<dyn UserService + Send + Sync>>,
+    rate_limit_service: &State<Arc<dyn RateLimitService + Send + Sync>>,
+    verification: &State<EmailVerificationConfig>,
+    invitations_dto: Json<CreateInvitationsDto>,
+) -> Result<Json<Vec<InvitationResponse>>, Error> {
+    let tournament_id = parse_id(id, "tournament")?;
+
+    if verification.required_for_invites {
+        ensure_email_verified(user_service, &auth.user_id).await?;
+    }
+    rate_limit_service
+        .check_invitations(&auth.user_id, &tournament_id)
+        .await?;
+
+    let invitations = service
+        .invite(&tournament_id, invitations_dto.into_inner(), &auth.user_id)
+        .await
+        .map_err(invitation_error)?;
+
+    Ok(Json(invitations))
+}
+
+#[get("/<id>/invitations")]
+pub async fn list_for_tournament(
+    id: &str,
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn InvitationService + Send + Sync>>,
+) -> Result<Json<Vec<InvitationResponse>>, Error> {
+    let invitations = service
+        .list_for_tournament(&parse_id(id, "tournament")?, &auth.user_id)
+        .await
+        .map_err(invitation_error)?;
+
+    Ok(Json(invitations))
+}
+
+#[get("/")]
+pub async fn list_pending(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn InvitationService + Send + Sync>>,
+) -> Result<Json<Vec<InvitationResponse>>, Error> {
+    let invitations = service
+        .list_pending(&auth.user_id)
+        .await
+        .map_err(invitation_error)?;
+
+    Ok(Json(invitations))
+}
+
+#[post("/<id>/accept")]
+pub async fn accept(
+    id: &str,
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn InvitationService + Send + Sync>>,
+) -> Result<Json<InvitationResponse>, Error> {
+    let invitation = service
+        .accept(&parse_id(id, "invitation")?, &auth.user_id)
+        .await
+        .map_err(invitation_error)?;
+
+    Ok(Json(invitation))
+}
+
+#[post("/<id>/decline")]
+pub async fn decline(
+    id: &str,
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn InvitationService + Send + Sync>>,
+) -> Result<Json<InvitationResponse>, Error> {
+    let invitation = service
+        .decline(&parse_id(id, "invitation")?, &auth.user_id)
+        .await
+        .map_err(invitation_error)?;
+
+    Ok(Json(invitation))
+}
+
+/// Routes for the invited user, mounted at `/api/invitations`.
+pub fn routes() -> Vec<rocket::Route> {
+    routes![list_pending, accept, decline]
+}
+
+/// Routes for organizers, mounted next to the tournament routes.
+pub fn tournament_routes() -> Vec<rocket::Route> {
+    routes![invite, list_for_tournament]
+}
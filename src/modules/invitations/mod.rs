@@ -0,0 +1,9 @@
+pub mod controller;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+pub use controller::{routes, tournament_routes};
+
+#[cfg(test)]
+mod tests;
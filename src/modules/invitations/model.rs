@@ -0,0 +1,130 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::common::json::{
+    deserialize_vec_oid, serialize_datetime, serialize_oid, serialize_option_datetime,
+    serialize_option_oid,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InvitationStatus {
+    Pending,
+    Accepted,
+    Declined,
+}
+
+/// A personal invitation to take part in a tournament as a registered
+/// user. Exactly one of `user_id` and `email` is set: `email` is used for
+/// people who had no account when they were invited, and is matched against
+/// the verified address of whoever answers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TournamentInvitation {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub tournament_id: ObjectId,
+    pub tournament_name: String,
+    pub invited_by: ObjectId,
+    pub user_id: Option<ObjectId>,
+    /// Lowercased address
+    pub email: Option<String>,
+    pub status: InvitationStatus,
+    pub created_at: DateTime,
+    pub responded_at: Option<DateTime>,
+    pub expires_at: DateTime,
+}
+
+impl TournamentInvitation {
+    pub fn new(
+        tournament_id: ObjectId,
+        tournament_name: String,
+        invited_by: ObjectId,
+        invitee: Invitee,
+        expires_at: DateTime,
+    ) -> Self {
+        let (user_id, email) = match invitee {
+            Invitee::User(id) => (Some(id), None),
+            Invitee::Email(email) => (None, Some(email)),
+        };
+        Self {
+            id: None,
+            tournament_id,
+            tournament_name,
+            invited_by,
+            user_id,
+            email,
+            status: InvitationStatus::Pending,
+            created_at: DateTime::now(),
+            responded_at: None,
+            expires_at,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= DateTime::now()
+    }
+
+    /// Whether the invitation is addressed to this account. Email
+    /// invitations only count once the address is verified.
+    pub fn is_for(&self, user_id: &ObjectId, email: &str, email_verified: bool) -> bool {
+        match (&self.user_id, &self.email) {
+            (Some(invited), _) => invited == user_id,
+            (None, Some(invited)) => email_verified && *invited == email.to_lowercase(),
+            (None, None) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Invitee {
+    User(ObjectId),
+    Email(String),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateInvitationsDto {
+    #[serde(default, deserialize_with = "deserialize_vec_oid")]
+    pub user_ids: Vec<ObjectId>,
+    #[serde(default)]
+    pub emails: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvitationResponse {
+    #[serde(serialize_with = "serialize_oid")]
+    pub id: ObjectId,
+    #[serde(serialize_with = "serialize_oid")]
+    pub tournament_id: ObjectId,
+    pub tournament_name: String,
+    #[serde(serialize_with = "serialize_oid")]
+    pub invited_by: ObjectId,
+    #[serde(serialize_with = "serialize_option_oid")]
+    pub user_id: Option<ObjectId>,
+    pub email: Option<String>,
+    pub status: InvitationStatus,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub created_at: DateTime,
+    #[serde(serialize_with = "serialize_option_datetime")]
+    pub responded_at: Option<DateTime>,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub expires_at: DateTime,
+}
+
+impl From<TournamentInvitation> for InvitationResponse {
+    fn from(invitation: TournamentInvitation) -> Self {
+        Self {
+            id: invitation
+                .id
+                .expect("Invitation must have an id to convert to response"),
+            tournament_id: invitation.tournament_id,
+            tournament_name: invitation.tournament_name,
+            invited_by: invitation.invited_by,
+            user_id: invitation.user_id,
+            email: invitation.email,
+            status: invitation.status,
+            created_at: invitation.created_at,
+            responded_at: invitation.responded_at,
+            expires_at: invitation.expires_at,
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, to_bson, DateTime};
+use mongodb::Database;
+
+use crate::modules::invitations::model::{InvitationStatus, TournamentInvitation};
+
+#[async_trait]
+pub trait InvitationRepository: Send + Sync {
+    async fn create(&self, invitation: &TournamentInvitation) -> Result<ObjectId, String>;
+    async fn find_by_id(&self, id: &ObjectId) -> Result<Option<TournamentInvitation>, String>;
+    /// Every invitation of a tournament, newest first.
+    async fn find_by_tournament(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<TournamentInvitation>, String>;
+    /// Unexpired pending invitations addressed to `user_id`, or to `email`
+    /// when given, newest first.
+    async fn find_pending_for(
+        &self,
+        user_id: &ObjectId,
+        email: Option<String>,
+    ) -> Result<Vec<TournamentInvitation>, String>;
+    /// Answers a pending invitation. Returns `false` when it was already
+    /// answered.
+    async fn respond(
+        &self,
+        id: &ObjectId,
+        user_id: &ObjectId,
+        status: InvitationStatus,
+    ) -> Result<bool, String>;
+}
+
+pub struct InvitationRepositoryImpl {
+    db: Database,
+}
+
+impl InvitationRepositoryImpl {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait]
+impl InvitationRepository for InvitationRepositoryImpl {
+    async fn create(&self, invitation: &TournamentInvitation) -> Result<ObjectId, String> {
+        let result = self
+            .db
+            .collection::<TournamentInvitation>("tournament_invitations")
+            .insert_one(invitation)
+            .await
+            .map_err(|e| format!("Error creating invitation: {}", e))?;
+        result
+            .inserted_id
+            .as_object_id()
+            .ok_or_else(|| "Invitation was stored without an id".to_string())
+    }
+
+    async fn find_by_id(&self, id: &ObjectId) -> Result<Option<TournamentInvitation>, String> {
+        self.db
+            .collection::<TournamentInvitation>("tournament_invitations")
+            .find_one(doc! { "_id": id })
+            .await
+            .map_err(|e| format!("Error finding invitation: {}", e))
+    }
+
+    async fn find_by_tournament(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<TournamentInvitation>, String> {
+        self.db
+            .collection::<TournamentInvitation>("tournament_invitations")
+            .find(doc! { "tournament_id": tournament_id })
+            .sort(doc! { "_id": -1 })
+            .await
+            .map_err(|e| format!("Error finding invitations: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error finding invitations: {}", e))
+    }
+
+    async fn find_pending_for(
+        &self,
+        user_id: &ObjectId,
+        email: Option<String>,
+    ) -> Result<Vec<TournamentInvitation>, String> {
+        let mut invitee = vec![doc! { "user_id": user_id }];
+        if let Some(email) = email {
+            invitee.push(doc! { "email": email.to_lowercase() });
+        }
+
+        self.db
+            .collection::<TournamentInvitation>("tournament_invitations")
+            .find(doc! {
+                "$or": invitee,
+                "status": "pending",
+                "expires_at": { "$gt": DateTime::now() },
+            })
+            .sort(doc! { "_id": -1 })
+            .await
+            .map_err(|e| format!("Error finding invitations: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error finding invitations: {}", e))
+    }
+
+    async fn respond(
+        &self,
+        id: &ObjectId,
+        user_id: &ObjectId,
+        status: InvitationStatus,
+    ) -> Result<bool, String> {
+        let status = to_bson(&status).map_err(|e| format!("Invalid status: {}", e))?;
+        let result = self
+            .db
+            .collection::<TournamentInvitation>("tournament_invitations")
+            .update_one(
+                doc! { "_id": id, "status": "pending" },
+                doc! {
+                    "$set": {
+                        "status": status,
+                        "user_id": user_id,
+                        "responded_at": DateTime::now(),
+                    }
+                },
+            )
+            .await
+            .map_err(|e| format!("Error answering invitation: {}", e))?;
+        Ok(result.modified_count == 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invitation_repository_impl_creation() {
+        let _repo_fn = InvitationRepositoryImpl::new;
+    }
+}
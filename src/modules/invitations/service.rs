@@ -0,0 +1,381 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mongodb::bson::{oid::ObjectId, DateTime};
+
+use crate::modules::invitations::model::{
+    CreateInvitationsDto, InvitationResponse, InvitationStatus, Invitee, TournamentInvitation,
+};
+use crate::modules::invitations::repository::InvitationRepository;
+use crate::modules::mail::model::EmailMessage;
+use crate::modules::mail::service::Mailer;
+use crate::modules::tournaments::model::{
+    Tournament, TournamentRole, TournamentStatus, TournamentUser, VoterId,
+};
+use crate::modules::tournaments::repository::TournamentRepository;
+use crate::modules::users::model::User;
+use crate::modules::users::repository::UserRepository;
+use crate::modules::websocket::broadcaster::TournamentBroadcaster;
+use crate::modules::websocket::model::TournamentEvent;
+
+const INVITATION_TTL_DAYS: i64 = 14;
+/// Users and addresses one request can invite
+const MAX_INVITEES: usize = 50;
+const MAX_EMAIL_LENGTH: usize = 254;
+/// Accepting gives up after losing the race for a free display name this often
+const MAX_JOIN_ATTEMPTS: usize = 5;
+
+#[async_trait]
+pub trait InvitationService: Send + Sync {
+    /// Invites registered users and email addresses. People who already
+    /// participate are skipped, and pending invitations are returned as they
+    /// are instead of being sent again.
+    async fn invite(
+        &self,
+        tournament_id: &ObjectId,
+        dto: CreateInvitationsDto,
+        invited_by: &ObjectId,
+    ) -> Result<Vec<InvitationResponse>, String>;
+    async fn list_for_tournament(
+        &self,
+        tournament_id: &ObjectId,
+        user_id: &ObjectId,
+    ) -> Result<Vec<InvitationResponse>, String>;
+    /// Pending invitations addressed to the user.
+    async fn list_pending(&self, user_id: &ObjectId) -> Result<Vec<InvitationResponse>, String>;
+    /// Adds the user to the tournament as a registered participant.
+    async fn accept(
+        &self,
+        invitation_id: &ObjectId,
+        user_id: &ObjectId,
+    ) -> Result<InvitationResponse, String>;
+    async fn decline(
+        &self,
+        invitation_id: &ObjectId,
+        user_id: &ObjectId,
+    ) -> Result<InvitationResponse, String>;
+}
+
+pub struct InvitationServiceImpl {
+    invitation_repository: Arc<dyn InvitationRepository>,
+    tournament_repository: Arc<dyn TournamentRepository>,
+    user_repository: Arc<dyn UserRepository>,
+    mailer: Arc<dyn Mailer>,
+    broadcaster: Arc<TournamentBroadcaster>,
+    app_url: String,
+}
+
+/// Lowercases and checks an invited address.
+fn normalize_email(email: &str) -> Result<String, String> {
+    let email = email.trim().to_lowercase();
+    let valid = email.len() <= MAX_EMAIL_LENGTH
+        && !email.chars().any(char::is_whitespace)
+        && email
+            .split_once('@')
+            .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'));
+    if !valid {
+        return Err(format!("Invalid email address: {}", email));
+    }
+    Ok(email)
+}
+
+impl InvitationServiceImpl {
+    pub fn new(
+        invitation_repository: Arc<dyn InvitationRepository>,
+        tournament_repository: Arc<dyn TournamentRepository>,
+        user_repository: Arc<dyn UserRepository>,
+        mailer: Arc<dyn Mailer>,
+        broadcaster: Arc<TournamentBroadcaster>,
+        app_url: String,
+    ) -> Self {
+        Self {
+            invitation_repository,
+            tournament_repository,
+            user_repository,
+            mailer,
+            broadcaster,
+            app_url,
+        }
+    }
+
+    async fn find_tournament(&self, tournament_id: &ObjectId) -> Result<Tournament, String> {
+        self.tournament_repository
+            .find_by_id(tournament_id)
+            .await?
+            .ok_or_else(|| "Tournament not found".to_string())
+    }
+
+    async fn find_user(&self, user_id: &ObjectId) -> Result<User, String> {
+        self.user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| "User not found".to_string())
+    }
+
+    /// The invitation, if it is addressed to `user`. Others get the same
+    /// error as for a missing invitation.
+    async fn find_addressed_to(
+        &self,
+        invitation_id: &ObjectId,
+        user: &User,
+    ) -> Result<TournamentInvitation, String> {
+        let user_id = user.id.ok_or("User must have an id")?;
+        let invitation = self
+            .invitation_repository
+            .find_by_id(invitation_id)
+            .await?
+            .filter(|i| i.is_for(&user_id, &user.email, user.email_verified))
+            .ok_or_else(|| "Invitation not found".to_string())?;
+
+        if invitation.status != InvitationStatus::Pending {
+            return Err("Invitation has already been answered".to_string());
+        }
+        if invitation.is_expired() {
+            return Err("Invitation has expired".to_string());
+        }
+        Ok(invitation)
+    }
+
+    /// Marks the invitation answered and returns it as stored.
+    async fn respond(
+        &self,
+        mut invitation: TournamentInvitation,
+        user_id: &ObjectId,
+        status: InvitationStatus,
+    ) -> Result<InvitationResponse, String> {
+        let id = invitation.id.ok_or("Invitation must have an id")?;
+        if !self
+            .invitation_repository
+            .respond(&id, user_id, status)
+            .await?
+        {
+            return Err("Invitation has already been answered".to_string());
+        }
+
+        invitation.status = status;
+        invitation.user_id = Some(*user_id);
+        invitation.responded_at = Some(DateTime::now());
+        Ok(InvitationResponse::from(invitation))
+    }
+
+    async fn send_invitation(&self, to: &str, tournament: &Tournament) {
+        let link = format!("{}/invitations", self.app_url);
+        let message = EmailMessage::new(
+            to,
+            "You are invited to a Torvi tournament",
+            format!(
+                "Hi,\n\n\
+                 You have been invited to take part in the tournament \"{}\". \
+                 Log in with this email address to accept or decline. \
+                 The invitation expires in {} days.\n\n\
+                 {}",
+                tournament.name, INVITATION_TTL_DAYS, link
+            ),
+        );
+        if let Err(e) = self.mailer.send(&message).await {
+            tracing::warn!(error = %e, "Failed to send tournament invitation");
+        }
+    }
+}
+
+#[async_trait]
+impl InvitationService for InvitationServiceImpl {
+    async fn invite(
+        &self,
+        tournament_id: &ObjectId,
+        dto: CreateInvitationsDto,
+        invited_by: &ObjectId,
+    ) -> Result<Vec<InvitationResponse>, String> {
+        let tournament = self.find_tournament(tournament_id).await?;
+        tournament.authorize(invited_by, TournamentRole::CoOrganizer, "invite to")?;
+        if !matches!(tournament.status, TournamentStatus::Active) {
+            return Err("Tournament is not active".to_string());
+        }
+
+        let mut invitees = Vec::new();
+        let mut seen = HashSet::new();
+        for user_id in dto.user_ids {
+            let invitee = Invitee::User(user_id);
+            if seen.insert(invitee.clone()) {
+                invitees.push(invitee);
+            }
+        }
+        for email in &dto.emails {
+            let invitee = Invitee::Email(normalize_email(email)?);
+            if seen.insert(invitee.clone()) {
+                invitees.push(invitee);
+            }
+        }
+        if invitees.is_empty() {
+            return Err("Invite at least one user or email address".to_string());
+        }
+        if invitees.len() > MAX_INVITEES {
+            return Err(format!(
+                "At most {} people can be invited at once",
+                MAX_INVITEES
+            ));
+        }
+
+        // Look every user up first so an unknown id creates no invitations
+        let mut recipients = Vec::with_capacity(invitees.len());
+        for invitee in invitees {
+            let address = match &invitee {
+                Invitee::User(user_id) => {
+                    if tournament
+                        .users
+                        .iter()
+                        .any(|u| u.voter_id == VoterId::Registered(*user_id))
+                    {
+                        continue;
+                    }
+                    self.find_user(user_id).await?.email
+                }
+                Invitee::Email(email) => email.clone(),
+            };
+            recipients.push((invitee, address));
+        }
+
+        let pending: Vec<TournamentInvitation> = self
+            .invitation_repository
+            .find_by_tournament(tournament_id)
+            .await?
+            .into_iter()
+            .filter(|i| i.status == InvitationStatus::Pending && !i.is_expired())
+            .collect();
+        let expires_at = DateTime::from_millis(
+            DateTime::now().timestamp_millis() + INVITATION_TTL_DAYS * 24 * 60 * 60 * 1000,
+        );
+
+        let mut invitations = Vec::with_capacity(recipients.len());
+        for (invitee, address) in recipients {
+            let existing = pending.iter().find(|i| match &invitee {
+                Invitee::User(user_id) => i.user_id == Some(*user_id),
+                Invitee::Email(email) => i.email.as_ref() == Some(email),
+            });
+            if let Some(existing) = existing {
+                invitations.push(InvitationResponse::from(existing.clone()));
+                continue;
+            }
+
+            let mut invitation = TournamentInvitation::new(
+                *tournament_id,
+                tournament.name.clone(),
+                *invited_by,
+                invitee,
+                expires_at,
+            );
+            invitation.id = Some(self.invitation_repository.create(&invitation).await?);
+            self.send_invitation(&address, &tournament).await;
+            invitations.push(InvitationResponse::from(invitation));
+        }
+
+        Ok(invitations)
+    }
+
+    async fn list_for_tournament(
+        &self,
+        tournament_id: &ObjectId,
+        user_id: &ObjectId,
+    ) -> Result<Vec<InvitationResponse>, String> {
+        let tournament = self.find_tournament(tournament_id).await?;
+        tournament.authorize(
+            user_id,
+            TournamentRole::CoOrganizer,
+            "manage invitations for",
+        )?;
+
+        let invitations = self
+            .invitation_repository
+            .find_by_tournament(tournament_id)
+            .await?;
+        Ok(invitations
+            .into_iter()
+            .map(InvitationResponse::from)
+            .collect())
+    }
+
+    async fn list_pending(&self, user_id: &ObjectId) -> Result<Vec<InvitationResponse>, String> {
+        let user = self.find_user(user_id).await?;
+        let email = user.email_verified.then_some(user.email);
+
+        let invitations = self
+            .invitation_repository
+            .find_pending_for(user_id, email)
+            .await?;
+        Ok(invitations
+            .into_iter()
+            .map(InvitationResponse::from)
+            .collect())
+    }
+
+    async fn accept(
+        &self,
+        invitation_id: &ObjectId,
+        user_id: &ObjectId,
+    ) -> Result<InvitationResponse, String> {
+        let user = self.find_user(user_id).await?;
+        let invitation = self.find_addressed_to(invitation_id, &user).await?;
+
+        let mut tournament = self.find_tournament(&invitation.tournament_id).await?;
+        if !matches!(tournament.status, TournamentStatus::Active) {
+            return Err("Tournament is not active".to_string());
+        }
+
+        let voter_id = VoterId::Registered(*user_id);
+        let mut joined = None;
+        for _ in 0..MAX_JOIN_ATTEMPTS {
+            if tournament.users.iter().any(|u| u.voter_id == voter_id) {
+                break;
+            }
+            // Added on its own so concurrent joins and votes are kept
+            let participant = TournamentUser {
+                voter_id: voter_id.clone(),
+                name: tournament.unique_display_name(&user.name, None),
+                muted: false,
+            };
+            match self
+                .tournament_repository
+                .add_participant(&invitation.tournament_id, &participant)
+                .await?
+            {
+                Some(updated) => {
+                    joined = Some((participant.name, updated.users.len()));
+                    break;
+                }
+                // Someone took the name meanwhile; look again
+                None => tournament = self.find_tournament(&invitation.tournament_id).await?,
+            }
+        }
+        if joined.is_none() && !tournament.users.iter().any(|u| u.voter_id == voter_id) {
+            return Err("Display name is already taken, please try again".to_string());
+        }
+
+        let response = self
+            .respond(invitation, user_id, InvitationStatus::Accepted)
+            .await?;
+
+        if let Some((display_name, participant_count)) = joined {
+            self.broadcaster.broadcast(
+                &response.tournament_id,
+                TournamentEvent::ParticipantJoined {
+                    display_name,
+                    participant_count,
+                },
+            );
+        }
+        Ok(response)
+    }
+
+    async fn decline(
+        &self,
+        invitation_id: &ObjectId,
+        user_id: &ObjectId,
+    ) -> Result<InvitationResponse, String> {
+        let user = self.find_user(user_id).await?;
+        let invitation = self.find_addressed_to(invitation_id, &user).await?;
+
+        self.respond(invitation, user_id, InvitationStatus::Declined)
+            .await
+    }
+}
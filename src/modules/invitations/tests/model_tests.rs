@@ -0,0 +1,85 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+
+use crate::modules::invitations::model::{
+    CreateInvitationsDto, InvitationResponse, InvitationStatus, Invitee, TournamentInvitation,
+};
+
+fn create_invitation(invitee: Invitee) -> TournamentInvitation {
+    TournamentInvitation::new(
+        ObjectId::new(),
+        "Best Pizza".to_string(),
+        ObjectId::new(),
+        invitee,
+        DateTime::from_millis(DateTime::now().timestamp_millis() + 60_000),
+    )
+}
+
+#[test]
+fn test_new_invitation_is_pending() {
+    // Arrange
+    let user_id = ObjectId::new();
+
+    // Act
+    let invitation = create_invitation(Invitee::User(user_id));
+
+    // Assert
+    assert_eq!(invitation.status, InvitationStatus::Pending);
+    assert_eq!(invitation.user_id, Some(user_id));
+    assert!(invitation.email.is_none());
+    assert!(!invitation.is_expired());
+}
+
+#[test]
+fn test_is_for_registered_user() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let invitation = create_invitation(Invitee::User(user_id));
+
+    // Act & Assert
+    assert!(invitation.is_for(&user_id, "jane@example.com", false));
+    assert!(!invitation.is_for(&ObjectId::new(), "jane@example.com", true));
+}
+
+#[test]
+fn test_is_for_email_needs_verified_address() {
+    // Arrange
+    let invitation = create_invitation(Invitee::Email("jane@example.com".to_string()));
+    let user_id = ObjectId::new();
+
+    // Act & Assert
+    assert!(invitation.is_for(&user_id, "Jane@Example.com", true));
+    assert!(!invitation.is_for(&user_id, "jane@example.com", false));
+    assert!(!invitation.is_for(&user_id, "john@example.com", true));
+}
+
+#[test]
+fn test_create_invitations_dto_deserialization() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let json = format!(r#"{{"user_ids": ["{}"]}}"#, user_id);
+
+    // Act
+    let dto: CreateInvitationsDto = serde_json::from_str(&json).unwrap();
+
+    // Assert
+    assert_eq!(dto.user_ids, vec![user_id]);
+    assert!(dto.emails.is_empty());
+    assert!(serde_json::from_str::<CreateInvitationsDto>(r#"{"user_ids": ["nope"]}"#).is_err());
+}
+
+#[test]
+fn test_invitation_response_serialization() {
+    // Arrange
+    let mut invitation = create_invitation(Invitee::Email("jane@example.com".to_string()));
+    invitation.id = Some(ObjectId::new());
+
+    // Act
+    let json = serde_json::to_value(InvitationResponse::from(invitation.clone())).unwrap();
+
+    // Assert
+    assert_eq!(json["id"], invitation.id.unwrap().to_hex());
+    assert_eq!(json["tournament_name"], "Best Pizza");
+    assert_eq!(json["email"], "jane@example.com");
+    assert_eq!(json["user_id"], serde_json::Value::Null);
+    assert_eq!(json["status"], "pending");
+}
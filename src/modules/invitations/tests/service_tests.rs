@@ -0,0 +1,533 @@
+use async_trait::async_trait;
+use mockall::mock;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use std::sync::{Arc, Mutex};
+
+use crate::modules::invitations::model::{
+    CreateInvitationsDto, InvitationStatus, Invitee, TournamentInvitation,
+};
+use crate::modules::invitations::repository::InvitationRepository;
+use crate::modules::invitations::service::{InvitationService, InvitationServiceImpl};
+use crate::modules::mail::{model::EmailMessage, service::Mailer};
+use crate::modules::tournaments::model::{
+    Round, Tournament, TournamentStatus, TournamentUser, VoterId,
+};
+use crate::modules::tournaments::repository::TournamentRepository;
+use crate::modules::users::model::{User, UserRole};
+use crate::modules::users::repository::UserRepository;
+use crate::modules::websocket::broadcaster::TournamentBroadcaster;
+use crate::modules::websocket::model::TournamentEvent;
+
+mock! {
+    InvitationRepo {}
+
+    #[async_trait]
+    impl InvitationRepository for InvitationRepo {
+        async fn create(&self, invitation: &TournamentInvitation) -> Result<ObjectId, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<TournamentInvitation>, String>;
+        async fn find_by_tournament(&self, tournament_id: &ObjectId) -> Result<Vec<TournamentInvitation>, String>;
+        async fn find_pending_for(&self, user_id: &ObjectId, email: Option<String>) -> Result<Vec<TournamentInvitation>, String>;
+        async fn respond(&self, id: &ObjectId, user_id: &ObjectId, status: InvitationStatus) -> Result<bool, String>;
+    }
+}
+
+mock! {
+    UserRepo {}
+
+    #[async_trait]
+    impl UserRepository for UserRepo {
+        async fn create(&self, user: &User) -> Result<(), String>;
+        async fn find_by_email(&self, email: &str) -> Result<Option<User>, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<User>, String>;
+        async fn update(&self, user: &User) -> Result<(), String>;
+        async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn remove_recovery_code(&self, id: &ObjectId, code_hash: &str) -> Result<bool, String>;
+        async fn search(&self, query: Option<String>, role: Option<UserRole>, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<User>, String>;
+        async fn find_due_for_deletion(&self, now: DateTime, limit: i64) -> Result<Vec<User>, String>;
+        async fn find_discoverable(&self, query: &str, exclude: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<User>, String>;
+    }
+}
+
+mock! {
+    TournamentRepo {}
+
+    #[async_trait]
+    impl TournamentRepository for TournamentRepo {
+        async fn create(&self, tournament: Tournament) -> Result<(), String>;
+        async fn update(&self, tournament: &Tournament) -> Result<bool, String>;
+        async fn add_participant(&self, id: &ObjectId, user: &TournamentUser) -> Result<Option<Tournament>, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Tournament>, String>;
+        async fn find_by_creator(&self, user_id: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<Tournament>, String>;
+        async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn find_by_organizer(&self, user_id: &ObjectId) -> Result<Vec<Tournament>, String>;
+        async fn find_by_participant(&self, voter_id: &VoterId) -> Result<Vec<Tournament>, String>;
+        async fn find_used_opponents(&self, opponent_ids: &[ObjectId]) -> Result<Vec<ObjectId>, String>;
+        async fn count_by_organizer(&self, user_id: &ObjectId) -> Result<u64, String>;
+        async fn count_by_participant(&self, voter_id: &VoterId) -> Result<u64, String>;
+        async fn count_won_by(&self, opponent_ids: &[ObjectId]) -> Result<u64, String>;
+    }
+}
+
+mock! {
+    Mailer {}
+
+    #[async_trait]
+    impl Mailer for Mailer {
+        async fn send(&self, message: &EmailMessage) -> Result<(), String>;
+    }
+}
+
+struct Mocks {
+    invitations: MockInvitationRepo,
+    tournaments: MockTournamentRepo,
+    users: MockUserRepo,
+    mailer: MockMailer,
+}
+
+impl Mocks {
+    fn new() -> Self {
+        Self {
+            invitations: MockInvitationRepo::new(),
+            tournaments: MockTournamentRepo::new(),
+            users: MockUserRepo::new(),
+            mailer: MockMailer::new(),
+        }
+    }
+
+    fn expect_tournament(&mut self, tournament: Tournament) {
+        self.tournaments
+            .expect_find_by_id()
+            .returning(move |_| Ok(Some(tournament.clone())));
+    }
+
+    fn expect_users(&mut self, users: Vec<User>) {
+        self.users
+            .expect_find_by_id()
+            .returning(move |id| Ok(users.iter().find(|u| u.id == Some(*id)).cloned()));
+    }
+
+    /// Records the recipients of every email sent.
+    fn record_mail(&mut self) -> Arc<Mutex<Vec<String>>> {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&sent);
+        self.mailer.expect_send().returning(move |message| {
+            recorder.lock().unwrap().push(message.to.clone());
+            Ok(())
+        });
+        sent
+    }
+
+    fn service(self) -> (InvitationServiceImpl, Arc<TournamentBroadcaster>) {
+        let broadcaster = Arc::new(TournamentBroadcaster::new());
+        let service = InvitationServiceImpl::new(
+            Arc::new(self.invitations),
+            Arc::new(self.tournaments),
+            Arc::new(self.users),
+            Arc::new(self.mailer),
+            Arc::clone(&broadcaster),
+            "http://localhost:3000".to_string(),
+        );
+        (service, broadcaster)
+    }
+}
+
+fn create_test_tournament(owner: ObjectId) -> Tournament {
+    let mut tournament = Tournament::new(
+        "Best Pizza".to_string(),
+        owner,
+        vec![],
+        vec![],
+        Round {
+            round_number: 1,
+            matches: vec![],
+            automatic_winners: vec![],
+        },
+    );
+    tournament.id = Some(ObjectId::new());
+    tournament
+}
+
+fn create_test_user(email: &str, verified: bool) -> User {
+    let mut user = User::new(email.to_string(), "Jane".to_string(), "hash".to_string());
+    user.id = Some(ObjectId::new());
+    user.email_verified = verified;
+    user
+}
+
+fn create_invitation(tournament: &Tournament, invitee: Invitee) -> TournamentInvitation {
+    let mut invitation = TournamentInvitation::new(
+        tournament.id.unwrap(),
+        tournament.name.clone(),
+        tournament.created_by,
+        invitee,
+        DateTime::from_millis(DateTime::now().timestamp_millis() + 60_000),
+    );
+    invitation.id = Some(ObjectId::new());
+    invitation
+}
+
+#[tokio::test]
+async fn test_invite_users_and_emails() {
+    // Arrange
+    let owner = ObjectId::new();
+    let tournament = create_test_tournament(owner);
+    let tournament_id = tournament.id.unwrap();
+    let user = create_test_user("jane@example.com", true);
+    let user_id = user.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks.expect_tournament(tournament);
+    mocks.expect_users(vec![user]);
+    mocks
+        .invitations
+        .expect_find_by_tournament()
+        .returning(|_| Ok(vec![]));
+    mocks
+        .invitations
+        .expect_create()
+        .times(2)
+        .returning(|_| Ok(ObjectId::new()));
+    let sent = mocks.record_mail();
+    let (service, _) = mocks.service();
+
+    // Act
+    let dto = CreateInvitationsDto {
+        user_ids: vec![user_id, user_id],
+        emails: vec![" New.Person@Example.com ".to_string()],
+    };
+    let invitations = service.invite(&tournament_id, dto, &owner).await.unwrap();
+
+    // Assert
+    assert_eq!(invitations.len(), 2);
+    assert_eq!(invitations[0].user_id, Some(user_id));
+    assert_eq!(
+        invitations[1].email.as_deref(),
+        Some("new.person@example.com")
+    );
+    assert!(invitations
+        .iter()
+        .all(|i| i.status == InvitationStatus::Pending));
+    assert_eq!(
+        *sent.lock().unwrap(),
+        vec!["jane@example.com", "new.person@example.com"]
+    );
+}
+
+#[tokio::test]
+async fn test_invite_skips_participants_and_reuses_pending() {
+    // Arrange
+    let owner = ObjectId::new();
+    let mut tournament = create_test_tournament(owner);
+    let tournament_id = tournament.id.unwrap();
+    let participant = create_test_user("p@example.com", true);
+    tournament.users.push(TournamentUser {
+        voter_id: VoterId::Registered(participant.id.unwrap()),
+        name: participant.name.clone(),
+        muted: false,
+    });
+    let pending = create_invitation(&tournament, Invitee::Email("old@example.com".to_string()));
+    let pending_id = pending.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks.expect_tournament(tournament);
+    mocks
+        .invitations
+        .expect_find_by_tournament()
+        .returning(move |_| Ok(vec![pending.clone()]));
+    mocks.invitations.expect_create().never();
+    mocks.mailer.expect_send().never();
+    let (service, _) = mocks.service();
+
+    // Act
+    let dto = CreateInvitationsDto {
+        user_ids: vec![participant.id.unwrap()],
+        emails: vec!["old@example.com".to_string()],
+    };
+    let invitations = service.invite(&tournament_id, dto, &owner).await.unwrap();
+
+    // Assert
+    assert_eq!(invitations.len(), 1);
+    assert_eq!(invitations[0].id, pending_id);
+}
+
+#[tokio::test]
+async fn test_invite_unknown_user_creates_nothing() {
+    // Arrange
+    let owner = ObjectId::new();
+    let tournament = create_test_tournament(owner);
+    let tournament_id = tournament.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks.expect_tournament(tournament);
+    mocks.expect_users(vec![]);
+    mocks.invitations.expect_create().never();
+    let (service, _) = mocks.service();
+
+    // Act
+    let dto = CreateInvitationsDto {
+        user_ids: vec![ObjectId::new()],
+        emails: vec!["new@example.com".to_string()],
+    };
+    let result = service.invite(&tournament_id, dto, &owner).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "User not found");
+}
+
+#[tokio::test]
+async fn test_invite_validation() {
+    let owner = ObjectId::new();
+    let tournament = create_test_tournament(owner);
+    let tournament_id = tournament.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks.expect_tournament(tournament);
+    let (service, _) = mocks.service();
+    let dto = |emails: &[&str]| CreateInvitationsDto {
+        user_ids: vec![],
+        emails: emails.iter().map(|e| e.to_string()).collect(),
+    };
+
+    assert_eq!(
+        service
+            .invite(&tournament_id, dto(&[]), &owner)
+            .await
+            .unwrap_err(),
+        "Invite at least one user or email address"
+    );
+    assert_eq!(
+        service
+            .invite(&tournament_id, dto(&["not-an-email"]), &owner)
+            .await
+            .unwrap_err(),
+        "Invalid email address: not-an-email"
+    );
+    assert_eq!(
+        service
+            .invite(&tournament_id, dto(&["a@example.com"]), &ObjectId::new())
+            .await
+            .unwrap_err(),
+        "You do not have permission to invite to this tournament"
+    );
+}
+
+#[tokio::test]
+async fn test_accept_adds_participant_and_broadcasts() {
+    // Arrange
+    let tournament = create_test_tournament(ObjectId::new());
+    let tournament_id = tournament.id.unwrap();
+    let user = create_test_user("jane@example.com", false);
+    let user_id = user.id.unwrap();
+    let invitation = create_invitation(&tournament, Invitee::User(user_id));
+    let invitation_id = invitation.id.unwrap();
+    let joined = tournament.clone();
+    let mut mocks = Mocks::new();
+    mocks.expect_tournament(tournament);
+    mocks.expect_users(vec![user]);
+    mocks
+        .invitations
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(invitation.clone())));
+    mocks
+        .tournaments
+        .expect_add_participant()
+        .withf(move |_, u| u.voter_id == VoterId::Registered(user_id) && u.name == "Jane")
+        .times(1)
+        .returning(move |_, user| {
+            let mut tournament = joined.clone();
+            tournament.users.push(user.clone());
+            Ok(Some(tournament))
+        });
+    mocks
+        .invitations
+        .expect_respond()
+        .withf(|_, _, status| *status == InvitationStatus::Accepted)
+        .times(1)
+        .returning(|_, _, _| Ok(true));
+    let (service, broadcaster) = mocks.service();
+    let mut rx = broadcaster.subscribe(&tournament_id);
+
+    // Act
+    let response = service.accept(&invitation_id, &user_id).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status, InvitationStatus::Accepted);
+    assert!(response.responded_at.is_some());
+    match rx.try_recv().unwrap() {
+        TournamentEvent::ParticipantJoined {
+            display_name,
+            participant_count,
+        } => {
+            assert_eq!(display_name, "Jane");
+            assert_eq!(participant_count, 1);
+        }
+        other => panic!("Expected ParticipantJoined, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_accept_suffixes_name_already_in_use() {
+    // Arrange
+    let mut tournament = create_test_tournament(ObjectId::new());
+    tournament.users.push(TournamentUser {
+        voter_id: VoterId::Anonymous("session-1".to_string()),
+        name: "jane".to_string(),
+        muted: false,
+    });
+    let user = create_test_user("jane@example.com", false);
+    let user_id = user.id.unwrap();
+    let invitation = create_invitation(&tournament, Invitee::User(user_id));
+    let invitation_id = invitation.id.unwrap();
+    let joined = tournament.clone();
+    let mut mocks = Mocks::new();
+    mocks.expect_tournament(tournament);
+    mocks.expect_users(vec![user]);
+    mocks
+        .invitations
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(invitation.clone())));
+    mocks
+        .tournaments
+        .expect_add_participant()
+        .withf(move |_, u| u.voter_id == VoterId::Registered(user_id) && u.name == "Jane (2)")
+        .times(1)
+        .returning(move |_, user| {
+            let mut tournament = joined.clone();
+            tournament.users.push(user.clone());
+            Ok(Some(tournament))
+        });
+    mocks
+        .invitations
+        .expect_respond()
+        .returning(|_, _, _| Ok(true));
+    let (service, _) = mocks.service();
+
+    // Act
+    let result = service.accept(&invitation_id, &user_id).await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_accept_email_invitation_needs_verified_address() {
+    // Arrange
+    let tournament = create_test_tournament(ObjectId::new());
+    let user = create_test_user("jane@example.com", false);
+    let user_id = user.id.unwrap();
+    let invitation = create_invitation(&tournament, Invitee::Email("jane@example.com".to_string()));
+    let invitation_id = invitation.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks.expect_users(vec![user]);
+    mocks
+        .invitations
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(invitation.clone())));
+    mocks.tournaments.expect_update().never();
+    let (service, _) = mocks.service();
+
+    // Act
+    let result = service.accept(&invitation_id, &user_id).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invitation not found");
+}
+
+#[tokio::test]
+async fn test_accept_answered_invitation() {
+    // Arrange
+    let tournament = create_test_tournament(ObjectId::new());
+    let user = create_test_user("jane@example.com", true);
+    let user_id = user.id.unwrap();
+    let mut invitation = create_invitation(&tournament, Invitee::User(user_id));
+    invitation.status = InvitationStatus::Declined;
+    let invitation_id = invitation.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks.expect_users(vec![user]);
+    mocks
+        .invitations
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(invitation.clone())));
+    let (service, _) = mocks.service();
+
+    // Act
+    let result = service.accept(&invitation_id, &user_id).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invitation has already been answered");
+}
+
+#[tokio::test]
+async fn test_accept_inactive_tournament() {
+    // Arrange
+    let mut tournament = create_test_tournament(ObjectId::new());
+    tournament.status = TournamentStatus::Completed;
+    let user = create_test_user("jane@example.com", true);
+    let user_id = user.id.unwrap();
+    let invitation = create_invitation(&tournament, Invitee::User(user_id));
+    let invitation_id = invitation.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks.expect_tournament(tournament);
+    mocks.expect_users(vec![user]);
+    mocks
+        .invitations
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(invitation.clone())));
+    mocks.invitations.expect_respond().never();
+    let (service, _) = mocks.service();
+
+    // Act
+    let result = service.accept(&invitation_id, &user_id).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Tournament is not active");
+}
+
+#[tokio::test]
+async fn test_decline() {
+    // Arrange
+    let tournament = create_test_tournament(ObjectId::new());
+    let user = create_test_user("jane@example.com", true);
+    let user_id = user.id.unwrap();
+    let invitation = create_invitation(&tournament, Invitee::Email("jane@example.com".to_string()));
+    let invitation_id = invitation.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks.expect_users(vec![user]);
+    mocks
+        .invitations
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(invitation.clone())));
+    mocks
+        .invitations
+        .expect_respond()
+        .withf(move |_, by, status| *by == user_id && *status == InvitationStatus::Declined)
+        .times(1)
+        .returning(|_, _, _| Ok(true));
+    mocks.tournaments.expect_update().never();
+    let (service, _) = mocks.service();
+
+    // Act
+    let response = service.decline(&invitation_id, &user_id).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status, InvitationStatus::Declined);
+    assert_eq!(response.user_id, Some(user_id));
+}
+
+#[tokio::test]
+async fn test_list_pending_uses_verified_email_only() {
+    // Arrange
+    let user = create_test_user("jane@example.com", false);
+    let user_id = user.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks.expect_users(vec![user]);
+    mocks
+        .invitations
+        .expect_find_pending_for()
+        .withf(move |id, email| *id == user_id && email.is_none())
+        .times(1)
+        .returning(|_, _| Ok(vec![]));
+    let (service, _) = mocks.service();
+
+    // Act
+    let invitations = service.list_pending(&user_id).await.unwrap();
+
+    // Assert
+    assert!(invitations.is_empty());
+}
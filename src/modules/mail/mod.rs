@@ -0,0 +1,5 @@
+pub mod model;
+pub mod service;
+
+#[cfg(test)]
+mod tests;
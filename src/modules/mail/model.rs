@@ -0,0 +1,16 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl EmailMessage {
+    pub fn new(to: &str, subject: &str, body: String) -> Self {
+        Self {
+            to: to.to_string(),
+            subject: subject.to_string(),
+            body,
+        }
+    }
+}
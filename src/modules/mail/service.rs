@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use uuid::Uuid;
+
+use crate::config::mail::{MailConfig, MailTransport};
+use crate::modules::mail::model::EmailMessage;
+
+const IMPLICIT_TLS_PORT: u16 = 465;
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String>;
+}
+
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(
+        host: &str,
+        port: u16,
+        username: Option<String>,
+        password: Option<String>,
+        from: &str,
+    ) -> Result<Self, String> {
+        let builder = if port == IMPLICIT_TLS_PORT {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+        }
+        .map_err(|e| format!("Invalid SMTP host: {}", e))?
+        .port(port);
+
+        let builder = match (username, password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username, password))
+            }
+            _ => builder,
+        };
+
+        Ok(Self {
+            transport: builder.build(),
+            from: from
+                .parse()
+                .map_err(|e| format!("Invalid sender address: {}", e))?,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String> {
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(message
+                .to
+                .parse()
+                .map_err(|e| format!("Invalid recipient address: {}", e))?)
+            .subject(message.subject.clone())
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.body.clone())
+            .map_err(|e| format!("Error building email: {}", e))?;
+
+        self.transport
+            .send(email)
+            .await
+            .map_err(|e| format!("Error sending email: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Writes every email to its own file so it can be inspected in development.
+pub struct FileMailer {
+    dir: PathBuf,
+    from: String,
+}
+
+impl FileMailer {
+    pub fn new(dir: impl Into<PathBuf>, from: &str) -> Self {
+        Self {
+            dir: dir.into(),
+            from: from.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String> {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|e| format!("Error creating mail directory: {}", e))?;
+
+        let path = self.dir.join(format!(
+            "{}-{}.eml",
+            Utc::now().timestamp_millis(),
+            Uuid::new_v4()
+        ));
+        let content = format!(
+            "From: {}\nTo: {}\nSubject: {}\n\n{}\n",
+            self.from, message.to, message.subject, message.body
+        );
+
+        tokio::fs::write(&path, content)
+            .await
+            .map_err(|e| format!("Error writing email: {}", e))?;
+
+        tracing::info!(to = %message.to, path = %path.display(), "Email written to file");
+        Ok(())
+    }
+}
+
+/// Only logs the recipient and subject of emails; the default outside production.
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String> {
+        tracing::info!(
+            to = %message.to,
+            subject = %message.subject,
+            "Email not sent (log transport)"
+        );
+        Ok(())
+    }
+}
+
+pub fn from_config(config: &MailConfig) -> Result<Arc<dyn Mailer>, String> {
+    Ok(match &config.transport {
+        MailTransport::Smtp {
+            host,
+            port,
+            username,
+            password,
+        } => Arc::new(SmtpMailer::new(
+            host,
+            *port,
+            username.clone(),
+            password.clone(),
+            &config.from,
+        )?),
+        MailTransport::File { dir } => Arc::new(FileMailer::new(dir, &config.from)),
+        MailTransport::Log => Arc::new(LogMailer),
+    })
+}
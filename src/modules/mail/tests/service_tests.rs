@@ -0,0 +1,77 @@
+use crate::config::mail::{MailConfig, MailTransport};
+use crate::modules::mail::{
+    model::EmailMessage,
+    service::{from_config, FileMailer, LogMailer, Mailer, SmtpMailer},
+};
+use uuid::Uuid;
+
+fn create_test_message() -> EmailMessage {
+    EmailMessage::new("user@example.com", "Hello", "Welcome to Torvi".to_string())
+}
+
+#[tokio::test]
+async fn test_file_mailer_writes_message() {
+    // Arrange
+    let dir = std::env::temp_dir().join(format!("torvi-mail-{}", Uuid::new_v4()));
+    let mailer = FileMailer::new(&dir, "Torvi <no-reply@localhost>");
+
+    // Act
+    mailer.send(&create_test_message()).await.unwrap();
+
+    // Assert
+    let entries: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+    assert_eq!(entries.len(), 1);
+    let content = std::fs::read_to_string(entries[0].as_ref().unwrap().path()).unwrap();
+    assert!(content.contains("To: user@example.com"));
+    assert!(content.contains("Subject: Hello"));
+    assert!(content.contains("Welcome to Torvi"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_log_mailer_succeeds() {
+    let result = LogMailer.send(&create_test_message()).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_smtp_mailer_rejects_invalid_sender() {
+    let result = SmtpMailer::new("smtp.example.com", 587, None, None, "not an address");
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_smtp_mailer_creation() {
+    let result = SmtpMailer::new(
+        "smtp.example.com",
+        587,
+        Some("user".to_string()),
+        Some("pass".to_string()),
+        "Torvi <no-reply@example.com>",
+    );
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_from_config_builds_each_transport() {
+    for transport in [
+        MailTransport::Log,
+        MailTransport::File {
+            dir: "mail".to_string(),
+        },
+        MailTransport::Smtp {
+            host: "smtp.example.com".to_string(),
+            port: 465,
+            username: None,
+            password: None,
+        },
+    ] {
+        let config = MailConfig {
+            transport,
+            from: "Torvi <no-reply@localhost>".to_string(),
+            app_url: "http://localhost:3000".to_string(),
+        };
+        assert!(from_config(&config).is_ok());
+    }
+}
@@ -1,7 +1,14 @@
+pub mod account_deletion;
+pub mod admin;
+pub mod api_keys;
 pub mod auth;
+pub mod exports;
 pub mod health;
 pub mod images;
+pub mod invitations;
+pub mod mail;
 pub mod opponents;
+pub mod rate_limit;
 pub mod tournaments;
 pub mod users;
 pub mod websocket;
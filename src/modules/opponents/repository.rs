@@ -15,6 +15,7 @@ pub trait OpponentRepository: Send + Sync {
     ) -> Result<Vec<Opponent>, String>;
     async fn update(&self, opponent: &Opponent) -> Result<(), String>;
     async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+    async fn find_ids_by_creator(&self, user_id: &ObjectId) -> Result<Vec<ObjectId>, String>;
 }
 
 pub struct OpponentRepositoryImpl {
@@ -111,6 +112,17 @@ impl OpponentRepository for OpponentRepositoryImpl {
         }
         Ok(())
     }
+
+    async fn find_ids_by_creator(&self, user_id: &ObjectId) -> Result<Vec<ObjectId>, String> {
+        let ids = self
+            .db
+            .collection::<Opponent>("opponents")
+            .distinct("_id", doc! { "created_by": user_id })
+            .await
+            .map_err(|e| format!("Error finding opponents: {}", e))?;
+
+        Ok(ids.into_iter().filter_map(|id| id.as_object_id()).collect())
+    }
 }
 
 #[cfg(test)]
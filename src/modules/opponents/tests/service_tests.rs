@@ -21,6 +21,7 @@ mock! {
         async fn find_by_creator(&self, user_id: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<Opponent>, String>;
         async fn update(&self, opponent: &Opponent) -> Result<(), String>;
         async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn find_ids_by_creator(&self, user_id: &ObjectId) -> Result<Vec<ObjectId>, String>;
     }
 }
 
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+
+use crate::error::Error;
+use crate::modules::rate_limit::model::{RateLimitAction, RateLimitExceeded};
+use crate::modules::rate_limit::service::RateLimitService;
+
+/// Delay of the last rejected request, read back by the 429 catcher.
+struct RetryAfter(u64);
+
+async fn check_ip(request: &Request<'_>, action: RateLimitAction) -> Outcome<(), Error> {
+    let Some(rate_limit_service) = request
+        .rocket()
+        .state::<Arc<dyn RateLimitService + Send + Sync>>()
+    else {
+        return Outcome::Error((
+            Status::InternalServerError,
+            Error::Internal("Missing rate limit service".to_string()),
+        ));
+    };
+
+    let ip = request
+        .client_ip()
+        .map(|ip| ip.to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    match rate_limit_service.check_ip(action, &ip).await {
+        Ok(()) => Outcome::Success(()),
+        Err(RateLimitExceeded { retry_after_secs }) => {
+            request.local_cache(|| RetryAfter(retry_after_secs));
+            Outcome::Error((
+                Status::TooManyRequests,
+                Error::TooManyRequests(retry_after_secs),
+            ))
+        }
+    }
+}
+
+/// Rejects the request once its IP address is over the login limit.
+pub struct LoginRateLimit;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for LoginRateLimit {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        check_ip(request, RateLimitAction::Login)
+            .await
+            .map(|_| LoginRateLimit)
+    }
+}
+
+/// Rejects the request once its IP address is over the anonymous token limit.
+pub struct AnonymousRateLimit;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AnonymousRateLimit {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        check_ip(request, RateLimitAction::AnonymousToken)
+            .await
+            .map(|_| AnonymousRateLimit)
+    }
+}
+
+/// Rejects the request once its IP address is over the user search limit.
+pub struct UserSearchRateLimit;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for UserSearchRateLimit {
+    type Error = Error;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        check_ip(request, RateLimitAction::UserSearch)
+            .await
+            .map(|_| UserSearchRateLimit)
+    }
+}
+
+/// Answers throttled requests with the JSON error body and `Retry-After`.
+#[catch(429)]
+pub fn too_many_requests(request: &Request<'_>) -> Error {
+    let RetryAfter(secs) = request.local_cache(|| RetryAfter(1));
+    Error::TooManyRequests(*secs)
+}
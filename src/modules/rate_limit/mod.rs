@@ -0,0 +1,11 @@
+pub mod guard;
+pub mod model;
+pub mod repository;
+pub mod service;
+
+pub fn catchers() -> Vec<rocket::Catcher> {
+    catchers![guard::too_many_requests]
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,55 @@
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// Requests throttled per client IP address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitAction {
+    Login,
+    AnonymousToken,
+    UserSearch,
+}
+
+impl RateLimitAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitAction::Login => "login",
+            RateLimitAction::AnonymousToken => "anonymous",
+            RateLimitAction::UserSearch => "user_search",
+        }
+    }
+}
+
+/// Fixed-window counter. A bucket past `expires_at` counts as empty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitBucket {
+    #[serde(rename = "_id")]
+    pub key: String,
+    pub count: i64,
+    pub expires_at: DateTime,
+}
+
+impl RateLimitBucket {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= DateTime::now()
+    }
+
+    /// Whole seconds until the bucket expires, at least one.
+    pub fn retry_after_secs(&self) -> u64 {
+        let remaining_ms = self.expires_at.timestamp_millis() - DateTime::now().timestamp_millis();
+        (remaining_ms.max(0) as u64).div_ceil(1000).max(1)
+    }
+}
+
+/// The request is over a limit and may be retried after the given delay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitExceeded {
+    pub retry_after_secs: u64,
+}
+
+impl From<RateLimitExceeded> for Error {
+    fn from(exceeded: RateLimitExceeded) -> Self {
+        Error::TooManyRequests(exceeded.retry_after_secs)
+    }
+}
@@ -0,0 +1,177 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use mongodb::bson::{doc, DateTime};
+use mongodb::options::ReturnDocument;
+use mongodb::Database;
+
+use crate::modules::rate_limit::model::RateLimitBucket;
+
+/// Expired in-memory buckets are swept once every this many hits.
+const SWEEP_INTERVAL: u64 = 1024;
+
+#[async_trait]
+pub trait RateLimitRepository: Send + Sync {
+    /// Atomically counts a hit, starting a new window of `window_secs` when
+    /// the bucket is missing or expired. Returns the updated bucket.
+    async fn hit(&self, key: &str, window_secs: u64) -> Result<RateLimitBucket, String>;
+    /// The bucket under `key`, unless it has expired.
+    async fn find(&self, key: &str) -> Result<Option<RateLimitBucket>, String>;
+    /// Makes the bucket live for at least `duration_secs` from now.
+    async fn extend(&self, key: &str, duration_secs: u64) -> Result<(), String>;
+    async fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+fn expiry_after(secs: u64) -> DateTime {
+    DateTime::from_millis(DateTime::now().timestamp_millis() + secs as i64 * 1000)
+}
+
+/// Keeps buckets in process memory. Each instance counts on its own.
+#[derive(Default)]
+pub struct InMemoryRateLimitRepository {
+    buckets: DashMap<String, RateLimitBucket>,
+    hits: AtomicU64,
+}
+
+impl InMemoryRateLimitRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn sweep(&self) {
+        if self.hits.fetch_add(1, Ordering::Relaxed) % SWEEP_INTERVAL == SWEEP_INTERVAL - 1 {
+            self.buckets.retain(|_, bucket| !bucket.is_expired());
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimitRepository for InMemoryRateLimitRepository {
+    async fn hit(&self, key: &str, window_secs: u64) -> Result<RateLimitBucket, String> {
+        self.sweep();
+
+        let mut bucket = self
+            .buckets
+            .entry(key.to_string())
+            .or_insert_with(|| RateLimitBucket {
+                key: key.to_string(),
+                count: 0,
+                expires_at: expiry_after(window_secs),
+            });
+        if bucket.is_expired() {
+            bucket.count = 0;
+            bucket.expires_at = expiry_after(window_secs);
+        }
+        bucket.count += 1;
+        Ok(bucket.clone())
+    }
+
+    async fn find(&self, key: &str) -> Result<Option<RateLimitBucket>, String> {
+        Ok(self
+            .buckets
+            .get(key)
+            .map(|bucket| bucket.clone())
+            .filter(|bucket| !bucket.is_expired()))
+    }
+
+    async fn extend(&self, key: &str, duration_secs: u64) -> Result<(), String> {
+        let expires_at = expiry_after(duration_secs);
+        let mut bucket = self
+            .buckets
+            .entry(key.to_string())
+            .or_insert_with(|| RateLimitBucket {
+                key: key.to_string(),
+                count: 0,
+                expires_at,
+            });
+        if bucket.expires_at < expires_at {
+            bucket.expires_at = expires_at;
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        self.buckets.remove(key);
+        Ok(())
+    }
+}
+
+/// Keeps buckets in MongoDB so limits hold across instances.
+pub struct RateLimitRepositoryImpl {
+    db: Database,
+}
+
+impl RateLimitRepositoryImpl {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait]
+impl RateLimitRepository for RateLimitRepositoryImpl {
+    async fn hit(&self, key: &str, window_secs: u64) -> Result<RateLimitBucket, String> {
+        let now = DateTime::now();
+        let live = doc! { "$gt": ["$expires_at", now] };
+        let pipeline = vec![doc! {
+            "$set": {
+                "count": { "$cond": [live.clone(), { "$add": ["$count", 1] }, 1] },
+                "expires_at": {
+                    "$cond": [live, "$expires_at", expiry_after(window_secs)]
+                },
+            }
+        }];
+
+        self.db
+            .collection::<RateLimitBucket>("rate_limits")
+            .find_one_and_update(doc! { "_id": key }, pipeline)
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(|e| format!("Error updating rate limit: {}", e))?
+            .ok_or_else(|| "Error updating rate limit: no bucket returned".to_string())
+    }
+
+    async fn find(&self, key: &str) -> Result<Option<RateLimitBucket>, String> {
+        self.db
+            .collection::<RateLimitBucket>("rate_limits")
+            .find_one(doc! { "_id": key, "expires_at": { "$gt": DateTime::now() } })
+            .await
+            .map_err(|e| format!("Error finding rate limit: {}", e))
+    }
+
+    async fn extend(&self, key: &str, duration_secs: u64) -> Result<(), String> {
+        self.db
+            .collection::<RateLimitBucket>("rate_limits")
+            .update_one(
+                doc! { "_id": key },
+                doc! {
+                    "$max": { "expires_at": expiry_after(duration_secs) },
+                    "$setOnInsert": { "count": 0_i64 },
+                },
+            )
+            .upsert(true)
+            .await
+            .map_err(|e| format!("Error extending rate limit: {}", e))?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        self.db
+            .collection::<RateLimitBucket>("rate_limits")
+            .delete_one(doc! { "_id": key })
+            .await
+            .map_err(|e| format!("Error deleting rate limit: {}", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_repository_impl_creation() {
+        let _repo_fn = RateLimitRepositoryImpl::new;
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mongodb::bson::oid::ObjectId;
+
+use crate::config::rate_limit::RateLimitConfig;
+use crate::modules::rate_limit::model::{RateLimitAction, RateLimitExceeded};
+use crate::modules::rate_limit::repository::RateLimitRepository;
+
+const IP_WINDOW_SECS: u64 = 60;
+const HOUR_WINDOW_SECS: u64 = 3600;
+/// Failed logins are forgotten this long after the first one
+const FAILURE_WINDOW_SECS: u64 = 86400; // 24 hours
+
+/// Throttles requests per client IP address and locks accounts out after
+/// repeated failed logins. When the counters cannot be reached the request
+/// is let through rather than locking everyone out.
+#[async_trait]
+pub trait RateLimitService: Send + Sync {
+    /// Counts a request from `ip` against the action's per-minute limit.
+    async fn check_ip(&self, action: RateLimitAction, ip: &str) -> Result<(), RateLimitExceeded>;
+    /// Counts a login link requested for `email` against the hourly limit.
+    async fn check_magic_link(&self, email: &str) -> Result<(), RateLimitExceeded>;
+    /// Counts a password reset requested for `email` against the hourly
+    /// limit, whether or not the account exists.
+    async fn check_password_reset(&self, email: &str) -> Result<(), RateLimitExceeded>;
+    /// Counts a verification email resent to `email` against the hourly
+    /// limit.
+    async fn check_verification_email(&self, email: &str) -> Result<(), RateLimitExceeded>;
+    /// Counts invitations sent by `user_id` for a tournament against the
+    /// hourly limit.
+    async fn check_invitations(
+        &self,
+        user_id: &ObjectId,
+        tournament_id: &ObjectId,
+    ) -> Result<(), RateLimitExceeded>;
+    /// Fails while the account is locked out.
+    async fn check_account(&self, email: &str) -> Result<(), RateLimitExceeded>;
+    /// Counts a failed login. From the threshold on, each failure locks the
+    /// account for twice as long as the previous one.
+    async fn record_login_failure(&self, email: &str);
+    /// Forgets the failed logins of an account.
+    async fn record_login_success(&self, email: &str);
+}
+
+pub struct RateLimitServiceImpl {
+    repository: Arc<dyn RateLimitRepository>,
+    config: RateLimitConfig,
+}
+
+impl RateLimitServiceImpl {
+    pub fn new(repository: Arc<dyn RateLimitRepository>, config: RateLimitConfig) -> Self {
+        Self { repository, config }
+    }
+
+    fn limit(&self, action: RateLimitAction) -> u32 {
+        match action {
+            RateLimitAction::Login => self.config.login_per_minute,
+            RateLimitAction::AnonymousToken => self.config.anonymous_per_minute,
+            RateLimitAction::UserSearch => self.config.user_search_per_minute,
+        }
+    }
+
+    fn lockout_secs(&self, failures: i64) -> u64 {
+        let doublings = (failures - self.config.lockout_threshold as i64).clamp(0, 32) as u32;
+        self.config
+            .lockout_secs
+            .saturating_mul(2u64.saturating_pow(doublings))
+            .min(self.config.lockout_max_secs)
+    }
+
+    /// Counts a hit on `key` against `limit` per hour. `name` labels the
+    /// warning logged when the counters cannot be reached.
+    async fn check_hourly(
+        &self,
+        key: &str,
+        limit: u32,
+        name: &str,
+    ) -> Result<(), RateLimitExceeded> {
+        match self.repository.hit(key, HOUR_WINDOW_SECS).await {
+            Ok(bucket) if bucket.count > limit as i64 => Err(RateLimitExceeded {
+                retry_after_secs: bucket.retry_after_secs(),
+            }),
+            Ok(_) => Ok(()),
+            Err(e) => {
+                tracing::warn!(error = %e, "{} rate limit check failed", name);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn account_key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+#[async_trait]
+impl RateLimitService for RateLimitServiceImpl {
+    async fn check_ip(&self, action: RateLimitAction, ip: &str) -> Result<(), RateLimitExceeded> {
+        let key = format!("{}:ip:{}", action.as_str(), ip);
+        match self.repository.hit(&key, IP_WINDOW_SECS).await {
+            Ok(bucket) if bucket.count > self.limit(action) as i64 => Err(RateLimitExceeded {
+                retry_after_secs: bucket.retry_after_secs(),
+            }),
+            Ok(_) => Ok(()),
+            Err(e) => {
+                tracing::warn!(error = %e, "Rate limit check failed");
+                Ok(())
+            }
+        }
+    }
+
+    async fn check_magic_link(&self, email: &str) -> Result<(), RateLimitExceeded> {
+        let key = format!("magic_link:email:{}", account_key(email));
+        self.check_hourly(&key, self.config.magic_link_per_hour, "Magic link")
+            .await
+    }
+
+    async fn check_password_reset(&self, email: &str) -> Result<(), RateLimitExceeded> {
+        let key = format!("password_reset:email:{}", account_key(email));
+        self.check_hourly(&key, self.config.password_reset_per_hour, "Password reset")
+            .await
+    }
+
+    async fn check_verification_email(&self, email: &str) -> Result<(), RateLimitExceeded> {
+        let key = format!("verification_email:email:{}", account_key(email));
+        let limit = self.config.verification_email_per_hour;
+        self.check_hourly(&key, limit, "Verification email").await
+    }
+
+    async fn check_invitations(
+        &self,
+        user_id: &ObjectId,
+        tournament_id: &ObjectId,
+    ) -> Result<(), RateLimitExceeded> {
+        let key = format!("invitations:user:{}:tournament:{}", user_id, tournament_id);
+        self.check_hourly(&key, self.config.invitations_per_hour, "Invitation")
+            .await
+    }
+
+    async fn check_account(&self, email: &str) -> Result<(), RateLimitExceeded> {
+        let key = format!("login:lock:{}", account_key(email));
+        match self.repository.find(&key).await {
+            Ok(Some(lock)) => Err(RateLimitExceeded {
+                retry_after_secs: lock.retry_after_secs(),
+            }),
+            Ok(None) => Ok(()),
+            Err(e) => {
+                tracing::warn!(error = %e, "Account lockout check failed");
+                Ok(())
+            }
+        }
+    }
+
+    async fn record_login_failure(&self, email: &str) {
+        let account = account_key(email);
+        let failures = match self
+            .repository
+            .hit(&format!("login:fail:{}", account), FAILURE_WINDOW_SECS)
+            .await
+        {
+            Ok(bucket) => bucket.count,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to record login failure");
+                return;
+            }
+        };
+
+        if failures >= self.config.lockout_threshold as i64 {
+            let lock_key = format!("login:lock:{}", account);
+            if let Err(e) = self
+                .repository
+                .extend(&lock_key, self.lockout_secs(failures))
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to lock account");
+            }
+        }
+    }
+
+    async fn record_login_success(&self, email: &str) {
+        let key = format!("login:fail:{}", account_key(email));
+        if let Err(e) = self.repository.delete(&key).await {
+            tracing::warn!(error = %e, "Failed to reset login failures");
+        }
+    }
+}
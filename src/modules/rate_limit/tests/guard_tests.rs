@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use rocket::http::Status;
+use rocket::local::asynchronous::Client;
+use rocket::{Build, Rocket};
+
+use crate::config::rate_limit::RateLimitConfig;
+use crate::modules::rate_limit::guard::{AnonymousRateLimit, LoginRateLimit, UserSearchRateLimit};
+use crate::modules::rate_limit::repository::InMemoryRateLimitRepository;
+use crate::modules::rate_limit::service::{RateLimitService, RateLimitServiceImpl};
+
+#[post("/login")]
+fn login_route(_rate_limit: LoginRateLimit) -> &'static str {
+    "ok"
+}
+
+#[post("/anonymous")]
+fn anonymous_route(_rate_limit: AnonymousRateLimit) -> &'static str {
+    "ok"
+}
+
+#[get("/search")]
+fn search_route(_rate_limit: UserSearchRateLimit) -> &'static str {
+    "ok"
+}
+
+fn create_test_rocket() -> Rocket<Build> {
+    let rate_limit_service = Arc::new(RateLimitServiceImpl::new(
+        Arc::new(InMemoryRateLimitRepository::new()),
+        RateLimitConfig {
+            login_per_minute: 1,
+            anonymous_per_minute: 2,
+            user_search_per_minute: 1,
+            ..RateLimitConfig::default()
+        },
+    ));
+
+    rocket::build()
+        .mount("/", routes![login_route, anonymous_route, search_route])
+        .register("/", crate::modules::rate_limit::catchers())
+        .manage(rate_limit_service as Arc<dyn RateLimitService + Send + Sync>)
+}
+
+#[tokio::test]
+async fn test_rate_limit_guard_rejects_with_retry_after() {
+    let client = Client::tracked(create_test_rocket())
+        .await
+        .expect("valid rocket instance");
+
+    let first = client.post("/login").dispatch().await;
+    assert_eq!(first.status(), Status::Ok);
+
+    let second = client.post("/login").dispatch().await;
+    assert_eq!(second.status(), Status::TooManyRequests);
+    let retry_after: u64 = second
+        .headers()
+        .get_one("Retry-After")
+        .expect("Retry-After header")
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after));
+    let body: serde_json::Value = second.into_json().await.unwrap();
+    assert_eq!(body["error"], "Too Many Requests");
+}
+
+#[tokio::test]
+async fn test_rate_limit_guard_counts_per_ip() {
+    let client = Client::tracked(create_test_rocket())
+        .await
+        .expect("valid rocket instance");
+
+    let first = client
+        .post("/login")
+        .remote("10.0.0.1:5000".parse().unwrap())
+        .dispatch()
+        .await;
+    let other_ip = client
+        .post("/login")
+        .remote("10.0.0.2:5000".parse().unwrap())
+        .dispatch()
+        .await;
+
+    assert_eq!(first.status(), Status::Ok);
+    assert_eq!(other_ip.status(), Status::Ok);
+}
+
+#[tokio::test]
+async fn test_rate_limit_guard_limits_are_per_route() {
+    let client = Client::tracked(create_test_rocket())
+        .await
+        .expect("valid rocket instance");
+
+    client.post("/login").dispatch().await;
+    let anonymous = client.post("/anonymous").dispatch().await;
+
+    assert_eq!(anonymous.status(), Status::Ok);
+}
+
+#[tokio::test]
+async fn test_user_search_rate_limit() {
+    let client = Client::tracked(create_test_rocket())
+        .await
+        .expect("valid rocket instance");
+
+    let first = client.get("/search").dispatch().await;
+    let second = client.get("/search").dispatch().await;
+
+    assert_eq!(first.status(), Status::Ok);
+    assert_eq!(second.status(), Status::TooManyRequests);
+}
@@ -0,0 +1,3 @@
+mod guard_tests;
+mod repository_tests;
+mod service_tests;
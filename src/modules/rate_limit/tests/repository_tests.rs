@@ -0,0 +1,72 @@
+use crate::modules::rate_limit::repository::{InMemoryRateLimitRepository, RateLimitRepository};
+
+#[tokio::test]
+async fn test_hit_counts_within_window() {
+    // Arrange
+    let repo = InMemoryRateLimitRepository::new();
+
+    // Act
+    repo.hit("login:ip:1.2.3.4", 60).await.unwrap();
+    let bucket = repo.hit("login:ip:1.2.3.4", 60).await.unwrap();
+
+    // Assert
+    assert_eq!(bucket.count, 2);
+    assert!(bucket.retry_after_secs() <= 60);
+}
+
+#[tokio::test]
+async fn test_hit_starts_new_window_after_expiry() {
+    // Arrange
+    let repo = InMemoryRateLimitRepository::new();
+    repo.hit("key", 0).await.unwrap();
+    repo.hit("key", 0).await.unwrap();
+
+    // Act
+    let bucket = repo.hit("key", 60).await.unwrap();
+
+    // Assert
+    assert_eq!(bucket.count, 1);
+}
+
+#[tokio::test]
+async fn test_find_ignores_expired_bucket() {
+    // Arrange
+    let repo = InMemoryRateLimitRepository::new();
+    repo.hit("expired", 0).await.unwrap();
+    repo.hit("live", 60).await.unwrap();
+
+    // Act
+    let expired = repo.find("expired").await.unwrap();
+    let live = repo.find("live").await.unwrap();
+
+    // Assert
+    assert!(expired.is_none());
+    assert_eq!(live.unwrap().count, 1);
+}
+
+#[tokio::test]
+async fn test_extend_never_shortens() {
+    // Arrange
+    let repo = InMemoryRateLimitRepository::new();
+    repo.extend("lock", 120).await.unwrap();
+
+    // Act
+    repo.extend("lock", 10).await.unwrap();
+
+    // Assert
+    let bucket = repo.find("lock").await.unwrap().unwrap();
+    assert!(bucket.retry_after_secs() > 100);
+}
+
+#[tokio::test]
+async fn test_delete_removes_bucket() {
+    // Arrange
+    let repo = InMemoryRateLimitRepository::new();
+    repo.hit("key", 60).await.unwrap();
+
+    // Act
+    repo.delete("key").await.unwrap();
+
+    // Assert
+    assert!(repo.find("key").await.unwrap().is_none());
+}
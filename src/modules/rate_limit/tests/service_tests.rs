@@ -0,0 +1,250 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mockall::mock;
+use mongodb::bson::oid::ObjectId;
+
+use crate::config::rate_limit::RateLimitConfig;
+use crate::modules::rate_limit::model::{RateLimitAction, RateLimitBucket};
+use crate::modules::rate_limit::repository::{InMemoryRateLimitRepository, RateLimitRepository};
+use crate::modules::rate_limit::service::{RateLimitService, RateLimitServiceImpl};
+
+mock! {
+    RateLimitRepo {}
+    #[async_trait]
+    impl RateLimitRepository for RateLimitRepo {
+        async fn hit(&self, key: &str, window_secs: u64) -> Result<RateLimitBucket, String>;
+        async fn find(&self, key: &str) -> Result<Option<RateLimitBucket>, String>;
+        async fn extend(&self, key: &str, duration_secs: u64) -> Result<(), String>;
+        async fn delete(&self, key: &str) -> Result<(), String>;
+    }
+}
+
+fn test_config() -> RateLimitConfig {
+    RateLimitConfig {
+        login_per_minute: 2,
+        anonymous_per_minute: 1,
+        magic_link_per_hour: 2,
+        password_reset_per_hour: 1,
+        verification_email_per_hour: 1,
+        invitations_per_hour: 1,
+        lockout_threshold: 3,
+        lockout_secs: 30,
+        lockout_max_secs: 100,
+        ..RateLimitConfig::default()
+    }
+}
+
+fn in_memory_service() -> RateLimitServiceImpl {
+    RateLimitServiceImpl::new(Arc::new(InMemoryRateLimitRepository::new()), test_config())
+}
+
+#[tokio::test]
+async fn test_check_ip_allows_up_to_limit() {
+    // Arrange
+    let service = in_memory_service();
+
+    // Act
+    let first = service.check_ip(RateLimitAction::Login, "1.2.3.4").await;
+    let second = service.check_ip(RateLimitAction::Login, "1.2.3.4").await;
+    let third = service.check_ip(RateLimitAction::Login, "1.2.3.4").await;
+
+    // Assert
+    assert!(first.is_ok());
+    assert!(second.is_ok());
+    let exceeded = third.unwrap_err();
+    assert!(exceeded.retry_after_secs >= 1 && exceeded.retry_after_secs <= 60);
+}
+
+#[tokio::test]
+async fn test_check_ip_buckets_are_per_ip_and_action() {
+    // Arrange
+    let service = in_memory_service();
+    service
+        .check_ip(RateLimitAction::AnonymousToken, "1.2.3.4")
+        .await
+        .unwrap();
+
+    // Act
+    let same_ip = service
+        .check_ip(RateLimitAction::AnonymousToken, "1.2.3.4")
+        .await;
+    let other_ip = service
+        .check_ip(RateLimitAction::AnonymousToken, "5.6.7.8")
+        .await;
+    let other_action = service.check_ip(RateLimitAction::Login, "1.2.3.4").await;
+
+    // Assert
+    assert!(same_ip.is_err());
+    assert!(other_ip.is_ok());
+    assert!(other_action.is_ok());
+}
+
+#[tokio::test]
+async fn test_check_magic_link_limits_per_email() {
+    // Arrange
+    let service = in_memory_service();
+    service.check_magic_link("user@example.com").await.unwrap();
+    service.check_magic_link("User@Example.com ").await.unwrap();
+
+    // Act
+    let same_email = service.check_magic_link("user@example.com").await;
+    let other_email = service.check_magic_link("other@example.com").await;
+
+    // Assert
+    let exceeded = same_email.unwrap_err();
+    assert!(exceeded.retry_after_secs > 60 && exceeded.retry_after_secs <= 3600);
+    assert!(other_email.is_ok());
+}
+
+#[tokio::test]
+async fn test_check_password_reset_limits_per_email() {
+    // Arrange
+    let service = in_memory_service();
+    service
+        .check_password_reset("user@example.com")
+        .await
+        .unwrap();
+
+    // Act
+    let same_email = service.check_password_reset(" USER@example.com").await;
+    let other_email = service.check_password_reset("other@example.com").await;
+    let magic_link = service.check_magic_link("user@example.com").await;
+
+    // Assert
+    let exceeded = same_email.unwrap_err();
+    assert!(exceeded.retry_after_secs > 60 && exceeded.retry_after_secs <= 3600);
+    assert!(other_email.is_ok());
+    assert!(magic_link.is_ok());
+}
+
+#[tokio::test]
+async fn test_check_verification_email_limits_per_email() {
+    // Arrange
+    let service = in_memory_service();
+    service
+        .check_verification_email("user@example.com")
+        .await
+        .unwrap();
+
+    // Act
+    let same_email = service.check_verification_email("User@Example.com").await;
+    let other_email = service.check_verification_email("other@example.com").await;
+    let password_reset = service.check_password_reset("user@example.com").await;
+
+    // Assert
+    let exceeded = same_email.unwrap_err();
+    assert!(exceeded.retry_after_secs > 60 && exceeded.retry_after_secs <= 3600);
+    assert!(other_email.is_ok());
+    assert!(password_reset.is_ok());
+}
+
+#[tokio::test]
+async fn test_check_invitations_limits_per_user_and_tournament() {
+    // Arrange
+    let service = in_memory_service();
+    let user_id = ObjectId::new();
+    let tournament_id = ObjectId::new();
+    service
+        .check_invitations(&user_id, &tournament_id)
+        .await
+        .unwrap();
+
+    // Act
+    let same = service.check_invitations(&user_id, &tournament_id).await;
+    let other_tournament = service.check_invitations(&user_id, &ObjectId::new()).await;
+    let other_user = service
+        .check_invitations(&ObjectId::new(), &tournament_id)
+        .await;
+
+    // Assert
+    let exceeded = same.unwrap_err();
+    assert!(exceeded.retry_after_secs > 60 && exceeded.retry_after_secs <= 3600);
+    assert!(other_tournament.is_ok());
+    assert!(other_user.is_ok());
+}
+
+#[tokio::test]
+async fn test_account_locked_after_threshold() {
+    // Arrange
+    let service = in_memory_service();
+    service.record_login_failure("user@example.com").await;
+    service.record_login_failure("user@example.com").await;
+    assert!(service.check_account("user@example.com").await.is_ok());
+
+    // Act
+    service.record_login_failure("User@Example.com ").await;
+
+    // Assert
+    let exceeded = service.check_account("user@example.com").await.unwrap_err();
+    assert!(exceeded.retry_after_secs > 25 && exceeded.retry_after_secs <= 30);
+    assert!(service.check_account("other@example.com").await.is_ok());
+}
+
+#[tokio::test]
+async fn test_lockout_doubles_and_is_capped() {
+    // Arrange
+    let mut repo = MockRateLimitRepo::new();
+    let mut failures = 0;
+    repo.expect_hit().times(5).returning(move |key, _| {
+        failures += 1;
+        Ok(RateLimitBucket {
+            key: key.to_string(),
+            count: failures,
+            expires_at: mongodb::bson::DateTime::now(),
+        })
+    });
+    let mut sequence = mockall::Sequence::new();
+    for secs in [30, 60, 100] {
+        repo.expect_extend()
+            .withf(move |key, duration| key == "login:lock:user@example.com" && *duration == secs)
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_, _| Ok(()));
+    }
+    let service = RateLimitServiceImpl::new(Arc::new(repo), test_config());
+
+    // Act
+    for _ in 0..5 {
+        service.record_login_failure("user@example.com").await;
+    }
+}
+
+#[tokio::test]
+async fn test_login_success_clears_failures() {
+    // Arrange
+    let service = in_memory_service();
+    service.record_login_failure("user@example.com").await;
+    service.record_login_failure("user@example.com").await;
+
+    // Act
+    service.record_login_success("user@example.com").await;
+    service.record_login_failure("user@example.com").await;
+    service.record_login_failure("user@example.com").await;
+
+    // Assert
+    assert!(service.check_account("user@example.com").await.is_ok());
+}
+
+#[tokio::test]
+async fn test_store_errors_fail_open() {
+    // Arrange
+    let mut repo = MockRateLimitRepo::new();
+    repo.expect_hit()
+        .returning(|_, _| Err("Error updating rate limit: timeout".to_string()));
+    repo.expect_find()
+        .returning(|_| Err("Error finding rate limit: timeout".to_string()));
+    repo.expect_extend().never();
+    let service = RateLimitServiceImpl::new(Arc::new(repo), test_config());
+
+    // Act
+    let ip = service.check_ip(RateLimitAction::Login, "1.2.3.4").await;
+    let account = service.check_account("user@example.com").await;
+    let magic_link = service.check_magic_link("user@example.com").await;
+    service.record_login_failure("user@example.com").await;
+
+    // Assert
+    assert!(ip.is_ok());
+    assert!(magic_link.is_ok());
+    assert!(account.is_ok());
+}
@@ -1,26 +1,65 @@
 use std::sync::Arc;
 
 use mongodb::bson::oid::ObjectId;
+use rocket::http::ContentType;
 use rocket::serde::json::Json;
 use rocket::State;
 
-use crate::common::guards::{AuthenticatedUser, TournamentParticipant};
+use crate::common::guards::{AuthenticatedUser, ScopedParticipant, TournamentParticipant};
 use crate::common::pagination::{PaginatedResponse, PaginationParams};
+use crate::config::email_verification::EmailVerificationConfig;
 use crate::error::Error;
 use crate::modules::tournaments::{
     model::{
-        CreateInviteDto, CreateTournamentDto, InviteResponse, JoinTournamentDto,
-        JoinTournamentResponse, Match, TournamentResponse, UpdateTournamentDto, VoteMatchDto,
+        CreateInviteDto, CreateTournamentDto, GrantMemberDto, InviteRedemptionResponse,
+        InviteResponse, InviteSummaryResponse, JoinTournamentDto, JoinTournamentResponse,
+        Match, TournamentMemberResponse, TournamentResponse, TournamentUserResponse,
+        TransferOwnershipDto, UpdateParticipantDto, UpdateTournamentDto, VoteMatchDto,
     },
     service::TournamentService,
 };
+use crate::modules::users::service::UserService;
+
+pub(crate) async fn ensure_email_verified(
+    user_service: &Arc<dyn UserService + Send + Sync>,
+    user_id: &ObjectId,
+) -> Result<(), Error> {
+    let user = user_service
+        .find_by_id(user_id)
+        .await
+        .map_err(Error::Internal)?
+        .ok_or_else(|| Error::NotFound("User not found".to_string()))?;
+
+    if !user.email_verified {
+        return Err(Error::Forbidden("Email address must be verified first".to_string()));
+    }
+    Ok(())
+}
+
+async fn ensure_user_exists(
+    user_service: &Arc<dyn UserService + Send + Sync>,
+    user_id: &ObjectId,
+) -> Result<(), Error> {
+    user_service
+        .find_by_id(user_id)
+        .await
+        .map_err(Error::Internal)?
+        .ok_or_else(|| Error::NotFound("User not found".to_string()))?;
+    Ok(())
+}
 
 #[post("/create", data = "<tournament_dto>")]
 pub async fn create(
     auth: AuthenticatedUser,
     service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    user_service: &State<Arc<dyn UserService + Send + Sync>>,
+    verification: &State<EmailVerificationConfig>,
     tournament_dto: Json<CreateTournamentDto>,
 ) -> Result<Json<TournamentResponse>, Error> {
+    if verification.required_for_tournaments {
+        ensure_email_verified(user_service, &auth.user_id).await?;
+    }
+
     let tournament = service
         .create_tournament(tournament_dto.into_inner(), auth.user_id)
         .await
@@ -42,17 +81,13 @@ pub async fn list(
     Ok(Json(response))
 }
 
-#[get("/<id>")]
+#[get("/<_>")]
 pub async fn get_tournament(
-    _participant: TournamentParticipant,
+    scope: ScopedParticipant,
     service: &State<Arc<dyn TournamentService + Send + Sync>>,
-    id: &str,
 ) -> Result<Json<TournamentResponse>, Error> {
-    let tournament_id = ObjectId::parse_str(id)
-        .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
-
     let tournament = service
-        .find_by_id(&tournament_id)
+        .find_by_id(&scope.tournament_id)
         .await
         .map_err(|e| Error::Internal(e))?
         .ok_or(Error::NotFound("Tournament not found".to_string()))?;
@@ -131,17 +166,13 @@ pub async fn resume(
     Ok(Json(TournamentResponse::from(tournament)))
 }
 
-#[get("/<id>/bracket")]
+#[get("/<_>/bracket")]
 pub async fn bracket(
-    _participant: TournamentParticipant,
+    scope: ScopedParticipant,
     service: &State<Arc<dyn TournamentService + Send + Sync>>,
-    id: &str,
 ) -> Result<Json<TournamentResponse>, Error> {
-    let tournament_id = ObjectId::parse_str(id)
-        .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
-
     let tournament = service
-        .find_by_id(&tournament_id)
+        .find_by_id(&scope.tournament_id)
         .await
         .map_err(|e| Error::Internal(e))?
         .ok_or(Error::NotFound("Tournament not found".to_string()))?;
@@ -166,18 +197,14 @@ pub async fn results(
     Ok(Json(TournamentResponse::from(tournament)))
 }
 
-#[get("/<tournament_id>/matches/<match_id>")]
+#[get("/<_>/matches/<match_id>")]
 pub async fn match_detail(
-    _participant: TournamentParticipant,
+    scope: ScopedParticipant,
     service: &State<Arc<dyn TournamentService + Send + Sync>>,
-    tournament_id: &str,
     match_id: &str,
 ) -> Result<Json<Match>, Error> {
-    let tournament_id = ObjectId::parse_str(tournament_id)
-        .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
-
     let match_data = service
-        .get_match_detail(&tournament_id, match_id)
+        .get_match_detail(&scope.tournament_id, match_id)
         .await
         .map_err(|e| Error::NotFound(e))?;
 
@@ -190,6 +217,12 @@ pub async fn vote_match(
     service: &State<Arc<dyn TournamentService + Send + Sync>>,
     vote_dto: Json<VoteMatchDto>,
 ) -> Result<Json<TournamentResponse>, Error> {
+    if !participant.can_access(&vote_dto.tournament_id) {
+        return Err(Error::Forbidden(
+            "Token is not valid for this tournament".to_string(),
+        ));
+    }
+
     let tournament = service
         .vote_match(vote_dto.into_inner(), participant.voter_id())
         .await
@@ -202,12 +235,18 @@ pub async fn vote_match(
 pub async fn create_invite(
     auth: AuthenticatedUser,
     service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    user_service: &State<Arc<dyn UserService + Send + Sync>>,
+    verification: &State<EmailVerificationConfig>,
     tournament_id: &str,
     invite_dto: Json<CreateInviteDto>,
 ) -> Result<Json<InviteResponse>, Error> {
     let tournament_id = ObjectId::parse_str(tournament_id)
         .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
 
+    if verification.required_for_invites {
+        ensure_email_verified(user_service, &auth.user_id).await?;
+    }
+
     let response = service
         .create_invite(&tournament_id, invite_dto.into_inner(), auth.user_id)
         .await
@@ -216,6 +255,75 @@ pub async fn create_invite(
     Ok(Json(response))
 }
 
+#[get("/<id>/invites")]
+pub async fn list_invites(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    id: &str,
+) -> Result<Json<Vec<InviteSummaryResponse>>, Error> {
+    let tournament_id = ObjectId::parse_str(id)
+        .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
+
+    let invites = service
+        .list_invites(&tournament_id, &auth.user_id)
+        .await?;
+
+    Ok(Json(invites))
+}
+
+#[delete("/<id>/invites/<code>")]
+pub async fn revoke_invite(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    id: &str,
+    code: &str,
+) -> Result<Json<serde_json::Value>, Error> {
+    let tournament_id = ObjectId::parse_str(id)
+        .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
+
+    service
+        .revoke_invite(&tournament_id, code, &auth.user_id)
+        .await?;
+
+    Ok(Json(
+        serde_json::json!({ "message": "Invite code revoked successfully" }),
+    ))
+}
+
+#[get("/<id>/invites/<code>/joins")]
+pub async fn invite_joins(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    id: &str,
+    code: &str,
+) -> Result<Json<Vec<InviteRedemptionResponse>>, Error> {
+    let tournament_id = ObjectId::parse_str(id)
+        .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
+
+    let joins = service
+        .invite_joins(&tournament_id, code, &auth.user_id)
+        .await?;
+
+    Ok(Json(joins))
+}
+
+#[get("/<id>/invites/<code>/qr.png")]
+pub async fn invite_qr_code(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    id: &str,
+    code: &str,
+) -> Result<(ContentType, Vec<u8>), Error> {
+    let tournament_id = ObjectId::parse_str(id)
+        .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
+
+    let png = service
+        .invite_qr_code(&tournament_id, code, &auth.user_id)
+        .await?;
+
+    Ok((ContentType::PNG, png))
+}
+
 #[post("/<tournament_id>/join", data = "<join_dto>")]
 pub async fn join_tournament(
     service: &State<Arc<dyn TournamentService + Send + Sync>>,
@@ -246,6 +354,107 @@ pub async fn join_by_code(
     Ok(Json(response))
 }
 
+#[post("/<id>/members", data = "<grant_dto>")]
+pub async fn grant_member(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    user_service: &State<Arc<dyn UserService + Send + Sync>>,
+    id: &str,
+    grant_dto: Json<GrantMemberDto>,
+) -> Result<Json<Vec<TournamentMemberResponse>>, Error> {
+    let tournament_id = ObjectId::parse_str(id)
+        .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
+
+    ensure_user_exists(user_service, &grant_dto.user_id).await?;
+
+    let members = service
+        .grant_member(&tournament_id, grant_dto.into_inner(), &auth.user_id)
+        .await?;
+
+    Ok(Json(members))
+}
+
+#[delete("/<id>/members/<user_id>")]
+pub async fn revoke_member(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    id: &str,
+    user_id: &str,
+) -> Result<Json<Vec<TournamentMemberResponse>>, Error> {
+    let tournament_id = ObjectId::parse_str(id)
+        .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
+    let member_id = ObjectId::parse_str(user_id)
+        .map_err(|_| Error::BadRequest("Invalid user ID".to_string()))?;
+
+    let members = service
+        .revoke_member(&tournament_id, &member_id, &auth.user_id)
+        .await?;
+
+    Ok(Json(members))
+}
+
+#[put("/<id>/participants/<voter_id>", data = "<participant_dto>")]
+pub async fn update_participant(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    id: &str,
+    voter_id: &str,
+    participant_dto: Json<UpdateParticipantDto>,
+) -> Result<Json<TournamentUserResponse>, Error> {
+    let tournament_id = ObjectId::parse_str(id)
+        .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
+
+    let participant = service
+        .update_participant(
+            &tournament_id,
+            voter_id,
+            participant_dto.into_inner(),
+            &auth.user_id,
+        )
+        .await?;
+
+    Ok(Json(participant))
+}
+
+#[delete("/<id>/participants/<voter_id>")]
+pub async fn kick_participant(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    id: &str,
+    voter_id: &str,
+) -> Result<Json<serde_json::Value>, Error> {
+    let tournament_id = ObjectId::parse_str(id)
+        .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
+
+    service
+        .kick_participant(&tournament_id, voter_id, &auth.user_id)
+        .await?;
+
+    Ok(Json(
+        serde_json::json!({ "message": "Participant removed successfully" }),
+    ))
+}
+
+#[post("/<id>/transfer", data = "<transfer_dto>")]
+pub async fn transfer_ownership(
+    auth: AuthenticatedUser,
+    service: &State<Arc<dyn TournamentService + Send + Sync>>,
+    user_service: &State<Arc<dyn UserService + Send + Sync>>,
+    id: &str,
+    transfer_dto: Json<TransferOwnershipDto>,
+) -> Result<Json<TournamentResponse>, Error> {
+    let tournament_id = ObjectId::parse_str(id)
+        .map_err(|_| Error::BadRequest("Invalid tournament ID".to_string()))?;
+
+    ensure_user_exists(user_service, &transfer_dto.user_id).await?;
+
+    let tournament = service
+        .transfer_ownership(&tournament_id, &transfer_dto.user_id, &auth.user_id)
+        .await?;
+
+    Ok(Json(TournamentResponse::from(tournament)))
+}
+
 pub fn routes() -> Vec<rocket::Route> {
     routes![
         create,
@@ -260,7 +469,16 @@ pub fn routes() -> Vec<rocket::Route> {
         match_detail,
         vote_match,
         create_invite,
+        list_invites,
+        revoke_invite,
+        invite_joins,
+        invite_qr_code,
         join_tournament,
-        join_by_code
+        join_by_code,
+        grant_member,
+        revoke_member,
+        update_participant,
+        kick_participant,
+        transfer_ownership
     ]
 }
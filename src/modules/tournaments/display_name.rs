@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use crate::config::display_name::DisplayNameConfig;
+
+const BUNDLED_BLOCKLIST: &str = include_str!("../../../data/blocked-display-names.txt");
+
+/// Checks the display names anonymous participants pick against a length
+/// limit and a blocklist of reserved words and profanity.
+pub struct DisplayNameFilter {
+    max_length: usize,
+    blocked: HashSet<String>,
+}
+
+impl Default for DisplayNameFilter {
+    fn default() -> Self {
+        Self::with_terms(
+            DisplayNameConfig::default().max_length,
+            parse_list(BUNDLED_BLOCKLIST),
+        )
+    }
+}
+
+impl DisplayNameFilter {
+    pub fn new(config: &DisplayNameConfig) -> Result<Self, String> {
+        let mut terms = match &config.blocklist_file {
+            Some(path) => std::fs::read_to_string(path)
+                .map(|contents| parse_list(&contents))
+                .map_err(|e| format!("Error reading display name blocklist {}: {}", path, e))?,
+            None => parse_list(BUNDLED_BLOCKLIST),
+        };
+        terms.extend(config.blocked_terms.iter().cloned());
+
+        Ok(Self::with_terms(config.max_length, terms))
+    }
+
+    pub fn with_terms(max_length: usize, terms: Vec<String>) -> Self {
+        let blocked = terms
+            .iter()
+            .map(|term| fold(term).split_whitespace().collect::<String>())
+            .filter(|term| !term.is_empty())
+            .collect();
+        Self {
+            max_length,
+            blocked,
+        }
+    }
+
+    /// Returns `name` with surrounding whitespace trimmed and inner runs of
+    /// whitespace collapsed, or why it cannot be used.
+    pub fn check(&self, name: &str) -> Result<String, String> {
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        if name.is_empty() {
+            return Err("Display name cannot be empty".to_string());
+        }
+        if name.chars().count() > self.max_length {
+            return Err(format!(
+                "Display name must be at most {} characters",
+                self.max_length
+            ));
+        }
+        if self.is_blocked(&name) {
+            return Err("Display name is not allowed".to_string());
+        }
+        Ok(name)
+    }
+
+    /// Whether any run of consecutive words in `name`, joined together,
+    /// is a blocked term. Runs catch spaced-out spellings like "a d m i n".
+    fn is_blocked(&self, name: &str) -> bool {
+        let folded = fold(name);
+        let words: Vec<&str> = folded.split_whitespace().collect();
+        (0..words.len()).any(|start| {
+            (start + 1..=words.len()).any(|end| self.blocked.contains(&words[start..end].concat()))
+        })
+    }
+}
+
+/// One term per line; blank lines and `#` comments are skipped.
+fn parse_list(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Lowercases `text`, undoes common letter-for-digit swaps and turns
+/// punctuation into word breaks.
+fn fold(text: &str) -> String {
+    text.chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            '0' => 'o',
+            '1' | '!' => 'i',
+            '3' => 'e',
+            '4' | '@' => 'a',
+            '5' | '$' => 's',
+            '7' => 't',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
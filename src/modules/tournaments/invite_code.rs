@@ -0,0 +1,108 @@
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageFormat, Luma};
+use qrcode::QrCode;
+use rand::seq::SliceRandom;
+
+use crate::config::invite_code::{InviteCodeConfig, InviteCodeFormat};
+
+/// How many fresh codes to try before giving up on a unique one.
+pub const MAX_CODE_ATTEMPTS: usize = 5;
+
+/// Smallest width and height of a rendered QR code, in pixels
+const QR_MIN_SIZE: u32 = 256;
+
+/// Short, common words that are hard to mishear or misspell.
+const WORDS: &[&str] = &[
+    "acorn", "amber", "anchor", "apple", "arrow", "aspen", "atlas", "autumn", "badge", "bagel",
+    "bamboo", "banjo", "basil", "beacon", "berry", "birch", "bison", "blossom", "breeze", "brick",
+    "bridge", "bronze", "bubble", "bucket", "buffalo", "butter", "cabin", "cactus", "camel",
+    "candle", "canoe", "canyon", "carrot", "castle", "cedar", "cello", "cherry", "chess", "cider",
+    "cinder", "circus", "clover", "cobalt", "coconut", "comet", "copper", "coral", "cotton",
+    "cricket", "crystal", "daisy", "delta", "desert", "dolphin", "dragon", "eagle", "echo",
+    "ember", "falcon", "feather", "fern", "fiddle", "flame", "forest", "fossil", "fox", "galaxy",
+    "garden", "garnet", "ginger", "glacier", "granite", "grape", "gravel", "harbor", "hazel",
+    "hedge", "heron", "honey", "horizon", "husky", "island", "ivory", "jacket", "jasmine", "jelly",
+    "jungle", "kayak", "kettle", "kiwi", "koala", "ladder", "lagoon", "lantern", "lemon", "lilac",
+    "lily", "lizard", "llama", "lotus", "magnet", "mango", "maple", "marble", "meadow", "melon",
+    "meteor", "mint", "mirror", "mitten", "monsoon", "mosaic", "moss", "mountain", "muffin",
+    "nectar", "needle", "nickel", "nutmeg", "oasis", "ocean", "olive", "onion", "orbit", "orchid",
+    "otter", "oyster", "paddle", "panda", "papaya", "parrot", "peach", "pebble", "pepper", "piano",
+    "pickle", "pilot", "pine", "planet", "plum", "pocket", "polar", "pony", "poppy", "prairie",
+    "pretzel", "puffin", "pumpkin", "quartz", "quill", "rabbit", "radish", "rainbow", "raven",
+    "reef", "ribbon", "ripple", "river", "robin", "rocket", "saddle", "saffron", "salmon",
+    "sandal", "sapphire", "satin", "scarf", "shadow", "shell", "silver", "sketch", "sparrow",
+    "spice", "spruce", "squash", "star", "stone", "storm", "sugar", "summit", "sunset", "swan",
+    "tango", "thistle", "thunder", "tiger", "timber", "tomato", "topaz", "torch", "tulip",
+    "tundra", "turtle", "twig", "valley", "velvet", "violet", "volcano", "waffle", "walnut",
+    "walrus", "willow", "window", "winter", "wizard", "yarrow", "zebra", "zephyr",
+];
+
+/// Generates invite codes in the configured format.
+#[derive(Debug, Clone)]
+pub struct InviteCodeGenerator {
+    format: InviteCodeFormat,
+    alphabet: Vec<char>,
+    length: usize,
+    word_count: usize,
+}
+
+impl Default for InviteCodeGenerator {
+    fn default() -> Self {
+        Self::new(&InviteCodeConfig::default())
+    }
+}
+
+impl InviteCodeGenerator {
+    pub fn new(config: &InviteCodeConfig) -> Self {
+        Self {
+            format: config.format,
+            alphabet: config.alphabet.chars().collect(),
+            length: config.length,
+            word_count: config.word_count,
+        }
+    }
+
+    pub fn generate(&self) -> String {
+        let mut rng = rand::thread_rng();
+        match self.format {
+            InviteCodeFormat::Random => (0..self.length)
+                .filter_map(|_| self.alphabet.choose(&mut rng))
+                .collect(),
+            InviteCodeFormat::Words => (0..self.word_count)
+                .filter_map(|_| WORDS.choose(&mut rng).copied())
+                .collect::<Vec<_>>()
+                .join("-"),
+        }
+    }
+
+    /// Undoes the case changes people make when typing a code. Returns the
+    /// input unchanged when the alphabet mixes cases.
+    pub fn normalize(&self, code: &str) -> String {
+        let code = code.trim();
+        let has_lower = self.alphabet.iter().any(char::is_ascii_lowercase);
+        let has_upper = self.alphabet.iter().any(char::is_ascii_uppercase);
+        match self.format {
+            InviteCodeFormat::Words => code.to_lowercase(),
+            InviteCodeFormat::Random if !has_lower => code.to_uppercase(),
+            InviteCodeFormat::Random if !has_upper => code.to_lowercase(),
+            InviteCodeFormat::Random => code.to_string(),
+        }
+    }
+}
+
+/// Renders `link` as a black-on-white QR code PNG.
+pub fn render_qr_png(link: &str) -> Result<Vec<u8>, String> {
+    let code =
+        QrCode::new(link.as_bytes()).map_err(|e| format!("Error rendering QR code: {}", e))?;
+    let image = code
+        .render::<Luma<u8>>()
+        .min_dimensions(QR_MIN_SIZE, QR_MIN_SIZE)
+        .build();
+
+    let mut png = Vec::new();
+    DynamicImage::ImageLuma8(image)
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("Error rendering QR code: {}", e))?;
+    Ok(png)
+}
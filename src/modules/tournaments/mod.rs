@@ -1,4 +1,6 @@
 pub mod controller;
+pub mod display_name;
+pub mod invite_code;
 pub mod model;
 pub mod repository;
 pub mod service;
@@ -1,9 +1,11 @@
 use crate::common::json::{
-    deserialize_oid, serialize_datetime, serialize_oid, serialize_option_oid, serialize_vec_oid,
+    deserialize_oid, serialize_datetime, serialize_oid, serialize_option_datetime,
+    serialize_option_oid, serialize_vec_oid,
 };
 use mongodb::bson::{doc, oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(tag = "type", content = "id")]
@@ -35,6 +37,9 @@ pub struct TournamentOpponent {
 pub struct TournamentUser {
     pub voter_id: VoterId,
     pub name: String,
+    /// Muted participants stay in the tournament but cannot vote
+    #[serde(default)]
+    pub muted: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -64,12 +69,63 @@ pub enum TournamentStatus {
     Completed,
 }
 
+/// Organizer roles within a single tournament, ordered from least to most
+/// privileged. The owner is always `Tournament::created_by`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum TournamentRole {
+    Moderator,
+    CoOrganizer,
+    Owner,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TournamentMember {
+    pub user_id: ObjectId,
+    pub role: TournamentRole,
+    pub granted_by: ObjectId,
+    pub granted_at: DateTime,
+}
+
+/// Why managing a tournament's invites, members or participants failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MembershipError {
+    /// The tournament, invite, member or participant does not exist
+    NotFound(String),
+    /// The caller's role does not allow the change
+    Forbidden(String),
+    /// The change cannot be made, e.g. the display name is taken
+    Invalid(String),
+    /// Reading or saving failed
+    Internal(String),
+}
+
+impl fmt::Display for MembershipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(message)
+            | Self::Forbidden(message)
+            | Self::Invalid(message)
+            | Self::Internal(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<MembershipError> for String {
+    fn from(e: MembershipError) -> Self {
+        e.to_string()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tournament {
     #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
     pub id: Option<ObjectId>,
     pub name: String,
     pub created_by: ObjectId,
+    /// Co-organizers and moderators; the owner is not listed here.
+    #[serde(default)]
+    pub members: Vec<TournamentMember>,
     pub opponents: Vec<TournamentOpponent>,
     pub users: Vec<TournamentUser>,
     pub rounds: Vec<Round>,
@@ -77,6 +133,10 @@ pub struct Tournament {
     pub winner: Option<ObjectId>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    /// Counts the writes to the tournament so a stale copy is never saved
+    /// over a newer one
+    #[serde(default)]
+    pub version: i64,
 }
 
 impl Tournament {
@@ -92,6 +152,7 @@ impl Tournament {
             id: None,
             name,
             created_by,
+            members: Vec::new(),
             opponents: opponents
                 .into_iter()
                 .map(|o| TournamentOpponent {
@@ -104,6 +165,7 @@ impl Tournament {
                 .map(|u| TournamentUser {
                     voter_id: VoterId::Registered(u.id),
                     name: u.name,
+                    muted: false,
                 })
                 .collect(),
             rounds: vec![initial_round],
@@ -111,6 +173,7 @@ impl Tournament {
             winner: None,
             created_at: now,
             updated_at: now,
+            version: 0,
         }
     }
 }
@@ -136,6 +199,19 @@ pub struct UserDto {
     pub name: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GrantMemberDto {
+    #[serde(deserialize_with = "deserialize_oid")]
+    pub user_id: ObjectId,
+    pub role: TournamentRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransferOwnershipDto {
+    #[serde(deserialize_with = "deserialize_oid")]
+    pub user_id: ObjectId,
+}
+
 // --- Response types with plain string serialization ---
 
 #[derive(Debug, Serialize, PartialEq)]
@@ -149,6 +225,7 @@ pub struct TournamentOpponentResponse {
 pub struct TournamentUserResponse {
     pub voter_id: String,
     pub name: String,
+    pub muted: bool,
 }
 
 #[derive(Debug, Serialize, PartialEq)]
@@ -173,6 +250,13 @@ pub struct RoundResponse {
     pub automatic_winners: Vec<ObjectId>,
 }
 
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TournamentMemberResponse {
+    #[serde(serialize_with = "serialize_oid")]
+    pub user_id: ObjectId,
+    pub role: TournamentRole,
+}
+
 #[derive(Debug, Serialize)]
 pub struct TournamentResponse {
     #[serde(serialize_with = "serialize_oid")]
@@ -180,6 +264,7 @@ pub struct TournamentResponse {
     pub name: String,
     #[serde(serialize_with = "serialize_oid")]
     pub created_by: ObjectId,
+    pub members: Vec<TournamentMemberResponse>,
     pub opponents: Vec<TournamentOpponentResponse>,
     pub users: Vec<TournamentUserResponse>,
     pub rounds: Vec<RoundResponse>,
@@ -192,12 +277,24 @@ pub struct TournamentResponse {
     pub updated_at: DateTime,
 }
 
+impl From<TournamentUser> for TournamentUserResponse {
+    fn from(user: TournamentUser) -> Self {
+        Self {
+            voter_id: user.voter_id.as_string(),
+            name: user.name,
+            muted: user.muted,
+        }
+    }
+}
+
 impl From<Tournament> for TournamentResponse {
     fn from(tournament: Tournament) -> Self {
+        let members = tournament.member_responses();
         Self {
             id: tournament.id.unwrap(),
             name: tournament.name,
             created_by: tournament.created_by,
+            members,
             opponents: tournament
                 .opponents
                 .into_iter()
@@ -209,10 +306,7 @@ impl From<Tournament> for TournamentResponse {
             users: tournament
                 .users
                 .into_iter()
-                .map(|u| TournamentUserResponse {
-                    voter_id: u.voter_id.as_string(),
-                    name: u.name,
-                })
+                .map(TournamentUserResponse::from)
                 .collect(),
             rounds: tournament
                 .rounds
@@ -253,6 +347,14 @@ pub struct UpdateTournamentDto {
     pub name: Option<String>,
 }
 
+/// Changes organizers make to a participant. Omitted fields stay as they
+/// are.
+#[derive(Debug, Deserialize)]
+pub struct UpdateParticipantDto {
+    pub name: Option<String>,
+    pub muted: Option<bool>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct VoteMatchDto {
     #[serde(deserialize_with = "deserialize_oid")]
@@ -273,6 +375,35 @@ pub struct TournamentInvite {
     pub expires_at: DateTime,
     pub created_by: ObjectId,
     pub created_at: DateTime,
+    /// Set once an organizer revoked the code; it can no longer be used
+    #[serde(default)]
+    pub revoked_at: Option<DateTime>,
+    /// Every join made with the code, oldest first
+    #[serde(default)]
+    pub redemptions: Vec<InviteRedemption>,
+}
+
+impl TournamentInvite {
+    /// Whether the code can still be used to join, judging by this copy.
+    pub fn check_redeemable(&self) -> Result<(), String> {
+        if self.revoked_at.is_some() {
+            return Err("Invite code has been revoked".to_string());
+        }
+        if DateTime::now() > self.expires_at {
+            return Err("Invite code has expired".to_string());
+        }
+        if self.current_uses >= self.max_uses {
+            return Err("Invite code has reached maximum uses".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InviteRedemption {
+    pub session_id: String,
+    pub display_name: String,
+    pub joined_at: DateTime,
 }
 
 #[derive(Debug, Deserialize)]
@@ -291,6 +422,54 @@ pub struct InviteResponse {
     pub expires_at: DateTime,
 }
 
+/// An invite code as organizers see it when managing codes.
+#[derive(Debug, Serialize)]
+pub struct InviteSummaryResponse {
+    pub code: String,
+    pub max_uses: u32,
+    pub current_uses: u32,
+    #[serde(serialize_with = "serialize_oid")]
+    pub created_by: ObjectId,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub created_at: DateTime,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub expires_at: DateTime,
+    #[serde(serialize_with = "serialize_option_datetime")]
+    pub revoked_at: Option<DateTime>,
+}
+
+impl From<TournamentInvite> for InviteSummaryResponse {
+    fn from(invite: TournamentInvite) -> Self {
+        Self {
+            code: invite.code,
+            max_uses: invite.max_uses,
+            current_uses: invite.current_uses,
+            created_by: invite.created_by,
+            created_at: invite.created_at,
+            expires_at: invite.expires_at,
+            revoked_at: invite.revoked_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct InviteRedemptionResponse {
+    pub session_id: String,
+    pub display_name: String,
+    #[serde(serialize_with = "serialize_datetime")]
+    pub joined_at: DateTime,
+}
+
+impl From<InviteRedemption> for InviteRedemptionResponse {
+    fn from(redemption: InviteRedemption) -> Self {
+        Self {
+            session_id: redemption.session_id,
+            display_name: redemption.display_name,
+            joined_at: redemption.joined_at,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct JoinTournamentDto {
     pub invite_code: String,
@@ -329,27 +508,176 @@ impl Match {
             .or_insert_with(Vec::new)
             .push(voter_id);
 
+        Ok(self.resolve(all_users.iter().filter(|u| !u.muted).count()))
+    }
+
+    /// Decides the winner once every participant has voted. Returns the
+    /// winner only when it was decided by this call.
+    pub fn resolve(&mut self, participant_count: usize) -> Option<ObjectId> {
+        if self.winner.is_some() {
+            return None;
+        }
+
         let total_votes: usize = self.votes.values().map(|v| v.len()).sum();
-        if total_votes == all_users.len() {
-            let votes_1 = self
-                .votes
-                .get(&self.opponent1.to_string())
-                .map_or(0, |v| v.len());
-            let votes_2 = self
-                .votes
-                .get(&self.opponent2.to_string())
-                .map_or(0, |v| v.len());
-
-            let winner = if votes_1 > votes_2 {
-                self.opponent1
+        if total_votes < participant_count {
+            return None;
+        }
+
+        let votes_1 = self
+            .votes
+            .get(&self.opponent1.to_string())
+            .map_or(0, |v| v.len());
+        let votes_2 = self
+            .votes
+            .get(&self.opponent2.to_string())
+            .map_or(0, |v| v.len());
+
+        let winner = if votes_1 > votes_2 {
+            self.opponent1
+        } else {
+            self.opponent2
+        };
+
+        self.winner = Some(winner);
+        Some(winner)
+    }
+
+    /// Moves `from`'s vote to `to`. If `to` already voted in this match the
+    /// vote of `from` is discarded instead.
+    pub fn reassign_voter(&mut self, from: &VoterId, to: &VoterId) {
+        let already_voted = self.votes.values().any(|votes| votes.contains(to));
+        for votes in self.votes.values_mut() {
+            if already_voted {
+                votes.retain(|v| v != from);
             } else {
-                self.opponent2
-            };
+                for v in votes.iter_mut().filter(|v| *v == from) {
+                    *v = to.clone();
+                }
+            }
+        }
+    }
+}
+
+impl Tournament {
+    /// Returns the organizer role of `user_id`, if any.
+    pub fn role_of(&self, user_id: &ObjectId) -> Option<TournamentRole> {
+        if self.created_by == *user_id {
+            return Some(TournamentRole::Owner);
+        }
+        self.members
+            .iter()
+            .find(|m| m.user_id == *user_id)
+            .map(|m| m.role)
+    }
 
-            self.winner = Some(winner);
-            Ok(Some(winner))
+    /// Fails unless `user_id` holds at least the `required` role. `action`
+    /// completes the error message, e.g. "pause".
+    pub fn authorize(
+        &self,
+        user_id: &ObjectId,
+        required: TournamentRole,
+        action: &str,
+    ) -> Result<TournamentRole, String> {
+        match self.role_of(user_id) {
+            Some(role) if role >= required => Ok(role),
+            _ => Err(format!(
+                "You do not have permission to {} this tournament",
+                action
+            )),
+        }
+    }
+
+    /// The owner followed by every other member.
+    pub fn member_responses(&self) -> Vec<TournamentMemberResponse> {
+        std::iter::once(TournamentMemberResponse {
+            user_id: self.created_by,
+            role: TournamentRole::Owner,
+        })
+        .chain(self.members.iter().map(|m| TournamentMemberResponse {
+            user_id: m.user_id,
+            role: m.role,
+        }))
+        .collect()
+    }
+
+    /// The member who takes over when the owner leaves: the highest role
+    /// wins, then whoever was granted it first.
+    pub fn successor(&self) -> Option<ObjectId> {
+        self.members
+            .iter()
+            .max_by(|a, b| {
+                a.role
+                    .cmp(&b.role)
+                    .then_with(|| b.granted_at.cmp(&a.granted_at))
+            })
+            .map(|m| m.user_id)
+    }
+
+    /// Hands the participation and votes of `from` over to `to`. When `to`
+    /// already participates, the entry of `from` is dropped and its votes
+    /// only count where `to` has not voted yet. Returns `false` if `from` is
+    /// not a participant.
+    pub fn reassign_voter(&mut self, from: &VoterId, to: VoterId, name: String) -> bool {
+        let Some(index) = self.users.iter().position(|u| u.voter_id == *from) else {
+            return false;
+        };
+
+        if self.users.iter().any(|u| u.voter_id == to) {
+            self.users.remove(index);
         } else {
-            Ok(None)
+            self.users[index] = TournamentUser {
+                voter_id: to.clone(),
+                name,
+                muted: self.users[index].muted,
+            };
+        }
+
+        for round in &mut self.rounds {
+            for m in &mut round.matches {
+                m.reassign_voter(from, &to);
+            }
+        }
+
+        true
+    }
+
+    /// Participants who may vote, i.e. everyone who is not muted. Matches
+    /// are decided once this many votes are in.
+    pub fn voter_count(&self) -> usize {
+        self.users.iter().filter(|u| !u.muted).count()
+    }
+
+    /// Whether a participant other than `except` already goes by `name`,
+    /// ignoring case.
+    pub fn is_name_taken(&self, name: &str, except: Option<&VoterId>) -> bool {
+        let name = name.to_lowercase();
+        self.users
+            .iter()
+            .filter(|u| Some(&u.voter_id) != except)
+            .any(|u| u.name.to_lowercase() == name)
+    }
+
+    /// `name`, or the first of `name (2)`, `name (3)`, ... that no
+    /// participant other than `except` uses yet.
+    pub fn unique_display_name(&self, name: &str, except: Option<&VoterId>) -> String {
+        if !self.is_name_taken(name, except) {
+            return name.to_string();
+        }
+        (2..)
+            .map(|n| format!("{} ({})", name, n))
+            .find(|candidate| !self.is_name_taken(candidate, except))
+            .unwrap()
+    }
+
+    /// Drops the votes `voter_id` cast in matches that are still open, so
+    /// they no longer count towards the result.
+    pub fn withdraw_votes(&mut self, voter_id: &VoterId) {
+        for round in &mut self.rounds {
+            for m in round.matches.iter_mut().filter(|m| m.winner.is_none()) {
+                for votes in m.votes.values_mut() {
+                    votes.retain(|v| v != voter_id);
+                }
+            }
         }
     }
 }
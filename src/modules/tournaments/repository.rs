@@ -1,6 +1,8 @@
-use crate::modules::tournaments::model::{Tournament, TournamentInvite};
+use crate::modules::tournaments::model::{
+    InviteRedemption, Tournament, TournamentInvite, TournamentUser, VoterId,
+};
 use async_trait::async_trait;
-use mongodb::bson::{doc, oid::ObjectId};
+use mongodb::bson::{doc, oid::ObjectId, Bson, DateTime, Document};
 use mongodb::Database;
 
 #[async_trait]
@@ -13,8 +15,42 @@ pub trait TournamentRepository: Send + Sync {
         cursor: Option<ObjectId>,
         limit: i64,
     ) -> Result<Vec<Tournament>, String>;
-    async fn update(&self, tournament: &Tournament) -> Result<(), String>;
+    /// Saves the tournament unless another write changed it since it was
+    /// read. Returns `false` in that case, so the caller can read it again
+    /// and redo its change instead of overwriting the other write.
+    async fn update(&self, tournament: &Tournament) -> Result<bool, String>;
+    /// Appends a participant without touching the rest of the document, so
+    /// concurrent joins cannot overwrite each other. Returns the updated
+    /// tournament, or `None` when it does not exist or another participant
+    /// already goes by the same name, ignoring case.
+    async fn add_participant(
+        &self,
+        id: &ObjectId,
+        user: &TournamentUser,
+    ) -> Result<Option<Tournament>, String>;
     async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+    /// Every tournament `user_id` owns or helps run.
+    async fn find_by_organizer(&self, user_id: &ObjectId) -> Result<Vec<Tournament>, String>;
+    /// Every tournament `voter_id` takes part in.
+    async fn find_by_participant(&self, voter_id: &VoterId) -> Result<Vec<Tournament>, String>;
+    /// The opponents among `opponent_ids` that appear in any tournament.
+    async fn find_used_opponents(&self, opponent_ids: &[ObjectId]) -> Result<Vec<ObjectId>, String>;
+    /// Number of tournaments `user_id` owns or is a member of.
+    async fn count_by_organizer(&self, user_id: &ObjectId) -> Result<u64, String>;
+    async fn count_by_participant(&self, voter_id: &VoterId) -> Result<u64, String>;
+    /// Number of tournaments won by one of `opponent_ids`.
+    async fn count_won_by(&self, opponent_ids: &[ObjectId]) -> Result<u64, String>;
+}
+
+/// Matches tournaments `voter_id` takes part in. Compares the fields of the
+/// `users.voter_id` index rather than the whole subdocument, so the index
+/// is used and field order does not matter.
+pub fn participant_filter(voter_id: &VoterId) -> Document {
+    let (kind, id) = match voter_id {
+        VoterId::Registered(id) => ("Registered", Bson::ObjectId(*id)),
+        VoterId::Anonymous(id) => ("Anonymous", Bson::String(id.clone())),
+    };
+    doc! { "users": { "$elemMatch": { "voter_id.type": kind, "voter_id.id": id } } }
 }
 
 pub struct TournamentRepositoryImpl {
@@ -55,7 +91,10 @@ impl TournamentRepository for TournamentRepositoryImpl {
         use futures::TryStreamExt;
         use mongodb::options::FindOptions;
 
-        let mut filter = doc! { "created_by": user_id };
+        // Co-organizers and moderators see the tournaments they help run
+        let mut filter = doc! {
+            "$or": [{ "created_by": user_id }, { "members.user_id": user_id }]
+        };
         if let Some(cursor_id) = cursor {
             filter.insert("_id", doc! { "$lt": cursor_id });
         }
@@ -76,19 +115,59 @@ impl TournamentRepository for TournamentRepositoryImpl {
             .map_err(|e| format!("Error collecting tournaments: {}", e))
     }
 
-    async fn update(&self, tournament: &Tournament) -> Result<(), String> {
+    async fn update(&self, tournament: &Tournament) -> Result<bool, String> {
         let id = tournament
             .id
             .as_ref()
             .ok_or("Tournament must have an id to update")?;
 
-        self.db
+        let mut filter = doc! { "_id": id, "version": tournament.version };
+        if tournament.version == 0 {
+            // Tournaments saved before versioning have no version yet
+            filter.insert("version", doc! { "$in": [0_i64, null] });
+        }
+        let mut saved = tournament.clone();
+        saved.version += 1;
+
+        let result = self
+            .db
             .collection::<Tournament>("tournaments")
-            .replace_one(doc! { "_id": id }, tournament)
+            .replace_one(filter, &saved)
             .await
             .map_err(|e| format!("Error updating tournament: {}", e))?;
 
-        Ok(())
+        Ok(result.matched_count == 1)
+    }
+
+    async fn add_participant(
+        &self,
+        id: &ObjectId,
+        user: &TournamentUser,
+    ) -> Result<Option<Tournament>, String> {
+        use mongodb::options::{Collation, CollationStrength, ReturnDocument};
+
+        let name = user.name.clone();
+        let user = mongodb::bson::to_bson(user)
+            .map_err(|e| format!("Error adding participant: {}", e))?;
+        self.db
+            .collection::<Tournament>("tournaments")
+            .find_one_and_update(
+                doc! { "_id": id, "users.name": { "$ne": name } },
+                doc! {
+                    "$push": { "users": user },
+                    "$set": { "updated_at": DateTime::now() },
+                    "$inc": { "version": 1 },
+                },
+            )
+            .collation(
+                Collation::builder()
+                    .locale("en")
+                    .strength(CollationStrength::Secondary)
+                    .build(),
+            )
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(|e| format!("Error adding participant: {}", e))
     }
 
     async fn delete(&self, id: &ObjectId) -> Result<(), String> {
@@ -104,13 +183,116 @@ impl TournamentRepository for TournamentRepositoryImpl {
         }
         Ok(())
     }
+
+    async fn find_by_organizer(&self, user_id: &ObjectId) -> Result<Vec<Tournament>, String> {
+        use futures::TryStreamExt;
+
+        self.db
+            .collection::<Tournament>("tournaments")
+            .find(doc! {
+                "$or": [{ "created_by": user_id }, { "members.user_id": user_id }]
+            })
+            .await
+            .map_err(|e| format!("Error finding tournaments: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error collecting tournaments: {}", e))
+    }
+
+    async fn find_by_participant(&self, voter_id: &VoterId) -> Result<Vec<Tournament>, String> {
+        use futures::TryStreamExt;
+
+        self.db
+            .collection::<Tournament>("tournaments")
+            .find(participant_filter(voter_id))
+            .await
+            .map_err(|e| format!("Error finding tournaments: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error collecting tournaments: {}", e))
+    }
+
+    async fn find_used_opponents(
+        &self,
+        opponent_ids: &[ObjectId],
+    ) -> Result<Vec<ObjectId>, String> {
+        let used = self
+            .db
+            .collection::<Tournament>("tournaments")
+            .distinct(
+                "opponents.opponent_id",
+                doc! { "opponents.opponent_id": { "$in": opponent_ids } },
+            )
+            .await
+            .map_err(|e| format!("Error finding used opponents: {}", e))?;
+
+        // `distinct` also returns the other opponents of matching tournaments
+        Ok(used
+            .into_iter()
+            .filter_map(|id| id.as_object_id())
+            .filter(|id| opponent_ids.contains(id))
+            .collect())
+    }
+
+    async fn count_by_organizer(&self, user_id: &ObjectId) -> Result<u64, String> {
+        self.db
+            .collection::<Tournament>("tournaments")
+            .count_documents(doc! {
+                "$or": [{ "created_by": user_id }, { "members.user_id": user_id }]
+            })
+            .await
+            .map_err(|e| format!("Error counting tournaments: {}", e))
+    }
+
+    async fn count_by_participant(&self, voter_id: &VoterId) -> Result<u64, String> {
+        self.db
+            .collection::<Tournament>("tournaments")
+            .count_documents(participant_filter(voter_id))
+            .await
+            .map_err(|e| format!("Error counting tournaments: {}", e))
+    }
+
+    async fn count_won_by(&self, opponent_ids: &[ObjectId]) -> Result<u64, String> {
+        self.db
+            .collection::<Tournament>("tournaments")
+            .count_documents(doc! { "winner": { "$in": opponent_ids } })
+            .await
+            .map_err(|e| format!("Error counting tournaments: {}", e))
+    }
 }
 
+const DUPLICATE_KEY_CODE: i32 = 11000;
+
 #[async_trait]
 pub trait InviteRepository: Send + Sync {
-    async fn create(&self, invite: TournamentInvite) -> Result<(), String>;
+    /// Stores a new invite. Returns `false` when its code is already taken.
+    async fn create(&self, invite: TournamentInvite) -> Result<bool, String>;
     async fn find_by_code(&self, code: &str) -> Result<Option<TournamentInvite>, String>;
-    async fn increment_uses(&self, id: &ObjectId) -> Result<(), String>;
+    /// Counts a use of the invite and logs who joined with it, in a single
+    /// conditional update. Returns `false` when the invite is revoked,
+    /// expired or already used up, so concurrent joins never exceed
+    /// `max_uses`.
+    async fn redeem(&self, id: &ObjectId, redemption: &InviteRedemption)
+        -> Result<bool, String>;
+    /// Undoes a redemption whose join could not be completed.
+    async fn release(&self, id: &ObjectId, session_id: &str) -> Result<(), String>;
+    /// Every invite of a tournament, newest first, without the join log.
+    async fn find_by_tournament(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<TournamentInvite>, String>;
+    /// Revokes an unrevoked invite. Returns `false` when there is none.
+    async fn revoke(&self, tournament_id: &ObjectId, code: &str) -> Result<bool, String>;
+}
+
+/// Whether `error` is a unique index violation.
+fn is_duplicate_key(error: &mongodb::error::Error) -> bool {
+    use mongodb::error::{ErrorKind, WriteFailure};
+
+    matches!(
+        error.kind.as_ref(),
+        ErrorKind::Write(WriteFailure::WriteError(e)) if e.code == DUPLICATE_KEY_CODE
+    )
 }
 
 pub struct InviteRepositoryImpl {
@@ -125,13 +307,17 @@ impl InviteRepositoryImpl {
 
 #[async_trait]
 impl InviteRepository for InviteRepositoryImpl {
-    async fn create(&self, invite: TournamentInvite) -> Result<(), String> {
-        self.db
+    async fn create(&self, invite: TournamentInvite) -> Result<bool, String> {
+        match self
+            .db
             .collection::<TournamentInvite>("tournament_invites")
             .insert_one(invite)
             .await
-            .map_err(|e| format!("Error creating invite: {}", e))?;
-        Ok(())
+        {
+            Ok(_) => Ok(true),
+            Err(e) if is_duplicate_key(&e) => Ok(false),
+            Err(e) => Err(format!("Error creating invite: {}", e)),
+        }
     }
 
     async fn find_by_code(&self, code: &str) -> Result<Option<TournamentInvite>, String> {
@@ -142,17 +328,77 @@ impl InviteRepository for InviteRepositoryImpl {
             .map_err(|e| format!("Error finding invite: {}", e))
     }
 
-    async fn increment_uses(&self, id: &ObjectId) -> Result<(), String> {
+    async fn redeem(
+        &self,
+        id: &ObjectId,
+        redemption: &InviteRedemption,
+    ) -> Result<bool, String> {
+        let redemption = mongodb::bson::to_bson(redemption)
+            .map_err(|e| format!("Error redeeming invite: {}", e))?;
+        let result = self
+            .db
+            .collection::<TournamentInvite>("tournament_invites")
+            .update_one(
+                doc! {
+                    "_id": id,
+                    "revoked_at": null,
+                    "expires_at": { "$gt": DateTime::now() },
+                    "$expr": { "$lt": ["$current_uses", "$max_uses"] },
+                },
+                doc! {
+                    "$inc": { "current_uses": 1 },
+                    "$push": { "redemptions": redemption },
+                },
+            )
+            .await
+            .map_err(|e| format!("Error redeeming invite: {}", e))?;
+        Ok(result.modified_count == 1)
+    }
+
+    async fn release(&self, id: &ObjectId, session_id: &str) -> Result<(), String> {
         self.db
             .collection::<TournamentInvite>("tournament_invites")
             .update_one(
-                doc! { "_id": id },
-                doc! { "$inc": { "current_uses": 1 } },
+                doc! { "_id": id, "redemptions.session_id": session_id },
+                doc! {
+                    "$inc": { "current_uses": -1 },
+                    "$pull": { "redemptions": { "session_id": session_id } },
+                },
             )
             .await
-            .map_err(|e| format!("Error incrementing invite uses: {}", e))?;
+            .map_err(|e| format!("Error releasing invite: {}", e))?;
         Ok(())
     }
+
+    async fn find_by_tournament(
+        &self,
+        tournament_id: &ObjectId,
+    ) -> Result<Vec<TournamentInvite>, String> {
+        use futures::TryStreamExt;
+        self.db
+            .collection::<TournamentInvite>("tournament_invites")
+            .find(doc! { "tournament_id": tournament_id })
+            .projection(doc! { "redemptions": 0 })
+            .sort(doc! { "_id": -1 })
+            .await
+            .map_err(|e| format!("Error finding invites: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error finding invites: {}", e))
+    }
+
+    async fn revoke(&self, tournament_id: &ObjectId, code: &str) -> Result<bool, String> {
+        let result = self
+            .db
+            .collection::<TournamentInvite>("tournament_invites")
+            .update_one(
+                doc! { "tournament_id": tournament_id, "code": code, "revoked_at": null },
+                doc! { "$set": { "revoked_at": DateTime::now() } },
+            )
+            .await
+            .map_err(|e| format!("Error revoking invite: {}", e))?;
+        Ok(result.modified_count == 1)
+    }
 }
 
 #[cfg(test)]
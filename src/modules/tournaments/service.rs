@@ -1,10 +1,17 @@
+use crate::modules::account_deletion::model::OwnedTournamentPolicy;
 use crate::modules::auth::service::AuthService;
 use crate::common::pagination::{PaginatedResponse, PaginationParams};
 use crate::modules::tournaments::model::{
-    CreateInviteDto, CreateTournamentDto, InviteResponse, JoinTournamentDto,
-    JoinTournamentResponse, Match, OpponentDto, Round, Tournament, TournamentInvite,
-    TournamentResponse, TournamentStatus, TournamentUser, UpdateTournamentDto, VoterId,
-    VoteMatchDto,
+    CreateInviteDto, CreateTournamentDto, GrantMemberDto, InviteRedemption,
+    InviteRedemptionResponse, InviteResponse, InviteSummaryResponse, JoinTournamentDto,
+    JoinTournamentResponse, Match, MembershipError, OpponentDto, Round, Tournament,
+    TournamentInvite, TournamentMember, TournamentMemberResponse, TournamentResponse,
+    TournamentRole, TournamentStatus, TournamentUser, TournamentUserResponse,
+    UpdateParticipantDto, UpdateTournamentDto, VoterId, VoteMatchDto,
+};
+use crate::modules::tournaments::display_name::DisplayNameFilter;
+use crate::modules::tournaments::invite_code::{
+    render_qr_png, InviteCodeGenerator, MAX_CODE_ATTEMPTS,
 };
 use crate::modules::tournaments::repository::{InviteRepository, TournamentRepository};
 use crate::modules::websocket::broadcaster::TournamentBroadcaster;
@@ -16,6 +23,11 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// Joins give up after losing the race for a free display name this often
+const MAX_JOIN_ATTEMPTS: usize = 5;
+/// Changes give up after losing the race against other writes this often
+const MAX_UPDATE_ATTEMPTS: usize = 5;
+
 #[async_trait]
 pub trait TournamentService: Send + Sync {
     async fn create_tournament(
@@ -36,6 +48,10 @@ pub trait TournamentService: Send + Sync {
         user_id: &ObjectId,
     ) -> Result<Tournament, String>;
     async fn delete_tournament(&self, id: &ObjectId, user_id: &ObjectId) -> Result<(), String>;
+    /// Ends any tournament regardless of its owner or remaining matches.
+    async fn force_complete_tournament(&self, id: &ObjectId) -> Result<Tournament, String>;
+    /// Deletes any tournament regardless of its owner.
+    async fn force_delete_tournament(&self, id: &ObjectId) -> Result<(), String>;
     async fn pause_tournament(
         &self,
         id: &ObjectId,
@@ -62,6 +78,33 @@ pub trait TournamentService: Send + Sync {
         dto: CreateInviteDto,
         created_by: ObjectId,
     ) -> Result<InviteResponse, String>;
+    /// Every invite code of the tournament with its usage, newest first.
+    async fn list_invites(
+        &self,
+        tournament_id: &ObjectId,
+        user_id: &ObjectId,
+    ) -> Result<Vec<InviteSummaryResponse>, MembershipError>;
+    /// Stops a code from being used. Participants who already joined stay.
+    async fn revoke_invite(
+        &self,
+        tournament_id: &ObjectId,
+        code: &str,
+        user_id: &ObjectId,
+    ) -> Result<(), MembershipError>;
+    /// The anonymous sessions that joined with a code, oldest first.
+    async fn invite_joins(
+        &self,
+        tournament_id: &ObjectId,
+        code: &str,
+        user_id: &ObjectId,
+    ) -> Result<Vec<InviteRedemptionResponse>, MembershipError>;
+    /// A PNG QR code linking to the join page for the code.
+    async fn invite_qr_code(
+        &self,
+        tournament_id: &ObjectId,
+        code: &str,
+        user_id: &ObjectId,
+    ) -> Result<Vec<u8>, MembershipError>;
     async fn join_tournament(
         &self,
         tournament_id: &ObjectId,
@@ -71,6 +114,65 @@ pub trait TournamentService: Send + Sync {
         &self,
         dto: JoinTournamentDto,
     ) -> Result<JoinTournamentResponse, String>;
+    async fn claim_anonymous_session(
+        &self,
+        tournament_id: &ObjectId,
+        session_id: &str,
+        user_id: ObjectId,
+        name: String,
+    ) -> Result<(), String>;
+    /// Renames a participant or changes whether they may vote.
+    async fn update_participant(
+        &self,
+        tournament_id: &ObjectId,
+        voter_id: &str,
+        dto: UpdateParticipantDto,
+        user_id: &ObjectId,
+    ) -> Result<TournamentUserResponse, MembershipError>;
+    /// Removes a participant and their votes in open matches. An anonymous
+    /// participant's session stops working for the tournament.
+    async fn kick_participant(
+        &self,
+        tournament_id: &ObjectId,
+        voter_id: &str,
+        user_id: &ObjectId,
+    ) -> Result<(), MembershipError>;
+    /// Adds or changes a co-organizer or moderator. Members can only grant
+    /// roles below their own.
+    async fn grant_member(
+        &self,
+        tournament_id: &ObjectId,
+        dto: GrantMemberDto,
+        granted_by: &ObjectId,
+    ) -> Result<Vec<TournamentMemberResponse>, MembershipError>;
+    /// Removes a member. Members can remove themselves or anyone with a
+    /// lower role.
+    async fn revoke_member(
+        &self,
+        tournament_id: &ObjectId,
+        member_id: &ObjectId,
+        revoked_by: &ObjectId,
+    ) -> Result<Vec<TournamentMemberResponse>, MembershipError>;
+    /// Makes `new_owner` the owner; the previous owner stays on as
+    /// co-organizer.
+    async fn transfer_ownership(
+        &self,
+        tournament_id: &ObjectId,
+        new_owner: &ObjectId,
+        user_id: &ObjectId,
+    ) -> Result<Tournament, MembershipError>;
+    /// Transfers or deletes the tournaments `user_id` owns and removes them
+    /// from the ones they help run, ahead of deleting their account.
+    async fn release_tournaments(
+        &self,
+        user_id: &ObjectId,
+        policy: OwnedTournamentPolicy,
+    ) -> Result<(), String>;
+    /// Keeps the participation and votes of `user_id` under an anonymous
+    /// name, ahead of deleting their account.
+    async fn anonymize_participant(&self, user_id: &ObjectId) -> Result<(), String>;
+    /// The opponents among `opponent_ids` that some tournament still uses.
+    async fn opponents_in_use(&self, opponent_ids: &[ObjectId]) -> Result<Vec<ObjectId>, String>;
 }
 
 pub struct TournamentServiceImpl {
@@ -78,6 +180,9 @@ pub struct TournamentServiceImpl {
     invite_repository: Arc<dyn InviteRepository>,
     auth_service: Arc<dyn AuthService + Send + Sync>,
     broadcaster: Arc<TournamentBroadcaster>,
+    code_generator: InviteCodeGenerator,
+    display_names: DisplayNameFilter,
+    app_url: String,
 }
 
 impl TournamentServiceImpl {
@@ -86,12 +191,18 @@ impl TournamentServiceImpl {
         invite_repository: Arc<dyn InviteRepository>,
         auth_service: Arc<dyn AuthService + Send + Sync>,
         broadcaster: Arc<TournamentBroadcaster>,
+        code_generator: InviteCodeGenerator,
+        display_names: DisplayNameFilter,
+        app_url: String,
     ) -> Self {
         Self {
             tournament_repository,
             invite_repository,
             auth_service,
             broadcaster,
+            code_generator,
+            display_names,
+            app_url,
         }
     }
 
@@ -165,6 +276,225 @@ impl TournamentServiceImpl {
         winners.extend(&round.automatic_winners);
         winners
     }
+
+    async fn authorize_invite_management(
+        &self,
+        tournament_id: &ObjectId,
+        user_id: &ObjectId,
+    ) -> Result<(), MembershipError> {
+        let tournament = self
+            .tournament_repository
+            .find_by_id(tournament_id)
+            .await
+            .map_err(|e| MembershipError::Internal(format!("Error finding tournament: {}", e)))?
+            .ok_or_else(|| MembershipError::NotFound("Tournament not found".to_string()))?;
+        tournament
+            .authorize(user_id, TournamentRole::CoOrganizer, "manage invites for")
+            .map_err(MembershipError::Forbidden)?;
+        Ok(())
+    }
+
+    /// Reads the tournament and saves it with `change` applied, see
+    /// [`Self::save_with`].
+    async fn modify<T, E, F>(
+        &self,
+        id: &ObjectId,
+        change: F,
+    ) -> Result<(Tournament, Option<T>), E>
+    where
+        T: Send,
+        E: From<MembershipError> + Send,
+        F: FnMut(&mut Tournament) -> Result<Option<T>, E> + Send,
+    {
+        let tournament = self
+            .tournament_repository
+            .find_by_id(id)
+            .await
+            .map_err(|e| MembershipError::Internal(format!("Error finding tournament: {}", e)))?
+            .ok_or_else(|| MembershipError::NotFound("Tournament not found".to_string()))?;
+        self.save_with(id, tournament, change).await
+    }
+
+    /// Applies `change` to `tournament` and saves it. When another request
+    /// saved the tournament since it was read, e.g. a guest joined, the
+    /// change is made again on a fresh copy instead of overwriting theirs.
+    /// `change` returns `None` to leave the tournament as it is. Failures
+    /// while reading or saving are converted into the caller's error type.
+    async fn save_with<T, E, F>(
+        &self,
+        id: &ObjectId,
+        mut tournament: Tournament,
+        mut change: F,
+    ) -> Result<(Tournament, Option<T>), E>
+    where
+        T: Send,
+        E: From<MembershipError> + Send,
+        F: FnMut(&mut Tournament) -> Result<Option<T>, E> + Send,
+    {
+        for _ in 0..MAX_UPDATE_ATTEMPTS {
+            let Some(result) = change(&mut tournament)? else {
+                return Ok((tournament, None));
+            };
+            tournament.updated_at = DateTime::now();
+            let saved = self.tournament_repository.update(&tournament).await.map_err(|e| {
+                MembershipError::Internal(format!("Error updating tournament: {}", e))
+            })?;
+            if saved {
+                tournament.version += 1;
+                return Ok((tournament, Some(result)));
+            }
+
+            tournament = self
+                .tournament_repository
+                .find_by_id(id)
+                .await
+                .map_err(|e| MembershipError::Internal(format!("Error finding tournament: {}", e)))?
+                .ok_or_else(|| MembershipError::NotFound("Tournament not found".to_string()))?;
+        }
+        Err(MembershipError::Invalid(
+            "Tournament is being changed by someone else, please try again".to_string(),
+        )
+        .into())
+    }
+
+    /// Gives back an invite use claimed by a join that did not complete.
+    async fn release_invite(&self, invite_id: &ObjectId, session_id: &str) {
+        if let Err(e) = self.invite_repository.release(invite_id, session_id).await {
+            tracing::error!(
+                error = %e,
+                invite_id = %invite_id,
+                "Failed to release invite use after a failed join"
+            );
+        }
+    }
+
+    /// The invite with `code`, if it belongs to the tournament.
+    async fn find_tournament_invite(
+        &self,
+        tournament_id: &ObjectId,
+        code: &str,
+    ) -> Result<TournamentInvite, MembershipError> {
+        self.find_invite(code)
+            .await
+            .map_err(MembershipError::Internal)?
+            .filter(|invite| invite.tournament_id == *tournament_id)
+            .ok_or_else(|| MembershipError::NotFound("Invite not found".to_string()))
+    }
+
+    /// Looks a code up as typed, then as the generator would have written it.
+    async fn find_invite(&self, code: &str) -> Result<Option<TournamentInvite>, String> {
+        let invite = self
+            .invite_repository
+            .find_by_code(code)
+            .await
+            .map_err(|e| format!("Error finding invite: {}", e))?;
+        let normalized = self.code_generator.normalize(code);
+        if invite.is_some() || normalized == code {
+            return Ok(invite);
+        }
+        self.invite_repository
+            .find_by_code(&normalized)
+            .await
+            .map_err(|e| format!("Error finding invite: {}", e))
+    }
+
+    /// Decides the open matches of the current round that already have
+    /// every vote they need, e.g. after a participant stopped voting.
+    /// Returns the events to broadcast once the tournament is saved.
+    fn resolve_open_matches(&self, tournament: &mut Tournament) -> Vec<TournamentEvent> {
+        let mut events = Vec::new();
+        let current_round_index = tournament.rounds.len() - 1;
+        let voter_count = tournament.voter_count();
+        let match_ids: Vec<String> = tournament.rounds[current_round_index]
+            .matches
+            .iter()
+            .map(|m| m.match_id.clone())
+            .collect();
+        for match_id in match_ids {
+            let winner = tournament.rounds[current_round_index]
+                .matches
+                .iter_mut()
+                .find(|m| m.match_id == match_id)
+                .and_then(|m| m.resolve(voter_count));
+            if let Some(winner_id) = winner {
+                self.complete_match(
+                    tournament,
+                    current_round_index,
+                    &match_id,
+                    winner_id,
+                    &mut events,
+                );
+            }
+        }
+        events
+    }
+
+    /// Records a decided match and advances the bracket when it was the
+    /// last open match of its round. The events to broadcast once the
+    /// tournament is saved are added to `events`.
+    fn complete_match(
+        &self,
+        tournament: &mut Tournament,
+        round_index: usize,
+        match_id: &str,
+        winner_id: ObjectId,
+        events: &mut Vec<TournamentEvent>,
+    ) {
+        // Announce match_completed
+        if let Some(completed_match) = tournament.rounds[round_index]
+            .matches
+            .iter()
+            .find(|m| m.match_id == match_id)
+        {
+            let final_votes: HashMap<String, usize> = completed_match
+                .votes
+                .iter()
+                .map(|(k, v)| (k.clone(), v.len()))
+                .collect();
+            events.push(TournamentEvent::MatchCompleted {
+                match_id: match_id.to_string(),
+                winner_id,
+                final_votes,
+            });
+        }
+
+        let current_round = &tournament.rounds[round_index];
+
+        if self.is_round_complete(current_round) {
+            let winners = self.get_round_winners(current_round);
+            let round_number = current_round.round_number;
+
+            if winners.len() == 1 {
+                tournament.status = TournamentStatus::Completed;
+                tournament.winner = Some(winners[0]);
+
+                events.push(TournamentEvent::RoundCompleted {
+                    round_number,
+                    next_round_matches: 0,
+                });
+                events.push(TournamentEvent::TournamentCompleted {
+                    winner_id: winners[0],
+                });
+            } else {
+                let next_round = self.create_next_round(winners, round_number + 1);
+                let next_matches = next_round.matches.len();
+                tournament.rounds.push(next_round);
+
+                events.push(TournamentEvent::RoundCompleted {
+                    round_number,
+                    next_round_matches: next_matches,
+                });
+            }
+
+            tournament.updated_at = DateTime::now();
+        }
+    }
+
+    fn broadcast_all(&self, tournament_id: &ObjectId, events: Vec<TournamentEvent>) {
+        for event in events {
+            self.broadcaster.broadcast(tournament_id, event);
+        }
+    }
 }
 
 #[async_trait]
@@ -234,40 +564,58 @@ impl TournamentService for TournamentServiceImpl {
         dto: UpdateTournamentDto,
         user_id: &ObjectId,
     ) -> Result<Tournament, String> {
-        let mut tournament = self
+        let (tournament, _) = self
+            .modify(id, |tournament| {
+                tournament.authorize(user_id, TournamentRole::CoOrganizer, "update")?;
+
+                if let Some(name) = &dto.name {
+                    if name.trim().is_empty() {
+                        return Err("Tournament name cannot be empty".to_string());
+                    }
+                    tournament.name = name.trim().to_string();
+                }
+                Ok(Some(()))
+            })
+            .await?;
+
+        Ok(tournament)
+    }
+
+    async fn delete_tournament(&self, id: &ObjectId, user_id: &ObjectId) -> Result<(), String> {
+        let tournament = self
             .tournament_repository
             .find_by_id(id)
             .await?
             .ok_or("Tournament not found")?;
 
-        if tournament.created_by != *user_id {
-            return Err("You can only update your own tournaments".to_string());
-        }
+        tournament.authorize(user_id, TournamentRole::Owner, "delete")?;
 
-        if let Some(name) = dto.name {
-            if name.trim().is_empty() {
-                return Err("Tournament name cannot be empty".to_string());
-            }
-            tournament.name = name.trim().to_string();
-        }
+        self.tournament_repository.delete(id).await
+    }
+
+    async fn force_complete_tournament(&self, id: &ObjectId) -> Result<Tournament, String> {
+        let (tournament, _) = self
+            .modify(id, |tournament| {
+                if matches!(tournament.status, TournamentStatus::Completed) {
+                    return Err("Tournament is already completed".to_string());
+                }
+                tournament.status = TournamentStatus::Completed;
+                Ok(Some(()))
+            })
+            .await?;
 
-        tournament.updated_at = DateTime::now();
-        self.tournament_repository.update(&tournament).await?;
+        self.broadcaster
+            .broadcast(id, TournamentEvent::TournamentForceCompleted);
 
         Ok(tournament)
     }
 
-    async fn delete_tournament(&self, id: &ObjectId, user_id: &ObjectId) -> Result<(), String> {
-        let tournament = self
-            .tournament_repository
+    async fn force_delete_tournament(&self, id: &ObjectId) -> Result<(), String> {
+        self.tournament_repository
             .find_by_id(id)
             .await?
             .ok_or("Tournament not found")?;
 
-        if tournament.created_by != *user_id {
-            return Err("You can only delete your own tournaments".to_string());
-        }
-
         self.tournament_repository.delete(id).await
     }
 
@@ -276,23 +624,17 @@ impl TournamentService for TournamentServiceImpl {
         id: &ObjectId,
         user_id: &ObjectId,
     ) -> Result<Tournament, String> {
-        let mut tournament = self
-            .tournament_repository
-            .find_by_id(id)
-            .await?
-            .ok_or("Tournament not found")?;
-
-        if tournament.created_by != *user_id {
-            return Err("You can only pause your own tournaments".to_string());
-        }
-
-        if !matches!(tournament.status, TournamentStatus::Active) {
-            return Err("Tournament is not active".to_string());
-        }
+        let (tournament, _) = self
+            .modify(id, |tournament| {
+                tournament.authorize(user_id, TournamentRole::Moderator, "pause")?;
 
-        tournament.status = TournamentStatus::Paused;
-        tournament.updated_at = DateTime::now();
-        self.tournament_repository.update(&tournament).await?;
+                if !matches!(tournament.status, TournamentStatus::Active) {
+                    return Err("Tournament is not active".to_string());
+                }
+                tournament.status = TournamentStatus::Paused;
+                Ok(Some(()))
+            })
+            .await?;
 
         self.broadcaster.broadcast(id, TournamentEvent::TournamentPaused);
 
@@ -304,23 +646,17 @@ impl TournamentService for TournamentServiceImpl {
         id: &ObjectId,
         user_id: &ObjectId,
     ) -> Result<Tournament, String> {
-        let mut tournament = self
-            .tournament_repository
-            .find_by_id(id)
-            .await?
-            .ok_or("Tournament not found")?;
-
-        if tournament.created_by != *user_id {
-            return Err("You can only resume your own tournaments".to_string());
-        }
+        let (tournament, _) = self
+            .modify(id, |tournament| {
+                tournament.authorize(user_id, TournamentRole::Moderator, "resume")?;
 
-        if !matches!(tournament.status, TournamentStatus::Paused) {
-            return Err("Tournament is not paused".to_string());
-        }
-
-        tournament.status = TournamentStatus::Active;
-        tournament.updated_at = DateTime::now();
-        self.tournament_repository.update(&tournament).await?;
+                if !matches!(tournament.status, TournamentStatus::Paused) {
+                    return Err("Tournament is not paused".to_string());
+                }
+                tournament.status = TournamentStatus::Active;
+                Ok(Some(()))
+            })
+            .await?;
 
         self.broadcaster.broadcast(id, TournamentEvent::TournamentResumed);
 
@@ -352,120 +688,65 @@ impl TournamentService for TournamentServiceImpl {
         vote_dto: VoteMatchDto,
         voter_id: VoterId,
     ) -> Result<Tournament, String> {
-        let mut tournament = self
-            .tournament_repository
-            .find_by_id(&vote_dto.tournament_id)
-            .await
-            .map_err(|e| format!("Error finding tournament: {}", e))?
-            .ok_or("Tournament not found")?;
-
-        // Verify voter is a participant in this tournament
-        if !tournament
-            .users
-            .iter()
-            .any(|u| u.voter_id == voter_id)
-        {
-            return Err("Voter is not a participant in this tournament".to_string());
-        }
-
-        let current_round_index = tournament.rounds.len() - 1;
-        let match_winner = {
-            let current_match = tournament.rounds[current_round_index]
-                .matches
-                .iter_mut()
-                .find(|m| m.match_id == vote_dto.match_id)
-                .ok_or("Match not found")?;
-
-            current_match.process_vote(voter_id, vote_dto.voted_for, &tournament.users)?
-        };
-
-        // Broadcast vote_cast event
-        let voted_match = tournament.rounds[current_round_index]
-            .matches
-            .iter()
-            .find(|m| m.match_id == vote_dto.match_id)
-            .unwrap();
-        let vote_counts: HashMap<String, usize> = voted_match
-            .votes
-            .iter()
-            .map(|(k, v)| (k.clone(), v.len()))
-            .collect();
-        self.broadcaster.broadcast(
-            &vote_dto.tournament_id,
-            TournamentEvent::VoteCast {
-                match_id: vote_dto.match_id.clone(),
-                vote_counts,
-                total_needed: tournament.users.len(),
-            },
-        );
+        let (tournament, events) = self
+            .modify(&vote_dto.tournament_id, |tournament| {
+                // Verify voter is a participant in this tournament
+                if !tournament.users.iter().any(|u| u.voter_id == voter_id) {
+                    return Err("Voter is not a participant in this tournament".to_string());
+                }
+                if tournament
+                    .users
+                    .iter()
+                    .any(|u| u.voter_id == voter_id && u.muted)
+                {
+                    return Err("You have been muted in this tournament".to_string());
+                }
 
-        if let Some(winner_id) = match_winner {
-            // Broadcast match_completed
-            let completed_match = tournament.rounds[current_round_index]
-                .matches
-                .iter()
-                .find(|m| m.match_id == vote_dto.match_id)
-                .unwrap();
-            let final_votes: HashMap<String, usize> = completed_match
-                .votes
-                .iter()
-                .map(|(k, v)| (k.clone(), v.len()))
-                .collect();
-            self.broadcaster.broadcast(
-                &vote_dto.tournament_id,
-                TournamentEvent::MatchCompleted {
+                let current_round_index = tournament.rounds.len() - 1;
+                let match_winner = {
+                    let current_match = tournament.rounds[current_round_index]
+                        .matches
+                        .iter_mut()
+                        .find(|m| m.match_id == vote_dto.match_id)
+                        .ok_or("Match not found")?;
+
+                    current_match.process_vote(
+                        voter_id.clone(),
+                        vote_dto.voted_for,
+                        &tournament.users,
+                    )?
+                };
+
+                // Announce vote_cast
+                let voted_match = tournament.rounds[current_round_index]
+                    .matches
+                    .iter()
+                    .find(|m| m.match_id == vote_dto.match_id)
+                    .unwrap();
+                let vote_counts: HashMap<String, usize> = voted_match
+                    .votes
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.len()))
+                    .collect();
+                let mut events = vec![TournamentEvent::VoteCast {
                     match_id: vote_dto.match_id.clone(),
-                    winner_id,
-                    final_votes,
-                },
-            );
-
-            let current_round = &tournament.rounds[current_round_index];
-
-            if self.is_round_complete(current_round) {
-                let winners = self.get_round_winners(current_round);
-                let round_number = current_round.round_number;
-
-                if winners.len() == 1 {
-                    tournament.status = TournamentStatus::Completed;
-                    tournament.winner = Some(winners[0]);
-
-                    self.broadcaster.broadcast(
-                        &vote_dto.tournament_id,
-                        TournamentEvent::RoundCompleted {
-                            round_number,
-                            next_round_matches: 0,
-                        },
-                    );
-                    self.broadcaster.broadcast(
-                        &vote_dto.tournament_id,
-                        TournamentEvent::TournamentCompleted {
-                            winner_id: winners[0],
-                        },
-                    );
-                } else {
-                    let next_round =
-                        self.create_next_round(winners, round_number + 1);
-                    let next_matches = next_round.matches.len();
-                    tournament.rounds.push(next_round);
-
-                    self.broadcaster.broadcast(
-                        &vote_dto.tournament_id,
-                        TournamentEvent::RoundCompleted {
-                            round_number,
-                            next_round_matches: next_matches,
-                        },
+                    vote_counts,
+                    total_needed: tournament.voter_count(),
+                }];
+
+                if let Some(winner_id) = match_winner {
+                    self.complete_match(
+                        tournament,
+                        current_round_index,
+                        &vote_dto.match_id,
+                        winner_id,
+                        &mut events,
                     );
                 }
-
-                tournament.updated_at = DateTime::now();
-            }
-        }
-
-        self.tournament_repository
-            .update(&tournament)
-            .await
-            .map_err(|e| format!("Error updating tournament: {}", e))?;
+                Ok(Some(events))
+            })
+            .await?;
+        self.broadcast_all(&vote_dto.tournament_id, events.unwrap_or_default());
 
         Ok(tournament)
     }
@@ -483,6 +764,8 @@ impl TournamentService for TournamentServiceImpl {
             .map_err(|e| format!("Error finding tournament: {}", e))?
             .ok_or("Tournament not found")?;
 
+        tournament.authorize(&created_by, TournamentRole::CoOrganizer, "invite to")?;
+
         if !matches!(tournament.status, TournamentStatus::Active) {
             return Err("Tournament is not active".to_string());
         }
@@ -494,23 +777,37 @@ impl TournamentService for TournamentServiceImpl {
         let expires_at =
             DateTime::from_millis(expires_at_ts * 1000);
 
-        let code = Uuid::new_v4().to_string()[..8].to_string();
-
-        let invite = TournamentInvite {
+        let mut invite = TournamentInvite {
             id: None,
-            code: code.clone(),
+            code: String::new(),
             tournament_id: *tournament_id,
             max_uses,
             current_uses: 0,
             expires_at,
             created_by,
             created_at: now,
+            revoked_at: None,
+            redemptions: Vec::new(),
         };
 
-        self.invite_repository
-            .create(invite)
-            .await
-            .map_err(|e| format!("Error creating invite: {}", e))?;
+        // The unique index on code rejects collisions; retry with a new code
+        let mut stored = false;
+        for _ in 0..MAX_CODE_ATTEMPTS {
+            invite.code = self.code_generator.generate();
+            if self
+                .invite_repository
+                .create(invite.clone())
+                .await
+                .map_err(|e| format!("Error creating invite: {}", e))?
+            {
+                stored = true;
+                break;
+            }
+        }
+        if !stored {
+            return Err("Error creating invite: no unused code found".to_string());
+        }
+        let code = invite.code;
 
         Ok(InviteResponse {
             code,
@@ -520,35 +817,96 @@ impl TournamentService for TournamentServiceImpl {
         })
     }
 
+    async fn list_invites(
+        &self,
+        tournament_id: &ObjectId,
+        user_id: &ObjectId,
+    ) -> Result<Vec<InviteSummaryResponse>, MembershipError> {
+        self.authorize_invite_management(tournament_id, user_id).await?;
+
+        let invites = self
+            .invite_repository
+            .find_by_tournament(tournament_id)
+            .await
+            .map_err(|e| MembershipError::Internal(format!("Error finding invites: {}", e)))?;
+        Ok(invites
+            .into_iter()
+            .map(InviteSummaryResponse::from)
+            .collect())
+    }
+
+    async fn revoke_invite(
+        &self,
+        tournament_id: &ObjectId,
+        code: &str,
+        user_id: &ObjectId,
+    ) -> Result<(), MembershipError> {
+        self.authorize_invite_management(tournament_id, user_id).await?;
+
+        let invite = self.find_tournament_invite(tournament_id, code).await?;
+        let already_revoked =
+            || MembershipError::Invalid("Invite code has already been revoked".to_string());
+        if invite.revoked_at.is_some() {
+            return Err(already_revoked());
+        }
+        if !self
+            .invite_repository
+            .revoke(tournament_id, &invite.code)
+            .await
+            .map_err(|e| MembershipError::Internal(format!("Error revoking invite: {}", e)))?
+        {
+            return Err(already_revoked());
+        }
+        Ok(())
+    }
+
+    async fn invite_joins(
+        &self,
+        tournament_id: &ObjectId,
+        code: &str,
+        user_id: &ObjectId,
+    ) -> Result<Vec<InviteRedemptionResponse>, MembershipError> {
+        self.authorize_invite_management(tournament_id, user_id).await?;
+
+        let invite = self.find_tournament_invite(tournament_id, code).await?;
+        Ok(invite
+            .redemptions
+            .into_iter()
+            .map(InviteRedemptionResponse::from)
+            .collect())
+    }
+
+    async fn invite_qr_code(
+        &self,
+        tournament_id: &ObjectId,
+        code: &str,
+        user_id: &ObjectId,
+    ) -> Result<Vec<u8>, MembershipError> {
+        self.authorize_invite_management(tournament_id, user_id).await?;
+
+        let invite = self.find_tournament_invite(tournament_id, code).await?;
+        render_qr_png(&format!("{}/join?code={}", self.app_url, invite.code))
+            .map_err(MembershipError::Internal)
+    }
+
     async fn join_tournament(
         &self,
         tournament_id: &ObjectId,
         dto: JoinTournamentDto,
     ) -> Result<JoinTournamentResponse, String> {
-        if dto.display_name.trim().is_empty() {
-            return Err("Display name cannot be empty".to_string());
-        }
+        let display_name = self.display_names.check(&dto.display_name)?;
 
         let invite = self
-            .invite_repository
-            .find_by_code(&dto.invite_code)
-            .await
-            .map_err(|e| format!("Error finding invite: {}", e))?
+            .find_invite(&dto.invite_code)
+            .await?
             .ok_or("Invalid invite code")?;
 
         if invite.tournament_id != *tournament_id {
             return Err("Invite code does not match tournament".to_string());
         }
 
-        let now_ms = Utc::now().timestamp_millis();
-        let expires_ms = invite.expires_at.timestamp_millis();
-        if now_ms > expires_ms {
-            return Err("Invite code has expired".to_string());
-        }
-
-        if invite.current_uses >= invite.max_uses {
-            return Err("Invite code has reached maximum uses".to_string());
-        }
+        invite.check_redeemable()?;
+        let invite_id = invite.id.ok_or("Invite must have an id")?;
 
         let mut tournament = self
             .tournament_repository
@@ -557,41 +915,90 @@ impl TournamentService for TournamentServiceImpl {
             .map_err(|e| format!("Error finding tournament: {}", e))?
             .ok_or("Tournament not found")?;
 
-        let token_response = self
-            .auth_service
-            .generate_anonymous_token(tournament_id, &dto.display_name)
-            .map_err(|e| format!("Error generating token: {}", e))?;
+        for _ in 0..MAX_JOIN_ATTEMPTS {
+            // Suffix names already in use so participants can tell each other
+            // apart and nobody can pass as someone else
+            let name = tournament.unique_display_name(&display_name, None);
+
+            let token_response = self
+                .auth_service
+                .generate_anonymous_token(tournament_id, &name)
+                .map_err(|e| format!("Error generating token: {}", e))?;
+            let session_id = token_response.session_id.clone();
+
+            // Claim a use first: the conditional update is what enforces
+            // max_uses, the check above only gives early, precise errors
+            let redemption = InviteRedemption {
+                session_id: session_id.clone(),
+                display_name: name.clone(),
+                joined_at: DateTime::now(),
+            };
+            if !self
+                .invite_repository
+                .redeem(&invite_id, &redemption)
+                .await
+                .map_err(|e| format!("Error redeeming invite: {}", e))?
+            {
+                // Report why the invite stopped being usable meanwhile
+                self.invite_repository
+                    .find_by_code(&invite.code)
+                    .await
+                    .map_err(|e| format!("Error finding invite: {}", e))?
+                    .ok_or("Invalid invite code")?
+                    .check_redeemable()?;
+                return Err("Invite code has reached maximum uses".to_string());
+            }
 
-        tournament.users.push(TournamentUser {
-            voter_id: VoterId::Anonymous(token_response.session_id.clone()),
-            name: dto.display_name.clone(),
-        });
+            let participant = TournamentUser {
+                voter_id: VoterId::Anonymous(session_id.clone()),
+                name: name.clone(),
+                muted: false,
+            };
+            let added = self
+                .tournament_repository
+                .add_participant(tournament_id, &participant)
+                .await
+                .map_err(|e| format!("Error updating tournament: {}", e));
+
+            // Give the use back whenever the join did not go through
+            let joined = match added {
+                Ok(Some(joined)) => joined,
+                Ok(None) => {
+                    self.release_invite(&invite_id, &session_id).await;
+                    // Someone took the name meanwhile; retry with the next
+                    // free one unless the tournament is gone
+                    tournament = self
+                        .tournament_repository
+                        .find_by_id(tournament_id)
+                        .await
+                        .map_err(|e| format!("Error finding tournament: {}", e))?
+                        .ok_or("Tournament not found")?;
+                    continue;
+                }
+                Err(e) => {
+                    self.release_invite(&invite_id, &session_id).await;
+                    return Err(e);
+                }
+            };
 
-        self.tournament_repository
-            .update(&tournament)
-            .await
-            .map_err(|e| format!("Error updating tournament: {}", e))?;
+            self.broadcaster.broadcast(
+                tournament_id,
+                TournamentEvent::ParticipantJoined {
+                    display_name: name.clone(),
+                    participant_count: joined.users.len(),
+                },
+            );
 
-        self.invite_repository
-            .increment_uses(&invite.id.ok_or("Invite must have an id")?)
-            .await
-            .map_err(|e| format!("Error incrementing invite uses: {}", e))?;
-
-        self.broadcaster.broadcast(
-            tournament_id,
-            TournamentEvent::ParticipantJoined {
-                display_name: dto.display_name.clone(),
-                participant_count: tournament.users.len(),
-            },
-        );
+            return Ok(JoinTournamentResponse {
+                access_token: token_response.access_token,
+                token_type: token_response.token_type,
+                session_id: token_response.session_id,
+                display_name: name,
+                tournament_id: *tournament_id,
+            });
+        }
 
-        Ok(JoinTournamentResponse {
-            access_token: token_response.access_token,
-            token_type: token_response.token_type,
-            session_id: token_response.session_id,
-            display_name: dto.display_name,
-            tournament_id: *tournament_id,
-        })
+        Err("Display name is already taken, please try again".to_string())
     }
 
     async fn join_by_code(
@@ -599,12 +1006,312 @@ impl TournamentService for TournamentServiceImpl {
         dto: JoinTournamentDto,
     ) -> Result<JoinTournamentResponse, String> {
         let invite = self
-            .invite_repository
-            .find_by_code(&dto.invite_code)
-            .await
-            .map_err(|e| format!("Error finding invite: {}", e))?
+            .find_invite(&dto.invite_code)
+            .await?
             .ok_or("Invalid invite code")?;
 
         self.join_tournament(&invite.tournament_id, dto).await
     }
+
+    async fn claim_anonymous_session(
+        &self,
+        tournament_id: &ObjectId,
+        session_id: &str,
+        user_id: ObjectId,
+        name: String,
+    ) -> Result<(), String> {
+        let anonymous = VoterId::Anonymous(session_id.to_string());
+        let (_, events) = self
+            .modify::<_, String, _>(tournament_id, |tournament| {
+                let Some(guest) = tournament.users.iter().find(|u| u.voter_id == anonymous) else {
+                    // Never joined, or already claimed by an earlier login
+                    return Ok(None);
+                };
+                // Account names follow looser rules, so one that fails the display
+                // name check keeps the name the guest picked
+                let name = self
+                    .display_names
+                    .check(&name)
+                    .unwrap_or_else(|_| guest.name.clone());
+                let name = tournament.unique_display_name(&name, Some(&anonymous));
+
+                tournament.reassign_voter(&anonymous, VoterId::Registered(user_id), name);
+
+                // Dropping a duplicate entry lowers the number of votes a match needs
+                Ok(Some(self.resolve_open_matches(tournament)))
+            })
+            .await?;
+        self.broadcast_all(tournament_id, events.unwrap_or_default());
+        Ok(())
+    }
+
+    async fn update_participant(
+        &self,
+        tournament_id: &ObjectId,
+        voter_id: &str,
+        dto: UpdateParticipantDto,
+        user_id: &ObjectId,
+    ) -> Result<TournamentUserResponse, MembershipError> {
+        let (tournament, changed) = self
+            .modify(tournament_id, |tournament| {
+                tournament
+                    .authorize(user_id, TournamentRole::Moderator, "moderate participants of")
+                    .map_err(MembershipError::Forbidden)?;
+
+                let index = tournament
+                    .users
+                    .iter()
+                    .position(|u| u.voter_id.as_string() == voter_id)
+                    .ok_or_else(|| MembershipError::NotFound("Participant not found".to_string()))?;
+                let participant_id = tournament.users[index].voter_id.clone();
+
+                if let Some(name) = &dto.name {
+                    let name = self.display_names.check(name).map_err(MembershipError::Invalid)?;
+                    if tournament.is_name_taken(&name, Some(&participant_id)) {
+                        return Err(MembershipError::Invalid(
+                            "Display name is already taken".to_string(),
+                        ));
+                    }
+                    tournament.users[index].name = name;
+                }
+
+                let mut events = Vec::new();
+                if let Some(muted) = dto.muted {
+                    let newly_muted = muted && !tournament.users[index].muted;
+                    tournament.users[index].muted = muted;
+                    if newly_muted {
+                        // Open matches no longer wait for the muted participant
+                        tournament.withdraw_votes(&participant_id);
+                        events = self.resolve_open_matches(tournament);
+                    }
+                }
+                Ok(Some((index, events)))
+            })
+            .await?;
+        let (index, events) = changed
+            .ok_or_else(|| MembershipError::NotFound("Participant not found".to_string()))?;
+        self.broadcast_all(tournament_id, events);
+
+        Ok(tournament.users[index].clone().into())
+    }
+
+    async fn kick_participant(
+        &self,
+        tournament_id: &ObjectId,
+        voter_id: &str,
+        user_id: &ObjectId,
+    ) -> Result<(), MembershipError> {
+        let (_, events) = self
+            .modify(tournament_id, |tournament| {
+                tournament
+                    .authorize(user_id, TournamentRole::Moderator, "moderate participants of")
+                    .map_err(MembershipError::Forbidden)?;
+
+                let index = tournament
+                    .users
+                    .iter()
+                    .position(|u| u.voter_id.as_string() == voter_id)
+                    .ok_or_else(|| MembershipError::NotFound("Participant not found".to_string()))?;
+                let participant = tournament.users.remove(index);
+                tournament.withdraw_votes(&participant.voter_id);
+
+                let mut events = vec![TournamentEvent::ParticipantLeft {
+                    voter_id: participant.voter_id.as_string(),
+                    display_name: participant.name,
+                    participant_count: tournament.users.len(),
+                }];
+                events.extend(self.resolve_open_matches(tournament));
+                Ok(Some(events))
+            })
+            .await?;
+        self.broadcast_all(tournament_id, events.unwrap_or_default());
+        Ok(())
+    }
+
+    async fn grant_member(
+        &self,
+        tournament_id: &ObjectId,
+        dto: GrantMemberDto,
+        granted_by: &ObjectId,
+    ) -> Result<Vec<TournamentMemberResponse>, MembershipError> {
+        if dto.role == TournamentRole::Owner {
+            return Err(MembershipError::Invalid(
+                "Use an ownership transfer to change the owner".to_string(),
+            ));
+        }
+
+        let (tournament, _) = self
+            .modify(tournament_id, |tournament| {
+                let actor_role = tournament
+                    .authorize(granted_by, TournamentRole::CoOrganizer, "manage members of")
+                    .map_err(MembershipError::Forbidden)?;
+                if dto.role >= actor_role {
+                    return Err(MembershipError::Forbidden(
+                        "You can only grant roles below your own".to_string(),
+                    ));
+                }
+
+                match tournament.role_of(&dto.user_id) {
+                    Some(current) if current >= actor_role => {
+                        return Err(MembershipError::Forbidden(
+                            "You cannot change the role of this member".to_string(),
+                        ));
+                    }
+                    Some(current) if current == dto.role => return Ok(None),
+                    Some(_) => {
+                        let member = tournament
+                            .members
+                            .iter_mut()
+                            .find(|m| m.user_id == dto.user_id)
+                            .ok_or_else(|| {
+                                MembershipError::NotFound("Member not found".to_string())
+                            })?;
+                        member.role = dto.role;
+                        member.granted_by = *granted_by;
+                        member.granted_at = DateTime::now();
+                    }
+                    None => tournament.members.push(TournamentMember {
+                        user_id: dto.user_id,
+                        role: dto.role,
+                        granted_by: *granted_by,
+                        granted_at: DateTime::now(),
+                    }),
+                }
+                Ok(Some(()))
+            })
+            .await?;
+
+        Ok(tournament.member_responses())
+    }
+
+    async fn revoke_member(
+        &self,
+        tournament_id: &ObjectId,
+        member_id: &ObjectId,
+        revoked_by: &ObjectId,
+    ) -> Result<Vec<TournamentMemberResponse>, MembershipError> {
+        let (tournament, _) = self
+            .modify(tournament_id, |tournament| {
+                let target_role = tournament.role_of(member_id).ok_or_else(|| {
+                    MembershipError::NotFound("User is not a member of this tournament".to_string())
+                })?;
+                if target_role == TournamentRole::Owner {
+                    return Err(MembershipError::Invalid(
+                        "The owner cannot be removed; transfer ownership first".to_string(),
+                    ));
+                }
+
+                if member_id != revoked_by {
+                    let actor_role = tournament
+                        .authorize(revoked_by, TournamentRole::CoOrganizer, "manage members of")
+                        .map_err(MembershipError::Forbidden)?;
+                    if target_role >= actor_role {
+                        return Err(MembershipError::Forbidden(
+                            "You cannot remove this member".to_string(),
+                        ));
+                    }
+                }
+
+                tournament.members.retain(|m| m.user_id != *member_id);
+                Ok(Some(()))
+            })
+            .await?;
+
+        Ok(tournament.member_responses())
+    }
+
+    async fn transfer_ownership(
+        &self,
+        tournament_id: &ObjectId,
+        new_owner: &ObjectId,
+        user_id: &ObjectId,
+    ) -> Result<Tournament, MembershipError> {
+        let (tournament, _) = self
+            .modify(tournament_id, |tournament| {
+                tournament
+                    .authorize(user_id, TournamentRole::Owner, "transfer")
+                    .map_err(MembershipError::Forbidden)?;
+                if new_owner == user_id {
+                    return Err(MembershipError::Invalid(
+                        "You already own this tournament".to_string(),
+                    ));
+                }
+
+                tournament.members.retain(|m| m.user_id != *new_owner);
+                tournament.members.push(TournamentMember {
+                    user_id: *user_id,
+                    role: TournamentRole::CoOrganizer,
+                    granted_by: *user_id,
+                    granted_at: DateTime::now(),
+                });
+                tournament.created_by = *new_owner;
+                Ok(Some(()))
+            })
+            .await?;
+
+        Ok(tournament)
+    }
+
+    async fn release_tournaments(
+        &self,
+        user_id: &ObjectId,
+        policy: OwnedTournamentPolicy,
+    ) -> Result<(), String> {
+        for tournament in self.tournament_repository.find_by_organizer(user_id).await? {
+            let id = tournament.id.ok_or("Tournament has no id")?;
+
+            let (_, kept) = self
+                .save_with::<_, String, _>(&id, tournament, |tournament| {
+                    if tournament.created_by != *user_id {
+                        tournament.members.retain(|m| m.user_id != *user_id);
+                        return Ok(Some(()));
+                    }
+
+                    match (policy, tournament.successor()) {
+                        (OwnedTournamentPolicy::Transfer, Some(successor)) => {
+                            tournament.members.retain(|m| m.user_id != successor);
+                            tournament.created_by = successor;
+                            Ok(Some(()))
+                        }
+                        _ => Ok(None),
+                    }
+                })
+                .await?;
+            if kept.is_none() {
+                self.tournament_repository.delete(&id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn anonymize_participant(&self, user_id: &ObjectId) -> Result<(), String> {
+        let voter_id = VoterId::Registered(*user_id);
+        for tournament in self
+            .tournament_repository
+            .find_by_participant(&voter_id)
+            .await?
+        {
+            let id = tournament.id.ok_or("Tournament has no id")?;
+            // A fresh id per tournament so the entries cannot be linked
+            let anonymous = VoterId::Anonymous(format!("deleted-{}", Uuid::new_v4()));
+            self.save_with::<_, String, _>(&id, tournament, |tournament| {
+                let name = tournament.unique_display_name("Deleted user", Some(&voter_id));
+                let reassigned = tournament.reassign_voter(&voter_id, anonymous.clone(), name);
+                Ok(reassigned.then_some(()))
+            })
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn opponents_in_use(&self, opponent_ids: &[ObjectId]) -> Result<Vec<ObjectId>, String> {
+        if opponent_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.tournament_repository
+            .find_used_opponents(opponent_ids)
+            .await
+    }
 }
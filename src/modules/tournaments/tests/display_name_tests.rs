@@ -0,0 +1,117 @@
+use crate::config::display_name::DisplayNameConfig;
+use crate::modules::tournaments::display_name::DisplayNameFilter;
+
+#[test]
+fn test_check_trims_and_collapses_whitespace() {
+    // Arrange
+    let filter = DisplayNameFilter::default();
+
+    // Act
+    let result = filter.check("  Jane   Doe ");
+
+    // Assert
+    assert_eq!(result.unwrap(), "Jane Doe");
+}
+
+#[test]
+fn test_check_rejects_empty_and_long_names() {
+    // Arrange
+    let filter = DisplayNameFilter::default();
+
+    // Act & Assert
+    assert_eq!(
+        filter.check("   ").unwrap_err(),
+        "Display name cannot be empty"
+    );
+    assert_eq!(
+        filter.check(&"a".repeat(33)).unwrap_err(),
+        "Display name must be at most 32 characters"
+    );
+}
+
+#[test]
+fn test_check_rejects_disguised_blocked_terms() {
+    // Arrange
+    let filter = DisplayNameFilter::default();
+
+    // Act & Assert
+    for name in [
+        "Admin",
+        "The Organizer",
+        "4dm1n",
+        "a d m i n",
+        "ad-min",
+        "Sh!t",
+    ] {
+        assert_eq!(
+            filter.check(name).unwrap_err(),
+            "Display name is not allowed",
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_check_allows_words_containing_blocked_terms() {
+    // Arrange
+    let filter = DisplayNameFilter::default();
+
+    // Act & Assert
+    for name in ["Badminton Fan", "Scunthorpe", "Cassandra", "Player 1"] {
+        assert!(filter.check(name).is_ok(), "{}", name);
+    }
+}
+
+#[test]
+fn test_new_adds_configured_terms() {
+    // Arrange
+    let config = DisplayNameConfig {
+        blocked_terms: vec!["Quiz Master".to_string()],
+        ..DisplayNameConfig::default()
+    };
+
+    // Act
+    let filter = DisplayNameFilter::new(&config).unwrap();
+
+    // Assert
+    assert!(filter.check("quiz-master").is_err());
+    assert!(filter.check("admin").is_err());
+}
+
+#[test]
+fn test_new_reads_blocklist_file() {
+    // Arrange
+    let path = std::env::temp_dir().join(format!("blocklist-{}.txt", uuid::Uuid::new_v4()));
+    std::fs::write(&path, "# custom\nreferee\n").unwrap();
+    let config = DisplayNameConfig {
+        blocklist_file: Some(path.to_string_lossy().into_owned()),
+        ..DisplayNameConfig::default()
+    };
+
+    // Act
+    let filter = DisplayNameFilter::new(&config).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // Assert
+    assert!(filter.check("Referee").is_err());
+    assert!(filter.check("admin").is_ok());
+}
+
+#[test]
+fn test_new_fails_for_missing_blocklist_file() {
+    // Arrange
+    let config = DisplayNameConfig {
+        blocklist_file: Some("/nonexistent/blocklist.txt".to_string()),
+        ..DisplayNameConfig::default()
+    };
+
+    // Act
+    let result = DisplayNameFilter::new(&config);
+
+    // Assert
+    assert!(result
+        .err()
+        .unwrap()
+        .starts_with("Error reading display name blocklist"));
+}
@@ -0,0 +1,81 @@
+use crate::config::invite_code::{InviteCodeConfig, InviteCodeFormat, DEFAULT_ALPHABET};
+use crate::modules::tournaments::invite_code::{render_qr_png, InviteCodeGenerator};
+
+fn words_generator(word_count: usize) -> InviteCodeGenerator {
+    InviteCodeGenerator::new(&InviteCodeConfig {
+        format: InviteCodeFormat::Words,
+        word_count,
+        ..InviteCodeConfig::default()
+    })
+}
+
+#[test]
+fn test_default_alphabet_has_no_ambiguous_characters() {
+    for ambiguous in ['0', 'O', '1', 'I', 'L'] {
+        assert!(!DEFAULT_ALPHABET.contains(ambiguous));
+    }
+}
+
+#[test]
+fn test_random_code_uses_alphabet_and_length() {
+    // Arrange
+    let generator = InviteCodeGenerator::new(&InviteCodeConfig {
+        alphabet: "ABCDEFGHJK".to_string(),
+        length: 12,
+        ..InviteCodeConfig::default()
+    });
+
+    // Act
+    let code = generator.generate();
+
+    // Assert
+    assert_eq!(code.len(), 12);
+    assert!(code.chars().all(|c| "ABCDEFGHJK".contains(c)));
+}
+
+#[test]
+fn test_word_code_joins_words_with_hyphens() {
+    // Act
+    let code = words_generator(4).generate();
+
+    // Assert
+    let words: Vec<&str> = code.split('-').collect();
+    assert_eq!(words.len(), 4);
+    assert!(words
+        .iter()
+        .all(|w| !w.is_empty() && w.chars().all(|c| c.is_ascii_lowercase())));
+}
+
+#[test]
+fn test_normalize_matches_alphabet_case() {
+    // Arrange
+    let upper = InviteCodeGenerator::default();
+    let lower = InviteCodeGenerator::new(&InviteCodeConfig {
+        alphabet: "abcdefghjk".to_string(),
+        ..InviteCodeConfig::default()
+    });
+    let mixed = InviteCodeGenerator::new(&InviteCodeConfig {
+        alphabet: "abcdeFGHJK".to_string(),
+        ..InviteCodeConfig::default()
+    });
+
+    // Act & Assert
+    assert_eq!(upper.normalize(" k7qm2xpd "), "K7QM2XPD");
+    assert_eq!(lower.normalize("ABCD"), "abcd");
+    assert_eq!(mixed.normalize("abCD"), "abCD");
+    assert_eq!(
+        words_generator(3).normalize("Maple-River-Comet"),
+        "maple-river-comet"
+    );
+}
+
+#[test]
+fn test_render_qr_png() {
+    // Act
+    let png = render_qr_png("http://localhost:3000/join/K7QM2XPD").unwrap();
+
+    // Assert
+    let image = image::load_from_memory_with_format(&png, image::ImageFormat::Png).unwrap();
+    assert!(image.width() >= 256);
+    assert_eq!(image.width(), image.height());
+}
@@ -1,3 +1,6 @@
 mod controller_tests;
+mod display_name_tests;
+mod invite_code_tests;
 mod model_tests;
+mod repository_tests;
 mod service_tests;
@@ -2,10 +2,10 @@ use mongodb::bson::{oid::ObjectId, DateTime};
 use std::collections::HashMap;
 
 use crate::modules::tournaments::model::{
-    CreateInviteDto, CreateTournamentDto, InviteResponse, JoinTournamentDto,
-    JoinTournamentResponse, Match, OpponentDto, Round, Tournament, TournamentInvite,
-    TournamentOpponent, TournamentResponse, TournamentStatus, TournamentUser, UserDto,
-    VoteMatchDto, VoterId,
+    CreateInviteDto, CreateTournamentDto, InviteResponse, InviteSummaryResponse,
+    JoinTournamentDto, JoinTournamentResponse, Match, OpponentDto, Round, Tournament,
+    TournamentInvite, TournamentMember, TournamentOpponent, TournamentResponse, TournamentRole,
+    TournamentStatus, TournamentUser, UserDto, VoteMatchDto, VoterId,
 };
 
 fn create_test_opponents() -> Vec<OpponentDto> {
@@ -173,6 +173,7 @@ fn test_match_process_vote_success() {
     let users = vec![TournamentUser {
         voter_id: voter_id.clone(),
         name: "Test User".to_string(),
+        muted: false,
     }];
 
     // Act
@@ -200,6 +201,7 @@ fn test_match_process_vote_duplicate_vote() {
     let users = vec![TournamentUser {
         voter_id: voter_id.clone(),
         name: "Test User".to_string(),
+        muted: false,
     }];
 
     match_instance
@@ -222,6 +224,7 @@ fn test_match_process_vote_invalid_opponent() {
     let users = vec![TournamentUser {
         voter_id: voter_id.clone(),
         name: "Test User".to_string(),
+        muted: false,
     }];
 
     // Act
@@ -244,6 +247,7 @@ fn test_match_determine_winner() {
         .map(|vid| TournamentUser {
             voter_id: vid.clone(),
             name: "Test User".to_string(),
+            muted: false,
         })
         .collect();
 
@@ -273,6 +277,7 @@ fn test_process_vote_anonymous() {
     let users = vec![TournamentUser {
         voter_id: anonymous_voter.clone(),
         name: "Anonymous User".to_string(),
+        muted: false,
     }];
 
     // Act
@@ -294,10 +299,12 @@ fn test_process_vote_prevents_duplicate_anonymous() {
         TournamentUser {
             voter_id: anonymous_voter.clone(),
             name: "Anon 1".to_string(),
+            muted: false,
         },
         TournamentUser {
             voter_id: VoterId::Anonymous("session-uuid-456".to_string()),
             name: "Anon 2".to_string(),
+            muted: false,
         },
     ];
 
@@ -363,6 +370,8 @@ fn test_invite_serialization() {
         expires_at: DateTime::now(),
         created_by,
         created_at: DateTime::now(),
+        revoked_at: None,
+        redemptions: vec![],
     };
 
     let json = serde_json::to_string(&invite).unwrap();
@@ -374,6 +383,87 @@ fn test_invite_serialization() {
     assert_eq!(deserialized.created_by, created_by);
 }
 
+#[test]
+fn test_invite_without_revocation_or_log_deserializes() {
+    let invite = TournamentInvite {
+        id: Some(ObjectId::new()),
+        code: "ABC12345".to_string(),
+        tournament_id: ObjectId::new(),
+        max_uses: 10,
+        current_uses: 0,
+        expires_at: DateTime::now(),
+        created_by: ObjectId::new(),
+        created_at: DateTime::now(),
+        revoked_at: None,
+        redemptions: vec![],
+    };
+    let mut document = mongodb::bson::to_document(&invite).unwrap();
+    document.remove("revoked_at");
+    document.remove("redemptions");
+
+    let restored: TournamentInvite = mongodb::bson::from_document(document).unwrap();
+
+    assert!(restored.revoked_at.is_none());
+    assert!(restored.redemptions.is_empty());
+}
+
+#[test]
+fn test_invite_check_redeemable() {
+    let mut invite = TournamentInvite {
+        id: Some(ObjectId::new()),
+        code: "ABC12345".to_string(),
+        tournament_id: ObjectId::new(),
+        max_uses: 2,
+        current_uses: 1,
+        expires_at: DateTime::from_millis(DateTime::now().timestamp_millis() + 60_000),
+        created_by: ObjectId::new(),
+        created_at: DateTime::now(),
+        revoked_at: None,
+        redemptions: vec![],
+    };
+    assert!(invite.check_redeemable().is_ok());
+
+    invite.current_uses = 2;
+    assert_eq!(
+        invite.check_redeemable().unwrap_err(),
+        "Invite code has reached maximum uses"
+    );
+
+    invite.expires_at = DateTime::from_millis(1000);
+    assert_eq!(invite.check_redeemable().unwrap_err(), "Invite code has expired");
+
+    invite.revoked_at = Some(DateTime::now());
+    assert_eq!(
+        invite.check_redeemable().unwrap_err(),
+        "Invite code has been revoked"
+    );
+}
+
+#[test]
+fn test_invite_summary_response_serialization() {
+    let created_by = ObjectId::new();
+    let invite = TournamentInvite {
+        id: Some(ObjectId::new()),
+        code: "ABC12345".to_string(),
+        tournament_id: ObjectId::new(),
+        max_uses: 10,
+        current_uses: 3,
+        expires_at: DateTime::now(),
+        created_by,
+        created_at: DateTime::now(),
+        revoked_at: Some(DateTime::now()),
+        redemptions: vec![],
+    };
+
+    let json = serde_json::to_value(InviteSummaryResponse::from(invite)).unwrap();
+
+    assert_eq!(json["code"], "ABC12345");
+    assert_eq!(json["current_uses"], 3);
+    assert_eq!(json["max_uses"], 10);
+    assert_eq!(json["created_by"], created_by.to_hex());
+    assert!(json["revoked_at"].is_string());
+}
+
 #[test]
 fn test_create_invite_dto_deserialization() {
     let json = r#"{"max_uses": 5, "expires_in_hours": 48}"#;
@@ -424,3 +514,320 @@ fn test_join_response_serialization() {
     assert!(json.contains("session-uuid"));
     assert!(json.contains("Player 1"));
 }
+
+#[test]
+fn test_match_resolve_waits_for_all_votes() {
+    let mut match_instance = create_test_match();
+    match_instance.votes.insert(
+        match_instance.opponent1.to_string(),
+        vec![VoterId::Registered(ObjectId::new())],
+    );
+
+    assert_eq!(match_instance.resolve(2), None);
+    assert_eq!(match_instance.resolve(1), Some(match_instance.opponent1));
+    // Already decided
+    assert_eq!(match_instance.resolve(1), None);
+}
+
+#[test]
+fn test_match_reassign_voter() {
+    let mut match_instance = create_test_match();
+    let guest = VoterId::Anonymous("session-1".to_string());
+    let member = VoterId::Registered(ObjectId::new());
+    match_instance
+        .votes
+        .insert(match_instance.opponent1.to_string(), vec![guest.clone()]);
+
+    match_instance.reassign_voter(&guest, &member);
+
+    assert_eq!(
+        match_instance.votes[&match_instance.opponent1.to_string()],
+        vec![member]
+    );
+}
+
+#[test]
+fn test_match_reassign_voter_drops_duplicate_vote() {
+    let mut match_instance = create_test_match();
+    let guest = VoterId::Anonymous("session-1".to_string());
+    let member = VoterId::Registered(ObjectId::new());
+    match_instance
+        .votes
+        .insert(match_instance.opponent1.to_string(), vec![member.clone()]);
+    match_instance
+        .votes
+        .insert(match_instance.opponent2.to_string(), vec![guest.clone()]);
+
+    match_instance.reassign_voter(&guest, &member);
+
+    assert_eq!(
+        match_instance.votes[&match_instance.opponent1.to_string()],
+        vec![member]
+    );
+    assert!(match_instance.votes[&match_instance.opponent2.to_string()].is_empty());
+}
+
+#[test]
+fn test_tournament_reassign_voter_unknown_voter() {
+    let opponents = create_test_opponents();
+    let mut tournament = Tournament::new(
+        "Test".to_string(),
+        ObjectId::new(),
+        opponents,
+        create_test_users(),
+        Round {
+            round_number: 1,
+            matches: vec![create_test_match()],
+            automatic_winners: vec![],
+        },
+    );
+
+    let changed = tournament.reassign_voter(
+        &VoterId::Anonymous("missing".to_string()),
+        VoterId::Registered(ObjectId::new()),
+        "Jane".to_string(),
+    );
+
+    assert!(!changed);
+    assert_eq!(tournament.users.len(), 2);
+}
+
+fn tournament_with_member(user_id: ObjectId, role: TournamentRole) -> Tournament {
+    let mut tournament = Tournament::new(
+        "Test Tournament".to_string(),
+        ObjectId::new(),
+        create_test_opponents(),
+        create_test_users(),
+        Round {
+            round_number: 1,
+            matches: vec![],
+            automatic_winners: vec![],
+        },
+    );
+    tournament.members.push(TournamentMember {
+        user_id,
+        role,
+        granted_by: tournament.created_by,
+        granted_at: DateTime::now(),
+    });
+    tournament
+}
+
+#[test]
+fn test_tournament_role_of() {
+    let moderator = ObjectId::new();
+    let tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+
+    assert_eq!(
+        tournament.role_of(&tournament.created_by),
+        Some(TournamentRole::Owner)
+    );
+    assert_eq!(tournament.role_of(&moderator), Some(TournamentRole::Moderator));
+    assert_eq!(tournament.role_of(&ObjectId::new()), None);
+}
+
+#[test]
+fn test_tournament_role_ordering() {
+    assert!(TournamentRole::Owner > TournamentRole::CoOrganizer);
+    assert!(TournamentRole::CoOrganizer > TournamentRole::Moderator);
+}
+
+#[test]
+fn test_tournament_authorize() {
+    let co_organizer = ObjectId::new();
+    let tournament = tournament_with_member(co_organizer, TournamentRole::CoOrganizer);
+
+    assert_eq!(
+        tournament.authorize(&co_organizer, TournamentRole::Moderator, "pause"),
+        Ok(TournamentRole::CoOrganizer)
+    );
+    assert_eq!(
+        tournament.authorize(&co_organizer, TournamentRole::Owner, "delete"),
+        Err("You do not have permission to delete this tournament".to_string())
+    );
+    assert!(tournament
+        .authorize(&ObjectId::new(), TournamentRole::Moderator, "pause")
+        .is_err());
+}
+
+#[test]
+fn test_tournament_member_responses_lists_owner_first() {
+    let moderator = ObjectId::new();
+    let tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+
+    let members = tournament.member_responses();
+
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0].user_id, tournament.created_by);
+    assert_eq!(members[0].role, TournamentRole::Owner);
+    assert_eq!(members[1].user_id, moderator);
+    assert_eq!(members[1].role, TournamentRole::Moderator);
+}
+
+#[test]
+fn test_tournament_without_members_deserializes() {
+    let tournament = tournament_with_member(ObjectId::new(), TournamentRole::Moderator);
+    let mut document = mongodb::bson::to_document(&tournament).unwrap();
+    document.remove("members");
+
+    let restored: Tournament = mongodb::bson::from_document(document).unwrap();
+
+    assert!(restored.members.is_empty());
+    assert_eq!(
+        restored.role_of(&tournament.created_by),
+        Some(TournamentRole::Owner)
+    );
+}
+
+#[test]
+fn test_tournament_role_serialization() {
+    assert_eq!(
+        serde_json::to_string(&TournamentRole::CoOrganizer).unwrap(),
+        "\"co_organizer\""
+    );
+}
+
+#[test]
+fn test_tournament_successor_prefers_role_then_seniority() {
+    let moderator = ObjectId::new();
+    let mut tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+    assert_eq!(tournament.successor(), Some(moderator));
+
+    let senior = ObjectId::new();
+    let junior = ObjectId::new();
+    for (user_id, granted_at) in [(junior, 2_000), (senior, 1_000)] {
+        tournament.members.push(TournamentMember {
+            user_id,
+            role: TournamentRole::CoOrganizer,
+            granted_by: tournament.created_by,
+            granted_at: DateTime::from_millis(granted_at),
+        });
+    }
+
+    assert_eq!(tournament.successor(), Some(senior));
+}
+
+#[test]
+fn test_tournament_successor_without_members() {
+    let mut tournament = tournament_with_member(ObjectId::new(), TournamentRole::Moderator);
+    tournament.members.clear();
+
+    assert_eq!(tournament.successor(), None);
+}
+
+fn tournament_with_match() -> Tournament {
+    let opponents = create_test_opponents();
+    let round = Round {
+        round_number: 1,
+        matches: vec![Match {
+            match_id: "match1".to_string(),
+            opponent1: opponents[0].id,
+            opponent2: opponents[1].id,
+            votes: HashMap::new(),
+            winner: None,
+            match_date: DateTime::now(),
+        }],
+        automatic_winners: vec![],
+    };
+    Tournament::new(
+        "Test Tournament".to_string(),
+        ObjectId::new(),
+        opponents,
+        create_test_users(),
+        round,
+    )
+}
+
+#[test]
+fn test_unique_display_name_suffixes_taken_names() {
+    // Arrange
+    let mut tournament = tournament_with_match();
+
+    // Act
+    let free = tournament.unique_display_name("Player 1", None);
+    let taken = tournament.unique_display_name("user 1", None);
+    tournament.users.push(TournamentUser {
+        voter_id: VoterId::Anonymous("session-uuid-123".to_string()),
+        name: taken.clone(),
+        muted: false,
+    });
+    let taken_twice = tournament.unique_display_name("User 1", None);
+
+    // Assert
+    assert_eq!(free, "Player 1");
+    assert_eq!(taken, "user 1 (2)");
+    assert_eq!(taken_twice, "User 1 (3)");
+}
+
+#[test]
+fn test_unique_display_name_ignores_excepted_participant() {
+    // Arrange
+    let tournament = tournament_with_match();
+    let owner = tournament.users[0].voter_id.clone();
+
+    // Act
+    let own = tournament.unique_display_name("User 1", Some(&owner));
+    let other = tournament.unique_display_name("User 2", Some(&owner));
+
+    // Assert
+    assert_eq!(own, "User 1");
+    assert_eq!(other, "User 2 (2)");
+}
+
+#[test]
+fn test_is_name_taken_ignores_excepted_participant() {
+    // Arrange
+    let tournament = tournament_with_match();
+    let owner = tournament.users[0].voter_id.clone();
+
+    // Act & Assert
+    assert!(tournament.is_name_taken("USER 1", None));
+    assert!(!tournament.is_name_taken("USER 1", Some(&owner)));
+    assert!(tournament.is_name_taken("User 2", Some(&owner)));
+}
+
+#[test]
+fn test_muted_participants_do_not_hold_up_matches() {
+    // Arrange
+    let mut tournament = tournament_with_match();
+    tournament.users[1].muted = true;
+    let voter = tournament.users[0].voter_id.clone();
+    let voted_for = tournament.rounds[0].matches[0].opponent1;
+    let users = tournament.users.clone();
+
+    // Act
+    let result = tournament.rounds[0].matches[0].process_vote(voter, voted_for, &users);
+
+    // Assert
+    assert_eq!(tournament.voter_count(), 1);
+    assert_eq!(result.unwrap(), Some(voted_for));
+}
+
+#[test]
+fn test_withdraw_votes_keeps_decided_matches() {
+    // Arrange
+    let mut tournament = tournament_with_match();
+    let voter = tournament.users[0].voter_id.clone();
+    let mut decided = tournament.rounds[0].matches[0].clone();
+    decided.match_id = "match0".to_string();
+    decided.winner = Some(decided.opponent1);
+    for m in [&mut decided, &mut tournament.rounds[0].matches[0]] {
+        m.votes.insert(m.opponent1.to_string(), vec![voter.clone()]);
+    }
+    tournament.rounds.insert(
+        0,
+        Round {
+            round_number: 0,
+            matches: vec![decided],
+            automatic_winners: vec![],
+        },
+    );
+
+    // Act
+    tournament.withdraw_votes(&voter);
+
+    // Assert
+    let votes = |m: &Match| m.votes.values().map(Vec::len).sum::<usize>();
+    assert_eq!(votes(&tournament.rounds[0].matches[0]), 1);
+    assert_eq!(votes(&tournament.rounds[1].matches[0]), 0);
+}
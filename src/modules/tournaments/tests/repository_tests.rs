@@ -0,0 +1,30 @@
+use mongodb::bson::{doc, oid::ObjectId, to_document};
+
+use crate::modules::tournaments::model::VoterId;
+use crate::modules::tournaments::repository::participant_filter;
+
+#[test]
+fn test_participant_filter_matches_stored_voter_id_fields() {
+    // Arrange
+    let voter_ids = [
+        VoterId::Registered(ObjectId::new()),
+        VoterId::Anonymous("session-uuid-123".to_string()),
+    ];
+
+    for voter_id in voter_ids {
+        // Act
+        let filter = participant_filter(&voter_id);
+
+        // Assert: the filter compares exactly what a participant stores
+        let stored = to_document(&voter_id).unwrap();
+        let expected = doc! {
+            "users": {
+                "$elemMatch": {
+                    "voter_id.type": stored.get("type").unwrap(),
+                    "voter_id.id": stored.get("id").unwrap(),
+                }
+            }
+        };
+        assert_eq!(filter, expected);
+    }
+}
@@ -1,11 +1,16 @@
+use crate::config::invite_code::{InviteCodeConfig, InviteCodeFormat};
+use crate::modules::account_deletion::model::OwnedTournamentPolicy;
 use crate::modules::auth::model::{AnonymousClaims, AnonymousTokenResponse};
 use crate::modules::auth::service::AuthService;
 use crate::modules::tournaments::{
     model::{
-        CreateInviteDto, CreateTournamentDto, JoinTournamentDto, Match, OpponentDto, Round,
-        Tournament, TournamentInvite, TournamentOpponent, TournamentStatus, TournamentUser,
-        UpdateTournamentDto, UserDto, VoterId, VoteMatchDto,
+        CreateInviteDto, CreateTournamentDto, GrantMemberDto, InviteRedemption, JoinTournamentDto,
+        Match, MembershipError, OpponentDto, Round, Tournament, TournamentInvite, TournamentMember,
+        TournamentOpponent, TournamentRole, TournamentStatus, TournamentUser,
+        UpdateParticipantDto, UpdateTournamentDto, UserDto, VoterId, VoteMatchDto,
     },
+    display_name::DisplayNameFilter,
+    invite_code::{render_qr_png, InviteCodeGenerator, MAX_CODE_ATTEMPTS},
     repository::{InviteRepository, TournamentRepository},
     service::{TournamentService, TournamentServiceImpl},
 };
@@ -23,10 +28,17 @@ mock! {
     #[async_trait]
     impl TournamentRepository for TournamentRepo {
         async fn create(&self, tournament: Tournament) -> Result<(), String>;
-        async fn update(&self, tournament: &Tournament) -> Result<(), String>;
+        async fn update(&self, tournament: &Tournament) -> Result<bool, String>;
+        async fn add_participant(&self, id: &ObjectId, user: &TournamentUser) -> Result<Option<Tournament>, String>;
         async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Tournament>, String>;
         async fn find_by_creator(&self, user_id: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<Tournament>, String>;
         async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn find_by_organizer(&self, user_id: &ObjectId) -> Result<Vec<Tournament>, String>;
+        async fn find_by_participant(&self, voter_id: &VoterId) -> Result<Vec<Tournament>, String>;
+        async fn find_used_opponents(&self, opponent_ids: &[ObjectId]) -> Result<Vec<ObjectId>, String>;
+        async fn count_by_organizer(&self, user_id: &ObjectId) -> Result<u64, String>;
+        async fn count_by_participant(&self, voter_id: &VoterId) -> Result<u64, String>;
+        async fn count_won_by(&self, opponent_ids: &[ObjectId]) -> Result<u64, String>;
     }
 }
 
@@ -35,9 +47,12 @@ mock! {
 
     #[async_trait]
     impl InviteRepository for InviteRepo {
-        async fn create(&self, invite: TournamentInvite) -> Result<(), String>;
+        async fn create(&self, invite: TournamentInvite) -> Result<bool, String>;
         async fn find_by_code(&self, code: &str) -> Result<Option<TournamentInvite>, String>;
-        async fn increment_uses(&self, id: &ObjectId) -> Result<(), String>;
+        async fn redeem(&self, id: &ObjectId, redemption: &InviteRedemption) -> Result<bool, String>;
+        async fn release(&self, id: &ObjectId, session_id: &str) -> Result<(), String>;
+        async fn find_by_tournament(&self, tournament_id: &ObjectId) -> Result<Vec<TournamentInvite>, String>;
+        async fn revoke(&self, tournament_id: &ObjectId, code: &str) -> Result<bool, String>;
     }
 }
 
@@ -46,10 +61,26 @@ mock! {
 
     #[async_trait]
     impl AuthService for Auth {
-        async fn login(&self, email: &str, password: &str) -> Result<crate::modules::auth::model::LoginResponse, String>;
-        async fn register(&self, email: &str, name: &str, password: &str) -> Result<crate::modules::auth::model::LoginResponse, String>;
+        async fn login(&self, email: &str, password: &str, client: &crate::config::client_info::ClientInfo) -> Result<crate::modules::auth::model::LoginResult, String>;
+        async fn complete_mfa_login(&self, mfa_token: &str, code: &str, client: &crate::config::client_info::ClientInfo) -> Result<crate::modules::auth::model::LoginResponse, String>;
+        async fn register(&self, email: &str, name: &str, password: &str, client: &crate::config::client_info::ClientInfo) -> Result<crate::modules::auth::model::LoginResponse, String>;
+        async fn create_session(&self, user: crate::modules::users::model::User, client: &crate::config::client_info::ClientInfo) -> Result<crate::modules::auth::model::LoginResponse, String>;
         fn verify_token(&self, token: &str) -> Result<crate::modules::auth::model::JwtClaims, String>;
-        fn refresh_tokens(&self, refresh_token: &str) -> Result<crate::modules::auth::model::RefreshResponse, String>;
+        async fn refresh_tokens(&self, refresh_token: &str, client: &crate::config::client_info::ClientInfo) -> Result<crate::modules::auth::model::RefreshResponse, String>;
+        async fn logout(&self, refresh_token: &str) -> Result<(), String>;
+        async fn logout_all(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn list_sessions(&self, user_id: &ObjectId) -> Result<Vec<crate::modules::auth::model::SessionResponse>, String>;
+        async fn revoke_session(&self, user_id: &ObjectId, session_id: &str) -> Result<(), String>;
+        async fn request_password_reset(&self, email: &str) -> Result<(), String>;
+        async fn reset_password(&self, token: &str, new_password: &str) -> Result<(), String>;
+        async fn send_verification_email(&self, user_id: &ObjectId) -> Result<(), String>;
+        async fn verify_email(&self, token: &str) -> Result<(), String>;
+        async fn request_magic_link(&self, email: &str) -> Result<(), String>;
+        async fn redeem_magic_link(&self, token: &str, client: &crate::config::client_info::ClientInfo) -> Result<crate::modules::auth::model::LoginResult, String>;
+        async fn start_totp_enrollment(&self, user_id: &ObjectId) -> Result<crate::modules::auth::model::TotpEnrollmentResponse, String>;
+        async fn confirm_totp_enrollment(&self, user_id: &ObjectId, code: &str) -> Result<crate::modules::auth::model::RecoveryCodesResponse, String>;
+        async fn disable_totp(&self, user_id: &ObjectId, code: &str) -> Result<(), String>;
+        async fn regenerate_recovery_codes(&self, user_id: &ObjectId, code: &str) -> Result<crate::modules::auth::model::RecoveryCodesResponse, String>;
         fn generate_anonymous_token(&self, tournament_id: &ObjectId, display_name: &str) -> Result<AnonymousTokenResponse, String>;
         fn verify_anonymous_token(&self, token: &str) -> Result<AnonymousClaims, String>;
     }
@@ -63,12 +94,24 @@ fn create_service(
     repo: MockTournamentRepo,
     invite_repo: MockInviteRepo,
     auth: MockAuth,
+) -> TournamentServiceImpl {
+    create_service_with_generator(repo, invite_repo, auth, InviteCodeGenerator::default())
+}
+
+fn create_service_with_generator(
+    repo: MockTournamentRepo,
+    invite_repo: MockInviteRepo,
+    auth: MockAuth,
+    code_generator: InviteCodeGenerator,
 ) -> TournamentServiceImpl {
     TournamentServiceImpl::new(
         Arc::new(repo),
         Arc::new(invite_repo),
         Arc::new(auth),
         create_broadcaster(),
+        code_generator,
+        DisplayNameFilter::default(),
+        "http://localhost:3000".to_string(),
     )
 }
 
@@ -83,6 +126,9 @@ fn create_service_with_broadcaster(
         Arc::new(invite_repo),
         Arc::new(auth),
         Arc::clone(&broadcaster),
+        InviteCodeGenerator::default(),
+        DisplayNameFilter::default(),
+        "http://localhost:3000".to_string(),
     );
     (service, broadcaster)
 }
@@ -196,7 +242,7 @@ async fn test_vote_match_success() {
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
 
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let service = create_service_basic(mock_repo);
     let vote_dto = VoteMatchDto {
@@ -250,7 +296,7 @@ async fn test_complete_tournament() {
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
 
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let service = create_service_basic(mock_repo);
     let vote_dto = VoteMatchDto {
@@ -291,7 +337,7 @@ async fn test_create_next_round() {
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
 
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let service = create_service_basic(mock_repo);
 
@@ -317,6 +363,7 @@ async fn test_vote_match_anonymous_voter() {
     tournament.users.push(TournamentUser {
         voter_id: anonymous_voter.clone(),
         name: "Anonymous Player".to_string(),
+        muted: false,
     });
 
     let match_id = tournament.rounds[0].matches[0].match_id.clone();
@@ -327,7 +374,7 @@ async fn test_vote_match_anonymous_voter() {
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
 
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let service = create_service_basic(mock_repo);
     let vote_dto = VoteMatchDto {
@@ -419,6 +466,7 @@ async fn test_create_invite_success() {
     let mut tournament = create_test_tournament();
     let tournament_id = ObjectId::new();
     tournament.id = Some(tournament_id);
+    let created_by = tournament.created_by;
 
     mock_repo
         .expect_find_by_id()
@@ -426,14 +474,13 @@ async fn test_create_invite_success() {
         .returning(move |_| Ok(Some(tournament.clone())));
 
     let mut mock_invite = MockInviteRepo::new();
-    mock_invite.expect_create().times(1).returning(|_| Ok(()));
+    mock_invite.expect_create().times(1).returning(|_| Ok(true));
 
     let service = create_service(mock_repo, mock_invite, MockAuth::new());
     let dto = CreateInviteDto {
         max_uses: Some(5),
         expires_in_hours: Some(48),
     };
-    let created_by = ObjectId::new();
 
     let result = service.create_invite(&tournament_id, dto, created_by).await;
 
@@ -473,12 +520,20 @@ async fn test_join_tournament_success() {
     let tournament_id = ObjectId::new();
     tournament.id = Some(tournament_id);
 
+    let existing = tournament.clone();
     mock_repo
         .expect_find_by_id()
         .times(1)
-        .returning(move |_| Ok(Some(tournament.clone())));
-
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+        .returning(move |_| Ok(Some(existing.clone())));
+    mock_repo
+        .expect_add_participant()
+        .withf(move |id, user| *id == tournament_id && user.name == "Player 1")
+        .times(1)
+        .returning(move |_, user| {
+            let mut tournament = tournament.clone();
+            tournament.users.push(user.clone());
+            Ok(Some(tournament))
+        });
 
     let invite_id = ObjectId::new();
     let mut mock_invite = MockInviteRepo::new();
@@ -497,13 +552,20 @@ async fn test_join_tournament_success() {
                 ),
                 created_by: ObjectId::new(),
                 created_at: DateTime::now(),
+                revoked_at: None,
+                redemptions: vec![],
             }))
         });
 
     mock_invite
-        .expect_increment_uses()
+        .expect_redeem()
+        .withf(move |id, redemption| {
+            *id == invite_id
+                && redemption.session_id == "session-uuid-456"
+                && redemption.display_name == "Player 1"
+        })
         .times(1)
-        .returning(|_| Ok(()));
+        .returning(|_, _| Ok(true));
 
     let mut mock_auth = MockAuth::new();
     mock_auth
@@ -574,6 +636,8 @@ async fn test_join_tournament_expired() {
                 expires_at: DateTime::from_millis(1000), // expired (year 1970)
                 created_by: ObjectId::new(),
                 created_at: DateTime::now(),
+                revoked_at: None,
+                redemptions: vec![],
             }))
         });
 
@@ -609,6 +673,8 @@ async fn test_join_tournament_max_uses_exceeded() {
                 ),
                 created_by: ObjectId::new(),
                 created_at: DateTime::now(),
+                revoked_at: None,
+                redemptions: vec![],
             }))
         });
 
@@ -694,7 +760,7 @@ async fn test_update_tournament_success() {
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
 
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let service = create_service_basic(mock_repo);
     let dto = UpdateTournamentDto {
@@ -730,7 +796,10 @@ async fn test_update_tournament_not_owner() {
     let result = service.update_tournament(&tournament_id, dto, &other_user).await;
 
     assert!(result.is_err());
-    assert_eq!(result.unwrap_err(), "You can only update your own tournaments");
+    assert_eq!(
+        result.unwrap_err(),
+        "You do not have permission to update this tournament"
+    );
 }
 
 #[tokio::test]
@@ -799,7 +868,10 @@ async fn test_delete_tournament_not_owner() {
     let result = service.delete_tournament(&tournament_id, &other_user).await;
 
     assert!(result.is_err());
-    assert_eq!(result.unwrap_err(), "You can only delete your own tournaments");
+    assert_eq!(
+        result.unwrap_err(),
+        "You do not have permission to delete this tournament"
+    );
 }
 
 #[tokio::test]
@@ -816,7 +888,7 @@ async fn test_pause_tournament_success() {
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
 
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let service = create_service_basic(mock_repo);
     let result = service.pause_tournament(&tournament_id, &owner_id).await;
@@ -843,7 +915,10 @@ async fn test_pause_tournament_not_owner() {
     let result = service.pause_tournament(&tournament_id, &ObjectId::new()).await;
 
     assert!(result.is_err());
-    assert_eq!(result.unwrap_err(), "You can only pause your own tournaments");
+    assert_eq!(
+        result.unwrap_err(),
+        "You do not have permission to pause this tournament"
+    );
 }
 
 #[tokio::test]
@@ -883,7 +958,7 @@ async fn test_resume_tournament_success() {
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
 
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let service = create_service_basic(mock_repo);
     let result = service.resume_tournament(&tournament_id, &owner_id).await;
@@ -995,6 +1070,7 @@ async fn test_vote_cast_broadcasts_event() {
     tournament.users.push(TournamentUser {
         voter_id: VoterId::Registered(ObjectId::new()),
         name: "User 2".to_string(),
+        muted: false,
     });
 
     let match_id = tournament.rounds[0].matches[0].match_id.clone();
@@ -1005,7 +1081,7 @@ async fn test_vote_cast_broadcasts_event() {
         .expect_find_by_id()
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let (service, broadcaster) =
         create_service_with_broadcaster(mock_repo, MockInviteRepo::new(), MockAuth::new());
@@ -1047,7 +1123,7 @@ async fn test_vote_completing_match_broadcasts_match_completed() {
         .expect_find_by_id()
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let (service, broadcaster) =
         create_service_with_broadcaster(mock_repo, MockInviteRepo::new(), MockAuth::new());
@@ -1092,7 +1168,7 @@ async fn test_vote_completing_tournament_broadcasts_all_events() {
         .expect_find_by_id()
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let (service, broadcaster) =
         create_service_with_broadcaster(mock_repo, MockInviteRepo::new(), MockAuth::new());
@@ -1165,7 +1241,7 @@ async fn test_vote_completing_round_broadcasts_round_completed() {
         .expect_find_by_id()
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let (service, broadcaster) =
         create_service_with_broadcaster(mock_repo, MockInviteRepo::new(), MockAuth::new());
@@ -1204,11 +1280,19 @@ async fn test_join_broadcasts_participant_joined() {
     let tournament_id = ObjectId::new();
     tournament.id = Some(tournament_id);
 
+    let existing = tournament.clone();
     mock_repo
         .expect_find_by_id()
         .times(1)
-        .returning(move |_| Ok(Some(tournament.clone())));
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+        .returning(move |_| Ok(Some(existing.clone())));
+    mock_repo
+        .expect_add_participant()
+        .times(1)
+        .returning(move |_, user| {
+            let mut tournament = tournament.clone();
+            tournament.users.push(user.clone());
+            Ok(Some(tournament))
+        });
 
     let invite_id = ObjectId::new();
     let mut mock_invite = MockInviteRepo::new();
@@ -1227,12 +1311,14 @@ async fn test_join_broadcasts_participant_joined() {
                 ),
                 created_by: ObjectId::new(),
                 created_at: DateTime::now(),
+                revoked_at: None,
+                redemptions: vec![],
             }))
         });
     mock_invite
-        .expect_increment_uses()
+        .expect_redeem()
         .times(1)
-        .returning(|_| Ok(()));
+        .returning(|_, _| Ok(true));
 
     let mut mock_auth = MockAuth::new();
     mock_auth
@@ -1282,7 +1368,7 @@ async fn test_pause_broadcasts_paused() {
         .expect_find_by_id()
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let (service, broadcaster) =
         create_service_with_broadcaster(mock_repo, MockInviteRepo::new(), MockAuth::new());
@@ -1307,7 +1393,7 @@ async fn test_resume_broadcasts_resumed() {
         .expect_find_by_id()
         .times(1)
         .returning(move |_| Ok(Some(tournament.clone())));
-    mock_repo.expect_update().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
 
     let (service, broadcaster) =
         create_service_with_broadcaster(mock_repo, MockInviteRepo::new(), MockAuth::new());
@@ -1344,6 +1430,258 @@ async fn test_no_broadcast_on_error() {
     assert!(rx.try_recv().is_err());
 }
 
+// --- Anonymous session upgrade tests ---
+
+fn tournament_with_anonymous_voter(session_id: &str) -> Tournament {
+    let mut tournament = create_test_tournament();
+    tournament.users.push(TournamentUser {
+        voter_id: VoterId::Anonymous(session_id.to_string()),
+        name: "Guest".to_string(),
+        muted: false,
+    });
+    tournament
+}
+
+#[tokio::test]
+async fn test_claim_anonymous_session_moves_entry_and_votes() {
+    let mut mock_repo = MockTournamentRepo::new();
+    let mut tournament = tournament_with_anonymous_voter("session-1");
+    let tournament_id = ObjectId::new();
+    tournament.id = Some(tournament_id);
+    let opponent1 = tournament.rounds[0].matches[0].opponent1;
+    tournament.rounds[0].matches[0].votes.insert(
+        opponent1.to_string(),
+        vec![VoterId::Anonymous("session-1".to_string())],
+    );
+    let user_id = ObjectId::new();
+
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(tournament.clone())));
+    mock_repo
+        .expect_update()
+        .times(1)
+        .withf(move |t: &Tournament| {
+            let registered = VoterId::Registered(user_id);
+            t.users.len() == 2
+                && t.users.iter().any(|u| u.voter_id == registered && u.name == "Jane")
+                && !t.users.iter().any(|u| u.voter_id.is_anonymous())
+                && t.rounds[0].matches[0].votes[&opponent1.to_string()] == vec![registered]
+        })
+        .returning(|_| Ok(true));
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .claim_anonymous_session(&tournament_id, "session-1", user_id, "Jane".to_string())
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_claim_anonymous_session_suffixes_taken_name() {
+    // Arrange: another participant already goes by the account name
+    let mut mock_repo = MockTournamentRepo::new();
+    let mut tournament = tournament_with_anonymous_voter("session-1");
+    let tournament_id = ObjectId::new();
+    tournament.id = Some(tournament_id);
+    let user_id = ObjectId::new();
+
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(tournament.clone())));
+    mock_repo
+        .expect_update()
+        .times(1)
+        .withf(move |t: &Tournament| {
+            let registered = VoterId::Registered(user_id);
+            t.users
+                .iter()
+                .any(|u| u.voter_id == registered && u.name == "test user (2)")
+        })
+        .returning(|_| Ok(true));
+
+    // Act
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .claim_anonymous_session(&tournament_id, "session-1", user_id, "test user".to_string())
+        .await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_claim_anonymous_session_keeps_guest_name_when_account_name_is_invalid() {
+    // Arrange
+    let mut mock_repo = MockTournamentRepo::new();
+    let mut tournament = tournament_with_anonymous_voter("session-1");
+    let tournament_id = ObjectId::new();
+    tournament.id = Some(tournament_id);
+    let user_id = ObjectId::new();
+
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(tournament.clone())));
+    mock_repo
+        .expect_update()
+        .times(1)
+        .withf(move |t: &Tournament| {
+            let registered = VoterId::Registered(user_id);
+            t.users
+                .iter()
+                .any(|u| u.voter_id == registered && u.name == "Guest")
+        })
+        .returning(|_| Ok(true));
+
+    // Act
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .claim_anonymous_session(&tournament_id, "session-1", user_id, "x".repeat(200))
+        .await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_claim_anonymous_session_merges_duplicate_participant() {
+    let mut mock_repo = MockTournamentRepo::new();
+    let mut tournament = tournament_with_anonymous_voter("session-1");
+    let tournament_id = ObjectId::new();
+    tournament.id = Some(tournament_id);
+    let registered = tournament.users[0].voter_id.clone();
+    let user_id = match registered {
+        VoterId::Registered(id) => id,
+        VoterId::Anonymous(_) => unreachable!(),
+    };
+    let opponent1 = tournament.rounds[0].matches[0].opponent1;
+    let opponent2 = tournament.rounds[0].matches[0].opponent2;
+    tournament.rounds[0].matches[0]
+        .votes
+        .insert(opponent1.to_string(), vec![registered.clone()]);
+    tournament.rounds[0].matches[0].votes.insert(
+        opponent2.to_string(),
+        vec![VoterId::Anonymous("session-1".to_string())],
+    );
+
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(tournament.clone())));
+    mock_repo
+        .expect_update()
+        .times(1)
+        .withf(move |t: &Tournament| {
+            let votes = &t.rounds[0].matches[0].votes;
+            t.users.len() == 1
+                && votes[&opponent1.to_string()] == vec![VoterId::Registered(user_id)]
+                && votes[&opponent2.to_string()].is_empty()
+        })
+        .returning(|_| Ok(true));
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .claim_anonymous_session(&tournament_id, "session-1", user_id, "Test User".to_string())
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_claim_anonymous_session_duplicate_completes_pending_match() {
+    let mut mock_repo = MockTournamentRepo::new();
+    let mut tournament = tournament_with_anonymous_voter("session-1");
+    let tournament_id = ObjectId::new();
+    tournament.id = Some(tournament_id);
+    let user_id = match tournament.users[0].voter_id {
+        VoterId::Registered(id) => id,
+        VoterId::Anonymous(_) => unreachable!(),
+    };
+    let opponent1 = tournament.rounds[0].matches[0].opponent1;
+    let match_id = tournament.rounds[0].matches[0].match_id.clone();
+    // Only the guest voted; the match waited for the registered user
+    tournament.rounds[0].matches[0].votes.insert(
+        opponent1.to_string(),
+        vec![VoterId::Anonymous("session-1".to_string())],
+    );
+
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(tournament.clone())));
+    mock_repo
+        .expect_update()
+        .times(1)
+        .withf(move |t: &Tournament| {
+            t.rounds[0].matches[0].winner == Some(opponent1)
+                && t.winner == Some(opponent1)
+                && matches!(t.status, TournamentStatus::Completed)
+        })
+        .returning(|_| Ok(true));
+
+    let (service, broadcaster) =
+        create_service_with_broadcaster(mock_repo, MockInviteRepo::new(), MockAuth::new());
+    let mut rx = broadcaster.subscribe(&tournament_id);
+
+    service
+        .claim_anonymous_session(&tournament_id, "session-1", user_id, "Test User".to_string())
+        .await
+        .unwrap();
+
+    match rx.try_recv().unwrap() {
+        TournamentEvent::MatchCompleted {
+            match_id: mid,
+            winner_id,
+            ..
+        } => {
+            assert_eq!(mid, match_id);
+            assert_eq!(winner_id, opponent1);
+        }
+        other => panic!("Expected MatchCompleted, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_claim_anonymous_session_unknown_session_is_noop() {
+    let mut mock_repo = MockTournamentRepo::new();
+    let mut tournament = create_test_tournament();
+    let tournament_id = ObjectId::new();
+    tournament.id = Some(tournament_id);
+
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(tournament.clone())));
+    mock_repo.expect_update().times(0);
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .claim_anonymous_session(&tournament_id, "unknown", ObjectId::new(), "Jane".to_string())
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_claim_anonymous_session_tournament_not_found() {
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(|_| Ok(None));
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .claim_anonymous_session(&ObjectId::new(), "session-1", ObjectId::new(), "Jane".to_string())
+        .await;
+
+    assert_eq!(result.unwrap_err(), "Tournament not found");
+}
+
 // --- Integration tests (require MongoDB) ---
 
 #[tokio::test]
@@ -1361,3 +1699,1673 @@ async fn test_integration_vote_persists_in_db() {
     // NOTE: This integration test needs a real repo, keeping as ignored placeholder
     let _ = service;
 }
+
+// --- Force complete / delete (admin) ---
+
+#[tokio::test]
+async fn test_force_complete_tournament_ignores_owner() {
+    let mut mock_repo = MockTournamentRepo::new();
+    let mut tournament = create_test_tournament();
+    let tournament_id = ObjectId::new();
+    tournament.id = Some(tournament_id);
+
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(tournament.clone())));
+    mock_repo
+        .expect_update()
+        .withf(|t| matches!(t.status, TournamentStatus::Completed))
+        .times(1)
+        .returning(|_| Ok(true));
+
+    let (service, broadcaster) =
+        create_service_with_broadcaster(mock_repo, MockInviteRepo::new(), MockAuth::new());
+    let mut rx = broadcaster.subscribe(&tournament_id);
+    let result = service.force_complete_tournament(&tournament_id).await;
+
+    assert!(matches!(result.unwrap().status, TournamentStatus::Completed));
+    assert_eq!(rx.try_recv().unwrap(), TournamentEvent::TournamentForceCompleted);
+}
+
+#[tokio::test]
+async fn test_force_complete_tournament_already_completed() {
+    let mut mock_repo = MockTournamentRepo::new();
+    let mut tournament = create_test_tournament();
+    tournament.status = TournamentStatus::Completed;
+
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(tournament.clone())));
+    mock_repo.expect_update().times(0);
+
+    let service = create_service_basic(mock_repo);
+    let result = service.force_complete_tournament(&ObjectId::new()).await;
+
+    assert_eq!(result.unwrap_err(), "Tournament is already completed");
+}
+
+#[tokio::test]
+async fn test_force_delete_tournament_ignores_owner() {
+    let mut mock_repo = MockTournamentRepo::new();
+    let tournament = create_test_tournament();
+
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(tournament.clone())));
+    mock_repo.expect_delete().times(1).returning(|_| Ok(()));
+
+    let service = create_service_basic(mock_repo);
+    let result = service.force_delete_tournament(&ObjectId::new()).await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_force_delete_tournament_not_found() {
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo.expect_find_by_id().times(1).returning(|_| Ok(None));
+    mock_repo.expect_delete().times(0);
+
+    let service = create_service_basic(mock_repo);
+    let result = service.force_delete_tournament(&ObjectId::new()).await;
+
+    assert_eq!(result.unwrap_err(), "Tournament not found");
+}
+
+// --- Membership tests ---
+
+fn tournament_with_member(user_id: ObjectId, role: TournamentRole) -> Tournament {
+    let mut tournament = create_test_tournament();
+    tournament.id = Some(ObjectId::new());
+    tournament.members.push(TournamentMember {
+        user_id,
+        role,
+        granted_by: tournament.created_by,
+        granted_at: DateTime::now(),
+    });
+    tournament
+}
+
+fn repo_returning(tournament: Tournament) -> MockTournamentRepo {
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(tournament.clone())));
+    mock_repo
+}
+
+#[tokio::test]
+async fn test_co_organizer_can_update_tournament() {
+    let co_organizer = ObjectId::new();
+    let tournament = tournament_with_member(co_organizer, TournamentRole::CoOrganizer);
+    let tournament_id = tournament.id.unwrap();
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
+
+    let service = create_service_basic(mock_repo);
+    let dto = UpdateTournamentDto {
+        name: Some("Renamed".to_string()),
+    };
+    let result = service.update_tournament(&tournament_id, dto, &co_organizer).await;
+
+    assert_eq!(result.unwrap().name, "Renamed");
+}
+
+#[tokio::test]
+async fn test_co_organizer_cannot_delete_tournament() {
+    let co_organizer = ObjectId::new();
+    let tournament = tournament_with_member(co_organizer, TournamentRole::CoOrganizer);
+    let tournament_id = tournament.id.unwrap();
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo.expect_delete().times(0);
+
+    let service = create_service_basic(mock_repo);
+    let result = service.delete_tournament(&tournament_id, &co_organizer).await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        "You do not have permission to delete this tournament"
+    );
+}
+
+#[tokio::test]
+async fn test_moderator_can_pause_tournament() {
+    let moderator = ObjectId::new();
+    let tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+    let tournament_id = tournament.id.unwrap();
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
+
+    let service = create_service_basic(mock_repo);
+    let result = service.pause_tournament(&tournament_id, &moderator).await;
+
+    assert!(matches!(result.unwrap().status, TournamentStatus::Paused));
+}
+
+#[tokio::test]
+async fn test_moderator_cannot_create_invite() {
+    let moderator = ObjectId::new();
+    let tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+    let tournament_id = tournament.id.unwrap();
+    let mock_repo = repo_returning(tournament);
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite.expect_create().times(0);
+
+    let service = create_service(mock_repo, mock_invite, MockAuth::new());
+    let dto = CreateInviteDto {
+        max_uses: None,
+        expires_in_hours: None,
+    };
+    let result = service.create_invite(&tournament_id, dto, moderator).await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        "You do not have permission to invite to this tournament"
+    );
+}
+
+#[tokio::test]
+async fn test_grant_member_by_owner() {
+    let tournament = create_test_tournament();
+    let owner = tournament.created_by;
+    let new_member = ObjectId::new();
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo
+        .expect_update()
+        .withf(move |t| t.role_of(&new_member) == Some(TournamentRole::CoOrganizer))
+        .times(1)
+        .returning(|_| Ok(true));
+
+    let service = create_service_basic(mock_repo);
+    let dto = GrantMemberDto {
+        user_id: new_member,
+        role: TournamentRole::CoOrganizer,
+    };
+    let members = service
+        .grant_member(&ObjectId::new(), dto, &owner)
+        .await
+        .unwrap();
+
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[1].user_id, new_member);
+}
+
+#[tokio::test]
+async fn test_grant_member_changes_existing_role() {
+    let member = ObjectId::new();
+    let tournament = tournament_with_member(member, TournamentRole::Moderator);
+    let owner = tournament.created_by;
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo
+        .expect_update()
+        .withf(move |t| {
+            t.members.len() == 1 && t.role_of(&member) == Some(TournamentRole::CoOrganizer)
+        })
+        .times(1)
+        .returning(|_| Ok(true));
+
+    let service = create_service_basic(mock_repo);
+    let dto = GrantMemberDto {
+        user_id: member,
+        role: TournamentRole::CoOrganizer,
+    };
+    let result = service.grant_member(&ObjectId::new(), dto, &owner).await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_co_organizer_cannot_grant_co_organizer() {
+    let co_organizer = ObjectId::new();
+    let tournament = tournament_with_member(co_organizer, TournamentRole::CoOrganizer);
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo.expect_update().times(0);
+
+    let service = create_service_basic(mock_repo);
+    let dto = GrantMemberDto {
+        user_id: ObjectId::new(),
+        role: TournamentRole::CoOrganizer,
+    };
+    let result = service
+        .grant_member(&ObjectId::new(), dto, &co_organizer)
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::Forbidden("You can only grant roles below your own".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_grant_member_rejects_owner_role() {
+    let service = create_service_basic(MockTournamentRepo::new());
+    let dto = GrantMemberDto {
+        user_id: ObjectId::new(),
+        role: TournamentRole::Owner,
+    };
+
+    let result = service
+        .grant_member(&ObjectId::new(), dto, &ObjectId::new())
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::Invalid("Use an ownership transfer to change the owner".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_moderator_cannot_grant_members() {
+    let moderator = ObjectId::new();
+    let tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+    let mock_repo = repo_returning(tournament);
+
+    let service = create_service_basic(mock_repo);
+    let dto = GrantMemberDto {
+        user_id: ObjectId::new(),
+        role: TournamentRole::Moderator,
+    };
+    let result = service.grant_member(&ObjectId::new(), dto, &moderator).await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::Forbidden(
+            "You do not have permission to manage members of this tournament".to_string()
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_revoke_member_by_owner() {
+    let member = ObjectId::new();
+    let tournament = tournament_with_member(member, TournamentRole::CoOrganizer);
+    let owner = tournament.created_by;
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo
+        .expect_update()
+        .withf(|t| t.members.is_empty())
+        .times(1)
+        .returning(|_| Ok(true));
+
+    let service = create_service_basic(mock_repo);
+    let members = service
+        .revoke_member(&ObjectId::new(), &member, &owner)
+        .await
+        .unwrap();
+
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].role, TournamentRole::Owner);
+}
+
+#[tokio::test]
+async fn test_member_can_leave() {
+    let moderator = ObjectId::new();
+    let tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .revoke_member(&ObjectId::new(), &moderator, &moderator)
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_moderator_cannot_revoke_other_member() {
+    let moderator = ObjectId::new();
+    let mut tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+    let other = ObjectId::new();
+    tournament.members.push(TournamentMember {
+        user_id: other,
+        role: TournamentRole::Moderator,
+        granted_by: tournament.created_by,
+        granted_at: DateTime::now(),
+    });
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo.expect_update().times(0);
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .revoke_member(&ObjectId::new(), &other, &moderator)
+        .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_revoke_owner_rejected() {
+    let tournament = create_test_tournament();
+    let owner = tournament.created_by;
+    let mock_repo = repo_returning(tournament);
+
+    let service = create_service_basic(mock_repo);
+    let result = service.revoke_member(&ObjectId::new(), &owner, &owner).await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::Invalid(
+            "The owner cannot be removed; transfer ownership first".to_string()
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_revoke_non_member() {
+    let tournament = create_test_tournament();
+    let owner = tournament.created_by;
+    let mock_repo = repo_returning(tournament);
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .revoke_member(&ObjectId::new(), &ObjectId::new(), &owner)
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::NotFound("User is not a member of this tournament".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_transfer_ownership() {
+    let co_organizer = ObjectId::new();
+    let tournament = tournament_with_member(co_organizer, TournamentRole::CoOrganizer);
+    let owner = tournament.created_by;
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo.expect_update().times(1).returning(|_| Ok(true));
+
+    let service = create_service_basic(mock_repo);
+    let tournament = service
+        .transfer_ownership(&ObjectId::new(), &co_organizer, &owner)
+        .await
+        .unwrap();
+
+    assert_eq!(tournament.created_by, co_organizer);
+    assert_eq!(tournament.role_of(&co_organizer), Some(TournamentRole::Owner));
+    assert_eq!(tournament.role_of(&owner), Some(TournamentRole::CoOrganizer));
+    assert_eq!(tournament.members.len(), 1);
+}
+
+#[tokio::test]
+async fn test_transfer_ownership_requires_owner() {
+    let co_organizer = ObjectId::new();
+    let tournament = tournament_with_member(co_organizer, TournamentRole::CoOrganizer);
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo.expect_update().times(0);
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .transfer_ownership(&ObjectId::new(), &co_organizer, &co_organizer)
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::Forbidden(
+            "You do not have permission to transfer this tournament".to_string()
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_release_tournaments_transfers_to_successor() {
+    let co_organizer = ObjectId::new();
+    let tournament = tournament_with_member(co_organizer, TournamentRole::CoOrganizer);
+    let owner = tournament.created_by;
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo
+        .expect_find_by_organizer()
+        .times(1)
+        .returning(move |_| Ok(vec![tournament.clone()]));
+    mock_repo
+        .expect_update()
+        .times(1)
+        .withf(move |t: &Tournament| t.created_by == co_organizer && t.members.is_empty())
+        .returning(|_| Ok(true));
+    mock_repo.expect_delete().times(0);
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .release_tournaments(&owner, OwnedTournamentPolicy::Transfer)
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_release_tournaments_deletes_without_successor() {
+    let mut tournament = create_test_tournament();
+    let tournament_id = ObjectId::new();
+    tournament.id = Some(tournament_id);
+    let owner = tournament.created_by;
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo
+        .expect_find_by_organizer()
+        .times(1)
+        .returning(move |_| Ok(vec![tournament.clone()]));
+    mock_repo
+        .expect_delete()
+        .with(mockall::predicate::eq(tournament_id))
+        .times(1)
+        .returning(|_| Ok(()));
+    mock_repo.expect_update().times(0);
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .release_tournaments(&owner, OwnedTournamentPolicy::Transfer)
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_release_tournaments_delete_policy_ignores_successor() {
+    let tournament = tournament_with_member(ObjectId::new(), TournamentRole::CoOrganizer);
+    let owner = tournament.created_by;
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo
+        .expect_find_by_organizer()
+        .times(1)
+        .returning(move |_| Ok(vec![tournament.clone()]));
+    mock_repo.expect_delete().times(1).returning(|_| Ok(()));
+    mock_repo.expect_update().times(0);
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .release_tournaments(&owner, OwnedTournamentPolicy::Delete)
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_release_tournaments_drops_membership() {
+    let moderator = ObjectId::new();
+    let tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+    let owner = tournament.created_by;
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo
+        .expect_find_by_organizer()
+        .times(1)
+        .returning(move |_| Ok(vec![tournament.clone()]));
+    mock_repo
+        .expect_update()
+        .times(1)
+        .withf(move |t: &Tournament| t.created_by == owner && t.members.is_empty())
+        .returning(|_| Ok(true));
+    mock_repo.expect_delete().times(0);
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .release_tournaments(&moderator, OwnedTournamentPolicy::Delete)
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_anonymize_participant_keeps_votes() {
+    let user_id = ObjectId::new();
+    let registered = VoterId::Registered(user_id);
+    let mut tournament = create_test_tournament();
+    tournament.id = Some(ObjectId::new());
+    tournament.users.push(TournamentUser {
+        voter_id: registered.clone(),
+        name: "Jane".to_string(),
+        muted: false,
+    });
+    let opponent1 = tournament.rounds[0].matches[0].opponent1;
+    tournament.rounds[0].matches[0]
+        .votes
+        .insert(opponent1.to_string(), vec![registered.clone()]);
+
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo
+        .expect_find_by_participant()
+        .withf(move |voter_id| *voter_id == VoterId::Registered(user_id))
+        .times(1)
+        .returning(move |_| Ok(vec![tournament.clone()]));
+    mock_repo
+        .expect_update()
+        .times(1)
+        .withf(move |t: &Tournament| {
+            let entry = t.users.iter().find(|u| u.name == "Deleted user");
+            let votes = &t.rounds[0].matches[0].votes[&opponent1.to_string()];
+            !t.users.iter().any(|u| u.voter_id == registered)
+                && entry.is_some_and(|u| u.voter_id.is_anonymous())
+                && votes.len() == 1
+                && votes[0] == entry.unwrap().voter_id
+        })
+        .returning(|_| Ok(true));
+
+    let service = create_service_basic(mock_repo);
+    let result = service.anonymize_participant(&user_id).await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_anonymize_participant_suffixes_name_of_earlier_deleted_user() {
+    // Arrange
+    let user_id = ObjectId::new();
+    let registered = VoterId::Registered(user_id);
+    let mut tournament = create_test_tournament();
+    tournament.id = Some(ObjectId::new());
+    tournament.users.push(TournamentUser {
+        voter_id: VoterId::Anonymous("deleted-earlier".to_string()),
+        name: "Deleted user".to_string(),
+        muted: false,
+    });
+    tournament.users.push(TournamentUser {
+        voter_id: registered.clone(),
+        name: "Jane".to_string(),
+        muted: false,
+    });
+
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo
+        .expect_find_by_participant()
+        .times(1)
+        .returning(move |_| Ok(vec![tournament.clone()]));
+    mock_repo
+        .expect_update()
+        .times(1)
+        .withf(move |t: &Tournament| {
+            t.users.iter().any(|u| u.name == "Deleted user")
+                && t.users
+                    .iter()
+                    .any(|u| u.name == "Deleted user (2)" && u.voter_id.is_anonymous())
+        })
+        .returning(|_| Ok(true));
+
+    // Act
+    let service = create_service_basic(mock_repo);
+    let result = service.anonymize_participant(&user_id).await;
+
+    // Assert
+    assert!(result.is_ok());
+}
+
+// --- Invite management tests ---
+
+fn invite_for(tournament_id: ObjectId, code: &str) -> TournamentInvite {
+    TournamentInvite {
+        id: Some(ObjectId::new()),
+        code: code.to_string(),
+        tournament_id,
+        max_uses: 10,
+        current_uses: 1,
+        expires_at: DateTime::from_millis((chrono::Utc::now().timestamp() + 86400) * 1000),
+        created_by: ObjectId::new(),
+        created_at: DateTime::now(),
+        revoked_at: None,
+        redemptions: vec![InviteRedemption {
+            session_id: "session-1".to_string(),
+            display_name: "Player 1".to_string(),
+            joined_at: DateTime::now(),
+        }],
+    }
+}
+
+#[tokio::test]
+async fn test_list_invites_returns_usage() {
+    let co_organizer = ObjectId::new();
+    let tournament = tournament_with_member(co_organizer, TournamentRole::CoOrganizer);
+    let tournament_id = tournament.id.unwrap();
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_tournament()
+        .withf(move |id| *id == tournament_id)
+        .times(1)
+        .returning(move |_| Ok(vec![invite_for(tournament_id, "ABC12345")]));
+
+    let service = create_service(repo_returning(tournament), mock_invite, MockAuth::new());
+    let invites = service
+        .list_invites(&tournament_id, &co_organizer)
+        .await
+        .unwrap();
+
+    assert_eq!(invites.len(), 1);
+    assert_eq!(invites[0].code, "ABC12345");
+    assert_eq!(invites[0].current_uses, 1);
+    assert_eq!(invites[0].max_uses, 10);
+    assert!(invites[0].revoked_at.is_none());
+}
+
+#[tokio::test]
+async fn test_list_invites_rejects_moderator() {
+    let moderator = ObjectId::new();
+    let tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+    let tournament_id = tournament.id.unwrap();
+
+    let service = create_service(
+        repo_returning(tournament),
+        MockInviteRepo::new(),
+        MockAuth::new(),
+    );
+    let result = service.list_invites(&tournament_id, &moderator).await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::Forbidden(
+            "You do not have permission to manage invites for this tournament".to_string()
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_revoke_invite_success() {
+    let mut tournament = create_test_tournament();
+    tournament.id = Some(ObjectId::new());
+    let tournament_id = tournament.id.unwrap();
+    let owner = tournament.created_by;
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .times(1)
+        .returning(move |code| Ok(Some(invite_for(tournament_id, code))));
+    mock_invite
+        .expect_revoke()
+        .withf(move |id, code| *id == tournament_id && code == "ABC12345")
+        .times(1)
+        .returning(|_, _| Ok(true));
+
+    let service = create_service(repo_returning(tournament), mock_invite, MockAuth::new());
+    let result = service
+        .revoke_invite(&tournament_id, "ABC12345", &owner)
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_revoke_invite_uses_stored_code() {
+    let mut tournament = create_test_tournament();
+    tournament.id = Some(ObjectId::new());
+    let tournament_id = tournament.id.unwrap();
+    let owner = tournament.created_by;
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .times(2)
+        .returning(move |code| Ok((code == "K7QM2XPD").then(|| invite_for(tournament_id, code))));
+    mock_invite
+        .expect_revoke()
+        .withf(move |id, code| *id == tournament_id && code == "K7QM2XPD")
+        .times(1)
+        .returning(|_, _| Ok(true));
+
+    let service = create_service(repo_returning(tournament), mock_invite, MockAuth::new());
+    let result = service
+        .revoke_invite(&tournament_id, "k7qm2xpd", &owner)
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_revoke_invite_of_other_tournament_not_found() {
+    let mut tournament = create_test_tournament();
+    tournament.id = Some(ObjectId::new());
+    let tournament_id = tournament.id.unwrap();
+    let owner = tournament.created_by;
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .times(1)
+        .returning(|code| Ok(Some(invite_for(ObjectId::new(), code))));
+    mock_invite.expect_revoke().never();
+
+    let service = create_service(repo_returning(tournament), mock_invite, MockAuth::new());
+    let result = service
+        .revoke_invite(&tournament_id, "ABC12345", &owner)
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::NotFound("Invite not found".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_revoke_invite_twice() {
+    let mut tournament = create_test_tournament();
+    tournament.id = Some(ObjectId::new());
+    let tournament_id = tournament.id.unwrap();
+    let owner = tournament.created_by;
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .times(1)
+        .returning(move |code| {
+            let mut invite = invite_for(tournament_id, code);
+            invite.revoked_at = Some(DateTime::now());
+            Ok(Some(invite))
+        });
+    mock_invite.expect_revoke().never();
+
+    let service = create_service(repo_returning(tournament), mock_invite, MockAuth::new());
+    let result = service
+        .revoke_invite(&tournament_id, "ABC12345", &owner)
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::Invalid("Invite code has already been revoked".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_invite_joins_lists_sessions() {
+    let mut tournament = create_test_tournament();
+    tournament.id = Some(ObjectId::new());
+    let tournament_id = tournament.id.unwrap();
+    let owner = tournament.created_by;
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .times(1)
+        .returning(move |code| Ok(Some(invite_for(tournament_id, code))));
+
+    let service = create_service(repo_returning(tournament), mock_invite, MockAuth::new());
+    let joins = service
+        .invite_joins(&tournament_id, "ABC12345", &owner)
+        .await
+        .unwrap();
+
+    assert_eq!(joins.len(), 1);
+    assert_eq!(joins[0].session_id, "session-1");
+    assert_eq!(joins[0].display_name, "Player 1");
+}
+
+#[tokio::test]
+async fn test_join_tournament_with_revoked_code() {
+    let tournament_id = ObjectId::new();
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .times(1)
+        .returning(move |code| {
+            let mut invite = invite_for(tournament_id, code);
+            invite.revoked_at = Some(DateTime::now());
+            Ok(Some(invite))
+        });
+    mock_invite.expect_redeem().never();
+
+    let service = create_service(MockTournamentRepo::new(), mock_invite, MockAuth::new());
+    let dto = JoinTournamentDto {
+        invite_code: "ABC12345".to_string(),
+        display_name: "Player 1".to_string(),
+    };
+    let result = service.join_tournament(&tournament_id, dto).await;
+
+    assert_eq!(result.unwrap_err(), "Invite code has been revoked");
+}
+
+// --- Invite redemption tests ---
+
+fn anonymous_token_auth() -> MockAuth {
+    let mut mock_auth = MockAuth::new();
+    mock_auth
+        .expect_generate_anonymous_token()
+        .returning(|_tid, name| {
+            Ok(AnonymousTokenResponse {
+                access_token: "token".to_string(),
+                token_type: "Bearer".to_string(),
+                session_id: uuid::Uuid::new_v4().to_string(),
+                display_name: name.to_string(),
+            })
+        });
+    mock_auth
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_joins_respect_max_uses() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+
+    const MAX_USES: u32 = 3;
+    let mut tournament = create_test_tournament();
+    let tournament_id = ObjectId::new();
+    tournament.id = Some(tournament_id);
+    let initial_users = tournament.users.len();
+    let stored = Arc::new(Mutex::new(tournament));
+    let uses = Arc::new(AtomicU32::new(0));
+
+    let mut mock_repo = MockTournamentRepo::new();
+    let current = Arc::clone(&stored);
+    mock_repo
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(current.lock().unwrap().clone())));
+    let participants = Arc::clone(&stored);
+    mock_repo.expect_add_participant().returning(move |_, user| {
+        let mut tournament = participants.lock().unwrap();
+        tournament.users.push(user.clone());
+        Ok(Some(tournament.clone()))
+    });
+
+    // The invite store behaves like Mongo: reads may be stale, but the
+    // conditional increment is atomic
+    let mut mock_invite = MockInviteRepo::new();
+    let read_uses = Arc::clone(&uses);
+    mock_invite.expect_find_by_code().returning(move |code| {
+        let mut invite = invite_for(tournament_id, code);
+        invite.max_uses = MAX_USES;
+        invite.current_uses = read_uses.load(Ordering::SeqCst);
+        Ok(Some(invite))
+    });
+    let redeem_uses = Arc::clone(&uses);
+    mock_invite.expect_redeem().returning(move |_, _| {
+        Ok(redeem_uses
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < MAX_USES).then_some(n + 1)
+            })
+            .is_ok())
+    });
+    mock_invite.expect_release().never();
+
+    let service = Arc::new(create_service(
+        mock_repo,
+        mock_invite,
+        anonymous_token_auth(),
+    ));
+    let joins: Vec<_> = (0..12)
+        .map(|i| {
+            let service = Arc::clone(&service);
+            tokio::spawn(async move {
+                let dto = JoinTournamentDto {
+                    invite_code: "ABC12345".to_string(),
+                    display_name: format!("Player {}", i),
+                };
+                service.join_tournament(&tournament_id, dto).await
+            })
+        })
+        .collect();
+
+    let mut joined = 0;
+    for join in joins {
+        match join.await.unwrap() {
+            Ok(_) => joined += 1,
+            Err(e) => assert_eq!(e, "Invite code has reached maximum uses"),
+        }
+    }
+
+    assert_eq!(joined, MAX_USES as usize);
+    assert_eq!(uses.load(Ordering::SeqCst), MAX_USES);
+    assert_eq!(
+        stored.lock().unwrap().users.len(),
+        initial_users + MAX_USES as usize
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_vote_does_not_overwrite_a_concurrent_join() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc::sync_channel;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tokio::sync::Notify;
+
+    let mut tournament = create_test_tournament();
+    let tournament_id = ObjectId::new();
+    tournament.id = Some(tournament_id);
+    let voter = tournament.users[0].voter_id.clone();
+    let voted_for = tournament.rounds[0].matches[0].opponent1;
+    let stored = Arc::new(Mutex::new(tournament));
+    let (joined_tx, joined_rx) = sync_channel(1);
+    let joined_rx = Mutex::new(joined_rx);
+    let read = Arc::new(Notify::new());
+    let held_back = AtomicBool::new(false);
+
+    // The store behaves like Mongo: a write based on an outdated read is
+    // refused and every write bumps the version
+    let mut mock_repo = MockTournamentRepo::new();
+    let current = Arc::clone(&stored);
+    let reads = Arc::clone(&read);
+    mock_repo.expect_find_by_id().returning(move |_| {
+        reads.notify_one();
+        Ok(Some(current.lock().unwrap().clone()))
+    });
+    let participants = Arc::clone(&stored);
+    mock_repo.expect_add_participant().returning(move |_, user| {
+        let mut tournament = participants.lock().unwrap();
+        tournament.users.push(user.clone());
+        tournament.version += 1;
+        joined_tx.send(()).unwrap();
+        Ok(Some(tournament.clone()))
+    });
+    let saved = Arc::clone(&stored);
+    mock_repo.expect_update().times(2).returning(move |update| {
+        // Hold the vote's first write back until the guest has joined
+        if !held_back.swap(true, Ordering::SeqCst) {
+            let _ = joined_rx
+                .lock()
+                .unwrap()
+                .recv_timeout(Duration::from_secs(5));
+        }
+        let mut tournament = saved.lock().unwrap();
+        if update.version != tournament.version {
+            return Ok(false);
+        }
+        *tournament = update.clone();
+        tournament.version += 1;
+        Ok(true)
+    });
+
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .returning(move |code| Ok(Some(invite_for(tournament_id, code))));
+    mock_invite.expect_redeem().returning(|_, _| Ok(true));
+    mock_invite.expect_release().never();
+
+    let service = Arc::new(create_service(
+        mock_repo,
+        mock_invite,
+        anonymous_token_auth(),
+    ));
+    let voting = Arc::clone(&service);
+    let vote = tokio::spawn(async move {
+        let dto = VoteMatchDto {
+            tournament_id,
+            match_id: "test_match".to_string(),
+            voted_for,
+        };
+        voting.vote_match(dto, voter).await
+    });
+    let joining = Arc::clone(&service);
+    let join = tokio::spawn(async move {
+        // Join only once the vote has read the tournament
+        read.notified().await;
+        let dto = JoinTournamentDto {
+            invite_code: "ABC12345".to_string(),
+            display_name: "Guest".to_string(),
+        };
+        joining.join_tournament(&tournament_id, dto).await
+    });
+
+    join.await.unwrap().unwrap();
+    vote.await.unwrap().unwrap();
+
+    // The vote was redone on top of the join, so with two voters the match
+    // is still open
+    let tournament = stored.lock().unwrap();
+    assert!(tournament.users.iter().any(|u| u.name == "Guest"));
+    assert_eq!(tournament.users.len(), 2);
+    let m = &tournament.rounds[0].matches[0];
+    assert_eq!(m.votes.values().map(Vec::len).sum::<usize>(), 1);
+    assert!(m.winner.is_none());
+    assert!(matches!(tournament.status, TournamentStatus::Active));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+#[ignore]
+async fn test_integration_concurrent_redeems_respect_max_uses() {
+    use crate::config::database::MongoDB;
+    use crate::modules::tournaments::repository::InviteRepositoryImpl;
+
+    const MAX_USES: u32 = 3;
+    let mongodb = MongoDB::init().await.expect("Failed to init MongoDB");
+    let repo = Arc::new(InviteRepositoryImpl::new(&mongodb.db));
+    let mut invite = invite_for(ObjectId::new(), &ObjectId::new().to_hex());
+    invite.max_uses = MAX_USES;
+    invite.current_uses = 0;
+    invite.redemptions.clear();
+    let invite_id = invite.id.unwrap();
+    let code = invite.code.clone();
+    assert!(repo.create(invite).await.unwrap());
+
+    let redeems: Vec<_> = (0..12)
+        .map(|i| {
+            let repo = Arc::clone(&repo);
+            tokio::spawn(async move {
+                let redemption = InviteRedemption {
+                    session_id: format!("session-{}", i),
+                    display_name: format!("Player {}", i),
+                    joined_at: DateTime::now(),
+                };
+                repo.redeem(&invite_id, &redemption).await.unwrap()
+            })
+        })
+        .collect();
+
+    let mut redeemed = 0;
+    for redeem in redeems {
+        if redeem.await.unwrap() {
+            redeemed += 1;
+        }
+    }
+
+    let stored = repo.find_by_code(&code).await.unwrap().unwrap();
+    assert_eq!(redeemed, MAX_USES);
+    assert_eq!(stored.current_uses, MAX_USES);
+    assert_eq!(stored.redemptions.len(), MAX_USES as usize);
+}
+
+#[tokio::test]
+async fn test_join_reports_why_redemption_was_refused() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let tournament_id = ObjectId::new();
+    let reads = AtomicUsize::new(0);
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .times(2)
+        .returning(move |code| {
+            // Revoked between the first read and the redemption
+            let mut invite = invite_for(tournament_id, code);
+            if reads.fetch_add(1, Ordering::SeqCst) > 0 {
+                invite.revoked_at = Some(DateTime::now());
+            }
+            Ok(Some(invite))
+        });
+    mock_invite
+        .expect_redeem()
+        .times(1)
+        .returning(|_, _| Ok(false));
+
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo
+        .expect_find_by_id()
+        .returning(|_| Ok(Some(create_test_tournament())));
+    mock_repo.expect_add_participant().never();
+
+    let service = create_service(mock_repo, mock_invite, anonymous_token_auth());
+    let dto = JoinTournamentDto {
+        invite_code: "ABC12345".to_string(),
+        display_name: "Player 1".to_string(),
+    };
+    let result = service.join_tournament(&tournament_id, dto).await;
+
+    assert_eq!(result.unwrap_err(), "Invite code has been revoked");
+}
+
+#[tokio::test]
+async fn test_join_releases_invite_when_tournament_is_gone() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let tournament_id = ObjectId::new();
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .times(1)
+        .returning(move |code| Ok(Some(invite_for(tournament_id, code))));
+    mock_invite
+        .expect_redeem()
+        .times(1)
+        .returning(|_, _| Ok(true));
+    mock_invite
+        .expect_release()
+        .times(1)
+        .returning(|_, _| Ok(()));
+
+    // The tournament is deleted between reading it and adding the participant
+    let reads = AtomicUsize::new(0);
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo.expect_find_by_id().times(2).returning(move |_| {
+        if reads.fetch_add(1, Ordering::SeqCst) == 0 {
+            Ok(Some(create_test_tournament()))
+        } else {
+            Ok(None)
+        }
+    });
+    mock_repo
+        .expect_add_participant()
+        .times(1)
+        .returning(|_, _| Ok(None));
+
+    let service = create_service(mock_repo, mock_invite, anonymous_token_auth());
+    let dto = JoinTournamentDto {
+        invite_code: "ABC12345".to_string(),
+        display_name: "Player 1".to_string(),
+    };
+    let result = service.join_tournament(&tournament_id, dto).await;
+
+    assert_eq!(result.unwrap_err(), "Tournament not found");
+}
+
+#[tokio::test]
+async fn test_join_retries_with_next_suffix_when_name_is_taken_meanwhile() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let tournament_id = ObjectId::new();
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .times(1)
+        .returning(move |code| Ok(Some(invite_for(tournament_id, code))));
+    mock_invite
+        .expect_redeem()
+        .times(2)
+        .returning(|_, _| Ok(true));
+    mock_invite
+        .expect_release()
+        .times(1)
+        .returning(|_, _| Ok(()));
+
+    // Another guest joins as "player 1" before our participant is added
+    let reads = AtomicUsize::new(0);
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo.expect_find_by_id().times(2).returning(move |_| {
+        let mut tournament = create_test_tournament();
+        if reads.fetch_add(1, Ordering::SeqCst) > 0 {
+            tournament.users.push(TournamentUser {
+                voter_id: VoterId::Anonymous("session-other".to_string()),
+                name: "player 1".to_string(),
+                muted: false,
+            });
+        }
+        Ok(Some(tournament))
+    });
+    mock_repo
+        .expect_add_participant()
+        .times(2)
+        .returning(|_, user| {
+            if user.name == "Player 1" {
+                return Ok(None);
+            }
+            let mut tournament = create_test_tournament();
+            tournament.users.push(user.clone());
+            Ok(Some(tournament))
+        });
+
+    let service = create_service(mock_repo, mock_invite, anonymous_token_auth());
+    let dto = JoinTournamentDto {
+        invite_code: "ABC12345".to_string(),
+        display_name: "Player 1".to_string(),
+    };
+    let result = service.join_tournament(&tournament_id, dto).await;
+
+    assert_eq!(result.unwrap().display_name, "Player 1 (2)");
+}
+
+#[tokio::test]
+async fn test_join_releases_invite_when_update_fails() {
+    let tournament_id = ObjectId::new();
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .times(1)
+        .returning(move |code| Ok(Some(invite_for(tournament_id, code))));
+    mock_invite
+        .expect_redeem()
+        .times(1)
+        .returning(|_, _| Ok(true));
+    mock_invite
+        .expect_release()
+        .withf(|_, session_id| !session_id.is_empty())
+        .times(1)
+        .returning(|_, _| Ok(()));
+
+    let mut mock_repo = MockTournamentRepo::new();
+    mock_repo
+        .expect_find_by_id()
+        .returning(|_| Ok(Some(create_test_tournament())));
+    mock_repo
+        .expect_add_participant()
+        .times(1)
+        .returning(|_, _| Err("connection reset".to_string()));
+
+    let service = create_service(mock_repo, mock_invite, anonymous_token_auth());
+    let dto = JoinTournamentDto {
+        invite_code: "ABC12345".to_string(),
+        display_name: "Player 1".to_string(),
+    };
+    let result = service.join_tournament(&tournament_id, dto).await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        "Error updating tournament: connection reset"
+    );
+}
+
+// --- Invite code tests ---
+
+fn owned_tournament() -> (Tournament, ObjectId, ObjectId) {
+    let mut tournament = create_test_tournament();
+    let tournament_id = ObjectId::new();
+    tournament.id = Some(tournament_id);
+    let owner = tournament.created_by;
+    (tournament, tournament_id, owner)
+}
+
+#[tokio::test]
+async fn test_create_invite_retries_taken_codes() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let (tournament, tournament_id, owner) = owned_tournament();
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let counted = Arc::clone(&attempts);
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_create()
+        .times(3)
+        .returning(move |_| Ok(counted.fetch_add(1, Ordering::SeqCst) == 2));
+
+    let service = create_service(repo_returning(tournament), mock_invite, MockAuth::new());
+    let dto = CreateInviteDto {
+        max_uses: None,
+        expires_in_hours: None,
+    };
+    let result = service.create_invite(&tournament_id, dto, owner).await;
+
+    assert!(result.is_ok());
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_create_invite_gives_up_after_max_attempts() {
+    let (tournament, tournament_id, owner) = owned_tournament();
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_create()
+        .times(MAX_CODE_ATTEMPTS)
+        .returning(|_| Ok(false));
+
+    let service = create_service(repo_returning(tournament), mock_invite, MockAuth::new());
+    let dto = CreateInviteDto {
+        max_uses: None,
+        expires_in_hours: None,
+    };
+    let result = service.create_invite(&tournament_id, dto, owner).await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        "Error creating invite: no unused code found"
+    );
+}
+
+#[tokio::test]
+async fn test_create_invite_with_word_codes() {
+    let (tournament, tournament_id, owner) = owned_tournament();
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_create()
+        .withf(|invite| invite.code.split('-').count() == 3)
+        .times(1)
+        .returning(|_| Ok(true));
+
+    let generator = InviteCodeGenerator::new(&InviteCodeConfig {
+        format: InviteCodeFormat::Words,
+        ..InviteCodeConfig::default()
+    });
+    let service = create_service_with_generator(
+        repo_returning(tournament),
+        mock_invite,
+        MockAuth::new(),
+        generator,
+    );
+    let dto = CreateInviteDto {
+        max_uses: None,
+        expires_in_hours: None,
+    };
+    let response = service
+        .create_invite(&tournament_id, dto, owner)
+        .await
+        .unwrap();
+
+    assert_eq!(response.code.split('-').count(), 3);
+}
+
+#[tokio::test]
+async fn test_join_accepts_code_typed_in_lowercase() {
+    let (tournament, tournament_id, _) = owned_tournament();
+    let mut mock_repo = MockTournamentRepo::new();
+    let existing = tournament.clone();
+    mock_repo
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(existing.clone())));
+    mock_repo
+        .expect_add_participant()
+        .times(1)
+        .returning(move |_, _| Ok(Some(tournament.clone())));
+
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .times(2)
+        .returning(move |code| {
+            Ok((code == "K7QM2XPD").then(|| invite_for(tournament_id, code)))
+        });
+    mock_invite
+        .expect_redeem()
+        .times(1)
+        .returning(|_, _| Ok(true));
+
+    let service = create_service(mock_repo, mock_invite, anonymous_token_auth());
+    let dto = JoinTournamentDto {
+        invite_code: "k7qm2xpd".to_string(),
+        display_name: "Player 1".to_string(),
+    };
+    let result = service.join_tournament(&tournament_id, dto).await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_invite_qr_code_renders_png() {
+    let (tournament, tournament_id, owner) = owned_tournament();
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .times(1)
+        .returning(move |code| Ok(Some(invite_for(tournament_id, code))));
+
+    let service = create_service(repo_returning(tournament), mock_invite, MockAuth::new());
+    let png = service
+        .invite_qr_code(&tournament_id, "K7QM2XPD", &owner)
+        .await
+        .unwrap();
+
+    assert!(png.starts_with(b"\x89PNG"));
+    assert_eq!(
+        png,
+        render_qr_png("http://localhost:3000/join?code=K7QM2XPD").unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_invite_qr_code_rejects_moderator() {
+    let moderator = ObjectId::new();
+    let tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+    let tournament_id = tournament.id.unwrap();
+
+    let service = create_service(
+        repo_returning(tournament),
+        MockInviteRepo::new(),
+        MockAuth::new(),
+    );
+    let result = service
+        .invite_qr_code(&tournament_id, "K7QM2XPD", &moderator)
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::Forbidden(
+            "You do not have permission to manage invites for this tournament".to_string()
+        )
+    );
+}
+
+// --- Participant moderation tests ---
+
+#[tokio::test]
+async fn test_join_suffixes_duplicate_display_name() {
+    let (tournament, tournament_id, _) = owned_tournament();
+    let mut mock_repo = MockTournamentRepo::new();
+    let existing = tournament.clone();
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(existing.clone())));
+    mock_repo
+        .expect_add_participant()
+        .withf(|_, user| user.name == "test user (2)")
+        .times(1)
+        .returning(move |_, _| Ok(Some(tournament.clone())));
+
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite
+        .expect_find_by_code()
+        .returning(move |code| Ok(Some(invite_for(tournament_id, code))));
+    mock_invite
+        .expect_redeem()
+        .withf(|_, redemption| redemption.display_name == "test user (2)")
+        .times(1)
+        .returning(|_, _| Ok(true));
+
+    let service = create_service(mock_repo, mock_invite, anonymous_token_auth());
+    let dto = JoinTournamentDto {
+        invite_code: "ABC12345".to_string(),
+        display_name: "  test   user ".to_string(),
+    };
+    let result = service.join_tournament(&tournament_id, dto).await;
+
+    assert_eq!(result.unwrap().display_name, "test user (2)");
+}
+
+#[tokio::test]
+async fn test_join_rejects_blocked_display_name() {
+    let mut mock_invite = MockInviteRepo::new();
+    mock_invite.expect_find_by_code().never();
+
+    let service = create_service(MockTournamentRepo::new(), mock_invite, MockAuth::new());
+    let dto = JoinTournamentDto {
+        invite_code: "ABC12345".to_string(),
+        display_name: "Organizer".to_string(),
+    };
+    let result = service.join_tournament(&ObjectId::new(), dto).await;
+
+    assert_eq!(result.unwrap_err(), "Display name is not allowed");
+}
+
+#[tokio::test]
+async fn test_update_participant_renames() {
+    let moderator = ObjectId::new();
+    let tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+    let tournament_id = tournament.id.unwrap();
+    let voter_id = tournament.users[0].voter_id.as_string();
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo
+        .expect_update()
+        .withf(|t| t.users[0].name == "Renamed Player")
+        .times(1)
+        .returning(|_| Ok(true));
+
+    let service = create_service_basic(mock_repo);
+    let dto = UpdateParticipantDto {
+        name: Some("Renamed Player".to_string()),
+        muted: None,
+    };
+    let result = service
+        .update_participant(&tournament_id, &voter_id, dto, &moderator)
+        .await;
+
+    let participant = result.unwrap();
+    assert_eq!(participant.name, "Renamed Player");
+    assert!(!participant.muted);
+}
+
+#[tokio::test]
+async fn test_update_participant_rejects_taken_name() {
+    let moderator = ObjectId::new();
+    let mut tournament = tournament_with_member(moderator, TournamentRole::Moderator);
+    let tournament_id = tournament.id.unwrap();
+    tournament.users.push(TournamentUser {
+        voter_id: VoterId::Anonymous("session-1".to_string()),
+        name: "Guest".to_string(),
+        muted: false,
+    });
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo.expect_update().never();
+
+    let service = create_service_basic(mock_repo);
+    let dto = UpdateParticipantDto {
+        name: Some("TEST USER".to_string()),
+        muted: None,
+    };
+    let result = service
+        .update_participant(&tournament_id, "session-1", dto, &moderator)
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::Invalid("Display name is already taken".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_muting_withdraws_votes_and_decides_waiting_matches() {
+    let (mut tournament, tournament_id, owner) = owned_tournament();
+    let registered = tournament.users[0].voter_id.clone();
+    tournament.users.push(TournamentUser {
+        voter_id: VoterId::Anonymous("session-1".to_string()),
+        name: "Guest".to_string(),
+        muted: false,
+    });
+    let opponent1 = tournament.rounds[0].matches[0].opponent1;
+    tournament.rounds[0].matches[0].votes.insert(
+        opponent1.to_string(),
+        vec![VoterId::Anonymous("session-1".to_string())],
+    );
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo
+        .expect_update()
+        .withf(move |t| {
+            // Only the guest is left to vote, and they already have
+            t.users[0].muted && t.status == TournamentStatus::Completed
+        })
+        .times(1)
+        .returning(|_| Ok(true));
+
+    let service = create_service_basic(mock_repo);
+    let dto = UpdateParticipantDto {
+        name: None,
+        muted: Some(true),
+    };
+    let result = service
+        .update_participant(&tournament_id, &registered.as_string(), dto, &owner)
+        .await;
+
+    assert!(result.unwrap().muted);
+}
+
+#[tokio::test]
+async fn test_vote_match_rejects_muted_participant() {
+    let mut tournament = tournament_with_anonymous_voter("session-1");
+    let tournament_id = ObjectId::new();
+    tournament.id = Some(tournament_id);
+    tournament.users[1].muted = true;
+    let voted_for = tournament.rounds[0].matches[0].opponent1;
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo.expect_update().never();
+
+    let service = create_service_basic(mock_repo);
+    let dto = VoteMatchDto {
+        tournament_id,
+        match_id: "test_match".to_string(),
+        voted_for,
+    };
+    let result = service
+        .vote_match(dto, VoterId::Anonymous("session-1".to_string()))
+        .await;
+
+    assert_eq!(result.unwrap_err(), "You have been muted in this tournament");
+}
+
+#[tokio::test]
+async fn test_kick_participant_removes_votes_and_broadcasts() {
+    let (mut tournament, tournament_id, owner) = owned_tournament();
+    tournament.users.push(TournamentUser {
+        voter_id: VoterId::Anonymous("session-1".to_string()),
+        name: "Guest".to_string(),
+        muted: false,
+    });
+    let opponent1 = tournament.rounds[0].matches[0].opponent1;
+    tournament.rounds[0].matches[0].votes.insert(
+        opponent1.to_string(),
+        vec![VoterId::Anonymous("session-1".to_string())],
+    );
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo
+        .expect_update()
+        .withf(|t| {
+            t.users.len() == 1
+                && t.rounds[0].matches[0].winner.is_none()
+                && t.rounds[0].matches[0].votes.values().all(Vec::is_empty)
+        })
+        .times(1)
+        .returning(|_| Ok(true));
+
+    let (service, broadcaster) =
+        create_service_with_broadcaster(mock_repo, MockInviteRepo::new(), MockAuth::new());
+    let mut rx = broadcaster.subscribe(&tournament_id);
+    service
+        .kick_participant(&tournament_id, "session-1", &owner)
+        .await
+        .unwrap();
+
+    match rx.try_recv().unwrap() {
+        TournamentEvent::ParticipantLeft {
+            voter_id,
+            display_name,
+            participant_count,
+        } => {
+            assert_eq!(voter_id, "session-1");
+            assert_eq!(display_name, "Guest");
+            assert_eq!(participant_count, 1);
+        }
+        other => panic!("Expected ParticipantLeft, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_kick_participant_broadcasts_nothing_when_saving_fails() {
+    let (mut tournament, tournament_id, owner) = owned_tournament();
+    tournament.users.push(TournamentUser {
+        voter_id: VoterId::Anonymous("session-1".to_string()),
+        name: "Guest".to_string(),
+        muted: false,
+    });
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo
+        .expect_update()
+        .times(1)
+        .returning(|_| Err("timeout".to_string()));
+
+    let (service, broadcaster) =
+        create_service_with_broadcaster(mock_repo, MockInviteRepo::new(), MockAuth::new());
+    let mut rx = broadcaster.subscribe(&tournament_id);
+    let result = service
+        .kick_participant(&tournament_id, "session-1", &owner)
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::Internal("Error updating tournament: timeout".to_string())
+    );
+    assert!(rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_kick_unknown_participant() {
+    let (tournament, tournament_id, owner) = owned_tournament();
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo.expect_update().never();
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .kick_participant(&tournament_id, "session-unknown", &owner)
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::NotFound("Participant not found".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_participant_moderation_requires_a_role() {
+    let (tournament, tournament_id, _) = owned_tournament();
+    let voter_id = tournament.users[0].voter_id.as_string();
+    let mut mock_repo = repo_returning(tournament);
+    mock_repo.expect_update().never();
+
+    let service = create_service_basic(mock_repo);
+    let result = service
+        .kick_participant(&tournament_id, &voter_id, &ObjectId::new())
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        MembershipError::Forbidden(
+            "You do not have permission to moderate participants of this tournament".to_string()
+        )
+    );
+}
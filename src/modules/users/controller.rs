@@ -1,14 +1,30 @@
 use std::sync::Arc;
 
 use mongodb::bson::oid::ObjectId;
+use rocket::data::{Data, ToByteUnit};
+use rocket::http::ContentType;
 use rocket::serde::json::Json;
+use rocket::tokio::io::AsyncReadExt;
 use rocket::State;
 
 use crate::common::guards::AuthenticatedUser;
+use crate::common::pagination::{PaginatedResponse, PaginationParams};
+use crate::config::account_deletion::AccountDeletionConfig;
 use crate::error::Error;
-use crate::modules::users::model::{PublicUserResponse, UpdateUserDto, UserResponse};
+use crate::modules::account_deletion::controller::deletion_error;
+use crate::modules::account_deletion::model::{AccountDeletionResponse, OwnedTournamentPolicy};
+use crate::modules::account_deletion::service::AccountDeletionService;
+use crate::modules::auth::model::SessionResponse;
+use crate::modules::auth::service::AuthService;
+use crate::modules::rate_limit::guard::UserSearchRateLimit;
+use crate::modules::users::model::{
+    PublicProfileResponse, PublicUserResponse, UpdateUserDto, UserResponse,
+};
+use crate::modules::users::profile::ProfileService;
 use crate::modules::users::service::UserService;
 
+const MAX_AVATAR_SIZE: u64 = 10 << 20;
+
 #[get("/me")]
 pub async fn get_me(
     auth: AuthenticatedUser,
@@ -23,21 +39,39 @@ pub async fn get_me(
     Ok(Json(UserResponse::from(user)))
 }
 
+#[get("/search?<q>&<params..>")]
+pub async fn search_users(
+    q: &str,
+    params: PaginationParams,
+    auth: AuthenticatedUser,
+    _rate_limit: UserSearchRateLimit,
+    profile_service: &State<Arc<dyn ProfileService + Send + Sync>>,
+) -> Result<Json<PaginatedResponse<PublicUserResponse>>, Error> {
+    let users = profile_service
+        .search_users(&auth.user_id, q, params)
+        .await
+        .map_err(Error::BadRequest)?;
+
+    Ok(Json(users))
+}
+
 #[get("/<id>")]
 pub async fn get_user(
     id: &str,
-    service: &State<Arc<dyn UserService + Send + Sync>>,
-) -> Result<Json<PublicUserResponse>, Error> {
+    profile_service: &State<Arc<dyn ProfileService + Send + Sync>>,
+) -> Result<Json<PublicProfileResponse>, Error> {
     let user_id =
         ObjectId::parse_str(id).map_err(|_| Error::BadRequest("Invalid user ID".to_string()))?;
 
-    let user = service
-        .find_by_id(&user_id)
+    let profile = profile_service
+        .public_profile(&user_id)
         .await
-        .map_err(|e| Error::Internal(e))?
-        .ok_or(Error::NotFound("User not found".to_string()))?;
+        .map_err(|e| match e.as_str() {
+            "User not found" => Error::NotFound(e),
+            _ => Error::Internal(e),
+        })?;
 
-    Ok(Json(PublicUserResponse::from(user)))
+    Ok(Json(profile))
 }
 
 #[put("/me", data = "<update_dto>")]
@@ -46,6 +80,17 @@ pub async fn update_me(
     service: &State<Arc<dyn UserService + Send + Sync>>,
     update_dto: Json<UpdateUserDto>,
 ) -> Result<Json<UserResponse>, Error> {
+    if let Some(password) = &update_dto.password {
+        let current = service
+            .find_by_id(&auth.user_id)
+            .await
+            .map_err(Error::Internal)?
+            .ok_or(Error::NotFound("User not found".to_string()))?;
+        let email = update_dto.email.as_deref().unwrap_or(&current.email);
+        let name = update_dto.name.as_deref().unwrap_or(&current.name);
+        service.check_password(password, email, name)?;
+    }
+
     let user = service
         .update_user(&auth.user_id, update_dto.into_inner())
         .await
@@ -54,19 +99,104 @@ pub async fn update_me(
     Ok(Json(UserResponse::from(user)))
 }
 
-#[delete("/me")]
+#[put("/me/avatar", data = "<file>")]
+pub async fn set_avatar(
+    auth: AuthenticatedUser,
+    profile_service: &State<Arc<dyn ProfileService + Send + Sync>>,
+    content_type: &ContentType,
+    file: Data<'_>,
+) -> Result<Json<UserResponse>, Error> {
+    if content_type.top() != "image" {
+        return Err(Error::BadRequest("File must be an image".to_string()));
+    }
+
+    let mut buffer = Vec::new();
+    file.open(MAX_AVATAR_SIZE.bytes())
+        .read_to_end(&mut buffer)
+        .await
+        .map_err(|e| Error::BadRequest(format!("Failed to read file: {}", e)))?;
+
+    let user = profile_service
+        .set_avatar(&auth.user_id, buffer)
+        .await
+        .map_err(|e| Error::BadRequest(format!("Failed to process image: {}", e)))?;
+
+    Ok(Json(UserResponse::from(user)))
+}
+
+#[delete("/me/avatar")]
+pub async fn remove_avatar(
+    auth: AuthenticatedUser,
+    profile_service: &State<Arc<dyn ProfileService + Send + Sync>>,
+) -> Result<Json<UserResponse>, Error> {
+    let user = profile_service
+        .remove_avatar(&auth.user_id)
+        .await
+        .map_err(Error::NotFound)?;
+
+    Ok(Json(UserResponse::from(user)))
+}
+
+/// Schedules the account for deletion. `tournaments` says whether owned
+/// tournaments are transferred (the default) or deleted.
+#[delete("/me?<tournaments>")]
 pub async fn delete_me(
     auth: AuthenticatedUser,
-    service: &State<Arc<dyn UserService + Send + Sync>>,
+    tournaments: Option<&str>,
+    service: &State<Arc<dyn AccountDeletionService + Send + Sync>>,
+    config: &State<AccountDeletionConfig>,
+) -> Result<Json<AccountDeletionResponse>, Error> {
+    let policy = tournaments
+        .map(str::parse::<OwnedTournamentPolicy>)
+        .transpose()
+        .map_err(Error::BadRequest)?
+        .unwrap_or_default();
+
+    let deletion = service
+        .schedule(&auth.user_id, policy, config.grace_period_secs())
+        .await
+        .map_err(deletion_error)?;
+
+    Ok(Json(AccountDeletionResponse::from(deletion)))
+}
+
+#[get("/me/sessions")]
+pub async fn list_sessions(
+    auth: AuthenticatedUser,
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+) -> Result<Json<Vec<SessionResponse>>, Error> {
+    let sessions = auth_service
+        .list_sessions(&auth.user_id)
+        .await
+        .map_err(Error::Internal)?;
+
+    Ok(Json(sessions))
+}
+
+#[delete("/me/sessions/<id>")]
+pub async fn revoke_session(
+    id: &str,
+    auth: AuthenticatedUser,
+    auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
 ) -> Result<Json<serde_json::Value>, Error> {
-    service
-        .delete_user(&auth.user_id)
+    auth_service
+        .revoke_session(&auth.user_id, id)
         .await
-        .map_err(|e| Error::Internal(e))?;
+        .map_err(Error::NotFound)?;
 
-    Ok(Json(serde_json::json!({ "message": "User deleted successfully" })))
+    Ok(Json(serde_json::json!({ "message": "Session revoked successfully" })))
 }
 
 pub fn routes() -> Vec<rocket::Route> {
-    routes![get_me, get_user, update_me, delete_me]
+    routes![
+        get_me,
+        search_users,
+        get_user,
+        update_me,
+        set_avatar,
+        remove_avatar,
+        delete_me,
+        list_sessions,
+        revoke_session
+    ]
 }
@@ -0,0 +1,87 @@
+use argon2::password_hash::{
+    Error as HashError, PasswordHash, PasswordHasher as _, PasswordVerifier, SaltString,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::RngCore;
+
+use crate::config::password::PasswordHashConfig;
+
+/// Hashes and checks account passwords.
+pub trait PasswordHasher: Send + Sync {
+    fn hash(&self, password: &str) -> Result<String, String>;
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, String>;
+    /// Whether `hash` was made with another algorithm or other parameters
+    /// and should be replaced once the password is known.
+    fn needs_rehash(&self, hash: &str) -> bool;
+}
+
+/// Hashes with Argon2id and still accepts bcrypt hashes from before the
+/// switch, reporting them as needing a rehash.
+pub struct Argon2Hasher {
+    params: Params,
+}
+
+impl Argon2Hasher {
+    pub fn new(config: &PasswordHashConfig) -> Result<Self, String> {
+        Ok(Self {
+            params: config.params()?,
+        })
+    }
+
+    fn argon2(&self) -> Argon2<'static> {
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+    }
+}
+
+fn is_bcrypt(hash: &str) -> bool {
+    ["$2a$", "$2b$", "$2x$", "$2y$"]
+        .iter()
+        .any(|prefix| hash.starts_with(prefix))
+}
+
+impl PasswordHasher for Argon2Hasher {
+    fn hash(&self, password: &str) -> Result<String, String> {
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let salt =
+            SaltString::encode_b64(&salt).map_err(|e| format!("Failed to hash password: {}", e))?;
+
+        self.argon2()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| format!("Failed to hash password: {}", e))
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, String> {
+        if is_bcrypt(hash) {
+            return bcrypt::verify(password.as_bytes(), hash)
+                .map_err(|e| format!("Error verifying password: {}", e));
+        }
+
+        let parsed =
+            PasswordHash::new(hash).map_err(|e| format!("Error verifying password: {}", e))?;
+        match self.argon2().verify_password(password.as_bytes(), &parsed) {
+            Ok(()) => Ok(true),
+            Err(HashError::Password) => Ok(false),
+            Err(e) => Err(format!("Error verifying password: {}", e)),
+        }
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        let Ok(parsed) = PasswordHash::new(hash) else {
+            return true;
+        };
+        if parsed.algorithm != Algorithm::Argon2id.ident() {
+            return true;
+        }
+
+        match Params::try_from(&parsed) {
+            Ok(params) => {
+                params.m_cost() != self.params.m_cost()
+                    || params.t_cost() != self.params.t_cost()
+                    || params.p_cost() != self.params.p_cost()
+            }
+            Err(_) => true,
+        }
+    }
+}
@@ -1,5 +1,8 @@
 pub mod controller;
+pub mod hasher;
 pub mod model;
+pub mod password_policy;
+pub mod profile;
 pub mod repository;
 pub mod service;
 
@@ -1,4 +1,5 @@
-use crate::common::json::{serialize_datetime, serialize_oid};
+use crate::common::json::{serialize_datetime, serialize_oid, serialize_option_datetime};
+use crate::modules::account_deletion::model::AccountDeletion;
 use mongodb::bson::{doc, oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 
@@ -9,10 +10,143 @@ pub struct User {
     pub email: String,
     pub name: String,
     pub password: String,
+    #[serde(default)]
+    pub email_verified: bool,
+    #[serde(default)]
+    pub two_factor: TwoFactor,
+    #[serde(default)]
+    pub role: UserRole,
+    /// Set while a moderator has suspended the account
+    #[serde(default)]
+    pub suspended_at: Option<DateTime>,
+    /// Set while the account is waiting to be deleted
+    #[serde(default)]
+    pub deletion: Option<AccountDeletion>,
+    #[serde(default)]
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub links: Vec<ProfileLink>,
+    /// One image per entry of `AVATAR_SIZES`; empty without an avatar
+    #[serde(default)]
+    pub avatar: Vec<AvatarVariant>,
+    #[serde(default)]
+    pub privacy: PrivacySettings,
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
 
+/// Avatar edge lengths in pixels
+pub const AVATAR_SIZES: [u32; 3] = [64, 128, 256];
+pub const MAX_BIO_LENGTH: usize = 500;
+pub const MAX_PROFILE_LINKS: usize = 5;
+pub const MAX_LINK_LABEL_LENGTH: usize = 50;
+/// Stored in place of a hash for accounts without a password; never verifies
+pub const UNUSABLE_PASSWORD: &str = "!";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileLink {
+    pub label: String,
+    pub url: String,
+}
+
+impl ProfileLink {
+    pub fn validate(&self) -> Result<(), String> {
+        let label = self.label.trim();
+        if label.is_empty() || label.chars().count() > MAX_LINK_LABEL_LENGTH {
+            return Err(format!(
+                "Link labels must be between 1 and {} characters",
+                MAX_LINK_LABEL_LENGTH
+            ));
+        }
+        if !self.url.starts_with("https://") && !self.url.starts_with("http://") {
+            return Err("Links must be http or https URLs".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AvatarVariant {
+    pub size: u32,
+    pub image_id: ObjectId,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct AvatarResponse {
+    pub size: u32,
+    pub url: String,
+}
+
+fn avatar_response(avatar: Vec<AvatarVariant>) -> Vec<AvatarResponse> {
+    avatar
+        .into_iter()
+        .map(|v| AvatarResponse {
+            size: v.size,
+            url: v.url,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrivacySettings {
+    /// Whether the user shows up in `GET /api/users/search`
+    #[serde(default = "default_discoverable")]
+    pub discoverable: bool,
+}
+
+fn default_discoverable() -> bool {
+    true
+}
+
+impl Default for PrivacySettings {
+    fn default() -> Self {
+        Self {
+            discoverable: default_discoverable(),
+        }
+    }
+}
+
+/// Platform-wide role. Each role includes the permissions of the ones
+/// before it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    #[default]
+    User,
+    /// Can list, search and suspend users
+    Moderator,
+    /// Can also delete users, change roles and manage any tournament
+    Admin,
+}
+
+impl std::str::FromStr for UserRole {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "user" => Ok(Self::User),
+            "moderator" => Ok(Self::Moderator),
+            "admin" => Ok(Self::Admin),
+            _ => Err(format!("Unknown role: {}", value)),
+        }
+    }
+}
+
+/// TOTP second factor. `secret` is only set once enrollment is confirmed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TwoFactor {
+    /// Base32 TOTP secret
+    pub secret: Option<String>,
+    /// Secret offered during enrollment, until a code confirms it
+    pub pending_secret: Option<String>,
+    /// SHA-256 of each unused recovery code
+    #[serde(default)]
+    pub recovery_codes: Vec<String>,
+    /// Last time step accepted, so a code cannot be replayed
+    pub last_used_step: Option<i64>,
+}
+
 impl User {
     pub fn new(email: String, name: String, password: String) -> Self {
         let now = DateTime::now();
@@ -21,10 +155,27 @@ impl User {
             email,
             name,
             password,
+            email_verified: false,
+            two_factor: TwoFactor::default(),
+            role: UserRole::User,
+            suspended_at: None,
+            deletion: None,
+            bio: None,
+            links: Vec::new(),
+            avatar: Vec::new(),
+            privacy: PrivacySettings::default(),
             created_at: now,
             updated_at: now,
         }
     }
+
+    pub fn two_factor_enabled(&self) -> bool {
+        self.two_factor.secret.is_some()
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.suspended_at.is_some()
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -39,6 +190,10 @@ pub struct UpdateUserDto {
     pub email: Option<String>,
     pub name: Option<String>,
     pub password: Option<String>,
+    /// An empty bio removes it
+    pub bio: Option<String>,
+    pub links: Option<Vec<ProfileLink>>,
+    pub privacy: Option<PrivacySettings>,
 }
 
 #[derive(Debug, Serialize)]
@@ -47,6 +202,18 @@ pub struct UserResponse {
     pub id: ObjectId,
     pub email: String,
     pub name: String,
+    pub email_verified: bool,
+    pub two_factor_enabled: bool,
+    pub role: UserRole,
+    #[serde(serialize_with = "serialize_option_datetime")]
+    pub suspended_at: Option<DateTime>,
+    /// When a requested account deletion takes effect
+    #[serde(serialize_with = "serialize_option_datetime")]
+    pub deletion_scheduled_for: Option<DateTime>,
+    pub bio: Option<String>,
+    pub links: Vec<ProfileLink>,
+    pub avatar: Vec<AvatarResponse>,
+    pub privacy: PrivacySettings,
     #[serde(serialize_with = "serialize_datetime")]
     pub created_at: DateTime,
     #[serde(serialize_with = "serialize_datetime")]
@@ -58,15 +225,39 @@ pub struct PublicUserResponse {
     #[serde(serialize_with = "serialize_oid")]
     pub id: ObjectId,
     pub name: String,
+    pub bio: Option<String>,
+    pub links: Vec<ProfileLink>,
+    pub avatar: Vec<AvatarResponse>,
     #[serde(serialize_with = "serialize_datetime")]
     pub created_at: DateTime,
 }
 
+/// What others see of a user, with activity counts.
+#[derive(Debug, Serialize)]
+pub struct PublicProfileResponse {
+    #[serde(flatten)]
+    pub user: PublicUserResponse,
+    pub stats: ProfileStats,
+}
+
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct ProfileStats {
+    /// Tournaments the user owns or helps organize
+    pub tournaments_organized: u64,
+    pub tournaments_participated: u64,
+    pub opponents_created: u64,
+    /// Tournaments won by one of the user's opponents
+    pub wins: u64,
+}
+
 impl From<User> for PublicUserResponse {
     fn from(user: User) -> Self {
         Self {
             id: user.id.expect("User must have an id to convert to response"),
             name: user.name,
+            bio: user.bio,
+            links: user.links,
+            avatar: avatar_response(user.avatar),
             created_at: user.created_at,
         }
     }
@@ -76,8 +267,17 @@ impl From<User> for UserResponse {
     fn from(user: User) -> Self {
         Self {
             id: user.id.expect("User must have an id to convert to response"),
+            two_factor_enabled: user.two_factor_enabled(),
             email: user.email,
             name: user.name,
+            email_verified: user.email_verified,
+            role: user.role,
+            suspended_at: user.suspended_at,
+            deletion_scheduled_for: user.deletion.map(|d| d.scheduled_for),
+            bio: user.bio,
+            links: user.links,
+            avatar: avatar_response(user.avatar),
+            privacy: user.privacy,
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
@@ -0,0 +1,212 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+
+use crate::config::password_policy::PasswordPolicyConfig;
+
+const BUNDLED_BREACHED_LIST: &str = include_str!("../../../data/breached-passwords.txt");
+
+/// Length of the hash prefix used for range lookups.
+const RANGE_PREFIX_LEN: usize = 5;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PasswordRule {
+    MinLength,
+    CharacterClasses,
+    ContainsEmail,
+    ContainsName,
+    Breached,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PasswordViolation {
+    pub rule: PasswordRule,
+    pub message: String,
+}
+
+/// Every rule a password failed, in the order they were checked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordPolicyError {
+    pub violations: Vec<PasswordViolation>,
+}
+
+impl PasswordPolicyError {
+    pub fn has(&self, rule: PasswordRule) -> bool {
+        self.violations.iter().any(|v| v.rule == rule)
+    }
+}
+
+impl fmt::Display for PasswordPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<&str> = self.violations.iter().map(|v| v.message.as_str()).collect();
+        write!(f, "{}", messages.join("; "))
+    }
+}
+
+/// Uppercase SHA-1 hashes of known passwords. Lookups go through a
+/// 5-character prefix the way the Pwned Passwords range API works, so the
+/// list can be swapped for a downloaded or remote one.
+pub struct BreachedPasswordList {
+    hashes: BTreeSet<String>,
+}
+
+impl BreachedPasswordList {
+    /// Reads one hash per line; `#` comments and `:count` suffixes are
+    /// ignored.
+    pub fn parse(contents: &str) -> Self {
+        let hashes = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split(':').next())
+            .map(str::to_uppercase)
+            .collect();
+        Self { hashes }
+    }
+
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_BREACHED_LIST)
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Error reading breached password list {}: {}", path, e))?;
+        Ok(Self::parse(&contents))
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Hash suffixes listed under `prefix`.
+    pub fn range(&self, prefix: &str) -> Vec<&str> {
+        let prefix = prefix.to_uppercase();
+        self.hashes
+            .range(prefix.clone()..)
+            .take_while(|hash| hash.starts_with(&prefix))
+            .map(|hash| &hash[prefix.len()..])
+            .collect()
+    }
+
+    pub fn contains(&self, password: &str) -> bool {
+        let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+        let (prefix, suffix) = hash.split_at(RANGE_PREFIX_LEN);
+        self.range(prefix).contains(&suffix)
+    }
+}
+
+pub struct PasswordPolicy {
+    min_length: usize,
+    min_character_classes: usize,
+    breached: BreachedPasswordList,
+}
+
+impl PasswordPolicy {
+    pub fn new(config: &PasswordPolicyConfig) -> Result<Self, String> {
+        let breached = match &config.breached_list_file {
+            Some(path) => BreachedPasswordList::load(path)?,
+            None => BreachedPasswordList::bundled(),
+        };
+        // A custom list without a single valid line would silently disable the check
+        if breached.is_empty() {
+            tracing::warn!("Breached password list is empty");
+        } else {
+            tracing::debug!(hashes = breached.len(), "Loaded breached password list");
+        }
+
+        Ok(Self {
+            min_length: config.min_length,
+            min_character_classes: config.min_character_classes,
+            breached,
+        })
+    }
+
+    /// Checks `password` against every rule. `email` and `name` belong to
+    /// the account and may be empty when unknown.
+    pub fn check(
+        &self,
+        password: &str,
+        email: &str,
+        name: &str,
+    ) -> Result<(), PasswordPolicyError> {
+        let mut violations = Vec::new();
+        let mut fail = |rule, message: String| violations.push(PasswordViolation { rule, message });
+
+        if password.chars().count() < self.min_length {
+            fail(
+                PasswordRule::MinLength,
+                format!("Password must be at least {} characters", self.min_length),
+            );
+        }
+
+        if character_classes(password) < self.min_character_classes {
+            fail(
+                PasswordRule::CharacterClasses,
+                format!(
+                    "Password must contain at least {} of: lowercase letters, uppercase letters, \
+                     digits, symbols",
+                    self.min_character_classes
+                ),
+            );
+        }
+
+        let lowered = password.to_lowercase();
+        let email = email.trim().to_lowercase();
+        let local_part = email.split('@').next().unwrap_or_default();
+        if contains_fragment(&lowered, local_part) || contains_fragment(&lowered, &email) {
+            fail(
+                PasswordRule::ContainsEmail,
+                "Password must not contain your email address".to_string(),
+            );
+        }
+
+        let name = name.to_lowercase();
+        if name
+            .split_whitespace()
+            .any(|part| contains_fragment(&lowered, part))
+        {
+            fail(
+                PasswordRule::ContainsName,
+                "Password must not contain your name".to_string(),
+            );
+        }
+
+        if self.breached.contains(password) || self.breached.contains(&lowered) {
+            fail(
+                PasswordRule::Breached,
+                "Password is too common or has appeared in a data breach".to_string(),
+            );
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PasswordPolicyError { violations })
+        }
+    }
+}
+
+fn character_classes(password: &str) -> usize {
+    let checks: [fn(&char) -> bool; 4] = [
+        |c| c.is_lowercase(),
+        |c| c.is_uppercase(),
+        |c| c.is_ascii_digit(),
+        |c| !c.is_alphanumeric(),
+    ];
+    checks
+        .iter()
+        .filter(|check| password.chars().any(|c| check(&c)))
+        .count()
+}
+
+/// Short fragments such as initials would reject too many passwords.
+fn contains_fragment(password: &str, fragment: &str) -> bool {
+    fragment.chars().count() >= 3 && password.contains(fragment)
+}
@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mongodb::bson::{oid::ObjectId, DateTime};
+
+use crate::common::pagination::{PaginatedResponse, PaginationParams};
+use crate::modules::images::service::ImageService;
+use crate::modules::opponents::repository::OpponentRepository;
+use crate::modules::tournaments::model::VoterId;
+use crate::modules::tournaments::repository::TournamentRepository;
+use crate::modules::users::model::{
+    AvatarVariant, ProfileStats, PublicProfileResponse, PublicUserResponse, User, AVATAR_SIZES,
+};
+use crate::modules::users::repository::UserRepository;
+
+#[async_trait]
+pub trait ProfileService: Send + Sync {
+    async fn public_profile(&self, user_id: &ObjectId) -> Result<PublicProfileResponse, String>;
+    /// Replaces the avatar with the uploaded image, stored in every size of
+    /// `AVATAR_SIZES`.
+    async fn set_avatar(&self, user_id: &ObjectId, file_data: Vec<u8>) -> Result<User, String>;
+    async fn remove_avatar(&self, user_id: &ObjectId) -> Result<User, String>;
+    /// Finds other users by name or email for inviting them, skipping those
+    /// who opted out of discovery.
+    async fn search_users(
+        &self,
+        user_id: &ObjectId,
+        query: &str,
+        params: PaginationParams,
+    ) -> Result<PaginatedResponse<PublicUserResponse>, String>;
+}
+
+const MIN_SEARCH_LENGTH: usize = 2;
+const MAX_SEARCH_LENGTH: usize = 64;
+
+pub struct ProfileServiceImpl {
+    user_repository: Arc<dyn UserRepository>,
+    tournament_repository: Arc<dyn TournamentRepository>,
+    opponent_repository: Arc<dyn OpponentRepository>,
+    image_service: Arc<dyn ImageService + Send + Sync>,
+}
+
+impl ProfileServiceImpl {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        tournament_repository: Arc<dyn TournamentRepository>,
+        opponent_repository: Arc<dyn OpponentRepository>,
+        image_service: Arc<dyn ImageService + Send + Sync>,
+    ) -> Self {
+        Self {
+            user_repository,
+            tournament_repository,
+            opponent_repository,
+            image_service,
+        }
+    }
+
+    async fn find_user(&self, user_id: &ObjectId) -> Result<User, String> {
+        self.user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| "User not found".to_string())
+    }
+
+    /// Swaps the avatar and deletes the images of the previous one.
+    async fn replace_avatar(
+        &self,
+        user_id: &ObjectId,
+        mut user: User,
+        avatar: Vec<AvatarVariant>,
+    ) -> Result<User, String> {
+        let previous = std::mem::replace(&mut user.avatar, avatar);
+        user.updated_at = DateTime::now();
+        self.user_repository.update(&user).await?;
+
+        for variant in previous {
+            if let Err(e) = self
+                .image_service
+                .delete_image(&variant.image_id, user_id)
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to delete previous avatar image");
+            }
+        }
+        Ok(user)
+    }
+}
+
+#[async_trait]
+impl ProfileService for ProfileServiceImpl {
+    async fn public_profile(&self, user_id: &ObjectId) -> Result<PublicProfileResponse, String> {
+        let user = self.find_user(user_id).await?;
+
+        let opponent_ids = self
+            .opponent_repository
+            .find_ids_by_creator(user_id)
+            .await?;
+        let wins = if opponent_ids.is_empty() {
+            0
+        } else {
+            self.tournament_repository
+                .count_won_by(&opponent_ids)
+                .await?
+        };
+        let stats = ProfileStats {
+            tournaments_organized: self
+                .tournament_repository
+                .count_by_organizer(user_id)
+                .await?,
+            tournaments_participated: self
+                .tournament_repository
+                .count_by_participant(&VoterId::Registered(*user_id))
+                .await?,
+            opponents_created: opponent_ids.len() as u64,
+            wins,
+        };
+
+        Ok(PublicProfileResponse {
+            user: PublicUserResponse::from(user),
+            stats,
+        })
+    }
+
+    async fn set_avatar(&self, user_id: &ObjectId, file_data: Vec<u8>) -> Result<User, String> {
+        let user = self.find_user(user_id).await?;
+
+        let images = self
+            .image_service
+            .upload_square_variants(file_data, &AVATAR_SIZES, *user_id)
+            .await?;
+        let avatar = AVATAR_SIZES
+            .iter()
+            .zip(images)
+            .filter_map(|(&size, image)| {
+                Some(AvatarVariant {
+                    size,
+                    image_id: image.id?,
+                    url: image.url,
+                })
+            })
+            .collect();
+
+        self.replace_avatar(user_id, user, avatar).await
+    }
+
+    async fn remove_avatar(&self, user_id: &ObjectId) -> Result<User, String> {
+        let user = self.find_user(user_id).await?;
+        if user.avatar.is_empty() {
+            return Err("No avatar to remove".to_string());
+        }
+
+        self.replace_avatar(user_id, user, Vec::new()).await
+    }
+
+    async fn search_users(
+        &self,
+        user_id: &ObjectId,
+        query: &str,
+        params: PaginationParams,
+    ) -> Result<PaginatedResponse<PublicUserResponse>, String> {
+        let query = query.trim();
+        let length = query.chars().count();
+        if !(MIN_SEARCH_LENGTH..=MAX_SEARCH_LENGTH).contains(&length) {
+            return Err(format!(
+                "Search query must be between {} and {} characters",
+                MIN_SEARCH_LENGTH, MAX_SEARCH_LENGTH
+            ));
+        }
+        let cursor = params.cursor_oid()?;
+        let limit = params.effective_limit();
+
+        let users = self
+            .user_repository
+            .find_discoverable(query, user_id, cursor, limit)
+            .await?;
+
+        let users = users.into_iter().map(PublicUserResponse::from).collect();
+        Ok(PaginatedResponse::with_cursor(users, limit, |u| {
+            u.id.to_string()
+        }))
+    }
+}
@@ -1,7 +1,18 @@
-use crate::modules::users::model::User;
+use crate::modules::users::model::{User, UserRole};
 use async_trait::async_trait;
-use mongodb::bson::{doc, oid::ObjectId};
+use futures::TryStreamExt;
+use mongodb::bson::{doc, oid::ObjectId, DateTime, Document};
+use mongodb::options::{Collation, CollationStrength};
 use mongodb::Database;
+use std::time::Duration;
+
+/// Queries of this many characters also match names one typo away. The
+/// typo branch scans the names, so longer queries stay prefix-only.
+const FUZZY_MIN_LENGTH: usize = 4;
+const FUZZY_MAX_LENGTH: usize = 12;
+/// Upper bound on a directory search, so a slow scan fails instead of
+/// piling up
+const DIRECTORY_SEARCH_MAX_TIME: Duration = Duration::from_secs(2);
 
 #[async_trait]
 pub trait UserRepository: Send + Sync {
@@ -10,6 +21,109 @@ pub trait UserRepository: Send + Sync {
     async fn find_by_id(&self, id: &ObjectId) -> Result<Option<User>, String>;
     async fn update(&self, user: &User) -> Result<(), String>;
     async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+    /// Atomically removes one recovery code hash. Returns `false` when the
+    /// user has no such code.
+    async fn remove_recovery_code(&self, id: &ObjectId, code_hash: &str) -> Result<bool, String>;
+    /// Users whose email or name contains `query`, newest first, fetching
+    /// one more than `limit` so callers can tell whether there are more.
+    async fn search(
+        &self,
+        query: Option<String>,
+        role: Option<UserRole>,
+        cursor: Option<ObjectId>,
+        limit: i64,
+    ) -> Result<Vec<User>, String>;
+    /// Discoverable, active users other than `exclude` matching `query`
+    /// (see `directory_filter`), newest first, fetching one more than
+    /// `limit`.
+    async fn find_discoverable(
+        &self,
+        query: &str,
+        exclude: &ObjectId,
+        cursor: Option<ObjectId>,
+        limit: i64,
+    ) -> Result<Vec<User>, String>;
+    /// Accounts whose deletion grace period ended before `now`, oldest first.
+    async fn find_due_for_deletion(&self, now: DateTime, limit: i64) -> Result<Vec<User>, String>;
+}
+
+/// Escapes `text` so it matches literally inside a regular expression.
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Case-insensitive comparison, matching the `name_ci` and `email_ci`
+/// indexes.
+pub fn search_collation() -> Collation {
+    Collation::builder()
+        .locale("en")
+        .strength(CollationStrength::Secondary)
+        .build()
+}
+
+/// A regular expression matching any word that starts with `query` or with
+/// a string one edit (substitution, insertion, deletion or transposition of
+/// neighbours) away from it.
+fn fuzzy_pattern(query: &str) -> String {
+    let chars: Vec<String> = query.chars().map(|c| escape_regex(&c.to_string())).collect();
+    let join = |parts: &[String]| parts.concat();
+
+    let mut alternatives = vec![join(&chars)];
+    for i in 0..chars.len() {
+        let (before, after) = (&chars[..i], &chars[i + 1..]);
+        alternatives.push(format!("{}.{}", join(before), join(after)));
+        alternatives.push(format!("{}{}", join(before), join(after)));
+        alternatives.push(format!("{}.{}", join(before), join(&chars[i..])));
+        if i + 1 < chars.len() {
+            alternatives.push(format!(
+                "{}{}{}{}",
+                join(before),
+                chars[i + 1],
+                chars[i],
+                join(&chars[i + 2..])
+            ));
+        }
+    }
+    alternatives.sort();
+    alternatives.dedup();
+
+    format!("(?:^|\\s)(?:{})", alternatives.join("|"))
+}
+
+/// Matches names starting with `query`, and emails starting with it once it
+/// contains an `@`, so addresses cannot be guessed letter by letter. Queries
+/// of `FUZZY_MIN_LENGTH` to `FUZZY_MAX_LENGTH` characters also match any
+/// word of the name with up to one typo; that branch cannot use an index
+/// and scans the names.
+pub fn directory_filter(query: &str, exclude: &ObjectId, cursor: Option<ObjectId>) -> Document {
+    let prefix = doc! { "$gte": query, "$lt": format!("{}\u{ffff}", query) };
+    let mut matches = vec![doc! { "name": prefix.clone() }];
+    if query.contains('@') {
+        matches.push(doc! { "email": prefix });
+    }
+    if (FUZZY_MIN_LENGTH..=FUZZY_MAX_LENGTH).contains(&query.chars().count()) {
+        matches.push(doc! { "name": { "$regex": fuzzy_pattern(query), "$options": "i" } });
+    }
+
+    let mut id = doc! { "$ne": exclude };
+    if let Some(cursor_id) = cursor {
+        id.insert("$lt", cursor_id);
+    }
+
+    doc! {
+        "$or": matches,
+        "privacy.discoverable": { "$ne": false },
+        "suspended_at": null,
+        "deletion": null,
+        "_id": id,
+    }
 }
 
 pub struct UserRepositoryImpl {
@@ -77,12 +191,160 @@ impl UserRepository for UserRepositoryImpl {
         }
         Ok(())
     }
+
+    async fn remove_recovery_code(&self, id: &ObjectId, code_hash: &str) -> Result<bool, String> {
+        let result = self
+            .db
+            .collection::<User>("users")
+            .update_one(
+                doc! { "_id": id, "two_factor.recovery_codes": code_hash },
+                doc! { "$pull": { "two_factor.recovery_codes": code_hash } },
+            )
+            .await
+            .map_err(|e| format!("Error using recovery code: {}", e))?;
+        Ok(result.modified_count == 1)
+    }
+
+    async fn search(
+        &self,
+        query: Option<String>,
+        role: Option<UserRole>,
+        cursor: Option<ObjectId>,
+        limit: i64,
+    ) -> Result<Vec<User>, String> {
+        let mut filter = doc! {};
+        if let Some(query) = query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            let pattern = doc! { "$regex": escape_regex(query), "$options": "i" };
+            filter.insert(
+                "$or",
+                vec![doc! { "email": pattern.clone() }, doc! { "name": pattern }],
+            );
+        }
+        if let Some(role) = role {
+            filter.insert(
+                "role",
+                mongodb::bson::to_bson(&role).map_err(|e| format!("Invalid role: {}", e))?,
+            );
+        }
+        if let Some(cursor_id) = cursor {
+            filter.insert("_id", doc! { "$lt": cursor_id });
+        }
+
+        self.db
+            .collection::<User>("users")
+            .find(filter)
+            .sort(doc! { "_id": -1 })
+            .limit(limit + 1)
+            .await
+            .map_err(|e| format!("Error searching users: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error searching users: {}", e))
+    }
+
+    async fn find_discoverable(
+        &self,
+        query: &str,
+        exclude: &ObjectId,
+        cursor: Option<ObjectId>,
+        limit: i64,
+    ) -> Result<Vec<User>, String> {
+        self.db
+            .collection::<User>("users")
+            .find(directory_filter(query, exclude, cursor))
+            .collation(search_collation())
+            .sort(doc! { "_id": -1 })
+            .limit(limit + 1)
+            .max_time(DIRECTORY_SEARCH_MAX_TIME)
+            .await
+            .map_err(|e| format!("Error searching users: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error searching users: {}", e))
+    }
+
+    async fn find_due_for_deletion(&self, now: DateTime, limit: i64) -> Result<Vec<User>, String> {
+        self.db
+            .collection::<User>("users")
+            .find(doc! { "deletion.scheduled_for": { "$lte": now } })
+            .sort(doc! { "deletion.scheduled_for": 1 })
+            .limit(limit)
+            .await
+            .map_err(|e| format!("Error finding accounts to delete: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| format!("Error finding accounts to delete: {}", e))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_escape_regex() {
+        assert_eq!(escape_regex("a.b+c@d.com"), "a\\.b\\+c@d\\.com");
+        assert_eq!(escape_regex("plain"), "plain");
+    }
+
+    #[test]
+    fn test_fuzzy_pattern() {
+        let pattern = fuzzy_pattern("ab.c");
+
+        let alternatives: Vec<&str> = pattern
+            .strip_prefix("(?:^|\\s)(?:")
+            .and_then(|p| p.strip_suffix(')'))
+            .unwrap()
+            .split('|')
+            .collect();
+        // exact, substitution, deletion, insertion and transposition
+        for alternative in ["ab\\.c", "a.\\.c", "ab\\.", ".ab\\.c", "a\\.bc"] {
+            assert!(alternatives.contains(&alternative), "{} in {}", alternative, pattern);
+        }
+    }
+
+    #[test]
+    fn test_directory_filter_short_query_is_prefix_only() {
+        let exclude = ObjectId::new();
+
+        let filter = directory_filter("jo", &exclude, None);
+
+        let matches = filter.get_array("$or").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            filter.get_document("_id").unwrap(),
+            &doc! { "$ne": exclude }
+        );
+        assert_eq!(
+            filter.get_document("privacy.discoverable").unwrap(),
+            &doc! { "$ne": false }
+        );
+    }
+
+    #[test]
+    fn test_directory_filter_email_and_fuzzy() {
+        let exclude = ObjectId::new();
+        let cursor = ObjectId::new();
+
+        let filter = directory_filter("jane@ex", &exclude, Some(cursor));
+
+        let matches = filter.get_array("$or").unwrap();
+        assert_eq!(matches.len(), 3);
+        assert!(matches[1].as_document().unwrap().contains_key("email"));
+        assert_eq!(
+            filter.get_document("_id").unwrap(),
+            &doc! { "$ne": exclude, "$lt": cursor }
+        );
+    }
+
+    #[test]
+    fn test_directory_filter_long_query_is_prefix_only() {
+        let filter = directory_filter("Bartholomew Jo", &ObjectId::new(), None);
+
+        let matches = filter.get_array("$or").unwrap();
+        assert_eq!(matches.len(), 1);
+    }
+
     #[test]
     fn test_user_repository_impl_creation() {
         let _repo_fn = UserRepositoryImpl::new;
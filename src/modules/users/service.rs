@@ -1,7 +1,10 @@
-use crate::modules::users::model::{UpdateUserDto, User};
+use crate::modules::users::hasher::PasswordHasher;
+use crate::modules::users::model::{
+    TwoFactor, UpdateUserDto, User, MAX_BIO_LENGTH, MAX_PROFILE_LINKS, UNUSABLE_PASSWORD,
+};
+use crate::modules::users::password_policy::{PasswordPolicy, PasswordPolicyError};
 use crate::modules::users::repository::UserRepository;
 use async_trait::async_trait;
-use bcrypt::{hash, verify, DEFAULT_COST};
 use mongodb::bson::{oid::ObjectId, DateTime};
 use std::sync::Arc;
 
@@ -13,21 +16,44 @@ pub trait UserService: Send + Sync {
         name: String,
         password: String,
     ) -> Result<User, String>;
+    /// Creates an account without a usable password, for sign-ups through
+    /// an identity provider. A password can be set later through a reset.
+    async fn provision_user(&self, email: String, name: String) -> Result<User, String>;
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, String>;
     async fn find_by_id(&self, id: &ObjectId) -> Result<Option<User>, String>;
     async fn verify_credentials(&self, email: &str, password: &str)
         -> Result<Option<User>, String>;
     async fn update_user(&self, id: &ObjectId, dto: UpdateUserDto) -> Result<User, String>;
-    async fn delete_user(&self, id: &ObjectId) -> Result<(), String>;
+    async fn mark_email_verified(&self, id: &ObjectId) -> Result<User, String>;
+    async fn update_two_factor(&self, id: &ObjectId, two_factor: TwoFactor)
+        -> Result<User, String>;
+    /// Uses up a recovery code. Returns `false` when the code is unknown or
+    /// was already used.
+    async fn consume_recovery_code(&self, id: &ObjectId, code_hash: &str)
+        -> Result<bool, String>;
+    /// Checks a new password against the password policy. `email` and
+    /// `name` may be empty when the account is not known yet.
+    fn check_password(&self, password: &str, email: &str, name: &str)
+        -> Result<(), PasswordPolicyError>;
 }
 
 pub struct UserServiceImpl {
     user_repository: Arc<dyn UserRepository>,
+    password_hasher: Arc<dyn PasswordHasher>,
+    password_policy: Arc<PasswordPolicy>,
 }
 
 impl UserServiceImpl {
-    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
-        Self { user_repository }
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        password_hasher: Arc<dyn PasswordHasher>,
+        password_policy: Arc<PasswordPolicy>,
+    ) -> Self {
+        Self {
+            user_repository,
+            password_hasher,
+            password_policy,
+        }
     }
 }
 
@@ -47,16 +73,15 @@ impl UserService for UserServiceImpl {
             return Err("Email and password cannot be empty".to_string());
         }
 
-        if password.trim().len() < 8 {
-            return Err("Password must be at least 8 characters".to_string());
-        }
+        self.password_policy
+            .check(&password, &email, &name)
+            .map_err(|e| e.to_string())?;
 
         if let Some(_) = self.user_repository.find_by_email(&email).await? {
             return Err("Email already exists".to_string());
         }
 
-        let hashed_password = hash(password.as_bytes(), DEFAULT_COST)
-            .map_err(|e| format!("Failed to hash password: {}", e))?;
+        let hashed_password = self.password_hasher.hash(&password)?;
 
         let mut user = User::new(email.trim().to_string(), name, hashed_password);
         user.id = Some(ObjectId::new());
@@ -66,6 +91,23 @@ impl UserService for UserServiceImpl {
         Ok(user)
     }
 
+    async fn provision_user(&self, email: String, name: String) -> Result<User, String> {
+        if email.trim().is_empty() {
+            return Err("Email cannot be empty".to_string());
+        }
+
+        if self.user_repository.find_by_email(&email).await?.is_some() {
+            return Err("Email already exists".to_string());
+        }
+
+        let mut user = User::new(email.trim().to_string(), name, UNUSABLE_PASSWORD.to_string());
+        user.id = Some(ObjectId::new());
+
+        self.user_repository.create(&user).await?;
+
+        Ok(user)
+    }
+
     async fn find_by_id(&self, id: &ObjectId) -> Result<Option<User>, String> {
         self.user_repository.find_by_id(id).await
     }
@@ -75,16 +117,35 @@ impl UserService for UserServiceImpl {
         email: &str,
         password: &str,
     ) -> Result<Option<User>, String> {
-        let user = match self.user_repository.find_by_email(email).await? {
+        let mut user = match self.user_repository.find_by_email(email).await? {
             Some(user) => user,
             None => return Ok(None),
         };
 
-        match verify(password.as_bytes(), &user.password) {
-            Ok(true) => Ok(Some(user)),
-            Ok(false) => Ok(None),
-            Err(e) => Err(format!("Error verifying password: {}", e)),
+        if user.password == UNUSABLE_PASSWORD {
+            return Ok(None);
+        }
+
+        if !self.password_hasher.verify(password, &user.password)? {
+            return Ok(None);
         }
+
+        // Upgrade bcrypt hashes and outdated Argon2 parameters while the
+        // plain password is at hand; a failure here must not block the login
+        if self.password_hasher.needs_rehash(&user.password) {
+            match self.password_hasher.hash(password) {
+                Ok(rehashed) => {
+                    user.password = rehashed;
+                    user.updated_at = DateTime::now();
+                    if let Err(e) = self.user_repository.update(&user).await {
+                        tracing::warn!(error = %e, "Failed to store rehashed password");
+                    }
+                }
+                Err(e) => tracing::warn!(error = %e, "Failed to rehash password"),
+            }
+        }
+
+        Ok(Some(user))
     }
 
     async fn update_user(&self, id: &ObjectId, dto: UpdateUserDto) -> Result<User, String> {
@@ -103,6 +164,9 @@ impl UserService for UserServiceImpl {
                     return Err("Email already exists".to_string());
                 }
             }
+            if email.trim() != user.email {
+                user.email_verified = false;
+            }
             user.email = email.trim().to_string();
         }
 
@@ -110,12 +174,36 @@ impl UserService for UserServiceImpl {
             user.name = name;
         }
 
-        if let Some(password) = dto.password {
-            if password.trim().len() < 8 {
-                return Err("Password must be at least 8 characters".to_string());
+        if let Some(bio) = dto.bio {
+            let bio = bio.trim();
+            if bio.chars().count() > MAX_BIO_LENGTH {
+                return Err(format!(
+                    "Bio cannot be longer than {} characters",
+                    MAX_BIO_LENGTH
+                ));
+            }
+            user.bio = (!bio.is_empty()).then(|| bio.to_string());
+        }
+
+        if let Some(links) = dto.links {
+            if links.len() > MAX_PROFILE_LINKS {
+                return Err(format!("At most {} links are allowed", MAX_PROFILE_LINKS));
             }
-            user.password = hash(password.as_bytes(), DEFAULT_COST)
-                .map_err(|e| format!("Failed to hash password: {}", e))?;
+            for link in &links {
+                link.validate()?;
+            }
+            user.links = links;
+        }
+
+        if let Some(privacy) = dto.privacy {
+            user.privacy = privacy;
+        }
+
+        if let Some(password) = dto.password {
+            self.password_policy
+                .check(&password, &user.email, &user.name)
+                .map_err(|e| e.to_string())?;
+            user.password = self.password_hasher.hash(&password)?;
         }
 
         user.updated_at = DateTime::now();
@@ -124,7 +212,51 @@ impl UserService for UserServiceImpl {
         Ok(user)
     }
 
-    async fn delete_user(&self, id: &ObjectId) -> Result<(), String> {
-        self.user_repository.delete(id).await
+    async fn mark_email_verified(&self, id: &ObjectId) -> Result<User, String> {
+        let mut user = self
+            .user_repository
+            .find_by_id(id)
+            .await?
+            .ok_or("User not found")?;
+
+        user.email_verified = true;
+        user.updated_at = DateTime::now();
+        self.user_repository.update(&user).await?;
+
+        Ok(user)
+    }
+    async fn update_two_factor(
+        &self,
+        id: &ObjectId,
+        two_factor: TwoFactor,
+    ) -> Result<User, String> {
+        let mut user = self
+            .user_repository
+            .find_by_id(id)
+            .await?
+            .ok_or("User not found")?;
+
+        user.two_factor = two_factor;
+        user.updated_at = DateTime::now();
+        self.user_repository.update(&user).await?;
+
+        Ok(user)
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        id: &ObjectId,
+        code_hash: &str,
+    ) -> Result<bool, String> {
+        self.user_repository.remove_recovery_code(id, code_hash).await
+    }
+
+    fn check_password(
+        &self,
+        password: &str,
+        email: &str,
+        name: &str,
+    ) -> Result<(), PasswordPolicyError> {
+        self.password_policy.check(password, email, name)
     }
 }
@@ -0,0 +1,89 @@
+use crate::config::password::PasswordHashConfig;
+use crate::modules::users::hasher::{Argon2Hasher, PasswordHasher};
+
+fn fast_config() -> PasswordHashConfig {
+    PasswordHashConfig {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    }
+}
+
+fn create_hasher() -> Argon2Hasher {
+    Argon2Hasher::new(&fast_config()).unwrap()
+}
+
+#[test]
+fn test_argon2_hash_uses_argon2id_with_configured_params() {
+    let hash = create_hasher().hash("password123").unwrap();
+
+    assert!(hash.starts_with("$argon2id$v=19$m=64,t=1,p=1$"));
+}
+
+#[test]
+fn test_argon2_verify() {
+    let hasher = create_hasher();
+    let hash = hasher.hash("password123").unwrap();
+
+    assert_eq!(hasher.verify("password123", &hash), Ok(true));
+    assert_eq!(hasher.verify("wrong_password", &hash), Ok(false));
+}
+
+#[test]
+fn test_argon2_hashes_are_salted() {
+    let hasher = create_hasher();
+
+    assert_ne!(
+        hasher.hash("password123").unwrap(),
+        hasher.hash("password123").unwrap()
+    );
+}
+
+#[test]
+fn test_argon2_verifies_legacy_bcrypt_hash() {
+    let hasher = create_hasher();
+    let hash = bcrypt::hash("password123", 4).unwrap();
+
+    assert_eq!(hasher.verify("password123", &hash), Ok(true));
+    assert_eq!(hasher.verify("wrong_password", &hash), Ok(false));
+    assert!(hasher.needs_rehash(&hash));
+}
+
+#[test]
+fn test_argon2_current_hash_needs_no_rehash() {
+    let hasher = create_hasher();
+    let hash = hasher.hash("password123").unwrap();
+
+    assert!(!hasher.needs_rehash(&hash));
+}
+
+#[test]
+fn test_argon2_needs_rehash_when_params_change() {
+    let hash = create_hasher().hash("password123").unwrap();
+    let stronger = Argon2Hasher::new(&PasswordHashConfig {
+        iterations: 2,
+        ..fast_config()
+    })
+    .unwrap();
+
+    assert!(stronger.needs_rehash(&hash));
+    assert_eq!(stronger.verify("password123", &hash), Ok(true));
+}
+
+#[test]
+fn test_argon2_rejects_unknown_hash_format() {
+    let hasher = create_hasher();
+
+    assert!(hasher.verify("password123", "not-a-hash").is_err());
+    assert!(hasher.needs_rehash("not-a-hash"));
+}
+
+#[test]
+fn test_argon2_rejects_invalid_params() {
+    let config = PasswordHashConfig {
+        memory_kib: 1,
+        ..fast_config()
+    };
+
+    assert!(Argon2Hasher::new(&config).is_err());
+}
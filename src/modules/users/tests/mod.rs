@@ -1,3 +1,6 @@
 mod controller_tests;
+mod hasher_tests;
 mod model_tests;
+mod password_policy_tests;
+mod profile_tests;
 mod service_tests;
@@ -2,7 +2,8 @@ use mongodb::bson::{oid::ObjectId, DateTime};
 use serde_json;
 
 use crate::modules::users::model::{
-    CreateUserDto, PublicUserResponse, UpdateUserDto, User, UserResponse,
+    AvatarVariant, CreateUserDto, PrivacySettings, ProfileLink, PublicUserResponse, UpdateUserDto,
+    User, UserResponse,
 };
 
 #[test]
@@ -159,3 +160,78 @@ fn test_public_user_response_from_user() {
     assert!(!serialized.contains("password"));
     assert!(serialized.contains("Test User"));
 }
+
+#[test]
+fn test_public_user_response_includes_profile() {
+    let mut user = User::new(
+        "test@example.com".to_string(),
+        "Test User".to_string(),
+        "password123".to_string(),
+    );
+    user.id = Some(ObjectId::new());
+    user.bio = Some("Bracket enthusiast".to_string());
+    user.links = vec![ProfileLink {
+        label: "Blog".to_string(),
+        url: "https://example.com".to_string(),
+    }];
+    user.avatar = vec![AvatarVariant {
+        size: 64,
+        image_id: ObjectId::new(),
+        url: "https://cdn.example.com/a-64.webp".to_string(),
+    }];
+
+    let serialized = serde_json::to_value(PublicUserResponse::from(user)).unwrap();
+
+    assert_eq!(serialized["bio"], "Bracket enthusiast");
+    assert_eq!(serialized["links"][0]["label"], "Blog");
+    assert_eq!(serialized["avatar"][0]["size"], 64);
+    assert_eq!(serialized["avatar"][0]["url"], "https://cdn.example.com/a-64.webp");
+    assert!(serialized["avatar"][0].get("image_id").is_none());
+}
+
+#[test]
+fn test_user_without_profile_fields_deserializes() {
+    let user = User::new(
+        "test@example.com".to_string(),
+        "Test User".to_string(),
+        "password123".to_string(),
+    );
+    let mut doc = mongodb::bson::to_document(&user).unwrap();
+    doc.remove("bio");
+    doc.remove("links");
+    doc.remove("avatar");
+    doc.remove("privacy");
+
+    let user: User = mongodb::bson::from_document(doc).unwrap();
+
+    assert!(user.bio.is_none());
+    assert!(user.links.is_empty());
+    assert!(user.avatar.is_empty());
+    assert!(user.privacy.discoverable);
+}
+
+#[test]
+fn test_privacy_settings_default_to_discoverable() {
+    let privacy: PrivacySettings = serde_json::from_str("{}").unwrap();
+    assert!(privacy.discoverable);
+
+    let privacy: PrivacySettings = serde_json::from_str(r#"{"discoverable": false}"#).unwrap();
+    assert!(!privacy.discoverable);
+}
+
+#[test]
+fn test_profile_link_validate() {
+    let link = |label: &str, url: &str| ProfileLink {
+        label: label.to_string(),
+        url: url.to_string(),
+    };
+
+    assert!(link("Blog", "https://example.com").validate().is_ok());
+    assert!(link("Blog", "http://example.com").validate().is_ok());
+    assert!(link("  ", "https://example.com").validate().is_err());
+    assert!(link(&"a".repeat(51), "https://example.com").validate().is_err());
+    assert_eq!(
+        link("Blog", "javascript:alert(1)").validate(),
+        Err("Links must be http or https URLs".to_string())
+    );
+}
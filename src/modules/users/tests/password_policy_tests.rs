@@ -0,0 +1,146 @@
+use crate::config::password_policy::PasswordPolicyConfig;
+use crate::modules::users::password_policy::{BreachedPasswordList, PasswordPolicy, PasswordRule};
+
+fn create_policy() -> PasswordPolicy {
+    PasswordPolicy::new(&PasswordPolicyConfig::default()).unwrap()
+}
+
+#[test]
+fn test_policy_accepts_strong_password() {
+    let policy = create_policy();
+
+    assert!(policy
+        .check("Crisp-Otter-Lantern-42", "ada@example.com", "Ada Lovelace")
+        .is_ok());
+}
+
+#[test]
+fn test_policy_min_length() {
+    let policy = create_policy();
+
+    let error = policy.check("Ab1!x", "", "").unwrap_err();
+
+    assert!(error.has(PasswordRule::MinLength));
+    assert!(!error.has(PasswordRule::CharacterClasses));
+}
+
+#[test]
+fn test_policy_counts_characters_not_bytes() {
+    let policy = create_policy();
+
+    assert!(policy.check("Ünïcödé-Pässwört", "", "").is_ok());
+    assert!(policy
+        .check("Äb1!äb1!ä", "", "")
+        .unwrap_err()
+        .has(PasswordRule::MinLength));
+}
+
+#[test]
+fn test_policy_character_classes() {
+    let policy = create_policy();
+
+    let error = policy.check("correcthorsebattery", "", "").unwrap_err();
+
+    assert_eq!(error.violations.len(), 1);
+    assert_eq!(error.violations[0].rule, PasswordRule::CharacterClasses);
+    assert!(policy.check("correcthorsebattery9!", "", "").is_ok());
+}
+
+#[test]
+fn test_policy_character_classes_configurable() {
+    let policy = PasswordPolicy::new(&PasswordPolicyConfig {
+        min_character_classes: 1,
+        ..PasswordPolicyConfig::default()
+    })
+    .unwrap();
+
+    assert!(policy.check("correcthorsebattery", "", "").is_ok());
+}
+
+#[test]
+fn test_policy_rejects_email_local_part() {
+    let policy = create_policy();
+
+    let error = policy
+        .check("MyBrooklyn#Pass7", "brooklyn@example.com", "")
+        .unwrap_err();
+
+    assert!(error.has(PasswordRule::ContainsEmail));
+}
+
+#[test]
+fn test_policy_rejects_name_case_insensitively() {
+    let policy = create_policy();
+
+    let error = policy
+        .check("LOVELACE-rules-42", "", "Ada Lovelace")
+        .unwrap_err();
+
+    assert!(error.has(PasswordRule::ContainsName));
+}
+
+#[test]
+fn test_policy_ignores_short_name_fragments() {
+    let policy = create_policy();
+
+    assert!(policy
+        .check("Jo-Crisp-Otter-42", "jo@example.com", "Jo")
+        .is_ok());
+}
+
+#[test]
+fn test_policy_rejects_breached_password() {
+    let policy = create_policy();
+
+    let error = policy.check("Welcome123!", "", "").unwrap_err();
+
+    assert_eq!(error.violations.len(), 1);
+    assert_eq!(error.violations[0].rule, PasswordRule::Breached);
+}
+
+#[test]
+fn test_policy_error_lists_every_message() {
+    let policy = create_policy();
+
+    let error = policy.check("ada", "ada@example.com", "Ada").unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Password must be at least 10 characters; Password must contain at least 3 of: \
+         lowercase letters, uppercase letters, digits, symbols; Password must not contain your \
+         email address; Password must not contain your name"
+    );
+}
+
+#[test]
+fn test_bundled_list_contains_common_passwords() {
+    let list = BreachedPasswordList::bundled();
+
+    assert!(!list.is_empty());
+    assert!(list.contains("123456"));
+    assert!(list.contains("qwerty123"));
+    assert!(!list.contains("Crisp-Otter-Lantern-42"));
+}
+
+#[test]
+fn test_breached_list_range_lookup() {
+    // SHA-1 of "password" is 5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8
+    let list = BreachedPasswordList::parse(
+        "# comment\n5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8:3861493\n\n",
+    );
+
+    assert_eq!(list.len(), 1);
+    assert_eq!(
+        list.range("5baa6"),
+        vec!["1E4C9B93F3F0682250B6CF8331B7EE68FD8"]
+    );
+    assert!(list.range("5BAA7").is_empty());
+    assert!(list.contains("password"));
+}
+
+#[test]
+fn test_breached_list_missing_file() {
+    let result = BreachedPasswordList::load("/nonexistent/breached.txt");
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,421 @@
+use async_trait::async_trait;
+use mockall::mock;
+use mongodb::bson::{oid::ObjectId, DateTime};
+use std::sync::Arc;
+
+use crate::common::pagination::PaginationParams;
+use crate::modules::images::model::Image;
+use crate::modules::images::service::ImageService;
+use crate::modules::opponents::model::Opponent;
+use crate::modules::opponents::repository::OpponentRepository;
+use crate::modules::tournaments::model::{Tournament, TournamentUser, VoterId};
+use crate::modules::tournaments::repository::TournamentRepository;
+use crate::modules::users::model::{AvatarVariant, User, UserRole, AVATAR_SIZES};
+use crate::modules::users::profile::{ProfileService, ProfileServiceImpl};
+use crate::modules::users::repository::UserRepository;
+
+mock! {
+    UserRepo {}
+
+    #[async_trait]
+    impl UserRepository for UserRepo {
+        async fn create(&self, user: &User) -> Result<(), String>;
+        async fn find_by_email(&self, email: &str) -> Result<Option<User>, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<User>, String>;
+        async fn update(&self, user: &User) -> Result<(), String>;
+        async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn remove_recovery_code(&self, id: &ObjectId, code_hash: &str) -> Result<bool, String>;
+        async fn search(&self, query: Option<String>, role: Option<UserRole>, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<User>, String>;
+        async fn find_due_for_deletion(&self, now: DateTime, limit: i64) -> Result<Vec<User>, String>;
+        async fn find_discoverable(&self, query: &str, exclude: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<User>, String>;
+    }
+}
+
+mock! {
+    TournamentRepo {}
+
+    #[async_trait]
+    impl TournamentRepository for TournamentRepo {
+        async fn create(&self, tournament: Tournament) -> Result<(), String>;
+        async fn update(&self, tournament: &Tournament) -> Result<bool, String>;
+        async fn add_participant(&self, id: &ObjectId, user: &TournamentUser) -> Result<Option<Tournament>, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Tournament>, String>;
+        async fn find_by_creator(&self, user_id: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<Tournament>, String>;
+        async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn find_by_organizer(&self, user_id: &ObjectId) -> Result<Vec<Tournament>, String>;
+        async fn find_by_participant(&self, voter_id: &VoterId) -> Result<Vec<Tournament>, String>;
+        async fn find_used_opponents(&self, opponent_ids: &[ObjectId]) -> Result<Vec<ObjectId>, String>;
+        async fn count_by_organizer(&self, user_id: &ObjectId) -> Result<u64, String>;
+        async fn count_by_participant(&self, voter_id: &VoterId) -> Result<u64, String>;
+        async fn count_won_by(&self, opponent_ids: &[ObjectId]) -> Result<u64, String>;
+    }
+}
+
+mock! {
+    OpponentRepo {}
+
+    #[async_trait]
+    impl OpponentRepository for OpponentRepo {
+        async fn create(&self, opponent: &Opponent) -> Result<Opponent, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Opponent>, String>;
+        async fn find_by_creator(&self, user_id: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<Opponent>, String>;
+        async fn update(&self, opponent: &Opponent) -> Result<(), String>;
+        async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn find_ids_by_creator(&self, user_id: &ObjectId) -> Result<Vec<ObjectId>, String>;
+    }
+}
+
+mock! {
+    Images {}
+
+    #[async_trait]
+    impl ImageService for Images {
+        async fn upload_image(&self, file_data: Vec<u8>, filename: String, content_type: String, created_by: ObjectId) -> Result<Image, String>;
+        async fn find_by_id(&self, id: &ObjectId) -> Result<Option<Image>, String>;
+        async fn delete_image(&self, id: &ObjectId, user_id: &ObjectId) -> Result<(), String>;
+        async fn find_by_creator(&self, user_id: &ObjectId) -> Result<Vec<Image>, String>;
+        async fn upload_square_variants(&self, file_data: Vec<u8>, sizes: &[u32], created_by: ObjectId) -> Result<Vec<Image>, String>;
+    }
+}
+
+struct Mocks {
+    users: MockUserRepo,
+    tournaments: MockTournamentRepo,
+    opponents: MockOpponentRepo,
+    images: MockImages,
+}
+
+impl Mocks {
+    fn new() -> Self {
+        Self {
+            users: MockUserRepo::new(),
+            tournaments: MockTournamentRepo::new(),
+            opponents: MockOpponentRepo::new(),
+            images: MockImages::new(),
+        }
+    }
+
+    fn expect_user(&mut self, user: User) {
+        self.users
+            .expect_find_by_id()
+            .returning(move |_| Ok(Some(user.clone())));
+    }
+
+    fn service(self) -> ProfileServiceImpl {
+        ProfileServiceImpl::new(
+            Arc::new(self.users),
+            Arc::new(self.tournaments),
+            Arc::new(self.opponents),
+            Arc::new(self.images),
+        )
+    }
+}
+
+fn create_test_user() -> User {
+    let mut user = User::new(
+        "jane@example.com".to_string(),
+        "Jane".to_string(),
+        "hash".to_string(),
+    );
+    user.id = Some(ObjectId::new());
+    user
+}
+
+fn create_variant(size: u32) -> AvatarVariant {
+    AvatarVariant {
+        size,
+        image_id: ObjectId::new(),
+        url: format!("https://cdn.example.com/old-{}.webp", size),
+    }
+}
+
+fn create_image(size: u32, user_id: ObjectId) -> Image {
+    let mut image = Image::new(
+        format!("https://cdn.example.com/new-{}.webp", size),
+        "image/webp".to_string(),
+        1024,
+        format!("new-{}.webp", size),
+        user_id,
+    );
+    image.id = Some(ObjectId::new());
+    image
+}
+
+#[tokio::test]
+async fn test_public_profile_counts_stats() {
+    // Arrange
+    let user = create_test_user();
+    let user_id = user.id.unwrap();
+    let opponent_ids = vec![ObjectId::new(), ObjectId::new(), ObjectId::new()];
+    let expected_ids = opponent_ids.clone();
+    let mut mocks = Mocks::new();
+    mocks.expect_user(user);
+    mocks
+        .opponents
+        .expect_find_ids_by_creator()
+        .returning(move |_| Ok(opponent_ids.clone()));
+    mocks
+        .tournaments
+        .expect_count_won_by()
+        .withf(move |ids| ids == expected_ids.as_slice())
+        .times(1)
+        .returning(|_| Ok(2));
+    mocks
+        .tournaments
+        .expect_count_by_organizer()
+        .returning(|_| Ok(4));
+    mocks
+        .tournaments
+        .expect_count_by_participant()
+        .withf(move |voter| *voter == VoterId::Registered(user_id))
+        .returning(|_| Ok(7));
+    let service = mocks.service();
+
+    // Act
+    let profile = service.public_profile(&user_id).await.unwrap();
+
+    // Assert
+    assert_eq!(profile.user.id, user_id);
+    assert_eq!(profile.stats.tournaments_organized, 4);
+    assert_eq!(profile.stats.tournaments_participated, 7);
+    assert_eq!(profile.stats.opponents_created, 3);
+    assert_eq!(profile.stats.wins, 2);
+}
+
+#[tokio::test]
+async fn test_public_profile_without_opponents_skips_wins() {
+    // Arrange
+    let user = create_test_user();
+    let user_id = user.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks.expect_user(user);
+    mocks
+        .opponents
+        .expect_find_ids_by_creator()
+        .returning(|_| Ok(vec![]));
+    mocks.tournaments.expect_count_won_by().never();
+    mocks
+        .tournaments
+        .expect_count_by_organizer()
+        .returning(|_| Ok(0));
+    mocks
+        .tournaments
+        .expect_count_by_participant()
+        .returning(|_| Ok(0));
+    let service = mocks.service();
+
+    // Act
+    let profile = service.public_profile(&user_id).await.unwrap();
+
+    // Assert
+    assert_eq!(profile.stats.opponents_created, 0);
+    assert_eq!(profile.stats.wins, 0);
+}
+
+#[tokio::test]
+async fn test_public_profile_user_not_found() {
+    // Arrange
+    let mut mocks = Mocks::new();
+    mocks.users.expect_find_by_id().returning(|_| Ok(None));
+    let service = mocks.service();
+
+    // Act
+    let result = service.public_profile(&ObjectId::new()).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "User not found");
+}
+
+#[tokio::test]
+async fn test_set_avatar_replaces_previous_images() {
+    // Arrange
+    let mut user = create_test_user();
+    let user_id = user.id.unwrap();
+    user.avatar = AVATAR_SIZES
+        .iter()
+        .map(|&size| create_variant(size))
+        .collect();
+    let old_ids: Vec<ObjectId> = user.avatar.iter().map(|v| v.image_id).collect();
+    let mut mocks = Mocks::new();
+    mocks.expect_user(user);
+    mocks
+        .images
+        .expect_upload_square_variants()
+        .withf(|_, sizes, _| sizes == AVATAR_SIZES.as_slice())
+        .times(1)
+        .returning(move |_, sizes, created_by| {
+            Ok(sizes
+                .iter()
+                .map(|&size| create_image(size, created_by))
+                .collect())
+        });
+    mocks
+        .users
+        .expect_update()
+        .withf(|user| user.avatar.len() == AVATAR_SIZES.len())
+        .times(1)
+        .returning(|_| Ok(()));
+    mocks
+        .images
+        .expect_delete_image()
+        .withf(move |id, owner| old_ids.contains(id) && *owner == user_id)
+        .times(AVATAR_SIZES.len())
+        .returning(|_, _| Ok(()));
+    let service = mocks.service();
+
+    // Act
+    let user = service.set_avatar(&user_id, vec![1, 2, 3]).await.unwrap();
+
+    // Assert
+    let sizes: Vec<u32> = user.avatar.iter().map(|v| v.size).collect();
+    assert_eq!(sizes, AVATAR_SIZES.to_vec());
+    assert_eq!(user.avatar[0].url, "https://cdn.example.com/new-64.webp");
+}
+
+#[tokio::test]
+async fn test_set_avatar_upload_failure_keeps_avatar() {
+    // Arrange
+    let mut user = create_test_user();
+    let user_id = user.id.unwrap();
+    user.avatar = vec![create_variant(64)];
+    let mut mocks = Mocks::new();
+    mocks.expect_user(user);
+    mocks
+        .images
+        .expect_upload_square_variants()
+        .returning(|_, _, _| Err("Failed to load image".to_string()));
+    mocks.users.expect_update().never();
+    mocks.images.expect_delete_image().never();
+    let service = mocks.service();
+
+    // Act
+    let result = service.set_avatar(&user_id, vec![0]).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Failed to load image");
+}
+
+#[tokio::test]
+async fn test_remove_avatar() {
+    // Arrange
+    let mut user = create_test_user();
+    let user_id = user.id.unwrap();
+    user.avatar = vec![create_variant(64), create_variant(128)];
+    let mut mocks = Mocks::new();
+    mocks.expect_user(user);
+    mocks
+        .users
+        .expect_update()
+        .withf(|user| user.avatar.is_empty())
+        .times(1)
+        .returning(|_| Ok(()));
+    mocks
+        .images
+        .expect_delete_image()
+        .times(2)
+        .returning(|_, _| Ok(()));
+    let service = mocks.service();
+
+    // Act
+    let user = service.remove_avatar(&user_id).await.unwrap();
+
+    // Assert
+    assert!(user.avatar.is_empty());
+}
+
+#[tokio::test]
+async fn test_remove_avatar_without_avatar() {
+    // Arrange
+    let user = create_test_user();
+    let user_id = user.id.unwrap();
+    let mut mocks = Mocks::new();
+    mocks.expect_user(user);
+    mocks.users.expect_update().never();
+    let service = mocks.service();
+
+    // Act
+    let result = service.remove_avatar(&user_id).await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "No avatar to remove");
+}
+
+fn search_params(cursor: Option<String>, limit: i64) -> PaginationParams {
+    PaginationParams {
+        cursor,
+        limit: Some(limit),
+    }
+}
+
+#[tokio::test]
+async fn test_search_users_paginates() {
+    // Arrange
+    let caller = ObjectId::new();
+    let cursor = ObjectId::new();
+    let found: Vec<User> = (0..3).map(|_| create_test_user()).collect();
+    let expected_cursor = found[1].id.unwrap().to_string();
+    let mut mocks = Mocks::new();
+    mocks
+        .users
+        .expect_find_discoverable()
+        .withf(move |query, exclude, c, limit| {
+            query == "jane" && *exclude == caller && *c == Some(cursor) && *limit == 2
+        })
+        .times(1)
+        .returning(move |_, _, _, _| Ok(found.clone()));
+    let service = mocks.service();
+
+    // Act
+    let page = service
+        .search_users(
+            &caller,
+            "  jane ",
+            search_params(Some(cursor.to_string()), 2),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(page.data.len(), 2);
+    assert!(page.has_more);
+    assert_eq!(page.next_cursor, Some(expected_cursor));
+}
+
+#[tokio::test]
+async fn test_search_users_rejects_short_query() {
+    // Arrange
+    let mut mocks = Mocks::new();
+    mocks.users.expect_find_discoverable().never();
+    let service = mocks.service();
+
+    // Act
+    let result = service
+        .search_users(&ObjectId::new(), " j ", search_params(None, 20))
+        .await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Search query must be between 2 and 64 characters"
+    );
+}
+
+#[tokio::test]
+async fn test_search_users_rejects_invalid_cursor() {
+    // Arrange
+    let mut mocks = Mocks::new();
+    mocks.users.expect_find_discoverable().never();
+    let service = mocks.service();
+
+    // Act
+    let result = service
+        .search_users(
+            &ObjectId::new(),
+            "jane",
+            search_params(Some("nope".to_string()), 20),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Invalid cursor");
+}
@@ -3,7 +3,12 @@ use mockall::mock;
 use mongodb::bson::oid::ObjectId;
 use std::sync::Arc;
 
-use crate::modules::users::model::{UpdateUserDto, User};
+use crate::config::password_policy::PasswordPolicyConfig;
+use crate::modules::users::hasher::PasswordHasher;
+use crate::modules::users::model::{
+    PrivacySettings, ProfileLink, TwoFactor, UpdateUserDto, User, UserRole, UNUSABLE_PASSWORD,
+};
+use crate::modules::users::password_policy::PasswordPolicy;
 use crate::modules::users::repository::UserRepository;
 use crate::modules::users::service::{UserService, UserServiceImpl};
 
@@ -17,14 +22,45 @@ mock! {
         async fn find_by_id(&self, id: &ObjectId) -> Result<Option<User>, String>;
         async fn update(&self, user: &User) -> Result<(), String>;
         async fn delete(&self, id: &ObjectId) -> Result<(), String>;
+        async fn remove_recovery_code(&self, id: &ObjectId, code_hash: &str) -> Result<bool, String>;
+        async fn search(&self, query: Option<String>, role: Option<UserRole>, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<User>, String>;
+        async fn find_due_for_deletion(&self, now: mongodb::bson::DateTime, limit: i64) -> Result<Vec<User>, String>;
+        async fn find_discoverable(&self, query: &str, exclude: &ObjectId, cursor: Option<ObjectId>, limit: i64) -> Result<Vec<User>, String>;
     }
 }
 
+/// Stores passwords as `fast$<password>` so tests skip real hashing. Hashes
+/// starting with `legacy$` verify too but ask to be rehashed.
+struct FastHasher;
+
+impl PasswordHasher for FastHasher {
+    fn hash(&self, password: &str) -> Result<String, String> {
+        Ok(format!("fast${}", password))
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, String> {
+        match hash.split_once('$') {
+            Some(("fast" | "legacy", stored)) => Ok(stored == password),
+            _ => Err("Unsupported password hash format".to_string()),
+        }
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        !hash.starts_with("fast$")
+    }
+}
+
+const STRONG_PASSWORD: &str = "Crisp-Otter-Lantern-42";
+
+fn test_policy() -> Arc<PasswordPolicy> {
+    Arc::new(PasswordPolicy::new(&PasswordPolicyConfig::default()).unwrap())
+}
+
 fn create_test_user() -> User {
     let mut user = User::new(
         "test@example.com".to_string(),
         "Test User".to_string(),
-        "$2b$12$hashedpassword".to_string(),
+        "fast$hashedpassword".to_string(),
     );
     user.id = Some(ObjectId::new());
     user
@@ -45,14 +81,14 @@ async fn test_create_user_success() {
         .times(1)
         .returning(|_| Ok(()));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     // Act
     let result = service
         .create_user(
             "new@example.com".to_string(),
             "New User".to_string(),
-            "password123".to_string(),
+            STRONG_PASSWORD.to_string(),
         )
         .await;
 
@@ -73,14 +109,14 @@ async fn test_create_user_email_already_exists() {
         .times(1)
         .returning(move |_| Ok(Some(existing_user.clone())));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     // Act
     let result = service
         .create_user(
             "test@example.com".to_string(),
             "Test User".to_string(),
-            "password123".to_string(),
+            STRONG_PASSWORD.to_string(),
         )
         .await;
 
@@ -93,14 +129,14 @@ async fn test_create_user_email_already_exists() {
 async fn test_create_user_empty_email() {
     // Arrange
     let mock_repo = MockUserRepo::new();
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     // Act
     let result = service
         .create_user(
             "".to_string(),
             "Test User".to_string(),
-            "password123".to_string(),
+            STRONG_PASSWORD.to_string(),
         )
         .await;
 
@@ -113,7 +149,7 @@ async fn test_create_user_empty_email() {
 async fn test_create_user_empty_password() {
     // Arrange
     let mock_repo = MockUserRepo::new();
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     // Act
     let result = service
@@ -133,7 +169,7 @@ async fn test_create_user_empty_password() {
 async fn test_create_user_short_password() {
     // Arrange
     let mock_repo = MockUserRepo::new();
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     // Act
     let result = service
@@ -146,14 +182,73 @@ async fn test_create_user_short_password() {
 
     // Assert
     assert!(result.is_err());
+    assert!(result
+        .unwrap_err()
+        .starts_with("Password must be at least 10 characters"));
+}
+
+#[tokio::test]
+async fn test_create_user_rejects_password_with_email() {
+    // Arrange
+    let mock_repo = MockUserRepo::new();
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    // Act
+    let result = service
+        .create_user(
+            "ada.lovelace@example.com".to_string(),
+            "Countess".to_string(),
+            "Ada.Lovelace-1815".to_string(),
+        )
+        .await;
+
+    // Assert
     assert_eq!(
         result.unwrap_err(),
-        "Password must be at least 8 characters"
+        "Password must not contain your email address"
     );
 }
 
 #[tokio::test]
-async fn test_create_user_exactly_8_char_password() {
+async fn test_create_user_rejects_breached_password() {
+    // Arrange
+    let mock_repo = MockUserRepo::new();
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    // Act
+    let result = service
+        .create_user(
+            "new@example.com".to_string(),
+            "New User".to_string(),
+            "Password123!".to_string(),
+        )
+        .await;
+
+    // Assert
+    assert_eq!(
+        result.unwrap_err(),
+        "Password is too common or has appeared in a data breach"
+    );
+}
+
+#[tokio::test]
+async fn test_check_password_reports_every_failed_rule() {
+    // Arrange
+    let service = UserServiceImpl::new(
+        Arc::new(MockUserRepo::new()),
+        Arc::new(FastHasher),
+        test_policy(),
+    );
+
+    // Act
+    let result = service.check_password("qwerty", "qwerty@example.com", "");
+
+    // Assert
+    assert_eq!(result.unwrap_err().violations.len(), 4);
+}
+
+#[tokio::test]
+async fn test_create_user_exactly_min_length_password() {
     // Arrange
     let mut mock_repo = MockUserRepo::new();
     mock_repo
@@ -162,14 +257,14 @@ async fn test_create_user_exactly_8_char_password() {
         .returning(|_| Ok(None));
     mock_repo.expect_create().times(1).returning(|_| Ok(()));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     // Act
     let result = service
         .create_user(
             "test@example.com".to_string(),
             "Test User".to_string(),
-            "12345678".to_string(),
+            "Tz8#mKp2vQ".to_string(),
         )
         .await;
 
@@ -189,7 +284,7 @@ async fn test_find_by_email_found() {
         .times(1)
         .returning(move |_| Ok(Some(user_clone.clone())));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     // Act
     let result = service.find_by_email("test@example.com").await;
@@ -210,7 +305,7 @@ async fn test_find_by_email_not_found() {
         .times(1)
         .returning(|_| Ok(None));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     // Act
     let result = service.find_by_email("nonexistent@example.com").await;
@@ -224,7 +319,7 @@ async fn test_find_by_email_not_found() {
 async fn test_verify_credentials_success() {
     // Arrange
     let mut mock_repo = MockUserRepo::new();
-    let hashed = bcrypt::hash("password123".as_bytes(), bcrypt::DEFAULT_COST).unwrap();
+    let hashed = "fast$password123".to_string();
     let mut user = User::new(
         "test@example.com".to_string(),
         "Test User".to_string(),
@@ -239,7 +334,7 @@ async fn test_verify_credentials_success() {
         .times(1)
         .returning(move |_| Ok(Some(user_clone.clone())));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     // Act
     let result = service
@@ -255,7 +350,7 @@ async fn test_verify_credentials_success() {
 async fn test_verify_credentials_wrong_password() {
     // Arrange
     let mut mock_repo = MockUserRepo::new();
-    let hashed = bcrypt::hash("password123".as_bytes(), bcrypt::DEFAULT_COST).unwrap();
+    let hashed = "fast$password123".to_string();
     let mut user = User::new(
         "test@example.com".to_string(),
         "Test User".to_string(),
@@ -269,7 +364,7 @@ async fn test_verify_credentials_wrong_password() {
         .times(1)
         .returning(move |_| Ok(Some(user_clone.clone())));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     // Act
     let result = service
@@ -281,6 +376,105 @@ async fn test_verify_credentials_wrong_password() {
     assert!(result.unwrap().is_none());
 }
 
+#[tokio::test]
+async fn test_verify_credentials_rehashes_legacy_hash() {
+    // Arrange
+    let mut mock_repo = MockUserRepo::new();
+    let mut user = create_test_user();
+    user.password = "legacy$password123".to_string();
+    mock_repo
+        .expect_find_by_email()
+        .times(1)
+        .returning(move |_| Ok(Some(user.clone())));
+    mock_repo
+        .expect_update()
+        .withf(|user| user.password == "fast$password123")
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    // Act
+    let result = service
+        .verify_credentials("test@example.com", "password123")
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap().unwrap().password, "fast$password123");
+}
+
+#[tokio::test]
+async fn test_verify_credentials_keeps_current_hash() {
+    // Arrange
+    let mut mock_repo = MockUserRepo::new();
+    let mut user = create_test_user();
+    user.password = "fast$password123".to_string();
+    mock_repo
+        .expect_find_by_email()
+        .times(1)
+        .returning(move |_| Ok(Some(user.clone())));
+    mock_repo.expect_update().times(0);
+
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    // Act
+    let result = service
+        .verify_credentials("test@example.com", "password123")
+        .await;
+
+    // Assert
+    assert!(result.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_verify_credentials_wrong_password_skips_rehash() {
+    // Arrange
+    let mut mock_repo = MockUserRepo::new();
+    let mut user = create_test_user();
+    user.password = "legacy$password123".to_string();
+    mock_repo
+        .expect_find_by_email()
+        .times(1)
+        .returning(move |_| Ok(Some(user.clone())));
+    mock_repo.expect_update().times(0);
+
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    // Act
+    let result = service
+        .verify_credentials("test@example.com", "wrong_password")
+        .await;
+
+    // Assert
+    assert!(result.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_verify_credentials_rehash_failure_still_logs_in() {
+    // Arrange
+    let mut mock_repo = MockUserRepo::new();
+    let mut user = create_test_user();
+    user.password = "legacy$password123".to_string();
+    mock_repo
+        .expect_find_by_email()
+        .times(1)
+        .returning(move |_| Ok(Some(user.clone())));
+    mock_repo
+        .expect_update()
+        .times(1)
+        .returning(|_| Err("Database error".to_string()));
+
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    // Act
+    let result = service
+        .verify_credentials("test@example.com", "password123")
+        .await;
+
+    // Assert
+    assert!(result.unwrap().is_some());
+}
+
 #[tokio::test]
 async fn test_verify_credentials_user_not_found() {
     // Arrange
@@ -290,7 +484,7 @@ async fn test_verify_credentials_user_not_found() {
         .times(1)
         .returning(|_| Ok(None));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     // Act
     let result = service
@@ -315,14 +509,14 @@ async fn test_create_user_repository_error() {
         .times(1)
         .returning(|_| Err("Database connection failed".to_string()));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     // Act
     let result = service
         .create_user(
             "test@example.com".to_string(),
             "Test User".to_string(),
-            "password123".to_string(),
+            STRONG_PASSWORD.to_string(),
         )
         .await;
 
@@ -331,6 +525,78 @@ async fn test_create_user_repository_error() {
     assert_eq!(result.unwrap_err(), "Database connection failed");
 }
 
+#[tokio::test]
+async fn test_provision_user_skips_password_policy() {
+    // Arrange
+    let mut mock_repo = MockUserRepo::new();
+    mock_repo
+        .expect_find_by_email()
+        .times(1)
+        .returning(|_| Ok(None));
+    mock_repo
+        .expect_create()
+        .withf(|user| user.password == UNUSABLE_PASSWORD)
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    // Act
+    let result = service
+        .provision_user("sso@example.com".to_string(), "SSO User".to_string())
+        .await;
+
+    // Assert
+    let user = result.unwrap();
+    assert_eq!(user.email, "sso@example.com");
+    assert_eq!(user.password, UNUSABLE_PASSWORD);
+}
+
+#[tokio::test]
+async fn test_provision_user_email_already_exists() {
+    // Arrange
+    let mut mock_repo = MockUserRepo::new();
+    let existing_user = create_test_user();
+    mock_repo
+        .expect_find_by_email()
+        .times(1)
+        .returning(move |_| Ok(Some(existing_user.clone())));
+    mock_repo.expect_create().times(0);
+
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    // Act
+    let result = service
+        .provision_user("test@example.com".to_string(), "Test User".to_string())
+        .await;
+
+    // Assert
+    assert_eq!(result.unwrap_err(), "Email already exists");
+}
+
+#[tokio::test]
+async fn test_verify_credentials_rejects_unusable_password() {
+    // Arrange
+    let mut mock_repo = MockUserRepo::new();
+    let mut user = create_test_user();
+    user.password = UNUSABLE_PASSWORD.to_string();
+    mock_repo
+        .expect_find_by_email()
+        .times(1)
+        .returning(move |_| Ok(Some(user.clone())));
+    mock_repo.expect_update().times(0);
+
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    // Act
+    let result = service
+        .verify_credentials("test@example.com", UNUSABLE_PASSWORD)
+        .await;
+
+    // Assert
+    assert!(result.unwrap().is_none());
+}
+
 #[tokio::test]
 async fn test_find_by_id_found() {
     let mut mock_repo = MockUserRepo::new();
@@ -343,7 +609,7 @@ async fn test_find_by_id_found() {
         .times(1)
         .returning(move |_| Ok(Some(user_clone.clone())));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     let result = service.find_by_id(&user_id).await;
     assert!(result.is_ok());
@@ -358,7 +624,7 @@ async fn test_find_by_id_not_found() {
         .times(1)
         .returning(|_| Ok(None));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
 
     let result = service.find_by_id(&ObjectId::new()).await;
     assert!(result.is_ok());
@@ -380,11 +646,14 @@ async fn test_update_user_name() {
         .times(1)
         .returning(|_| Ok(()));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
     let dto = UpdateUserDto {
         email: None,
         name: Some("Updated Name".to_string()),
         password: None,
+        bio: None,
+        links: None,
+        privacy: None,
     };
 
     let result = service.update_user(&user_id, dto).await;
@@ -395,7 +664,8 @@ async fn test_update_user_name() {
 #[tokio::test]
 async fn test_update_user_email() {
     let mut mock_repo = MockUserRepo::new();
-    let user = create_test_user();
+    let mut user = create_test_user();
+    user.email_verified = true;
     let user_id = user.id.unwrap();
     let user_clone = user.clone();
     mock_repo
@@ -411,16 +681,21 @@ async fn test_update_user_email() {
         .times(1)
         .returning(|_| Ok(()));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
     let dto = UpdateUserDto {
         email: Some("new@example.com".to_string()),
         name: None,
         password: None,
+        bio: None,
+        links: None,
+        privacy: None,
     };
 
     let result = service.update_user(&user_id, dto).await;
     assert!(result.is_ok());
-    assert_eq!(result.unwrap().email, "new@example.com");
+    let updated = result.unwrap();
+    assert_eq!(updated.email, "new@example.com");
+    assert!(!updated.email_verified);
 }
 
 #[tokio::test]
@@ -443,11 +718,14 @@ async fn test_update_user_email_already_taken() {
         .times(1)
         .returning(move |_| Ok(Some(other_user.clone())));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
     let dto = UpdateUserDto {
         email: Some("taken@example.com".to_string()),
         name: None,
         password: None,
+        bio: None,
+        links: None,
+        privacy: None,
     };
 
     let result = service.update_user(&user_id, dto).await;
@@ -466,11 +744,14 @@ async fn test_update_user_empty_email() {
         .times(1)
         .returning(move |_| Ok(Some(user_clone.clone())));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
     let dto = UpdateUserDto {
         email: Some("  ".to_string()),
         name: None,
         password: None,
+        bio: None,
+        links: None,
+        privacy: None,
     };
 
     let result = service.update_user(&user_id, dto).await;
@@ -489,19 +770,46 @@ async fn test_update_user_short_password() {
         .times(1)
         .returning(move |_| Ok(Some(user_clone.clone())));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
     let dto = UpdateUserDto {
         email: None,
         name: None,
         password: Some("short".to_string()),
+        bio: None,
+        links: None,
+        privacy: None,
     };
 
     let result = service.update_user(&user_id, dto).await;
     assert!(result.is_err());
-    assert_eq!(
-        result.unwrap_err(),
-        "Password must be at least 8 characters"
-    );
+    assert!(result
+        .unwrap_err()
+        .starts_with("Password must be at least 10 characters"));
+}
+
+#[tokio::test]
+async fn test_update_user_rejects_password_with_name() {
+    let mut mock_repo = MockUserRepo::new();
+    let user = create_test_user();
+    let user_id = user.id.unwrap();
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(user.clone())));
+    mock_repo.expect_update().times(0);
+
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+    let dto = UpdateUserDto {
+        email: None,
+        name: Some("Grace Hopper".to_string()),
+        password: Some("Hopper#Cobol-1959".to_string()),
+        bio: None,
+        links: None,
+        privacy: None,
+    };
+
+    let result = service.update_user(&user_id, dto).await;
+    assert_eq!(result.unwrap_err(), "Password must not contain your name");
 }
 
 #[tokio::test]
@@ -512,11 +820,14 @@ async fn test_update_user_not_found() {
         .times(1)
         .returning(|_| Ok(None));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
     let dto = UpdateUserDto {
         email: None,
         name: Some("New Name".to_string()),
         password: None,
+        bio: None,
+        links: None,
+        privacy: None,
     };
 
     let result = service.update_user(&ObjectId::new(), dto).await;
@@ -525,32 +836,78 @@ async fn test_update_user_not_found() {
 }
 
 #[tokio::test]
-async fn test_delete_user_success() {
+async fn test_mark_email_verified_success() {
     let mut mock_repo = MockUserRepo::new();
+    let user = create_test_user();
+    let user_id = user.id.unwrap();
     mock_repo
-        .expect_delete()
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(user.clone())));
+    mock_repo
+        .expect_update()
+        .withf(|user| user.email_verified)
         .times(1)
         .returning(|_| Ok(()));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
-    let result = service.delete_user(&ObjectId::new()).await;
-    assert!(result.is_ok());
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+    let result = service.mark_email_verified(&user_id).await;
+    assert!(result.unwrap().email_verified);
 }
 
 #[tokio::test]
-async fn test_delete_user_not_found() {
+async fn test_mark_email_verified_not_found() {
     let mut mock_repo = MockUserRepo::new();
     mock_repo
-        .expect_delete()
+        .expect_find_by_id()
         .times(1)
-        .returning(|_| Err("User not found".to_string()));
+        .returning(|_| Ok(None));
 
-    let service = UserServiceImpl::new(Arc::new(mock_repo));
-    let result = service.delete_user(&ObjectId::new()).await;
-    assert!(result.is_err());
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+    let result = service.mark_email_verified(&ObjectId::new()).await;
     assert_eq!(result.unwrap_err(), "User not found");
 }
 
+#[tokio::test]
+async fn test_update_two_factor_success() {
+    let mut mock_repo = MockUserRepo::new();
+    let user = create_test_user();
+    let user_id = user.id.unwrap();
+    mock_repo
+        .expect_find_by_id()
+        .times(1)
+        .returning(move |_| Ok(Some(user.clone())));
+    mock_repo
+        .expect_update()
+        .withf(|user| user.two_factor.secret.as_deref() == Some("JBSWY3DPEHPK3PXP"))
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+    let two_factor = TwoFactor {
+        secret: Some("JBSWY3DPEHPK3PXP".to_string()),
+        ..TwoFactor::default()
+    };
+    let result = service.update_two_factor(&user_id, two_factor).await;
+    assert!(result.unwrap().two_factor_enabled());
+}
+
+#[tokio::test]
+async fn test_consume_recovery_code_delegates_to_repository() {
+    let mut mock_repo = MockUserRepo::new();
+    mock_repo
+        .expect_remove_recovery_code()
+        .withf(|_, hash| hash == "code_hash")
+        .times(1)
+        .returning(|_, _| Ok(true));
+
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+    let result = service
+        .consume_recovery_code(&ObjectId::new(), "code_hash")
+        .await;
+    assert!(result.unwrap());
+}
+
 // --- Integration tests (require MongoDB) ---
 
 #[tokio::test]
@@ -560,13 +917,17 @@ async fn test_integration_create_user_success() {
     use crate::modules::users::repository::UserRepositoryImpl;
 
     let mongodb = MongoDB::init().await.expect("Failed to init MongoDB");
-    let service = UserServiceImpl::new(Arc::new(UserRepositoryImpl::new(&mongodb.db)));
+    let service = UserServiceImpl::new(
+        Arc::new(UserRepositoryImpl::new(&mongodb.db)),
+        Arc::new(FastHasher),
+        test_policy(),
+    );
 
     let result = service
         .create_user(
             "test_create@example.com".to_string(),
             "Test User".to_string(),
-            "password123".to_string(),
+            STRONG_PASSWORD.to_string(),
         )
         .await;
 
@@ -583,13 +944,17 @@ async fn test_integration_find_by_email() {
     use crate::modules::users::repository::UserRepositoryImpl;
 
     let mongodb = MongoDB::init().await.expect("Failed to init MongoDB");
-    let service = UserServiceImpl::new(Arc::new(UserRepositoryImpl::new(&mongodb.db)));
+    let service = UserServiceImpl::new(
+        Arc::new(UserRepositoryImpl::new(&mongodb.db)),
+        Arc::new(FastHasher),
+        test_policy(),
+    );
 
     service
         .create_user(
             "find@example.com".to_string(),
             "Test User".to_string(),
-            "password123".to_string(),
+            STRONG_PASSWORD.to_string(),
         )
         .await
         .ok();
@@ -606,13 +971,17 @@ async fn test_integration_verify_credentials() {
     use crate::modules::users::repository::UserRepositoryImpl;
 
     let mongodb = MongoDB::init().await.expect("Failed to init MongoDB");
-    let service = UserServiceImpl::new(Arc::new(UserRepositoryImpl::new(&mongodb.db)));
+    let service = UserServiceImpl::new(
+        Arc::new(UserRepositoryImpl::new(&mongodb.db)),
+        Arc::new(FastHasher),
+        test_policy(),
+    );
 
     service
         .create_user(
             "verify@example.com".to_string(),
             "Test User".to_string(),
-            "password123".to_string(),
+            STRONG_PASSWORD.to_string(),
         )
         .await
         .ok();
@@ -623,3 +992,122 @@ async fn test_integration_verify_credentials() {
     assert!(result.is_ok());
     assert!(result.unwrap().is_some());
 }
+
+fn profile_dto(bio: Option<&str>, links: Option<Vec<ProfileLink>>) -> UpdateUserDto {
+    UpdateUserDto {
+        email: None,
+        name: None,
+        password: None,
+        bio: bio.map(str::to_string),
+        links,
+        privacy: None,
+    }
+}
+
+fn link(url: &str) -> ProfileLink {
+    ProfileLink {
+        label: "Site".to_string(),
+        url: url.to_string(),
+    }
+}
+
+fn repo_returning(user: &User, updates: usize) -> MockUserRepo {
+    let mut mock_repo = MockUserRepo::new();
+    let user_clone = user.clone();
+    mock_repo
+        .expect_find_by_id()
+        .returning(move |_| Ok(Some(user_clone.clone())));
+    mock_repo
+        .expect_update()
+        .times(updates)
+        .returning(|_| Ok(()));
+    mock_repo
+}
+
+#[tokio::test]
+async fn test_update_user_bio_and_links() {
+    let user = create_test_user();
+    let mock_repo = repo_returning(&user, 1);
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    let dto = profile_dto(Some("  Loves brackets  "), Some(vec![link("https://example.com")]));
+    let updated = service.update_user(&user.id.unwrap(), dto).await.unwrap();
+
+    assert_eq!(updated.bio.as_deref(), Some("Loves brackets"));
+    assert_eq!(updated.links, vec![link("https://example.com")]);
+}
+
+#[tokio::test]
+async fn test_update_user_empty_bio_clears_it() {
+    let mut user = create_test_user();
+    user.bio = Some("Old bio".to_string());
+    let mock_repo = repo_returning(&user, 1);
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    let updated = service
+        .update_user(&user.id.unwrap(), profile_dto(Some("   "), None))
+        .await
+        .unwrap();
+
+    assert!(updated.bio.is_none());
+}
+
+#[tokio::test]
+async fn test_update_user_bio_too_long() {
+    let user = create_test_user();
+    let mock_repo = repo_returning(&user, 0);
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    let bio = "a".repeat(501);
+    let result = service
+        .update_user(&user.id.unwrap(), profile_dto(Some(&bio), None))
+        .await;
+
+    assert_eq!(
+        result.unwrap_err(),
+        "Bio cannot be longer than 500 characters"
+    );
+}
+
+#[tokio::test]
+async fn test_update_user_too_many_links() {
+    let user = create_test_user();
+    let mock_repo = repo_returning(&user, 0);
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    let links = vec![link("https://example.com"); 6];
+    let result = service
+        .update_user(&user.id.unwrap(), profile_dto(None, Some(links)))
+        .await;
+
+    assert_eq!(result.unwrap_err(), "At most 5 links are allowed");
+}
+
+#[tokio::test]
+async fn test_update_user_rejects_non_http_link() {
+    let user = create_test_user();
+    let mock_repo = repo_returning(&user, 0);
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    let links = vec![link("ftp://example.com")];
+    let result = service
+        .update_user(&user.id.unwrap(), profile_dto(None, Some(links)))
+        .await;
+
+    assert_eq!(result.unwrap_err(), "Links must be http or https URLs");
+}
+
+#[tokio::test]
+async fn test_update_user_privacy() {
+    let user = create_test_user();
+    let mock_repo = repo_returning(&user, 1);
+    let service = UserServiceImpl::new(Arc::new(mock_repo), Arc::new(FastHasher), test_policy());
+
+    let mut dto = profile_dto(None, None);
+    dto.privacy = Some(PrivacySettings {
+        discoverable: false,
+    });
+    let updated = service.update_user(&user.id.unwrap(), dto).await.unwrap();
+
+    assert!(!updated.privacy.discoverable);
+}
@@ -11,34 +11,55 @@ use tokio::sync::broadcast;
 use tokio::time::{interval, Duration};
 
 use crate::modules::auth::service::AuthService;
+use crate::modules::tournaments::model::VoterId;
+use crate::modules::tournaments::service::TournamentService;
 use crate::modules::websocket::broadcaster::TournamentBroadcaster;
-use crate::modules::websocket::model::ClientMessage;
+use crate::modules::websocket::model::{ClientMessage, TournamentEvent};
 
 const HEARTBEAT_INTERVAL_SECS: u64 = 30;
 
 #[get("/tournaments/<tournament_id>?<token>")]
-pub fn tournament_ws(
+pub async fn tournament_ws(
     ws: WebSocket,
     tournament_id: &str,
     token: &str,
     auth_service: &State<Arc<dyn AuthService + Send + Sync>>,
+    tournament_service: &State<Arc<dyn TournamentService + Send + Sync>>,
     broadcaster: &State<Arc<TournamentBroadcaster>>,
 ) -> Result<Channel<'static>, Status> {
     let tournament_id =
         ObjectId::parse_str(tournament_id).map_err(|_| Status::BadRequest)?;
 
-    validate_token(token, auth_service.inner()).map_err(|_| Status::Unauthorized)?;
+    let session_id = validate_token(token, &tournament_id, auth_service.inner())
+        .map_err(|_| Status::Unauthorized)?;
+
+    // Anonymous tokens cannot be revoked, so a kicked guest is recognised by
+    // no longer being listed in the tournament
+    if let Some(session_id) = &session_id {
+        let tournament = tournament_service
+            .find_by_id(&tournament_id)
+            .await
+            .map_err(|_| Status::InternalServerError)?
+            .ok_or(Status::NotFound)?;
+        let voter_id = VoterId::Anonymous(session_id.clone());
+        if !tournament.users.iter().any(|u| u.voter_id == voter_id) {
+            return Err(Status::Unauthorized);
+        }
+    }
 
     let rx = broadcaster.subscribe(&tournament_id);
 
     Ok(ws.channel(move |stream| {
-        Box::pin(handle_connection(stream, rx))
+        Box::pin(handle_connection(stream, rx, session_id))
     }))
 }
 
+/// Relays tournament events until either side hangs up. The connection of
+/// an anonymous session ends once that participant is kicked.
 async fn handle_connection(
     mut stream: DuplexStream,
-    mut rx: broadcast::Receiver<crate::modules::websocket::model::TournamentEvent>,
+    mut rx: broadcast::Receiver<TournamentEvent>,
+    session_id: Option<String>,
 ) -> Result<(), ws::result::Error> {
     let mut heartbeat = interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
 
@@ -51,6 +72,12 @@ async fn handle_connection(
                         if stream.send(Message::Text(json)).await.is_err() {
                             break;
                         }
+                        if let TournamentEvent::ParticipantLeft { voter_id, .. } = &event {
+                            if session_id.as_ref() == Some(voter_id) {
+                                let _ = stream.send(Message::Close(None)).await;
+                                break;
+                            }
+                        }
                     }
                     Err(broadcast::error::RecvError::Lagged(_)) => continue,
                     Err(broadcast::error::RecvError::Closed) => break,
@@ -86,17 +113,24 @@ async fn handle_connection(
     Ok(())
 }
 
+/// Checks a connection token. Returns the session id for anonymous tokens
+/// and `None` for registered users.
 pub fn validate_token(
     token: &str,
+    tournament_id: &ObjectId,
     auth_service: &Arc<dyn AuthService + Send + Sync>,
-) -> Result<(), String> {
+) -> Result<Option<String>, String> {
     if let Ok(claims) = auth_service.verify_token(token) {
         if claims.token_type == "access" {
-            return Ok(());
+            return Ok(None);
         }
     }
-    if auth_service.verify_anonymous_token(token).is_ok() {
-        return Ok(());
+    if let Ok(claims) = auth_service.verify_anonymous_token(token) {
+        // Anonymous sessions may only subscribe to their own tournament
+        if ObjectId::parse_str(&claims.tournament_id).ok().as_ref() == Some(tournament_id) {
+            return Ok(Some(claims.sub));
+        }
+        return Err("Token is not valid for this tournament".to_string());
     }
     Err("Invalid token".to_string())
 }
@@ -112,6 +146,7 @@ mod tests {
     struct MockAuthForWs {
         valid_access: bool,
         valid_anonymous: bool,
+        anonymous_tournament_id: ObjectId,
     }
 
     #[async_trait::async_trait]
@@ -120,6 +155,15 @@ mod tests {
             &self,
             _email: &str,
             _password: &str,
+            _client: &crate::config::client_info::ClientInfo,
+        ) -> Result<crate::modules::auth::model::LoginResult, String> {
+            unimplemented!()
+        }
+        async fn complete_mfa_login(
+            &self,
+            _mfa_token: &str,
+            _code: &str,
+            _client: &crate::config::client_info::ClientInfo,
         ) -> Result<crate::modules::auth::model::LoginResponse, String> {
             unimplemented!()
         }
@@ -128,6 +172,14 @@ mod tests {
             _email: &str,
             _name: &str,
             _password: &str,
+            _client: &crate::config::client_info::ClientInfo,
+        ) -> Result<crate::modules::auth::model::LoginResponse, String> {
+            unimplemented!()
+        }
+        async fn create_session(
+            &self,
+            _user: crate::modules::users::model::User,
+            _client: &crate::config::client_info::ClientInfo,
         ) -> Result<crate::modules::auth::model::LoginResponse, String> {
             unimplemented!()
         }
@@ -147,12 +199,77 @@ mod tests {
                 Err("invalid".to_string())
             }
         }
-        fn refresh_tokens(
+        async fn refresh_tokens(
             &self,
             _token: &str,
+            _client: &crate::config::client_info::ClientInfo,
         ) -> Result<crate::modules::auth::model::RefreshResponse, String> {
             unimplemented!()
         }
+        async fn logout(&self, _refresh_token: &str) -> Result<(), String> {
+            unimplemented!()
+        }
+        async fn logout_all(&self, _user_id: &ObjectId) -> Result<(), String> {
+            unimplemented!()
+        }
+        async fn list_sessions(
+            &self,
+            _user_id: &ObjectId,
+        ) -> Result<Vec<crate::modules::auth::model::SessionResponse>, String> {
+            unimplemented!()
+        }
+        async fn revoke_session(
+            &self,
+            _user_id: &ObjectId,
+            _session_id: &str,
+        ) -> Result<(), String> {
+            unimplemented!()
+        }
+        async fn request_password_reset(&self, _email: &str) -> Result<(), String> {
+            unimplemented!()
+        }
+        async fn reset_password(&self, _token: &str, _new_password: &str) -> Result<(), String> {
+            unimplemented!()
+        }
+        async fn send_verification_email(&self, _user_id: &ObjectId) -> Result<(), String> {
+            unimplemented!()
+        }
+        async fn verify_email(&self, _token: &str) -> Result<(), String> {
+            unimplemented!()
+        }
+        async fn request_magic_link(&self, _email: &str) -> Result<(), String> {
+            unimplemented!()
+        }
+        async fn redeem_magic_link(
+            &self,
+            _token: &str,
+            _client: &crate::config::client_info::ClientInfo,
+        ) -> Result<crate::modules::auth::model::LoginResult, String> {
+            unimplemented!()
+        }
+        async fn start_totp_enrollment(
+            &self,
+            _user_id: &ObjectId,
+        ) -> Result<crate::modules::auth::model::TotpEnrollmentResponse, String> {
+            unimplemented!()
+        }
+        async fn confirm_totp_enrollment(
+            &self,
+            _user_id: &ObjectId,
+            _code: &str,
+        ) -> Result<crate::modules::auth::model::RecoveryCodesResponse, String> {
+            unimplemented!()
+        }
+        async fn disable_totp(&self, _user_id: &ObjectId, _code: &str) -> Result<(), String> {
+            unimplemented!()
+        }
+        async fn regenerate_recovery_codes(
+            &self,
+            _user_id: &ObjectId,
+            _code: &str,
+        ) -> Result<crate::modules::auth::model::RecoveryCodesResponse, String> {
+            unimplemented!()
+        }
         fn generate_anonymous_token(
             &self,
             _tournament_id: &ObjectId,
@@ -167,7 +284,7 @@ mod tests {
             if self.valid_anonymous {
                 Ok(crate::modules::auth::model::AnonymousClaims {
                     sub: "session-123".to_string(),
-                    tournament_id: ObjectId::new().to_string(),
+                    tournament_id: self.anonymous_tournament_id.to_string(),
                     display_name: "Player".to_string(),
                     token_type: "anonymous".to_string(),
                     exp: 9999999999,
@@ -184,17 +301,34 @@ mod tests {
         let auth: Arc<dyn AuthService + Send + Sync> = Arc::new(MockAuthForWs {
             valid_access: true,
             valid_anonymous: false,
+            anonymous_tournament_id: ObjectId::new(),
         });
-        assert!(validate_token("valid_access", &auth).is_ok());
+        assert_eq!(validate_token("valid_access", &ObjectId::new(), &auth), Ok(None));
     }
 
     #[test]
     fn test_validate_token_accepts_anonymous_token() {
+        let tournament_id = ObjectId::new();
+        let auth: Arc<dyn AuthService + Send + Sync> = Arc::new(MockAuthForWs {
+            valid_access: false,
+            valid_anonymous: true,
+            anonymous_tournament_id: tournament_id,
+        });
+        assert_eq!(
+            validate_token("valid_anonymous", &tournament_id, &auth),
+            Ok(Some("session-123".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_validate_token_rejects_anonymous_token_for_other_tournament() {
         let auth: Arc<dyn AuthService + Send + Sync> = Arc::new(MockAuthForWs {
             valid_access: false,
             valid_anonymous: true,
+            anonymous_tournament_id: ObjectId::new(),
         });
-        assert!(validate_token("valid_anonymous", &auth).is_ok());
+        let result = validate_token("valid_anonymous", &ObjectId::new(), &auth);
+        assert!(result.unwrap_err().contains("not valid for this tournament"));
     }
 
     #[test]
@@ -202,7 +336,8 @@ mod tests {
         let auth: Arc<dyn AuthService + Send + Sync> = Arc::new(MockAuthForWs {
             valid_access: false,
             valid_anonymous: false,
+            anonymous_tournament_id: ObjectId::new(),
         });
-        assert!(validate_token("garbage", &auth).is_err());
+        assert!(validate_token("garbage", &ObjectId::new(), &auth).is_err());
     }
 }
@@ -29,10 +29,20 @@ pub enum TournamentEvent {
         display_name: String,
         participant_count: usize,
     },
+    /// An organizer removed a participant from the tournament
+    #[serde(rename = "participant_left")]
+    ParticipantLeft {
+        voter_id: String,
+        display_name: String,
+        participant_count: usize,
+    },
     #[serde(rename = "tournament_paused")]
     TournamentPaused,
     #[serde(rename = "tournament_resumed")]
     TournamentResumed,
+    /// An admin ended the tournament before a winner was decided
+    #[serde(rename = "tournament_force_completed")]
+    TournamentForceCompleted,
     #[serde(rename = "error")]
     Error { message: String },
 }
@@ -91,6 +91,24 @@ fn test_participant_joined_event_serialization() {
     assert_eq!(event, deserialized);
 }
 
+#[test]
+fn test_participant_left_event_serialization() {
+    let event = TournamentEvent::ParticipantLeft {
+        voter_id: "session-uuid-123".to_string(),
+        display_name: "Player 1".to_string(),
+        participant_count: 4,
+    };
+
+    let json = serde_json::to_string(&event).unwrap();
+    assert!(json.contains(r#""type":"participant_left""#));
+    assert!(json.contains(r#""voter_id":"session-uuid-123""#));
+    assert!(json.contains(r#""display_name":"Player 1""#));
+    assert!(json.contains(r#""participant_count":4"#));
+
+    let deserialized: TournamentEvent = serde_json::from_str(&json).unwrap();
+    assert_eq!(event, deserialized);
+}
+
 #[test]
 fn test_tournament_paused_event_serialization() {
     let event = TournamentEvent::TournamentPaused;
@@ -168,6 +186,11 @@ fn test_all_event_variants_roundtrip() {
             display_name: "Test".to_string(),
             participant_count: 1,
         },
+        TournamentEvent::ParticipantLeft {
+            voter_id: "s1".to_string(),
+            display_name: "Test".to_string(),
+            participant_count: 0,
+        },
         TournamentEvent::TournamentPaused,
         TournamentEvent::TournamentResumed,
         TournamentEvent::Error {